@@ -7,7 +7,9 @@ use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{APIAuth, APIClient};
 
-use crate::{multiplexer::CacheMultiplexer, CacheError, CacheHitMetadata, CacheOpts};
+use crate::{
+    fs::PruneSummary, multiplexer::CacheMultiplexer, CacheError, CacheHitMetadata, CacheOpts,
+};
 
 const WARNING_CUTOFF: u8 = 4;
 
@@ -172,6 +174,14 @@ impl AsyncCache {
         Ok(())
     }
 
+    /// Waits for any pending writes to finish and then evicts local cache
+    /// entries that exceed the configured limits.
+    #[tracing::instrument(skip_all)]
+    pub async fn prune(&self) -> Result<Option<PruneSummary>, CacheError> {
+        self.wait().await?;
+        self.real_cache.prune()
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn shutdown(&self) -> Result<(), CacheError> {
         let (tx, rx) = tokio::sync::oneshot::channel();
@@ -233,6 +243,7 @@ mod tests {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
             }),
+            ..CacheOpts::default()
         };
 
         let api_client = APIClient::new(format!("http://localhost:{}", port), 200, "2.0.0", true)?;
@@ -313,6 +324,7 @@ mod tests {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
             }),
+            ..CacheOpts::default()
         };
 
         // Initialize client with invalid API url to ensure that we don't hit the
@@ -403,6 +415,7 @@ mod tests {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
             }),
+            ..CacheOpts::default()
         };
 
         let api_client = APIClient::new(format!("http://localhost:{}", port), 200, "2.0.0", true)?;
@@ -6,4 +6,4 @@ mod restore_regular;
 mod restore_symlink;
 
 pub use create::CacheWriter;
-pub use restore::CacheReader;
+pub use restore::{content_hash, ArchiveEntry, CacheReader};
@@ -1,8 +1,12 @@
-use std::{backtrace::Backtrace, collections::HashMap, io::Read};
+use std::{
+    backtrace::Backtrace,
+    collections::{BTreeMap, HashMap},
+    io::{self, Read},
+};
 
 use petgraph::graph::DiGraph;
 use sha2::{Digest, Sha512};
-use tar::Entry;
+use tar::{Entry, EntryType};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 
 use crate::{
@@ -20,6 +24,21 @@ pub struct CacheReader<'a> {
     reader: Box<dyn Read + 'a>,
 }
 
+/// A file stored in a cache archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Hashes file contents the same way `CacheReader::file_hashes` does, so that
+/// files on disk can be compared against an archive
+pub fn content_hash(mut reader: impl Read) -> Result<String, io::Error> {
+    let mut hasher = Sha512::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
 impl<'a> CacheReader<'a> {
     pub fn from_reader(reader: impl Read + 'a, is_compressed: bool) -> Result<Self, CacheError> {
         let reader: Box<dyn Read> = if is_compressed {
@@ -58,6 +77,36 @@ impl<'a> CacheReader<'a> {
         Ok(hasher.finalize().to_vec())
     }
 
+    /// Lists the contents of the archive without restoring them
+    pub fn list(&mut self) -> Result<Vec<ArchiveEntry>, CacheError> {
+        let mut tr = tar::Archive::new(&mut self.reader);
+        tr.entries()?
+            .map(|entry| {
+                let entry = entry?;
+                Ok(ArchiveEntry {
+                    path: entry.path()?.to_string_lossy().into_owned(),
+                    size: entry.size(),
+                })
+            })
+            .collect()
+    }
+
+    /// Hashes the contents of every regular file in the archive without
+    /// restoring them, keyed by their path in the archive
+    pub fn file_hashes(&mut self) -> Result<BTreeMap<String, String>, CacheError> {
+        let mut tr = tar::Archive::new(&mut self.reader);
+        let mut hashes = BTreeMap::new();
+        for entry in tr.entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type() != EntryType::Regular {
+                continue;
+            }
+            let path = entry.path()?.to_string_lossy().into_owned();
+            hashes.insert(path, content_hash(&mut entry)?);
+        }
+        Ok(hashes)
+    }
+
     pub fn restore(
         &mut self,
         anchor: &AbsoluteSystemPath,
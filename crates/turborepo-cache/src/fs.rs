@@ -1,25 +1,48 @@
-use std::{backtrace::Backtrace, fs::OpenOptions};
+use std::{
+    backtrace::Backtrace,
+    collections::BTreeMap,
+    fs::OpenOptions,
+    io::{BufWriter, Read, Write},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use camino::Utf8Path;
+use hmac::Mac;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use tracing::debug;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{analytics, analytics::AnalyticsEvent};
 
 use crate::{
-    cache_archive::{CacheReader, CacheWriter},
+    cache_archive::{ArchiveEntry, CacheReader, CacheWriter},
+    signature_authentication::{encode_tag, ArtifactSignatureAuthenticator, HmacSha256},
     CacheError, CacheHitMetadata, CacheSource,
 };
 
 pub struct FSCache {
     cache_directory: AbsoluteSystemPathBuf,
     analytics_recorder: Option<AnalyticsSender>,
+    signer_verifier: Option<ArtifactSignatureAuthenticator>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct CacheMetadata {
     hash: String,
     duration: u64,
+    // Milliseconds since the unix epoch. Older versions of turbo didn't record
+    // this, in which case we fall back to the modification time of the
+    // artifact.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_accessed: Option<u64>,
+    // Hex encoded SHA-512 of the artifact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha: Option<String>,
+    // Signature of the artifact, only present if signing was enabled when it
+    // was written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tag: Option<String>,
 }
 
 impl CacheMetadata {
@@ -27,6 +50,172 @@ impl CacheMetadata {
         serde_json::from_str(&path.read_to_string()?)
             .map_err(|e| CacheError::InvalidMetadata(e, Backtrace::capture()))
     }
+
+    fn write(&self, path: &AbsoluteSystemPath) -> Result<(), CacheError> {
+        let mut metadata_options = OpenOptions::new();
+        metadata_options.create(true).write(true).truncate(true);
+
+        let metadata_file = path.open_with_options(metadata_options)?;
+
+        serde_json::to_writer(metadata_file, self)
+            .map_err(|e| CacheError::MetadataWriteFailure(e, Backtrace::capture()))
+    }
+
+    fn last_accessed(&self) -> Option<SystemTime> {
+        self.last_accessed
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis))
+    }
+}
+
+fn artifact_sha(body: &[u8]) -> String {
+    hex::encode(Sha512::digest(body))
+}
+
+// Feeds everything written to an artifact into its checksum and, when signing
+// is enabled, its signature, so that neither needs the artifact to be read
+// back.
+struct HashingWriter<'a, W> {
+    inner: W,
+    sha: &'a mut Sha512,
+    tag: Option<&'a mut HmacSha256>,
+}
+
+impl<'a, W: Write> Write for HashingWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.sha.update(&buf[..written]);
+        if let Some(tag) = self.tag.as_mut() {
+            tag.update(&buf[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Hashes are only ever used as file names, so we make sure they can't be used
+// to escape the cache directory.
+pub(crate) fn is_valid_hash(hash: &str) -> bool {
+    !hash.is_empty() && !hash.starts_with('.') && !hash.contains(['/', '\\', '\0'])
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Limits applied to the local cache directory when it is pruned.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EvictionOpts {
+    /// Maximum total size of all artifacts in bytes. Least recently used
+    /// artifacts are evicted first.
+    pub max_size: Option<u64>,
+    /// Artifacts that haven't been used for longer than this are evicted.
+    pub max_age: Option<Duration>,
+}
+
+impl EvictionOpts {
+    pub fn has_limits(&self) -> bool {
+        self.max_size.is_some() || self.max_age.is_some()
+    }
+
+    /// Parses a size such as `500MB`, `10GB` or `1024`. Units are binary
+    /// multiples and a bare number is a number of bytes.
+    pub fn parse_size(size: &str) -> Option<u64> {
+        let (value, unit) = split_unit(size)?;
+        let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "kb" | "kib" => 1 << 10,
+            "m" | "mb" | "mib" => 1 << 20,
+            "g" | "gb" | "gib" => 1 << 30,
+            "t" | "tb" | "tib" => 1 << 40,
+            _ => return None,
+        };
+        value.checked_mul(multiplier)
+    }
+
+    /// Parses an age such as `30m`, `12h` or `7d`. A bare number is a number
+    /// of seconds.
+    pub fn parse_age(age: &str) -> Option<Duration> {
+        let (value, unit) = split_unit(age)?;
+        let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+            "" | "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        value.checked_mul(multiplier).map(Duration::from_secs)
+    }
+}
+
+// Splits `10GB` into `(10, "GB")`
+fn split_unit(input: &str) -> Option<(u64, &str)> {
+    let input = input.trim();
+    let unit_start = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (value, unit) = input.split_at(unit_start);
+    Some((value.parse().ok()?, unit.trim()))
+}
+
+/// The outcome of pruning the local cache
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneSummary {
+    pub removed_entries: usize,
+    pub removed_bytes: u64,
+    pub remaining_entries: usize,
+    pub remaining_bytes: u64,
+}
+
+/// An artifact stored in the local cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheItem {
+    pub hash: String,
+    /// Size on disk in bytes, including metadata
+    pub size: u64,
+    /// How long the task that produced the artifact took, in milliseconds
+    pub duration: u64,
+    pub last_accessed: SystemTime,
+}
+
+/// The outcome of checking an artifact's integrity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    Valid,
+    /// The artifact is readable, but was written without a checksum
+    Unchecked,
+    ChecksumMismatch,
+    MissingSignature,
+    InvalidSignature,
+    Corrupt(String),
+}
+
+// All of the files that make up a single cache entry
+#[derive(Debug)]
+struct CacheEntry {
+    hash: String,
+    files: Vec<AbsoluteSystemPathBuf>,
+    size: u64,
+    has_artifact: bool,
+    duration: u64,
+    last_accessed: SystemTime,
+}
+
+impl From<CacheEntry> for CacheItem {
+    fn from(entry: CacheEntry) -> Self {
+        CacheItem {
+            hash: entry.hash,
+            size: entry.size,
+            duration: entry.duration,
+            last_accessed: entry.last_accessed,
+        }
+    }
 }
 
 impl FSCache {
@@ -53,9 +242,43 @@ impl FSCache {
         Ok(FSCache {
             cache_directory,
             analytics_recorder,
+            signer_verifier: None,
         })
     }
 
+    /// Signs artifacts as they're written and checks signatures when they're
+    /// verified.
+    pub fn with_signer_verifier(mut self, signer_verifier: ArtifactSignatureAuthenticator) -> Self {
+        self.signer_verifier = Some(signer_verifier);
+        self
+    }
+
+    fn artifact_path(&self, hash: &str) -> Option<AbsoluteSystemPathBuf> {
+        if !is_valid_hash(hash) {
+            return None;
+        }
+
+        let uncompressed_cache_path = self
+            .cache_directory
+            .join_component(&format!("{}.tar", hash));
+        let compressed_cache_path = self
+            .cache_directory
+            .join_component(&format!("{}.tar.zst", hash));
+
+        if uncompressed_cache_path.exists() {
+            Some(uncompressed_cache_path)
+        } else if compressed_cache_path.exists() {
+            Some(compressed_cache_path)
+        } else {
+            None
+        }
+    }
+
+    fn metadata_path(&self, hash: &str) -> AbsoluteSystemPathBuf {
+        self.cache_directory
+            .join_component(&format!("{}-meta.json", hash))
+    }
+
     fn log_fetch(&self, event: analytics::CacheEvent, hash: &str, duration: u64) {
         // If analytics fails to record, it's not worth failing the cache
         if let Some(analytics_recorder) = &self.analytics_recorder {
@@ -77,18 +300,7 @@ impl FSCache {
         anchor: &AbsoluteSystemPath,
         hash: &str,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        let uncompressed_cache_path = self
-            .cache_directory
-            .join_component(&format!("{}.tar", hash));
-        let compressed_cache_path = self
-            .cache_directory
-            .join_component(&format!("{}.tar.zst", hash));
-
-        let cache_path = if uncompressed_cache_path.exists() {
-            uncompressed_cache_path
-        } else if compressed_cache_path.exists() {
-            compressed_cache_path
-        } else {
+        let Some(cache_path) = self.artifact_path(hash) else {
             self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
             return Ok(None);
         };
@@ -97,11 +309,15 @@ impl FSCache {
 
         let restored_files = cache_reader.restore(anchor)?;
 
-        let meta = CacheMetadata::read(
-            &self
-                .cache_directory
-                .join_component(&format!("{}-meta.json", hash)),
-        )?;
+        let metadata_path = self.metadata_path(hash);
+        let mut meta = CacheMetadata::read(&metadata_path)?;
+
+        // Recording the access is only needed for eviction, so it's not worth
+        // failing a cache hit over.
+        meta.last_accessed = Some(now_millis());
+        if let Err(e) = meta.write(&metadata_path) {
+            debug!("failed to record cache access for {hash}: {e}");
+        }
 
         self.log_fetch(analytics::CacheEvent::Hit, hash, meta.duration);
 
@@ -116,24 +332,13 @@ impl FSCache {
 
     #[tracing::instrument(skip_all)]
     pub(crate) fn exists(&self, hash: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
-        let uncompressed_cache_path = self
-            .cache_directory
-            .join_component(&format!("{}.tar", hash));
-        let compressed_cache_path = self
-            .cache_directory
-            .join_component(&format!("{}.tar.zst", hash));
-
-        if !uncompressed_cache_path.exists() && !compressed_cache_path.exists() {
+        if self.artifact_path(hash).is_none() {
             return Ok(None);
         }
 
-        let duration = CacheMetadata::read(
-            &self
-                .cache_directory
-                .join_component(&format!("{}-meta.json", hash)),
-        )
-        .map(|meta| meta.duration)
-        .unwrap_or(0);
+        let duration = CacheMetadata::read(&self.metadata_path(hash))
+            .map(|meta| meta.duration)
+            .unwrap_or(0);
 
         Ok(Some(CacheHitMetadata {
             time_saved: duration,
@@ -153,45 +358,626 @@ impl FSCache {
             .cache_directory
             .join_component(&format!("{}.tar.zst", hash));
 
-        let mut cache_item = CacheWriter::create(&cache_path)?;
+        let mut sha = Sha512::new();
+        // Like the rest of the local cache, signing is best effort. A missing
+        // signature will be reported by `verify`.
+        let mut tag = self.signer_verifier.as_ref().and_then(|signer| {
+            signer
+                .get_tag_generator(hash.as_bytes())
+                .map_err(|e| debug!("failed to sign {hash}: {e}"))
+                .ok()
+        });
 
-        for file in files {
-            cache_item.add_file(anchor, file)?;
-        }
+        {
+            let mut options = OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            let file = cache_path.open_with_options(options)?;
+            let writer = HashingWriter {
+                // Flush to disk in 1mb chunks.
+                inner: BufWriter::with_capacity(2usize.pow(20), file),
+                sha: &mut sha,
+                tag: tag.as_mut(),
+            };
 
-        let metadata_path = self
-            .cache_directory
-            .join_component(&format!("{}-meta.json", hash));
+            let mut cache_item = CacheWriter::from_writer(writer, true)?;
+            for file in files {
+                cache_item.add_file(anchor, file)?;
+            }
+            cache_item.finish()?;
+        }
 
         let meta = CacheMetadata {
             hash: hash.to_string(),
             duration,
+            last_accessed: Some(now_millis()),
+            sha: Some(hex::encode(sha.finalize())),
+            tag: tag.map(encode_tag),
         };
+        meta.write(&self.metadata_path(hash))?;
 
-        let mut metadata_options = OpenOptions::new();
-        metadata_options.create(true).write(true).truncate(true);
+        Ok(())
+    }
+
+    /// Lists every artifact in the cache, ordered by hash
+    #[tracing::instrument(skip_all)]
+    pub fn list(&self) -> Result<Vec<CacheItem>, CacheError> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.has_artifact)
+            .map(CacheItem::from)
+            .collect())
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn item(&self, hash: &str) -> Result<Option<CacheItem>, CacheError> {
+        if self.artifact_path(hash).is_none() {
+            return Ok(None);
+        }
+        Ok(self
+            .entries()?
+            .into_iter()
+            .find(|entry| entry.hash == hash)
+            .map(CacheItem::from))
+    }
+
+    /// Lists the files stored in an artifact without restoring them
+    #[tracing::instrument(skip_all)]
+    pub fn files(&self, hash: &str) -> Result<Option<Vec<ArchiveEntry>>, CacheError> {
+        let Some(artifact_path) = self.artifact_path(hash) else {
+            return Ok(None);
+        };
+        let mut cache_reader = CacheReader::open(&artifact_path)?;
+        cache_reader.list().map(Some)
+    }
+
+    /// Hashes the contents of the files stored in an artifact, see
+    /// `CacheReader::file_hashes`
+    #[tracing::instrument(skip_all)]
+    pub fn file_hashes(&self, hash: &str) -> Result<Option<BTreeMap<String, String>>, CacheError> {
+        let Some(artifact_path) = self.artifact_path(hash) else {
+            return Ok(None);
+        };
+        let mut cache_reader = CacheReader::open(&artifact_path)?;
+        cache_reader.file_hashes().map(Some)
+    }
+
+    /// Checks that an artifact can be read and that it matches the checksum
+    /// and signature recorded when it was written.
+    #[tracing::instrument(skip_all)]
+    pub fn verify(&self, hash: &str) -> Result<Option<Verification>, CacheError> {
+        let Some(artifact_path) = self.artifact_path(hash) else {
+            return Ok(None);
+        };
+        let body = artifact_path.read()?;
+
+        let is_compressed = artifact_path.extension() == Some("zst");
+        if let Err(e) = CacheReader::from_reader(body.as_slice(), is_compressed)
+            .and_then(|mut cache_reader| cache_reader.list())
+        {
+            return Ok(Some(Verification::Corrupt(e.to_string())));
+        }
+
+        let meta = match CacheMetadata::read(&self.metadata_path(hash)) {
+            Ok(meta) => meta,
+            Err(e) => return Ok(Some(Verification::Corrupt(e.to_string()))),
+        };
+
+        if meta
+            .sha
+            .as_ref()
+            .is_some_and(|expected| *expected != artifact_sha(&body))
+        {
+            return Ok(Some(Verification::ChecksumMismatch));
+        }
+
+        if let Some(signer_verifier) = &self.signer_verifier {
+            let Some(tag) = &meta.tag else {
+                return Ok(Some(Verification::MissingSignature));
+            };
+            if !signer_verifier.validate(hash.as_bytes(), &body, tag)? {
+                return Ok(Some(Verification::InvalidSignature));
+            }
+        }
+
+        Ok(Some(if meta.sha.is_some() {
+            Verification::Valid
+        } else {
+            Verification::Unchecked
+        }))
+    }
+
+    /// Writes the given artifacts, or every artifact if `hashes` is empty, to
+    /// a single tar bundle that can be imported on another machine. Returns
+    /// the hashes that were exported.
+    #[tracing::instrument(skip_all)]
+    pub fn export(&self, hashes: &[String], writer: impl Write) -> Result<Vec<String>, CacheError> {
+        let entries = self.entries()?;
+        let entries = if hashes.is_empty() {
+            entries
+                .into_iter()
+                .filter(|entry| entry.has_artifact)
+                .collect::<Vec<_>>()
+        } else {
+            let mut entries = entries
+                .into_iter()
+                .filter(|entry| entry.has_artifact)
+                .map(|entry| (entry.hash.clone(), entry))
+                .collect::<BTreeMap<_, _>>();
+            hashes
+                .iter()
+                .map(|hash| {
+                    entries
+                        .remove(hash)
+                        .ok_or_else(|| CacheError::ArtifactNotFound(hash.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut bundle = tar::Builder::new(writer);
+        let mut exported = Vec::with_capacity(entries.len());
+        for entry in entries {
+            for file in &entry.files {
+                let name = file.file_name().expect("cache files have a file name");
+                bundle.append_path_with_name(file.as_std_path(), name)?;
+            }
+            exported.push(entry.hash);
+        }
+        bundle.finish()?;
+
+        Ok(exported)
+    }
+
+    /// Adds the artifacts from a bundle created by `export` to the cache.
+    /// Artifacts that are already cached are skipped unless `overwrite` is
+    /// set. Returns the hashes that were imported.
+    #[tracing::instrument(skip_all)]
+    pub fn import(&self, reader: impl Read, overwrite: bool) -> Result<Vec<String>, CacheError> {
+        let mut bundle = tar::Archive::new(reader);
+        let mut imported = Vec::new();
+        for entry in bundle.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?;
+            let name = path
+                .to_str()
+                .filter(|name| !name.contains(['/', '\\']))
+                .ok_or_else(|| CacheError::InvalidBundleEntry(path.to_string_lossy().into()))?
+                .to_string();
+            let (hash, is_artifact) = if let Some(hash) = name.strip_suffix("-meta.json") {
+                (hash, false)
+            } else if let Some(hash) = name
+                .strip_suffix(".tar.zst")
+                .or_else(|| name.strip_suffix(".tar"))
+            {
+                (hash, true)
+            } else {
+                return Err(CacheError::InvalidBundleEntry(name));
+            };
+            if !is_valid_hash(hash) {
+                return Err(CacheError::InvalidBundleEntry(name));
+            }
 
-        let metadata_file = metadata_path.open_with_options(metadata_options)?;
+            let destination = self.cache_directory.join_component(&name);
+            if destination.exists() && !overwrite {
+                debug!("skipping {name}, it is already cached");
+                continue;
+            }
 
-        serde_json::to_writer(metadata_file, &meta)
-            .map_err(|e| CacheError::InvalidMetadata(e, Backtrace::capture()))?;
+            let mut file = destination.create()?;
+            std::io::copy(&mut entry, &mut file)?;
+            if is_artifact {
+                imported.push(hash.to_string());
+            }
+        }
+
+        Ok(imported)
+    }
 
+    /// Evicts entries that exceed the given limits. Entries that are older
+    /// than `max_age` are removed first, then the least recently used entries
+    /// are removed until the cache fits in `max_size`.
+    #[tracing::instrument(skip_all)]
+    pub fn prune(&self, opts: &EvictionOpts) -> Result<PruneSummary, CacheError> {
+        let now = SystemTime::now();
+        let mut entries = self.entries()?;
+        // Oldest entries first
+        entries.sort_by_key(|entry| entry.last_accessed);
+
+        let mut remaining_bytes: u64 = entries.iter().map(|entry| entry.size).sum();
+        let mut summary = PruneSummary::default();
+        for entry in entries {
+            let expired = opts.max_age.is_some_and(|max_age| {
+                now.duration_since(entry.last_accessed)
+                    .is_ok_and(|age| age > max_age)
+            });
+            let over_size = opts
+                .max_size
+                .is_some_and(|max_size| remaining_bytes > max_size);
+
+            // Metadata without an artifact can never be a cache hit
+            if expired || over_size || !entry.has_artifact {
+                remaining_bytes -= entry.size;
+                summary.removed_entries += 1;
+                summary.removed_bytes += entry.size;
+                Self::remove_entry(entry)?;
+            } else {
+                summary.remaining_entries += 1;
+            }
+        }
+        summary.remaining_bytes = remaining_bytes;
+
+        debug!("pruned local cache: {summary:?}");
+        Ok(summary)
+    }
+
+    /// Removes every entry from the cache
+    #[tracing::instrument(skip_all)]
+    pub fn clean(&self) -> Result<PruneSummary, CacheError> {
+        let mut summary = PruneSummary::default();
+        for entry in self.entries()? {
+            summary.removed_entries += 1;
+            summary.removed_bytes += entry.size;
+            Self::remove_entry(entry)?;
+        }
+
+        Ok(summary)
+    }
+
+    fn remove_entry(entry: CacheEntry) -> Result<(), CacheError> {
+        for file in entry.files {
+            match file.remove_file() {
+                Ok(()) => {}
+                // Another process may have beaten us to it
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
         Ok(())
     }
+
+    fn entries(&self) -> Result<Vec<CacheEntry>, CacheError> {
+        let mut entries: BTreeMap<String, CacheEntry> = BTreeMap::new();
+        let mut metadata_files = Vec::new();
+
+        for dir_entry in self.cache_directory.as_std_path().read_dir()? {
+            let dir_entry = dir_entry?;
+            let file_name = dir_entry.file_name();
+            let Some(file_name) = file_name.to_str() else {
+                continue;
+            };
+            let (hash, is_artifact) = if let Some(hash) = file_name.strip_suffix("-meta.json") {
+                (hash, false)
+            } else if let Some(hash) = file_name
+                .strip_suffix(".tar.zst")
+                .or_else(|| file_name.strip_suffix(".tar"))
+            {
+                (hash, true)
+            } else {
+                // Not something we wrote
+                continue;
+            };
+
+            let metadata = dir_entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let path = self.cache_directory.join_component(file_name);
+            if !is_artifact {
+                metadata_files.push((hash.to_string(), path.clone()));
+            }
+
+            let entry = entries
+                .entry(hash.to_string())
+                .or_insert_with(|| CacheEntry {
+                    hash: hash.to_string(),
+                    files: Vec::new(),
+                    size: 0,
+                    has_artifact: false,
+                    duration: 0,
+                    last_accessed: UNIX_EPOCH,
+                });
+            entry.files.push(path);
+            entry.size += metadata.len();
+            entry.has_artifact |= is_artifact;
+            if let Ok(modified) = metadata.modified() {
+                entry.last_accessed = entry.last_accessed.max(modified);
+            }
+        }
+
+        // Prefer the access time recorded in the metadata over the modification
+        // time, since restoring an artifact doesn't modify it.
+        for (hash, path) in metadata_files {
+            let (Some(entry), Ok(meta)) = (entries.get_mut(&hash), CacheMetadata::read(&path))
+            else {
+                continue;
+            };
+            entry.duration = meta.duration;
+            if let Some(last_accessed) = meta.last_accessed() {
+                entry.last_accessed = last_accessed;
+            }
+        }
+
+        Ok(entries.into_values().collect())
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::assert_matches::assert_matches;
+
     use anyhow::Result;
     use futures::future::try_join_all;
     use tempfile::tempdir;
+    use test_case::test_case;
     use turbopath::AnchoredSystemPath;
     use turborepo_analytics::start_analytics;
     use turborepo_api_client::{APIAuth, APIClient};
     use turborepo_vercel_api_mock::start_test_server;
 
     use super::*;
-    use crate::test_cases::{get_test_cases, validate_analytics, TestCase};
+    use crate::{
+        cache_archive::content_hash,
+        test_cases::{get_test_cases, validate_analytics, TestCase},
+    };
+
+    #[test_case("1024", Some(1024) ; "bytes")]
+    #[test_case("10kb", Some(10 * 1024) ; "kilobytes")]
+    #[test_case("500 MB", Some(500 * 1024 * 1024) ; "megabytes with space")]
+    #[test_case("2GiB", Some(2 * 1024 * 1024 * 1024) ; "gibibytes")]
+    #[test_case("GB", None ; "missing value")]
+    #[test_case("10 parsecs", None ; "unknown unit")]
+    fn test_parse_size(input: &str, expected: Option<u64>) {
+        assert_eq!(EvictionOpts::parse_size(input), expected);
+    }
+
+    #[test_case("90", Some(Duration::from_secs(90)) ; "seconds")]
+    #[test_case("30m", Some(Duration::from_secs(30 * 60)) ; "minutes")]
+    #[test_case("7d", Some(Duration::from_secs(7 * 24 * 60 * 60)) ; "days")]
+    #[test_case("-1d", None ; "negative")]
+    #[test_case("1y", None ; "unknown unit")]
+    fn test_parse_age(input: &str, expected: Option<Duration>) {
+        assert_eq!(EvictionOpts::parse_age(input), expected);
+    }
+
+    // Writes an entry with a fixed size artifact that was last used `age` ago
+    fn put_entry(cache: &FSCache, hash: &str, age: Duration) -> Result<()> {
+        cache
+            .cache_directory
+            .join_component(&format!("{hash}.tar.zst"))
+            .create_with_contents(vec![0; 100])?;
+        let last_accessed = SystemTime::now() - age;
+        CacheMetadata {
+            hash: hash.to_string(),
+            duration: 0,
+            last_accessed: Some(last_accessed.duration_since(UNIX_EPOCH)?.as_millis() as u64),
+            sha: None,
+            tag: None,
+        }
+        .write(
+            &cache
+                .cache_directory
+                .join_component(&format!("{hash}-meta.json")),
+        )?;
+        Ok(())
+    }
+
+    fn cached_hashes(cache: &FSCache) -> Result<Vec<String>> {
+        let mut hashes = Vec::new();
+        for entry in cache.cache_directory.as_std_path().read_dir()? {
+            let file_name = entry?.file_name().into_string().unwrap();
+            if let Some(hash) = file_name.strip_suffix(".tar.zst") {
+                hashes.push(hash.to_string());
+            }
+        }
+        hashes.sort();
+        Ok(hashes)
+    }
+
+    #[test]
+    fn test_prune_max_age() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let cache = FSCache::new(None, repo_root_path, None)?;
+
+        put_entry(&cache, "old", Duration::from_secs(60 * 60))?;
+        put_entry(&cache, "new", Duration::from_secs(60))?;
+
+        let summary = cache.prune(&EvictionOpts {
+            max_size: None,
+            max_age: Some(Duration::from_secs(30 * 60)),
+        })?;
+
+        assert_eq!(summary.removed_entries, 1);
+        assert_eq!(summary.remaining_entries, 1);
+        assert_eq!(cached_hashes(&cache)?, vec!["new"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_max_size_evicts_least_recently_used() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let cache = FSCache::new(None, repo_root_path, None)?;
+
+        put_entry(&cache, "a", Duration::from_secs(30))?;
+        put_entry(&cache, "b", Duration::from_secs(20))?;
+        put_entry(&cache, "c", Duration::from_secs(10))?;
+        // Using `a` makes `b` the least recently used entry
+        CacheMetadata {
+            hash: "a".to_string(),
+            duration: 0,
+            last_accessed: Some(now_millis()),
+            sha: None,
+            tag: None,
+        }
+        .write(&cache.cache_directory.join_component("a-meta.json"))?;
+
+        let entry_size = cache
+            .entries()?
+            .iter()
+            .map(|entry| entry.size)
+            .max()
+            .unwrap();
+        let summary = cache.prune(&EvictionOpts {
+            max_size: Some(2 * entry_size),
+            max_age: None,
+        })?;
+
+        assert_eq!(summary.removed_entries, 1);
+        assert!(summary.remaining_bytes <= 2 * entry_size);
+        assert_eq!(cached_hashes(&cache)?, vec!["a", "c"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let cache = FSCache::new(None, repo_root_path, None)?;
+
+        put_entry(&cache, "a", Duration::from_secs(10))?;
+        put_entry(&cache, "b", Duration::from_secs(10))?;
+
+        let summary = cache.clean()?;
+
+        assert_eq!(summary.removed_entries, 2);
+        assert!(cached_hashes(&cache)?.is_empty());
+        assert!(cache
+            .cache_directory
+            .as_std_path()
+            .read_dir()?
+            .next()
+            .is_none());
+        Ok(())
+    }
+
+    // Puts an artifact containing a single file
+    fn put_artifact(cache: &FSCache, repo_root: &AbsoluteSystemPath, hash: &str) -> Result<()> {
+        let file = AnchoredSystemPathBuf::from_raw("dist/out.txt")?;
+        let file_path = repo_root.resolve(&file);
+        file_path.ensure_dir()?;
+        file_path.create_with_contents("output")?;
+        cache.put(repo_root, hash, &[file], 100)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let cache = FSCache::new(None, repo_root_path, None)?;
+
+        put_artifact(&cache, repo_root_path, "abc123")?;
+
+        let items = cache.list()?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].hash, "abc123");
+        assert_eq!(items[0].duration, 100);
+        assert_eq!(cache.item("abc123")?, Some(items[0].clone()));
+
+        assert_eq!(
+            cache.files("abc123")?,
+            Some(vec![ArchiveEntry {
+                path: "dist/out.txt".to_string(),
+                size: 6,
+            }])
+        );
+
+        assert_eq!(
+            cache.file_hashes("abc123")?,
+            Some(BTreeMap::from([(
+                "dist/out.txt".to_string(),
+                content_hash("output".as_bytes())?
+            )]))
+        );
+
+        assert!(cache.item("missing")?.is_none());
+        assert!(cache.files("missing")?.is_none());
+        assert!(cache.file_hashes("missing")?.is_none());
+        assert!(cache.item("../abc123")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let signer =
+            || ArtifactSignatureAuthenticator::new(b"my-team".to_vec(), Some(b"secret".to_vec()));
+        let cache = FSCache::new(None, repo_root_path, None)?.with_signer_verifier(signer());
+
+        put_artifact(&cache, repo_root_path, "signed")?;
+        assert_eq!(cache.verify("signed")?, Some(Verification::Valid));
+        assert_eq!(cache.verify("missing")?, None);
+
+        let other_key = FSCache::new(None, repo_root_path, None)?.with_signer_verifier(
+            ArtifactSignatureAuthenticator::new(b"my-team".to_vec(), Some(b"other".to_vec())),
+        );
+        assert_eq!(
+            other_key.verify("signed")?,
+            Some(Verification::InvalidSignature)
+        );
+
+        let unsigned = FSCache::new(None, repo_root_path, None)?;
+        put_artifact(&unsigned, repo_root_path, "unsigned")?;
+        assert_eq!(unsigned.verify("unsigned")?, Some(Verification::Valid));
+        assert_eq!(
+            cache.verify("unsigned")?,
+            Some(Verification::MissingSignature)
+        );
+
+        // An extra empty zstd frame changes the artifact without making it
+        // unreadable
+        let artifact = cache.cache_directory.join_component("signed.tar.zst");
+        let mut body = artifact.read()?;
+        body.extend(zstd::encode_all(std::io::empty(), 0)?);
+        artifact.create_with_contents(body)?;
+        assert_eq!(
+            unsigned.verify("signed")?,
+            Some(Verification::ChecksumMismatch)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_import() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let cache = FSCache::new(None, repo_root_path, None)?;
+        put_artifact(&cache, repo_root_path, "a")?;
+        put_artifact(&cache, repo_root_path, "b")?;
+
+        let mut bundle = Vec::new();
+        assert_eq!(cache.export(&["b".to_string()], &mut bundle)?, vec!["b"]);
+        assert_matches!(
+            cache.export(&["missing".to_string()], Vec::new()),
+            Err(CacheError::ArtifactNotFound(_))
+        );
+
+        let other_root = tempdir()?;
+        let other_root_path = AbsoluteSystemPath::from_std_path(other_root.path())?;
+        let other = FSCache::new(None, other_root_path, None)?;
+        assert_eq!(other.import(bundle.as_slice(), false)?, vec!["b"]);
+        assert_eq!(other.verify("b")?, Some(Verification::Valid));
+        assert_eq!(other.list()?.len(), 1);
+        // Already cached artifacts are skipped
+        assert!(other.import(bundle.as_slice(), false)?.is_empty());
+        assert_eq!(other.import(bundle.as_slice(), true)?, vec!["b"]);
+
+        let mut all = Vec::new();
+        assert_eq!(cache.export(&[], &mut all)?, vec!["a", "b"]);
+
+        let mut invalid = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        invalid.append_data(&mut header, "evil.sh", std::io::empty())?;
+        let invalid = invalid.into_inner()?;
+        assert_matches!(
+            other.import(invalid.as_slice(), false),
+            Err(CacheError::InvalidBundleEntry(_))
+        );
+        Ok(())
+    }
 
     #[tokio::test]
     async fn test_fs_cache() -> Result<()> {
@@ -5,20 +5,23 @@ use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{
     analytics::{self, AnalyticsEvent},
-    APIAuth, APIClient, CacheClient, Response,
+    APIAuth, APIClient,
 };
 
 use crate::{
     cache_archive::{CacheReader, CacheWriter},
+    remote::{
+        DirectoryBackend, HttpBackend, RemoteArtifact, RemoteCacheBackend, RemoteCacheBackendOpts,
+        S3Credentials, VercelBackend,
+    },
     signature_authentication::ArtifactSignatureAuthenticator,
     CacheError, CacheHitMetadata, CacheOpts, CacheSource,
 };
 
 pub struct HTTPCache {
-    client: APIClient,
+    backend: Box<dyn RemoteCacheBackend>,
     signer_verifier: Option<ArtifactSignatureAuthenticator>,
     repo_root: AbsoluteSystemPathBuf,
-    api_auth: APIAuth,
     analytics_recorder: Option<AnalyticsSender>,
 }
 
@@ -30,6 +33,83 @@ impl HTTPCache {
         repo_root: AbsoluteSystemPathBuf,
         api_auth: APIAuth,
         analytics_recorder: Option<AnalyticsSender>,
+    ) -> HTTPCache {
+        let team_id = api_auth.team_id.clone();
+        Self::with_backend(
+            Box::new(VercelBackend::new(client, api_auth)),
+            opts,
+            repo_root,
+            team_id.as_deref(),
+            analytics_recorder,
+        )
+    }
+
+    /// Creates a remote cache for the configured backend. Returns `None` if
+    /// the Vercel backend is configured but there are no credentials for it,
+    /// the other backends bring their own.
+    #[tracing::instrument(skip_all)]
+    pub fn from_opts(
+        opts: &CacheOpts,
+        repo_root: &AbsoluteSystemPath,
+        api_client: APIClient,
+        api_auth: Option<APIAuth>,
+        analytics_recorder: Option<AnalyticsSender>,
+    ) -> Result<Option<HTTPCache>, CacheError> {
+        let backend: Box<dyn RemoteCacheBackend> = match &opts.remote_cache_backend {
+            RemoteCacheBackendOpts::Vercel => {
+                return Ok(api_auth.map(|api_auth| {
+                    HTTPCache::new(
+                        api_client,
+                        opts,
+                        repo_root.to_owned(),
+                        api_auth,
+                        analytics_recorder,
+                    )
+                }));
+            }
+            RemoteCacheBackendOpts::S3 {
+                endpoint,
+                bucket,
+                region,
+                prefix,
+                timeout,
+            } => Box::new(HttpBackend::s3(
+                endpoint.as_deref(),
+                bucket,
+                region,
+                prefix.as_deref(),
+                *timeout,
+                S3Credentials::from_env()?,
+            )?),
+            RemoteCacheBackendOpts::Directory { path } => {
+                Box::new(DirectoryBackend::new(path.clone())?)
+            }
+            RemoteCacheBackendOpts::Http {
+                url,
+                token,
+                timeout,
+            } => Box::new(HttpBackend::new(url, token.clone(), *timeout)?),
+        };
+
+        let team_id = api_auth.and_then(|api_auth| api_auth.team_id);
+        Ok(Some(HTTPCache::with_backend(
+            backend,
+            opts,
+            repo_root.to_owned(),
+            team_id.as_deref(),
+            analytics_recorder,
+        )))
+    }
+
+    /// Creates a remote cache that stores artifacts in the given backend.
+    /// `team_id` is used when signing artifacts.
+    #[tracing::instrument(skip_all)]
+    pub fn with_backend(
+        backend: Box<dyn RemoteCacheBackend>,
+        opts: &CacheOpts,
+        repo_root: AbsoluteSystemPathBuf,
+        team_id: Option<&str>,
+        analytics_recorder: Option<AnalyticsSender>,
     ) -> HTTPCache {
         let signer_verifier = if opts
             .remote_cache_opts
@@ -37,12 +117,7 @@ impl HTTPCache {
             .map_or(false, |remote_cache_opts| remote_cache_opts.signature)
         {
             Some(ArtifactSignatureAuthenticator {
-                team_id: api_auth
-                    .team_id
-                    .as_deref()
-                    .unwrap_or_default()
-                    .as_bytes()
-                    .to_vec(),
+                team_id: team_id.unwrap_or_default().as_bytes().to_vec(),
                 secret_key_override: None,
             })
         } else {
@@ -50,10 +125,9 @@ impl HTTPCache {
         };
 
         HTTPCache {
-            client,
+            backend,
             signer_verifier,
             repo_root,
-            api_auth,
             analytics_recorder,
         }
     }
@@ -75,19 +149,7 @@ impl HTTPCache {
             .map(|signer| signer.generate_tag(hash.as_bytes(), &artifact_body))
             .transpose()?;
 
-        self.client
-            .put_artifact(
-                hash,
-                &artifact_body,
-                duration,
-                tag.as_deref(),
-                &self.api_auth.token,
-                self.api_auth.team_id.as_deref(),
-                self.api_auth.team_slug.as_deref(),
-            )
-            .await?;
-
-        Ok(())
+        self.backend.put(hash, artifact_body, duration, tag).await
     }
 
     #[tracing::instrument(skip_all)]
@@ -107,41 +169,16 @@ impl HTTPCache {
 
     #[tracing::instrument(skip_all)]
     pub async fn exists(&self, hash: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
-        let Some(response) = self
-            .client
-            .artifact_exists(
-                hash,
-                &self.api_auth.token,
-                self.api_auth.team_id.as_deref(),
-                self.api_auth.team_slug.as_deref(),
-            )
-            .await?
-        else {
+        let Some(duration) = self.backend.exists(hash).await? else {
             return Ok(None);
         };
 
-        let duration = Self::get_duration_from_response(&response)?;
-
         Ok(Some(CacheHitMetadata {
             source: CacheSource::Remote,
             time_saved: duration,
         }))
     }
 
-    fn get_duration_from_response(response: &Response) -> Result<u64, CacheError> {
-        if let Some(duration_value) = response.headers().get("x-artifact-duration") {
-            let duration = duration_value
-                .to_str()
-                .map_err(|_| CacheError::InvalidDuration(Backtrace::capture()))?;
-
-            duration
-                .parse::<u64>()
-                .map_err(|_| CacheError::InvalidDuration(Backtrace::capture()))
-        } else {
-            Ok(0)
-        }
-    }
-
     fn log_fetch(&self, event: analytics::CacheEvent, hash: &str, duration: u64) {
         // If analytics fails to record, it's not worth failing the cache
         if let Some(analytics_recorder) = &self.analytics_recorder {
@@ -162,54 +199,24 @@ impl HTTPCache {
         &self,
         hash: &str,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        let Some(response) = self
-            .client
-            .fetch_artifact(
-                hash,
-                &self.api_auth.token,
-                self.api_auth.team_id.as_deref(),
-                self.api_auth.team_slug.as_deref(),
-            )
-            .await?
+        let Some(RemoteArtifact {
+            body,
+            duration,
+            tag,
+        }) = self.backend.fetch(hash).await?
         else {
             self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
             return Ok(None);
         };
 
-        let duration = Self::get_duration_from_response(&response)?;
-
-        let body = if let Some(signer_verifier) = &self.signer_verifier {
-            let expected_tag = response
-                .headers()
-                .get("x-artifact-tag")
-                .ok_or(CacheError::ArtifactTagMissing(Backtrace::capture()))?;
-
-            let expected_tag = expected_tag
-                .to_str()
-                .map_err(|_| CacheError::InvalidTag(Backtrace::capture()))?
-                .to_string();
-
-            let body = response.bytes().await.map_err(|e| {
-                CacheError::ApiClientError(
-                    Box::new(turborepo_api_client::Error::ReqwestError(e)),
-                    Backtrace::capture(),
-                )
-            })?;
+        if let Some(signer_verifier) = &self.signer_verifier {
+            let expected_tag = tag.ok_or(CacheError::ArtifactTagMissing(Backtrace::capture()))?;
             let is_valid = signer_verifier.validate(hash.as_bytes(), &body, &expected_tag)?;
 
             if !is_valid {
                 return Err(CacheError::InvalidTag(Backtrace::capture()));
             }
-
-            body
-        } else {
-            response.bytes().await.map_err(|e| {
-                CacheError::ApiClientError(
-                    Box::new(turborepo_api_client::Error::ReqwestError(e)),
-                    Backtrace::capture(),
-                )
-            })?
-        };
+        }
 
         let files = Self::restore_tar(&self.repo_root, &body)?;
 
@@ -245,6 +252,7 @@ mod test {
 
     use crate::{
         http::{APIAuth, HTTPCache},
+        remote::DirectoryBackend,
         test_cases::{get_test_cases, validate_analytics, TestCase},
         CacheOpts, CacheSource,
     };
@@ -326,4 +334,49 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_http_cache_with_backend() -> Result<()> {
+        let shared_dir = tempdir()?;
+        let shared_path = AbsoluteSystemPathBuf::try_from(shared_dir.path())?;
+
+        for test_case in get_test_cases() {
+            let repo_root = tempdir()?;
+            let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+            test_case.initialize(&repo_root_path)?;
+
+            let cache = HTTPCache::with_backend(
+                Box::new(DirectoryBackend::new(shared_path.clone())?),
+                &CacheOpts::default(),
+                repo_root_path.clone(),
+                None,
+                None,
+            );
+
+            assert!(cache.fetch(test_case.hash).await?.is_none());
+
+            let anchored_files: Vec<_> = test_case
+                .files
+                .iter()
+                .map(|f| f.path().to_owned())
+                .collect();
+            cache
+                .put(
+                    &repo_root_path,
+                    test_case.hash,
+                    &anchored_files,
+                    test_case.duration,
+                )
+                .await?;
+
+            let cache_response = cache.exists(test_case.hash).await?.unwrap();
+            assert_eq!(cache_response.time_saved, test_case.duration);
+            assert_eq!(cache_response.source, CacheSource::Remote);
+
+            let (_, received_files) = cache.fetch(test_case.hash).await?.unwrap();
+            assert_eq!(received_files, anchored_files);
+        }
+
+        Ok(())
+    }
 }
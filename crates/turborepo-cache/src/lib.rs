@@ -14,6 +14,8 @@ pub mod http;
 /// A wrapper that allows reads and writes from the file system and remote
 /// cache.
 mod multiplexer;
+/// Storage backends for the remote cache
+pub mod remote;
 /// Cache signature authentication lets users provide a private key to sign
 /// their cache payloads.
 pub mod signature_authentication;
@@ -27,7 +29,9 @@ use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::signature_authentication::SignatureError;
+use crate::{
+    fs::EvictionOpts, remote::RemoteCacheBackendOpts, signature_authentication::SignatureError,
+};
 
 #[derive(Debug, Error)]
 pub enum CacheError {
@@ -44,6 +48,17 @@ pub enum CacheError {
     InvalidFilePath(String, #[backtrace] Backtrace),
     #[error("failed to contact remote cache: {0}")]
     ApiClientError(Box<turborepo_api_client::Error>, #[backtrace] Backtrace),
+    #[error("failed to contact remote cache: {0}")]
+    RemoteBackendError(Box<reqwest::Error>, #[backtrace] Backtrace),
+    #[error("remote cache responded with unexpected status {0}")]
+    UnexpectedBackendStatus(reqwest::StatusCode, #[backtrace] Backtrace),
+    #[error("invalid remote cache URL: {0}")]
+    InvalidBackendUrl(String),
+    #[error(
+        "the S3 remote cache requires the AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY environment \
+         variables to be set"
+    )]
+    MissingS3Credentials,
     #[error("signing artifact failed: {0}")]
     SignatureError(#[from] SignatureError, #[backtrace] Backtrace),
     #[error("invalid duration")]
@@ -72,6 +87,10 @@ pub enum CacheError {
     InvalidMetadata(serde_json::Error, #[backtrace] Backtrace),
     #[error("Failed to write cache metadata file")]
     MetadataWriteFailure(serde_json::Error, #[backtrace] Backtrace),
+    #[error("artifact {0} not found in the local cache")]
+    ArtifactNotFound(String),
+    #[error("invalid entry in cache bundle: {0}")]
+    InvalidBundleEntry(String),
     #[error("Unable to perform write as cache is shutting down")]
     CacheShuttingDown,
     #[error("Unable to determine config cache base")]
@@ -86,6 +105,12 @@ impl From<turborepo_api_client::Error> for CacheError {
     }
 }
 
+impl From<reqwest::Error> for CacheError {
+    fn from(value: reqwest::Error) -> Self {
+        CacheError::RemoteBackendError(Box::new(value), Backtrace::capture())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum CacheSource {
     Local,
@@ -98,7 +123,7 @@ pub struct CacheHitMetadata {
     pub time_saved: u64,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct CacheOpts {
     pub override_dir: Option<Utf8PathBuf>,
     pub remote_cache_read_only: bool,
@@ -106,6 +131,8 @@ pub struct CacheOpts {
     pub skip_filesystem: bool,
     pub workers: u32,
     pub remote_cache_opts: Option<RemoteCacheOpts>,
+    pub eviction_opts: EvictionOpts,
+    pub remote_cache_backend: RemoteCacheBackendOpts,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
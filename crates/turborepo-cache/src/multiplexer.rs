@@ -5,7 +5,12 @@ use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{APIAuth, APIClient};
 
-use crate::{fs::FSCache, http::HTTPCache, CacheError, CacheHitMetadata, CacheOpts};
+use crate::{
+    fs::{EvictionOpts, FSCache, PruneSummary},
+    http::HTTPCache,
+    signature_authentication::ArtifactSignatureAuthenticator,
+    CacheError, CacheHitMetadata, CacheOpts,
+};
 
 pub struct CacheMultiplexer {
     // We use an `AtomicBool` instead of removing the cache because that would require
@@ -17,6 +22,7 @@ pub struct CacheMultiplexer {
     // being read-only
     should_print_skipping_remote_put: AtomicBool,
     remote_cache_read_only: bool,
+    eviction_opts: EvictionOpts,
     fs: Option<FSCache>,
     http: Option<HTTPCache>,
 }
@@ -40,33 +46,45 @@ impl CacheMultiplexer {
             warn!("no caches are enabled");
         }
 
+        let signature = opts
+            .remote_cache_opts
+            .as_ref()
+            .map_or(false, |remote_cache_opts| remote_cache_opts.signature);
         let fs_cache = use_fs_cache
             .then(|| {
-                FSCache::new(
+                let fs_cache = FSCache::new(
                     opts.override_dir.as_deref(),
                     repo_root,
                     analytics_recorder.clone(),
-                )
+                )?;
+                // Sign local artifacts the same way as remote ones so they can be
+                // verified later.
+                Ok::<_, CacheError>(if signature {
+                    let team_id = api_auth
+                        .as_ref()
+                        .and_then(|api_auth| api_auth.team_id.as_deref())
+                        .unwrap_or_default();
+                    fs_cache.with_signer_verifier(ArtifactSignatureAuthenticator::new(
+                        team_id.as_bytes().to_vec(),
+                        None,
+                    ))
+                } else {
+                    fs_cache
+                })
             })
             .transpose()?;
 
-        let http_cache = use_http_cache
-            .then_some(api_auth)
-            .flatten()
-            .map(|api_auth| {
-                HTTPCache::new(
-                    api_client,
-                    opts,
-                    repo_root.to_owned(),
-                    api_auth,
-                    analytics_recorder.clone(),
-                )
-            });
+        let http_cache = if use_http_cache {
+            HTTPCache::from_opts(opts, repo_root, api_client, api_auth, analytics_recorder)?
+        } else {
+            None
+        };
 
         Ok(CacheMultiplexer {
             should_print_skipping_remote_put: AtomicBool::new(true),
             should_use_http_cache: AtomicBool::new(http_cache.is_some()),
             remote_cache_read_only: opts.remote_cache_read_only,
+            eviction_opts: opts.eviction_opts,
             fs: fs_cache,
             http: http_cache,
         })
@@ -164,6 +182,16 @@ impl CacheMultiplexer {
         Ok(None)
     }
 
+    // Applies the eviction limits to the local cache. Returns `None` if there
+    // is no local cache or no limits are configured.
+    #[tracing::instrument(skip_all)]
+    pub fn prune(&self) -> Result<Option<PruneSummary>, CacheError> {
+        match &self.fs {
+            Some(fs) if self.eviction_opts.has_limits() => fs.prune(&self.eviction_opts).map(Some),
+            _ => Ok(None),
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn exists(&self, key: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
         if let Some(fs) = &self.fs {
@@ -0,0 +1,151 @@
+use std::{
+    backtrace::Backtrace,
+    io::ErrorKind,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use async_trait::async_trait;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+
+use super::{artifact_key, metadata_key, ArtifactMetadata, RemoteArtifact, RemoteCacheBackend};
+use crate::{fs::is_valid_hash, CacheError};
+
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Stores artifacts in a directory, such as a network mount shared between
+/// machines.
+pub struct DirectoryBackend {
+    root: AbsoluteSystemPathBuf,
+}
+
+impl DirectoryBackend {
+    pub fn new(root: AbsoluteSystemPathBuf) -> Result<Self, CacheError> {
+        root.create_dir_all()?;
+        Ok(Self { root })
+    }
+
+    // Other machines may be reading the directory at the same time, so files
+    // are written under a temporary name and then renamed into place.
+    async fn write_atomic(
+        &self,
+        path: &AbsoluteSystemPath,
+        contents: &[u8],
+    ) -> Result<(), CacheError> {
+        let temp_path = self.root.join_component(&format!(
+            ".{}.{}.{}.tmp",
+            path.file_name().unwrap_or_default(),
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        tokio::fs::write(temp_path.as_std_path(), contents).await?;
+        if let Err(err) = tokio::fs::rename(temp_path.as_std_path(), path.as_std_path()).await {
+            let _ = tokio::fs::remove_file(temp_path.as_std_path()).await;
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    async fn read_metadata(&self, hash: &str) -> Result<Option<ArtifactMetadata>, CacheError> {
+        if !is_valid_hash(hash) {
+            return Ok(None);
+        }
+
+        let path = self.root.join_component(&metadata_key(hash));
+        match tokio::fs::read(path.as_std_path()).await {
+            Ok(contents) => {
+                Ok(Some(serde_json::from_slice(&contents).map_err(|err| {
+                    CacheError::InvalidMetadata(err, Backtrace::capture())
+                })?))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl RemoteCacheBackend for DirectoryBackend {
+    async fn put(
+        &self,
+        hash: &str,
+        body: Vec<u8>,
+        duration: u64,
+        tag: Option<String>,
+    ) -> Result<(), CacheError> {
+        if !is_valid_hash(hash) {
+            return Err(CacheError::InvalidFilePath(
+                hash.to_string(),
+                Backtrace::capture(),
+            ));
+        }
+
+        let metadata = serde_json::to_vec(&ArtifactMetadata { duration, tag })
+            .map_err(|err| CacheError::MetadataWriteFailure(err, Backtrace::capture()))?;
+        self.write_atomic(&self.root.join_component(&artifact_key(hash)), &body)
+            .await?;
+        self.write_atomic(&self.root.join_component(&metadata_key(hash)), &metadata)
+            .await
+    }
+
+    async fn exists(&self, hash: &str) -> Result<Option<u64>, CacheError> {
+        Ok(self
+            .read_metadata(hash)
+            .await?
+            .map(|metadata| metadata.duration))
+    }
+
+    async fn fetch(&self, hash: &str) -> Result<Option<RemoteArtifact>, CacheError> {
+        let Some(ArtifactMetadata { duration, tag }) = self.read_metadata(hash).await? else {
+            return Ok(None);
+        };
+
+        let path = self.root.join_component(&artifact_key(hash));
+        match tokio::fs::read(path.as_std_path()).await {
+            Ok(body) => Ok(Some(RemoteArtifact {
+                body: body.into(),
+                duration,
+                tag,
+            })),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_directory_backend() -> Result<()> {
+        let dir = tempdir()?;
+        let root = AbsoluteSystemPathBuf::try_from(dir.path())?.join_component("shared");
+        let backend = DirectoryBackend::new(root.clone())?;
+
+        assert_eq!(backend.exists("abc123").await?, None);
+        assert!(backend.fetch("abc123").await?.is_none());
+
+        backend
+            .put("abc123", b"artifact".to_vec(), 42, Some("tag".to_string()))
+            .await?;
+        assert_eq!(backend.exists("abc123").await?, Some(42));
+        let artifact = backend.fetch("abc123").await?.unwrap();
+        assert_eq!(&artifact.body[..], b"artifact");
+        assert_eq!(artifact.duration, 42);
+        assert_eq!(artifact.tag.as_deref(), Some("tag"));
+
+        // An artifact without its metadata hasn't finished uploading
+        root.join_component("def456.tar.zst")
+            .create_with_contents("partial")?;
+        assert_eq!(backend.exists("def456").await?, None);
+
+        assert!(backend.put("../escape", Vec::new(), 0, None).await.is_err());
+        assert_eq!(backend.exists("../escape").await?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+use std::{backtrace::Backtrace, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::{header::CONTENT_TYPE, Method, Response, StatusCode, Url};
+
+use super::{
+    artifact_key, metadata_key,
+    s3::{uri_encode, S3Credentials, S3Signer},
+    ArtifactMetadata, RemoteArtifact, RemoteCacheBackend,
+};
+use crate::{fs::is_valid_hash, CacheError};
+
+enum Auth {
+    None,
+    Bearer(String),
+    S3(S3Signer),
+}
+
+/// Stores artifacts on a server that supports `PUT` and `GET`, such as a
+/// WebDAV server or an S3-compatible object store.
+pub struct HttpBackend {
+    client: reqwest::Client,
+    base_url: String,
+    auth: Auth,
+}
+
+impl HttpBackend {
+    pub fn new(url: &str, token: Option<String>, timeout: u64) -> Result<Self, CacheError> {
+        Self::with_auth(url, token.map_or(Auth::None, Auth::Bearer), timeout)
+    }
+
+    /// Uses path-style URLs, which are supported by most S3-compatible
+    /// stores. Defaults to AWS if no endpoint is given.
+    pub fn s3(
+        endpoint: Option<&str>,
+        bucket: &str,
+        region: &str,
+        prefix: Option<&str>,
+        timeout: u64,
+        credentials: S3Credentials,
+    ) -> Result<Self, CacheError> {
+        let endpoint = endpoint.map_or_else(
+            || format!("https://s3.{region}.amazonaws.com"),
+            |endpoint| endpoint.to_string(),
+        );
+        let mut url = format!("{}/{}", endpoint.trim_end_matches('/'), uri_encode(bucket));
+        for segment in prefix
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+        {
+            url.push('/');
+            url.push_str(&uri_encode(segment));
+        }
+
+        Self::with_auth(
+            &url,
+            Auth::S3(S3Signer::new(credentials, region.to_string())),
+            timeout,
+        )
+    }
+
+    fn with_auth(url: &str, auth: Auth, timeout: u64) -> Result<Self, CacheError> {
+        let base_url = url.trim_end_matches('/').to_string();
+        Url::parse(&base_url).map_err(|_| CacheError::InvalidBackendUrl(url.to_string()))?;
+
+        let mut client = reqwest::Client::builder();
+        // A timeout of 0 means no timeout
+        if timeout != 0 {
+            client = client.timeout(Duration::from_secs(timeout));
+        }
+
+        Ok(Self {
+            client: client.build()?,
+            base_url,
+            auth,
+        })
+    }
+
+    // Returns `None` if the object doesn't exist
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<Option<Response>, CacheError> {
+        let url = Url::parse(&format!("{}/{}", self.base_url, uri_encode(key)))
+            .map_err(|_| CacheError::InvalidBackendUrl(self.base_url.clone()))?;
+        let mut request = self.client.request(method.clone(), url.clone());
+
+        match &self.auth {
+            Auth::None => {}
+            Auth::Bearer(token) => request = request.bearer_auth(token),
+            Auth::S3(signer) => {
+                let payload = body.as_deref().unwrap_or_default();
+                for (name, value) in
+                    signer.sign(method.as_str(), &url, &[], payload, chrono::Utc::now())
+                {
+                    request = request.header(name, value);
+                }
+            }
+        }
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, "application/octet-stream")
+                .body(body);
+        }
+
+        let response = request.send().await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response)),
+            status => Err(CacheError::UnexpectedBackendStatus(
+                status,
+                Backtrace::capture(),
+            )),
+        }
+    }
+
+    async fn read_metadata(&self, hash: &str) -> Result<Option<ArtifactMetadata>, CacheError> {
+        if !is_valid_hash(hash) {
+            return Ok(None);
+        }
+        let Some(response) = self.send(Method::GET, &metadata_key(hash), None).await? else {
+            return Ok(None);
+        };
+
+        let body = response.bytes().await?;
+        Ok(Some(serde_json::from_slice(&body).map_err(|err| {
+            CacheError::InvalidMetadata(err, Backtrace::capture())
+        })?))
+    }
+}
+
+#[async_trait]
+impl RemoteCacheBackend for HttpBackend {
+    async fn put(
+        &self,
+        hash: &str,
+        body: Vec<u8>,
+        duration: u64,
+        tag: Option<String>,
+    ) -> Result<(), CacheError> {
+        if !is_valid_hash(hash) {
+            return Err(CacheError::InvalidFilePath(
+                hash.to_string(),
+                Backtrace::capture(),
+            ));
+        }
+
+        let metadata = serde_json::to_vec(&ArtifactMetadata { duration, tag })
+            .map_err(|err| CacheError::MetadataWriteFailure(err, Backtrace::capture()))?;
+        self.send(Method::PUT, &artifact_key(hash), Some(body))
+            .await?;
+        self.send(Method::PUT, &metadata_key(hash), Some(metadata))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn exists(&self, hash: &str) -> Result<Option<u64>, CacheError> {
+        Ok(self
+            .read_metadata(hash)
+            .await?
+            .map(|metadata| metadata.duration))
+    }
+
+    async fn fetch(&self, hash: &str) -> Result<Option<RemoteArtifact>, CacheError> {
+        let Some(ArtifactMetadata { duration, tag }) = self.read_metadata(hash).await? else {
+            return Ok(None);
+        };
+        let Some(response) = self.send(Method::GET, &artifact_key(hash), None).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(RemoteArtifact {
+            body: response.bytes().await?,
+            duration,
+            tag,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use turborepo_vercel_api_mock::start_artifact_server;
+
+    use super::*;
+
+    async fn round_trip(backend: &HttpBackend) -> Result<()> {
+        assert_eq!(backend.exists("abc123").await?, None);
+        assert!(backend.fetch("abc123").await?.is_none());
+
+        backend
+            .put("abc123", b"artifact".to_vec(), 42, Some("tag".to_string()))
+            .await?;
+        assert_eq!(backend.exists("abc123").await?, Some(42));
+        let artifact = backend.fetch("abc123").await?.unwrap();
+        assert_eq!(&artifact.body[..], b"artifact");
+        assert_eq!(artifact.duration, 42);
+        assert_eq!(artifact.tag.as_deref(), Some("tag"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_http_backend() -> Result<()> {
+        let port = port_scanner::request_open_port().unwrap();
+        let handle = tokio::spawn(start_artifact_server(port));
+
+        let backend = HttpBackend::new(
+            &format!("http://localhost:{port}/webdav/"),
+            Some("my-token".to_string()),
+            10,
+        )?;
+        round_trip(&backend).await?;
+
+        handle.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_s3_backend() -> Result<()> {
+        let port = port_scanner::request_open_port().unwrap();
+        let handle = tokio::spawn(start_artifact_server(port));
+
+        let backend = HttpBackend::s3(
+            Some(&format!("http://localhost:{port}")),
+            "my-bucket",
+            "us-east-1",
+            Some("turbo/cache"),
+            10,
+            S3Credentials {
+                access_key_id: "access-key".to_string(),
+                secret_access_key: "secret-key".to_string(),
+                session_token: None,
+            },
+        )?;
+        round_trip(&backend).await?;
+
+        handle.abort();
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        assert!(HttpBackend::new("not a url", None, 0).is_err());
+    }
+}
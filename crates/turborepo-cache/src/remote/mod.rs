@@ -0,0 +1,93 @@
+// Backends for the remote cache. A backend only moves artifact bytes and
+// their metadata around; `HTTPCache` takes care of creating, signing and
+// restoring the artifacts themselves.
+
+mod directory;
+mod http;
+mod s3;
+mod vercel;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+pub use directory::DirectoryBackend;
+pub use http::HttpBackend;
+pub use s3::{S3Credentials, S3Signer};
+use serde::{Deserialize, Serialize};
+use turbopath::AbsoluteSystemPathBuf;
+pub use vercel::VercelBackend;
+
+use crate::CacheError;
+
+/// An artifact as it is stored in a remote cache
+#[derive(Debug, Clone)]
+pub struct RemoteArtifact {
+    pub body: Bytes,
+    pub duration: u64,
+    pub tag: Option<String>,
+}
+
+#[async_trait]
+pub trait RemoteCacheBackend: Send + Sync {
+    async fn put(
+        &self,
+        hash: &str,
+        body: Vec<u8>,
+        duration: u64,
+        tag: Option<String>,
+    ) -> Result<(), CacheError>;
+
+    /// Returns the duration of the task that produced the artifact, if it
+    /// exists
+    async fn exists(&self, hash: &str) -> Result<Option<u64>, CacheError>;
+
+    async fn fetch(&self, hash: &str) -> Result<Option<RemoteArtifact>, CacheError>;
+}
+
+/// Which backend the remote cache should use
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RemoteCacheBackendOpts {
+    /// The Vercel Remote Cache API, or a self-hosted implementation of it
+    #[default]
+    Vercel,
+    /// An S3-compatible object store, addressed with path-style URLs
+    S3 {
+        endpoint: Option<String>,
+        bucket: String,
+        region: String,
+        prefix: Option<String>,
+        timeout: u64,
+    },
+    /// A directory, usually a shared network mount
+    Directory { path: AbsoluteSystemPathBuf },
+    /// Any server that supports `PUT` and `GET`, such as a WebDAV server
+    Http {
+        url: String,
+        token: Option<String>,
+        timeout: u64,
+    },
+}
+
+impl RemoteCacheBackendOpts {
+    /// Whether the backend needs a linked Vercel team to be usable
+    pub fn requires_login(&self) -> bool {
+        matches!(self, RemoteCacheBackendOpts::Vercel)
+    }
+}
+
+// Backends other than Vercel store each artifact as two objects: the archive
+// itself and a small JSON file with its metadata. The metadata is written last
+// so that its presence means the artifact is complete.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ArtifactMetadata {
+    pub duration: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+pub(crate) fn artifact_key(hash: &str) -> String {
+    format!("{hash}.tar.zst")
+}
+
+pub(crate) fn metadata_key(hash: &str) -> String {
+    format!("{hash}-meta.json")
+}
@@ -0,0 +1,114 @@
+use std::backtrace::Backtrace;
+
+use async_trait::async_trait;
+use turborepo_api_client::{APIAuth, APIClient, CacheClient, Response};
+
+use super::{RemoteArtifact, RemoteCacheBackend};
+use crate::CacheError;
+
+/// Talks to the Vercel Remote Cache API
+pub struct VercelBackend {
+    client: APIClient,
+    api_auth: APIAuth,
+}
+
+impl VercelBackend {
+    pub fn new(client: APIClient, api_auth: APIAuth) -> Self {
+        Self { client, api_auth }
+    }
+
+    fn get_duration_from_response(response: &Response) -> Result<u64, CacheError> {
+        if let Some(duration_value) = response.headers().get("x-artifact-duration") {
+            let duration = duration_value
+                .to_str()
+                .map_err(|_| CacheError::InvalidDuration(Backtrace::capture()))?;
+
+            duration
+                .parse::<u64>()
+                .map_err(|_| CacheError::InvalidDuration(Backtrace::capture()))
+        } else {
+            Ok(0)
+        }
+    }
+}
+
+#[async_trait]
+impl RemoteCacheBackend for VercelBackend {
+    async fn put(
+        &self,
+        hash: &str,
+        body: Vec<u8>,
+        duration: u64,
+        tag: Option<String>,
+    ) -> Result<(), CacheError> {
+        self.client
+            .put_artifact(
+                hash,
+                &body,
+                duration,
+                tag.as_deref(),
+                &self.api_auth.token,
+                self.api_auth.team_id.as_deref(),
+                self.api_auth.team_slug.as_deref(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn exists(&self, hash: &str) -> Result<Option<u64>, CacheError> {
+        let Some(response) = self
+            .client
+            .artifact_exists(
+                hash,
+                &self.api_auth.token,
+                self.api_auth.team_id.as_deref(),
+                self.api_auth.team_slug.as_deref(),
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self::get_duration_from_response(&response)?))
+    }
+
+    async fn fetch(&self, hash: &str) -> Result<Option<RemoteArtifact>, CacheError> {
+        let Some(response) = self
+            .client
+            .fetch_artifact(
+                hash,
+                &self.api_auth.token,
+                self.api_auth.team_id.as_deref(),
+                self.api_auth.team_slug.as_deref(),
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let duration = Self::get_duration_from_response(&response)?;
+        let tag = response
+            .headers()
+            .get("x-artifact-tag")
+            .map(|tag| {
+                tag.to_str()
+                    .map(|tag| tag.to_string())
+                    .map_err(|_| CacheError::InvalidTag(Backtrace::capture()))
+            })
+            .transpose()?;
+
+        let body = response.bytes().await.map_err(|e| {
+            CacheError::ApiClientError(
+                Box::new(turborepo_api_client::Error::ReqwestError(e)),
+                Backtrace::capture(),
+            )
+        })?;
+
+        Ok(Some(RemoteArtifact {
+            body,
+            duration,
+            tag,
+        }))
+    }
+}
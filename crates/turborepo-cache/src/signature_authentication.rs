@@ -6,7 +6,7 @@ use os_str_bytes::OsStringBytes;
 use sha2::Sha256;
 use thiserror::Error;
 
-type HmacSha256 = Hmac<Sha256>;
+pub(crate) type HmacSha256 = Hmac<Sha256>;
 
 #[derive(Debug, Error)]
 pub enum SignatureError {
@@ -59,7 +59,9 @@ impl ArtifactSignatureAuthenticator {
         Ok(metadata)
     }
 
-    fn get_tag_generator(&self, hash: &[u8]) -> Result<HmacSha256, SignatureError> {
+    // Returns a MAC that the artifact body can be fed into incrementally, to be
+    // turned into a tag with `encode_tag`.
+    pub(crate) fn get_tag_generator(&self, hash: &[u8]) -> Result<HmacSha256, SignatureError> {
         let mut mac = HmacSha256::new_from_slice(&self.secret_key()?)?;
         let metadata = self.construct_metadata(hash)?;
 
@@ -90,8 +92,7 @@ impl ArtifactSignatureAuthenticator {
         let mut hmac_ctx = self.get_tag_generator(hash)?;
 
         hmac_ctx.update(artifact_body);
-        let hmac_output = hmac_ctx.finalize();
-        Ok(BASE64_STANDARD.encode(hmac_output.into_bytes()))
+        Ok(encode_tag(hmac_ctx))
     }
 
     #[tracing::instrument(skip_all)]
@@ -111,6 +112,10 @@ impl ArtifactSignatureAuthenticator {
     }
 }
 
+pub(crate) fn encode_tag(hmac_ctx: HmacSha256) -> String {
+    BASE64_STANDARD.encode(hmac_ctx.finalize().into_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -3,7 +3,7 @@
 mod vendor_behavior;
 mod vendors;
 
-use std::{env, sync::OnceLock};
+use std::{env, fs, sync::OnceLock};
 
 use crate::vendors::get_vendors;
 pub use crate::{vendor_behavior::VendorBehavior, vendors::Vendor};
@@ -54,6 +54,48 @@ impl Vendor {
             .unwrap_or_default()
     }
 
+    /// Gets the git ref that the current build should be compared against,
+    /// such as the target branch of a pull request or the commit before a
+    /// push. Returns `None` if the vendor doesn't expose one or we aren't
+    /// running in CI.
+    pub fn get_base_ref() -> Option<String> {
+        Vendor::infer()
+            .and_then(|v| v.base_ref(non_empty_var, |path| fs::read_to_string(path).ok()))
+    }
+
+    // Environment variables and files are looked up through `var` and
+    // `read_file` so that tests don't need to touch the real environment.
+    fn base_ref(
+        &self,
+        var: impl Fn(&str) -> Option<String>,
+        read_file: impl Fn(&str) -> Option<String>,
+    ) -> Option<String> {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        match self.constant {
+            "GITHUB_ACTIONS" => var("GITHUB_BASE_REF").map(remote_branch).or_else(|| {
+                if var("GITHUB_EVENT_NAME").as_deref() != Some("push") {
+                    return None;
+                }
+                let event = read_file(&var("GITHUB_EVENT_PATH")?)?;
+                github_push_before(&event)
+            }),
+            "GITLAB" => var("CI_MERGE_REQUEST_DIFF_BASE_SHA")
+                .or_else(|| var("CI_COMMIT_BEFORE_SHA").filter(|sha| !is_null_sha(sha))),
+            "BUILDKITE" => var("BUILDKITE_PULL_REQUEST_BASE_BRANCH").map(remote_branch),
+            "BITBUCKET" => var("BITBUCKET_PR_DESTINATION_BRANCH").map(remote_branch),
+            "AZURE_PIPELINES" => var("SYSTEM_PULLREQUEST_TARGETBRANCH").map(|branch| {
+                remote_branch(
+                    branch
+                        .strip_prefix("refs/heads/")
+                        .map(|branch| branch.to_string())
+                        .unwrap_or(branch),
+                )
+            }),
+            "VERCEL" => var("VERCEL_GIT_PREVIOUS_SHA"),
+            _ => None,
+        }
+    }
+
     fn infer_inner() -> Option<&'static Vendor> {
         for env in get_vendors() {
             if let Some(eval_env) = &env.eval_env {
@@ -93,8 +135,38 @@ impl Vendor {
     }
 }
 
+/// Reads an environment variable, treating an empty value as unset
+pub fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+// The commit that was checked out before a push, read from the GitHub event
+// payload
+fn github_push_before(event: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(event).ok()?;
+    event
+        .get("before")?
+        .as_str()
+        .filter(|sha| !sha.is_empty() && !is_null_sha(sha))
+        .map(|sha| sha.to_string())
+}
+
+// Vendors use an all zero SHA as the previous commit of the first push of a
+// branch
+fn is_null_sha(sha: &str) -> bool {
+    sha.chars().all(|c| c == '0')
+}
+
+// CI checkouts usually only have the target branch as a remote-tracking branch
+fn remote_branch(branch: String) -> String {
+    format!("origin/{branch}")
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use test_case::test_case;
     use tracing::info;
 
     use super::*;
@@ -204,4 +276,60 @@ mod tests {
             }
         }
     }
+
+    #[test_case("GitHub Actions", &[("GITHUB_BASE_REF", "main")], Some("origin/main") ; "github pull request")]
+    #[test_case("GitHub Actions", &[("GITHUB_BASE_REF", "")], None ; "github empty base")]
+    #[test_case(
+        "GitHub Actions",
+        &[("GITHUB_EVENT_NAME", "push"), ("GITHUB_EVENT_PATH", "event.json")],
+        Some("abc123")
+        ; "github push"
+    )]
+    #[test_case(
+        "GitHub Actions",
+        &[("GITHUB_EVENT_NAME", "workflow_dispatch"), ("GITHUB_EVENT_PATH", "event.json")],
+        None
+        ; "github other event"
+    )]
+    #[test_case(
+        "GitLab CI",
+        &[("CI_COMMIT_BEFORE_SHA", "0000000000000000000000000000000000000000")],
+        None
+        ; "gitlab first push"
+    )]
+    #[test_case("GitLab CI", &[("CI_COMMIT_BEFORE_SHA", "abc123")], Some("abc123") ; "gitlab push")]
+    #[test_case(
+        "GitLab CI",
+        &[("CI_MERGE_REQUEST_DIFF_BASE_SHA", "def456"), ("CI_COMMIT_BEFORE_SHA", "abc123")],
+        Some("def456")
+        ; "gitlab merge request"
+    )]
+    #[test_case(
+        "Azure Pipelines",
+        &[("SYSTEM_PULLREQUEST_TARGETBRANCH", "refs/heads/develop")],
+        Some("origin/develop")
+        ; "azure"
+    )]
+    #[test_case("Vercel", &[("VERCEL_GIT_PREVIOUS_SHA", "def456")], Some("def456") ; "vercel")]
+    #[test_case("AppVeyor", &[("APPVEYOR_REPO_BRANCH", "main")], None ; "unsupported")]
+    fn test_base_ref(vendor: &str, vars: &[(&str, &str)], expected: Option<&str>) {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        let base_ref = get_vendor(vendor).base_ref(
+            |name| vars.get(name).map(|value| value.to_string()),
+            |path| (path == "event.json").then(|| r#"{"before": "abc123"}"#.to_string()),
+        );
+        assert_eq!(base_ref.as_deref(), expected);
+    }
+
+    #[test_case(r#"{"before": "abc123", "after": "def456"}"#, Some("abc123") ; "push")]
+    #[test_case(
+        r#"{"before": "0000000000000000000000000000000000000000"}"#,
+        None
+        ; "new branch"
+    )]
+    #[test_case(r#"{"pull_request": {}}"#, None ; "missing")]
+    #[test_case("not json", None ; "invalid")]
+    fn test_github_push_before(event: &str, expected: Option<&str>) {
+        assert_eq!(github_push_before(event).as_deref(), expected);
+    }
 }
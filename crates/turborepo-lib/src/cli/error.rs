@@ -5,7 +5,7 @@ use thiserror::Error;
 use turborepo_repository::package_graph;
 
 use crate::{
-    commands::{bin, generate, prune},
+    commands::{bin, boundaries, cache, config, generate, prune, why},
     daemon::DaemonError,
     rewrite_json::RewriteError,
     run,
@@ -18,6 +18,9 @@ pub enum Error {
     #[error("{0}")]
     Bin(#[from] bin::Error, #[backtrace] backtrace::Backtrace),
     #[error(transparent)]
+    #[diagnostic(transparent)]
+    Boundaries(#[from] boundaries::Error),
+    #[error(transparent)]
     Path(#[from] turbopath::PathError),
     #[error("at least one task must be specified")]
     NoTasks(#[backtrace] backtrace::Backtrace),
@@ -25,6 +28,8 @@ pub enum Error {
     #[diagnostic(transparent)]
     Config(#[from] crate::config::Error),
     #[error(transparent)]
+    ConfigCommand(#[from] config::Error),
+    #[error(transparent)]
     ChromeTracing(#[from] crate::tracing::Error),
     #[error(transparent)]
     BuildPackageGraph(#[from] package_graph::builder::Error),
@@ -33,6 +38,9 @@ pub enum Error {
     #[error(transparent)]
     Auth(#[from] turborepo_auth::Error),
     #[error(transparent)]
+    #[diagnostic(transparent)]
+    Cache(#[from] cache::Error),
+    #[error(transparent)]
     Daemon(#[from] DaemonError),
     #[error(transparent)]
     Generate(#[from] generate::Error),
@@ -48,4 +56,8 @@ pub enum Error {
     Run(#[from] run::Error),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    Watch(#[from] run::watch::Error),
+    #[error(transparent)]
+    Why(#[from] why::Error),
 }
@@ -24,8 +24,8 @@ use turborepo_ui::UI;
 
 use crate::{
     commands::{
-        bin, daemon, generate, info, link, login, logout, prune, run, scan, telemetry, unlink,
-        CommandBase,
+        bin, boundaries, cache, config, daemon, generate, info, link, login, logout, prune, run,
+        scan, telemetry, unlink, watch, why, CommandBase,
     },
     get_version,
     shim::TurboState,
@@ -101,6 +101,53 @@ impl Display for LogOrder {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, ValueEnum)]
+pub enum OutputFormat {
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "ndjson")]
+    Ndjson,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        Self::Text
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Ndjson => "ndjson",
+        })
+    }
+}
+
+// `true` and `false` are kept as values so that `--summarize=<bool>` and
+// `TURBO_RUN_SUMMARY=<bool>` keep working
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, ValueEnum)]
+pub enum SummarizeMode {
+    #[serde(rename = "true")]
+    #[value(name = "true")]
+    Json,
+    #[serde(rename = "false")]
+    #[value(name = "false")]
+    Disabled,
+    #[serde(rename = "junit")]
+    Junit,
+}
+
+impl Display for SummarizeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SummarizeMode::Json => "true",
+            SummarizeMode::Disabled => "false",
+            SummarizeMode::Junit => "junit",
+        })
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, ValueEnum)]
 pub enum DryRunMode {
     Text,
@@ -116,6 +163,43 @@ impl Display for DryRunMode {
     }
 }
 
+/// One of `count` shards of a run, given as `index/count` with a 1-based
+/// index
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl std::str::FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s
+            .split_once('/')
+            .ok_or_else(|| format!("expected a shard like 1/4, got '{s}'"))?;
+        let index = index
+            .parse::<usize>()
+            .map_err(|_| format!("invalid shard index '{index}'"))?;
+        let count = count
+            .parse::<usize>()
+            .map_err(|_| format!("invalid shard count '{count}'"))?;
+        if count == 0 {
+            return Err("shard count must be at least 1".to_string());
+        }
+        if index == 0 || index > count {
+            return Err(format!("shard index must be between 1 and {count}"));
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, ValueEnum)]
 pub enum EnvMode {
     #[default]
@@ -265,6 +349,60 @@ pub enum DaemonCommand {
     Logs,
 }
 
+#[derive(Subcommand, Clone, Debug, Serialize, PartialEq)]
+#[serde(tag = "command")]
+pub enum CacheCommand {
+    /// Evict local cache entries that exceed the configured size and age
+    /// limits
+    Prune {
+        /// Maximum total size of the local cache, e.g. 10GB. Defaults to
+        /// `localCacheMaxSize`
+        #[clap(long)]
+        max_size: Option<String>,
+        /// Evict entries that haven't been used for this long, e.g. 7d.
+        /// Defaults to `localCacheMaxAge`
+        #[clap(long)]
+        max_age: Option<String>,
+    },
+    /// Remove every entry from the local cache
+    Clean,
+    /// List the artifacts in the local cache
+    Ls {
+        /// Output the artifacts as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Show the metadata and files of an artifact, checking the remote cache
+    /// if it isn't stored locally
+    Show {
+        /// The hash of the artifact
+        hash: String,
+        /// Output the artifact as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Check local artifacts against their recorded checksums and signatures.
+    /// Checks every artifact if no hashes are passed
+    Verify { hashes: Vec<String> },
+    /// Bundle local artifacts into a single archive. Exports every artifact if
+    /// no hashes are passed
+    Export {
+        hashes: Vec<String>,
+        /// The file to write the bundle to
+        #[clap(long, short)]
+        output: Utf8PathBuf,
+    },
+    /// Add the artifacts from a bundle created by `turbo cache export` to the
+    /// local cache
+    Import {
+        /// The bundle to import
+        bundle: Utf8PathBuf,
+        /// Overwrite artifacts that are already in the local cache
+        #[clap(long)]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand, Copy, Clone, Debug, Serialize, PartialEq)]
 #[serde(tag = "command")]
 pub enum TelemetryCommand {
@@ -319,7 +457,9 @@ impl Args {
                     run_args.single_package = is_single_package
                 }
 
-                if let Some(Command::Run(ref mut run_args)) = args.command {
+                if let Some(Command::Run(ref mut run_args) | Command::Watch(ref mut run_args)) =
+                    args.command
+                {
                     run_args.single_package = is_single_package;
                 }
 
@@ -427,9 +567,32 @@ pub enum Command {
     // them as `{ "Bin": {} }` instead of as `"Bin"`.
     /// Get the path to the Turbo binary
     Bin {},
+    /// Check the dependencies between packages against the "boundaries"
+    /// rules in the root turbo.json
+    Boundaries {
+        /// Also report imports of workspace packages that aren't declared
+        /// as dependencies in package.json
+        #[clap(long)]
+        imports: bool,
+    },
+    /// Manage the local filesystem cache
+    Cache {
+        /// Override the filesystem cache directory.
+        #[clap(long, value_parser = path_non_empty, env = "TURBO_CACHE_DIR")]
+        cache_dir: Option<Utf8PathBuf>,
+        #[clap(subcommand)]
+        #[serde(flatten)]
+        command: CacheCommand,
+    },
     /// Generate the autocompletion script for the specified shell
     #[serde(skip)]
     Completion { shell: Shell },
+    /// Print the resolved configuration and where each value was set
+    Config {
+        /// Output the configuration as JSON
+        #[clap(long)]
+        json: bool,
+    },
     /// Runs the Turborepo background daemon
     Daemon {
         /// Set the idle timeout for turbod
@@ -538,6 +701,20 @@ pub enum Command {
         #[clap(long, value_enum, default_value_t = LinkTarget::RemoteCache)]
         target: LinkTarget,
     },
+    /// Run tasks and re-run them whenever files in the affected packages
+    /// change
+    ///
+    /// Only the tasks of changed packages and the tasks that depend on them
+    /// are re-run. Persistent tasks are started once and kept running.
+    ///
+    /// Arguments passed after '--' will be passed through to the named tasks.
+    Watch(Box<RunArgs>),
+    /// Explain why a task missed the cache in its last run by comparing its
+    /// inputs against the last time it was cached
+    Why {
+        /// The task to explain, e.g. web#build
+        task: String,
+    },
 }
 
 #[derive(Parser, Clone, Debug, Default, Serialize, PartialEq)]
@@ -643,6 +820,15 @@ pub struct RunArgs {
     /// exit code. The default behavior is to bail
     #[clap(long = "continue")]
     pub continue_execution: bool,
+    /// Retry tasks that exit with an error or non-zero exit code up to the
+    /// given number of times. Overrides "retries" in turbo.json
+    #[clap(long, value_name = "COUNT")]
+    pub retries: Option<u32>,
+    /// Stop tasks that run for longer than the given number of seconds and
+    /// mark them as failed. Use 0 to disable timeouts. Overrides "timeout"
+    /// in turbo.json
+    #[clap(long, value_name = "SECONDS")]
+    pub task_timeout: Option<u64>,
     #[clap(alias = "dry", long = "dry-run", num_args = 0..=1, default_missing_value = "text")]
     pub dry_run: Option<DryRunMode>,
     /// Run turbo in single-package mode
@@ -684,10 +870,26 @@ pub struct RunArgs {
     #[clap(long, group = "scope-filter-group")]
     pub scope: Vec<String>,
 
+    /// Only run tasks whose inputs changed since a base git ref. The base is
+    /// read from TURBO_SCM_BASE or the CI provider, and defaults to main.
+    #[clap(long, group = "scope-filter-group")]
+    pub affected: bool,
+
+    /// Only run the tasks of one shard of the run, given as INDEX/COUNT.
+    /// Every shard gets the same number of tasks, unless --shard-durations
+    /// is used
+    #[clap(long, value_name = "INDEX/COUNT")]
+    pub shard: Option<Shard>,
+
+    /// A run summary to balance the shards of --shard by how long each task
+    /// took. Every shard must use the same file
+    #[clap(long, value_name = "FILE", requires = "shard")]
+    pub shard_durations: Option<Utf8PathBuf>,
+
     //  ignore filters out files from scope and filter, so we require it here
     // -----------------------
-    /// Files to ignore when calculating changed files from '--filter'.
-    /// Supports globs.
+    /// Files to ignore when calculating changed files from '--filter' or
+    /// '--affected'. Supports globs.
     #[clap(long, requires = "scope-filter-group")]
     pub ignore: Vec<String>,
 
@@ -743,6 +945,15 @@ pub struct RunArgs {
     /// turbo decide based on its own heuristics. (default auto)
     #[clap(long, env = "TURBO_LOG_ORDER", value_enum, default_value_t = LogOrder::Auto)]
     pub log_order: LogOrder,
+    /// Set the format of turbo's output. Use "ndjson" to write a stream of
+    /// run events to stdout as newline delimited JSON, and the human readable
+    /// output to stderr. (default text)
+    #[clap(long, value_enum, conflicts_with_all = ["dry_run", "graph"])]
+    pub output_format: Option<OutputFormat>,
+    /// Write a stream of run events as newline delimited JSON to the given
+    /// file
+    #[clap(long, value_parser = NonEmptyStringValueParser::new(), conflicts_with_all = ["output_format", "dry_run", "graph"])]
+    pub events_file: Option<String>,
     /// Only executes the tasks specified, does not execute parent tasks.
     #[clap(long)]
     pub only: bool,
@@ -769,9 +980,28 @@ pub struct RunArgs {
     #[clap(long, env = "TURBO_REMOTE_CACHE_READ_ONLY", value_name = "BOOL", action = ArgAction::Set, default_value = "false", default_missing_value = "true", num_args = 0..=1)]
     #[serde(skip)]
     pub remote_cache_read_only: bool,
-    /// Generate a summary of the turbo run
-    #[clap(long, env = "TURBO_RUN_SUMMARY", default_missing_value = "true")]
-    pub summarize: Option<Option<bool>>,
+    /// Generate a summary of the turbo run. Use "junit" to write it as JUnit
+    /// XML instead of JSON
+    #[clap(
+        long,
+        env = "TURBO_RUN_SUMMARY",
+        value_enum,
+        default_missing_value = "true"
+    )]
+    pub summarize: Option<Option<SummarizeMode>>,
+    /// Print why tasks missed the cache by comparing their inputs against
+    /// the last time they were cached
+    #[clap(long, conflicts_with_all = ["dry_run", "graph"])]
+    pub explain: bool,
+    /// Report environment variables that tasks read without declaring them
+    /// in turbo.json, and suggest the turbo.json changes to declare them
+    #[clap(long, conflicts_with_all = ["dry_run", "graph"])]
+    pub audit_env: bool,
+    /// Re-run tasks that hit the cache in a scratch directory and report the
+    /// outputs that differ from the cached ones, without touching the
+    /// workspace
+    #[clap(long, conflicts_with_all = ["dry_run", "graph"])]
+    pub verify_cache: bool,
 
     /// Use "none" to remove prefixes from task logs. Use "task" to get task id
     /// prefixing. Use "auto" to let turbo decide how to prefix the logs
@@ -831,8 +1061,12 @@ impl RunArgs {
         track_usage!(telemetry, self.no_daemon, |val| val);
         track_usage!(telemetry, self.only, |val| val);
         track_usage!(telemetry, self.parallel, |val| val);
+        track_usage!(telemetry, self.affected, |val| val);
         track_usage!(telemetry, self.remote_only, |val| val);
         track_usage!(telemetry, self.remote_cache_read_only, |val| val);
+        track_usage!(telemetry, self.explain, |val| val);
+        track_usage!(telemetry, self.audit_env, |val| val);
+        track_usage!(telemetry, self.verify_cache, |val| val);
 
         // default to None
         track_usage!(telemetry, &self.cache_dir, Option::is_some);
@@ -843,6 +1077,8 @@ impl RunArgs {
         track_usage!(telemetry, &self.anon_profile, Option::is_some);
         track_usage!(telemetry, &self.summarize, Option::is_some);
         track_usage!(telemetry, &self.experimental_space_id, Option::is_some);
+        track_usage!(telemetry, &self.events_file, Option::is_some);
+        track_usage!(telemetry, &self.shard_durations, Option::is_some);
 
         // track values
         if let Some(dry_run) = &self.dry_run {
@@ -857,6 +1093,18 @@ impl RunArgs {
             telemetry.track_arg_value("concurrency", concurrency, EventType::NonSensitive);
         }
 
+        if let Some(retries) = self.retries {
+            telemetry.track_arg_value("retries", retries, EventType::NonSensitive);
+        }
+
+        if let Some(task_timeout) = self.task_timeout {
+            telemetry.track_arg_value("task-timeout", task_timeout, EventType::NonSensitive);
+        }
+
+        if let Some(shard) = self.shard {
+            telemetry.track_arg_value("shard-count", shard.count, EventType::NonSensitive);
+        }
+
         if !self.global_deps.is_empty() {
             telemetry.track_arg_value("global-deps", self.cache_workers, EventType::NonSensitive);
         }
@@ -883,6 +1131,14 @@ impl RunArgs {
             telemetry.track_arg_value("log-prefix", self.log_prefix, EventType::NonSensitive);
         }
 
+        if let Some(output_format) = self.output_format {
+            telemetry.track_arg_value("output-format", output_format, EventType::NonSensitive);
+        }
+
+        if let Some(Some(summarize)) = self.summarize {
+            telemetry.track_arg_value("summarize", summarize, EventType::NonSensitive);
+        }
+
         // track sizes
         if !self.filter.is_empty() {
             telemetry.track_arg_value("filter:length", self.filter.len(), EventType::NonSensitive);
@@ -987,7 +1243,7 @@ pub async fn run(
     };
 
     // Set some run flags if we have the data and are executing a Run
-    if let Command::Run(run_args) = &mut command {
+    if let Command::Run(run_args) | Command::Watch(run_args) = &mut command {
         // Don't overwrite the flag if it's already been set for whatever reason
         run_args.single_package = run_args.single_package
             || repo_state
@@ -1056,6 +1312,21 @@ pub async fn run(
 
             Ok(0)
         }
+        Command::Boundaries { imports } => {
+            CommandEventBuilder::new("boundaries")
+                .with_parent(&root_telemetry)
+                .track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, ui);
+            boundaries::run(&base, *imports).await?;
+            Ok(0)
+        }
+        Command::Cache { cache_dir, command } => {
+            CommandEventBuilder::new("cache")
+                .with_parent(&root_telemetry)
+                .track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, ui);
+            Ok(cache::run(&base, cache_dir.as_deref(), command).await?)
+        }
         #[allow(unused_variables)]
         Command::Daemon { command, idle_time } => {
             CommandEventBuilder::new("daemon")
@@ -1214,6 +1485,20 @@ pub async fn run(
             })?;
             Ok(exit_code)
         }
+        Command::Watch(args) => {
+            let event = CommandEventBuilder::new("watch").with_parent(&root_telemetry);
+            event.track_call();
+            if args.tasks.is_empty() {
+                return Err(Error::NoTasks(backtrace::Backtrace::capture()));
+            }
+
+            let run_args = args.as_ref().clone();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, ui);
+
+            args.track(&event);
+            let exit_code = watch::run(base, run_args, event).await?;
+            Ok(exit_code)
+        }
         Command::Prune {
             scope,
             scope_arg,
@@ -1234,6 +1519,22 @@ pub async fn run(
             prune::prune(&base, &scope, docker, &output_dir, event_child).await?;
             Ok(0)
         }
+        Command::Why { task } => {
+            CommandEventBuilder::new("why")
+                .with_parent(&root_telemetry)
+                .track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, ui);
+            why::run(&base, task)?;
+            Ok(0)
+        }
+        Command::Config { json } => {
+            CommandEventBuilder::new("config")
+                .with_parent(&root_telemetry)
+                .track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, ui);
+            config::run(&base, *json)?;
+            Ok(0)
+        }
         Command::Completion { shell } => {
             CommandEventBuilder::new("completion")
                 .with_parent(&root_telemetry)
@@ -1318,7 +1619,8 @@ mod test {
     use anyhow::Result;
 
     use crate::cli::{
-        Args, Command, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode, RunArgs, Verbosity,
+        Args, CacheCommand, Command, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputFormat,
+        OutputLogsMode, RunArgs, Shard, SummarizeMode, Verbosity,
     };
 
     #[test_case::test_case(
@@ -1495,6 +1797,151 @@ mod test {
             ..Args::default()
         }
 	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--retries", "2"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                retries: Some(2),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--task-timeout", "600"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                task_timeout: Some(600),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--summarize"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                summarize: Some(Some(SummarizeMode::Json)),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--summarize=junit"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                summarize: Some(Some(SummarizeMode::Junit)),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--summarize=false"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                summarize: Some(Some(SummarizeMode::Disabled)),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--output-format", "ndjson"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                output_format: Some(OutputFormat::Ndjson),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--events-file", "events.ndjson"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                events_file: Some("events.ndjson".to_string()),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--explain"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                explain: true,
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--verify-cache"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                verify_cache: true,
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--audit-env"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                audit_env: true,
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--affected", "--ignore", "*.md"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["build".to_string()],
+                affected: true,
+                ignore: vec!["*.md".to_string()],
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "test", "--shard=3/8"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["test".to_string()],
+                shard: Some(Shard { index: 3, count: 8 }),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "test", "--shard=1/2", "--shard-durations", "summary.json"],
+        Args {
+            command: Some(Command::Run(Box::new(RunArgs {
+                tasks: vec!["test".to_string()],
+                shard: Some(Shard { index: 1, count: 2 }),
+                shard_durations: Some(Utf8PathBuf::from("summary.json")),
+                ..get_default_run_args()
+            }))),
+            ..Args::default()
+        }
+	)]
     #[test_case::test_case(
 		&["turbo", "run", "build", "--dry-run"],
         Args {
@@ -1957,6 +2404,31 @@ mod test {
         "the following required arguments were not provided" ;
         "no-deps without filter or scope"
     )]
+    #[test_case::test_case(
+        &["turbo", "run", "build", "--output-format", "ndjson", "--dry-run"],
+        "cannot be used with" ;
+        "output-format with dry-run"
+    )]
+    #[test_case::test_case(
+        &["turbo", "run", "build", "--output-format", "ndjson", "--events-file", "events.ndjson"],
+        "cannot be used with" ;
+        "output-format with events-file"
+    )]
+    #[test_case::test_case(
+        &["turbo", "run", "test", "--shard", "9/8"],
+        "shard index must be between 1 and 8" ;
+        "shard index out of range"
+    )]
+    #[test_case::test_case(
+        &["turbo", "run", "test", "--shard", "0/0"],
+        "shard count must be at least 1" ;
+        "zero shards"
+    )]
+    #[test_case::test_case(
+        &["turbo", "run", "test", "--shard", "3"],
+        "expected a shard like 1/4" ;
+        "shard without count"
+    )]
     fn test_parse_run_failures(args: &[&str], expected: &str) {
         assert_matches!(
             Args::try_parse_from(args),
@@ -2081,6 +2553,158 @@ mod test {
         .test();
     }
 
+    #[test]
+    fn test_parse_watch() {
+        assert_eq!(
+            Args::try_parse_from(["turbo", "watch", "build"]).unwrap(),
+            Args {
+                command: Some(Command::Watch(Box::new(RunArgs {
+                    tasks: vec!["build".to_string()],
+                    ..get_default_run_args()
+                }))),
+                ..Args::default()
+            }
+        );
+
+        CommandTestCase {
+            command: "watch",
+            command_args: vec![vec!["build"], vec!["--filter", "web"]],
+            global_args: vec![vec!["--cwd", "../examples/with-yarn"]],
+            expected_output: Args {
+                command: Some(Command::Watch(Box::new(RunArgs {
+                    tasks: vec!["build".to_string()],
+                    filter: vec!["web".to_string()],
+                    ..get_default_run_args()
+                }))),
+                cwd: Some(Utf8PathBuf::from("../examples/with-yarn")),
+                ..Args::default()
+            },
+        }
+        .test();
+    }
+
+    #[test]
+    fn test_parse_boundaries() {
+        assert_eq!(
+            Args::try_parse_from(["turbo", "boundaries"]).unwrap(),
+            Args {
+                command: Some(Command::Boundaries { imports: false }),
+                ..Args::default()
+            }
+        );
+        assert_eq!(
+            Args::try_parse_from(["turbo", "boundaries", "--imports"]).unwrap(),
+            Args {
+                command: Some(Command::Boundaries { imports: true }),
+                ..Args::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_why() {
+        assert_eq!(
+            Args::try_parse_from(["turbo", "why", "web#build"]).unwrap(),
+            Args {
+                command: Some(Command::Why {
+                    task: "web#build".to_string(),
+                }),
+                ..Args::default()
+            }
+        );
+        assert!(Args::try_parse_from(["turbo", "why"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_config() {
+        assert_eq!(
+            Args::try_parse_from(["turbo", "config"]).unwrap(),
+            Args {
+                command: Some(Command::Config { json: false }),
+                ..Args::default()
+            }
+        );
+        assert_eq!(
+            Args::try_parse_from(["turbo", "config", "--json"]).unwrap(),
+            Args {
+                command: Some(Command::Config { json: true }),
+                ..Args::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_cache() {
+        assert_eq!(
+            Args::try_parse_from(["turbo", "cache", "prune", "--max-size", "10GB"]).unwrap(),
+            Args {
+                command: Some(Command::Cache {
+                    cache_dir: None,
+                    command: CacheCommand::Prune {
+                        max_size: Some("10GB".to_string()),
+                        max_age: None,
+                    },
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert_eq!(
+            Args::try_parse_from(["turbo", "cache", "--cache-dir", "foobar", "clean"]).unwrap(),
+            Args {
+                command: Some(Command::Cache {
+                    cache_dir: Some(Utf8PathBuf::from("foobar")),
+                    command: CacheCommand::Clean,
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert_eq!(
+            Args::try_parse_from(["turbo", "cache", "show", "abc123", "--json"]).unwrap(),
+            Args {
+                command: Some(Command::Cache {
+                    cache_dir: None,
+                    command: CacheCommand::Show {
+                        hash: "abc123".to_string(),
+                        json: true,
+                    },
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert_eq!(
+            Args::try_parse_from(["turbo", "cache", "export", "a", "b", "-o", "bundle.tar"])
+                .unwrap(),
+            Args {
+                command: Some(Command::Cache {
+                    cache_dir: None,
+                    command: CacheCommand::Export {
+                        hashes: vec!["a".to_string(), "b".to_string()],
+                        output: Utf8PathBuf::from("bundle.tar"),
+                    },
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert_eq!(
+            Args::try_parse_from(["turbo", "cache", "verify"]).unwrap(),
+            Args {
+                command: Some(Command::Cache {
+                    cache_dir: None,
+                    command: CacheCommand::Verify { hashes: vec![] },
+                }),
+                ..Args::default()
+            }
+        );
+
+        assert!(Args::try_parse_from(["turbo", "cache"]).is_err());
+        assert!(Args::try_parse_from(["turbo", "cache", "export", "a"]).is_err());
+        assert!(Args::try_parse_from(["turbo", "cache", "import"]).is_err());
+    }
+
     #[test]
     fn test_parse_prune() {
         let default_prune = Command::Prune {
@@ -0,0 +1,613 @@
+//! Checks the dependencies between packages against the "boundaries" rules
+//! in the root turbo.json. Rules select packages by name glob or by the tags
+//! that packages declare in their turbo.json or package.json.
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+};
+
+use lazy_static::lazy_static;
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use regex::Regex;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_errors::Spanned;
+use turborepo_repository::{
+    package_graph::{self, PackageGraph, PackageInfo, PackageName, PackageNode},
+    package_json::{self, PackageJson},
+};
+use turborepo_ui::{cprintln, BOLD_GREEN};
+
+use super::CommandBase;
+use crate::{
+    config,
+    run::scope::{Match, SimpleGlob},
+    turbo_json::{BoundaryRule, TurboJson},
+};
+
+const TAG_PREFIX: &str = "tag:";
+
+// Files that are scanned for imports with `--imports`
+const SOURCE_GLOB: &str = "**/*.{js,jsx,ts,tsx,mjs,cjs,mts,cts}";
+
+lazy_static! {
+    // Matches the specifier of `import ... from "x"`, `export ... from "x"`,
+    // `import "x"`, `import("x")` and `require("x")`
+    static ref IMPORT: Regex =
+        Regex::new(r#"(?:\bfrom\s*|\bimport\s*\(?\s*|\brequire\s*\(\s*)["']([^"'\s]+)["']"#)
+            .unwrap();
+}
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum Error {
+    #[error(transparent)]
+    PackageJson(#[from] package_json::Error),
+    #[error(transparent)]
+    PackageGraph(#[from] package_graph::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Glob(#[from] globwalk::GlobError),
+    #[error(transparent)]
+    Walk(#[from] globwalk::WalkError),
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: AbsoluteSystemPathBuf,
+        source: std::io::Error,
+    },
+    #[error("\"tags\" in {path} must be a list of strings")]
+    InvalidPackageJsonTags { path: String },
+    #[error("invalid package selector \"{selector}\": {reason}")]
+    InvalidSelector {
+        selector: String,
+        reason: String,
+        #[label("used in this rule")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("\"boundaries\" can only be set in the root turbo.json")]
+    BoundariesInPackage {
+        #[label("move this rule to the root turbo.json")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("found {} boundary violation(s)", .violations.len())]
+    Violations {
+        #[related]
+        violations: Vec<Violation>,
+    },
+}
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum Violation {
+    #[error("\"{package}\" depends on \"{dependency}\", which is denied by \"{entry}\"")]
+    #[diagnostic(help("denied by the rule for \"{selector}\" in the root turbo.json"))]
+    Denied {
+        package: String,
+        dependency: String,
+        selector: String,
+        entry: String,
+        #[label("dependency declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("\"{package}\" depends on \"{dependency}\", which isn't allowed")]
+    #[diagnostic(help("allowed by the rule for \"{selector}\": {allowed}"))]
+    NotAllowed {
+        package: String,
+        dependency: String,
+        selector: String,
+        allowed: String,
+        #[label("dependency declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("\"{package}\" imports \"{dependency}\" without declaring it as a dependency")]
+    #[diagnostic(help("add \"{dependency}\" to the dependencies in {package_json}"))]
+    UndeclaredImport {
+        package: String,
+        dependency: String,
+        package_json: String,
+        #[label("imported here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+}
+
+/// Selects packages either by one of their tags or by a glob of their name
+enum Selector {
+    Tag(String),
+    Name(SimpleGlob),
+}
+
+impl Selector {
+    fn new(selector: &Spanned<String>) -> Result<Self, Error> {
+        if let Some(tag) = selector.strip_prefix(TAG_PREFIX) {
+            return Ok(Selector::Tag(tag.to_string()));
+        }
+        SimpleGlob::new(selector)
+            .map(Selector::Name)
+            .map_err(|err| {
+                let (span, text) = selector.span_and_text("turbo.json");
+                Error::InvalidSelector {
+                    selector: selector.as_inner().clone(),
+                    reason: err.to_string(),
+                    span,
+                    text,
+                }
+            })
+    }
+
+    fn matches(&self, package: &TaggedPackage) -> bool {
+        match self {
+            Selector::Tag(tag) => package.tags.contains(tag),
+            Selector::Name(glob) => glob.is_match(&package.name),
+        }
+    }
+}
+
+struct Rule {
+    selector: String,
+    applies_to: Selector,
+    allow: Option<Vec<(String, Selector)>>,
+    deny: Vec<(String, Selector)>,
+}
+
+/// The name and tags of a package, which is all that rules can select on
+#[derive(Debug)]
+struct TaggedPackage {
+    name: String,
+    tags: HashSet<String>,
+}
+
+/// Why a dependency breaks the rules
+#[derive(Debug, PartialEq)]
+enum Broken<'a> {
+    Denied { selector: &'a str, entry: &'a str },
+    NotAllowed { selector: &'a str, allowed: String },
+}
+
+struct Rules(Vec<Rule>);
+
+impl Rules {
+    fn new(boundaries: &BTreeMap<String, Spanned<BoundaryRule>>) -> Result<Self, Error> {
+        let selectors = |entries: &[Spanned<String>]| {
+            entries
+                .iter()
+                .map(|entry| Ok((entry.as_inner().clone(), Selector::new(entry)?)))
+                .collect::<Result<Vec<_>, Error>>()
+        };
+
+        let rules = boundaries
+            .iter()
+            .map(|(selector, rule)| {
+                // The selector is a key, so errors point at the rule it selects for
+                let applies_to = Selector::new(&rule.to(selector.clone()))?;
+                Ok(Rule {
+                    selector: selector.clone(),
+                    applies_to,
+                    allow: rule.allow.as_deref().map(selectors).transpose()?,
+                    deny: selectors(&rule.deny)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Rules(rules))
+    }
+
+    /// Checks whether `package` may depend on `dependency`. A dependency that
+    /// is denied by any rule is broken, otherwise it needs to be allowed by
+    /// every rule that has an allow list.
+    fn check<'a>(
+        &'a self,
+        package: &TaggedPackage,
+        dependency: &TaggedPackage,
+    ) -> Option<Broken<'a>> {
+        let rules = self
+            .0
+            .iter()
+            .filter(|rule| rule.applies_to.matches(package))
+            .collect::<Vec<_>>();
+
+        for rule in &rules {
+            if let Some((entry, _)) = rule
+                .deny
+                .iter()
+                .find(|(_, selector)| selector.matches(dependency))
+            {
+                return Some(Broken::Denied {
+                    selector: &rule.selector,
+                    entry,
+                });
+            }
+        }
+
+        rules.into_iter().find_map(|rule| {
+            let allow = rule.allow.as_ref()?;
+            if allow
+                .iter()
+                .any(|(_, selector)| selector.matches(dependency))
+            {
+                return None;
+            }
+            let allowed = match allow.is_empty() {
+                true => "none".to_string(),
+                false => allow
+                    .iter()
+                    .map(|(entry, _)| format!("\"{entry}\""))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            Some(Broken::NotAllowed {
+                selector: &rule.selector,
+                allowed,
+            })
+        })
+    }
+}
+
+pub async fn run(base: &CommandBase, imports: bool) -> Result<(), Error> {
+    let root_package_json = PackageJson::load(&base.repo_root.join_component("package.json"))?;
+    let package_graph = PackageGraph::builder(&base.repo_root, root_package_json)
+        .build()
+        .await?;
+
+    let mut packages = package_graph
+        .packages()
+        .map(|(name, info)| (name.clone(), info))
+        .collect::<Vec<_>>();
+    packages.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut rules = Rules(Vec::new());
+    let mut tagged = BTreeMap::new();
+    for (name, info) in &packages {
+        let turbo_json = load_turbo_json(&base.repo_root, info)?;
+        let mut tags = package_json_tags(info)?;
+        if let Some(turbo_json) = turbo_json {
+            tags.extend(turbo_json.tags.iter().map(|tag| tag.as_inner().clone()));
+            match name {
+                PackageName::Root => rules = Rules::new(&turbo_json.boundaries)?,
+                PackageName::Other(_) => {
+                    if let Some(rule) = turbo_json.boundaries.values().next() {
+                        let (span, text) = rule.span_and_text("turbo.json");
+                        return Err(Error::BoundariesInPackage { span, text });
+                    }
+                }
+            }
+        }
+        tagged.insert(
+            name.clone(),
+            TaggedPackage {
+                name: name.to_string(),
+                tags,
+            },
+        );
+    }
+
+    let mut violations = Vec::new();
+    for (name, info) in &packages {
+        let package_json_path = base.repo_root.resolve(info.package_json_path());
+        let package_json_text = read(&package_json_path)?;
+        let named_package_json = || {
+            NamedSource::new(
+                info.package_json_path().to_string(),
+                package_json_text.clone(),
+            )
+        };
+
+        let mut dependencies = package_graph
+            .immediate_dependencies(&PackageNode::Workspace(name.clone()))
+            .into_iter()
+            .flatten()
+            .filter_map(|node| match node {
+                PackageNode::Workspace(dependency) => Some(dependency),
+                PackageNode::Root => None,
+            })
+            .collect::<Vec<_>>();
+        dependencies.sort();
+
+        for dependency in dependencies {
+            let Some(broken) = rules.check(&tagged[name], &tagged[dependency]) else {
+                continue;
+            };
+            let span = dependency_span(&package_json_text, &dependency.to_string());
+            violations.push(match broken {
+                Broken::Denied { selector, entry } => Violation::Denied {
+                    package: name.to_string(),
+                    dependency: dependency.to_string(),
+                    selector: selector.to_string(),
+                    entry: entry.to_string(),
+                    span,
+                    text: named_package_json(),
+                },
+                Broken::NotAllowed { selector, allowed } => Violation::NotAllowed {
+                    package: name.to_string(),
+                    dependency: dependency.to_string(),
+                    selector: selector.to_string(),
+                    allowed,
+                    span,
+                    text: named_package_json(),
+                },
+            });
+        }
+
+        if imports {
+            violations.extend(undeclared_imports(&base.repo_root, name, info, &packages)?);
+        }
+    }
+
+    if !violations.is_empty() {
+        return Err(Error::Violations { violations });
+    }
+
+    cprintln!(
+        base.ui,
+        BOLD_GREEN,
+        "No boundary violations found in {} packages",
+        packages.len()
+    );
+    Ok(())
+}
+
+// Packages without a turbo.json have no tags or rules from it
+fn load_turbo_json(
+    repo_root: &AbsoluteSystemPath,
+    info: &PackageInfo,
+) -> Result<Option<TurboJson>, Error> {
+    match TurboJson::load(repo_root, info.package_path(), &info.package_json, false) {
+        Ok(turbo_json) => Ok(Some(turbo_json)),
+        Err(config::Error::NoTurboJSON) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn package_json_tags(info: &PackageInfo) -> Result<HashSet<String>, Error> {
+    let Some(tags) = info.package_json.other.get("tags") else {
+        return Ok(HashSet::new());
+    };
+    serde_json::from_value::<Vec<String>>(tags.clone())
+        .map(|tags| tags.into_iter().collect())
+        .map_err(|_| Error::InvalidPackageJsonTags {
+            path: info.package_json_path().to_string(),
+        })
+}
+
+fn read(path: &AbsoluteSystemPath) -> Result<String, Error> {
+    path.read_to_string().map_err(|source| Error::Read {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+// Points at the key of the dependency in package.json. Dependencies are the
+// only keys that are package names, so the first match is the declaration.
+fn dependency_span(package_json: &str, dependency: &str) -> Option<SourceSpan> {
+    let key = Regex::new(&format!(r#""{}"\s*:"#, regex::escape(dependency))).ok()?;
+    let found = key.find(package_json)?;
+    Some((found.start(), dependency.len() + 2).into())
+}
+
+/// The package that an import specifier refers to, e.g. `@acme/ui` for
+/// `@acme/ui/button`. Relative and absolute imports don't refer to a package.
+fn imported_package(specifier: &str) -> Option<&str> {
+    if specifier.starts_with('.') || specifier.starts_with('/') {
+        return None;
+    }
+    let segments = match specifier.starts_with('@') {
+        true => 2,
+        false => 1,
+    };
+    let end = specifier
+        .match_indices('/')
+        .nth(segments - 1)
+        .map_or(specifier.len(), |(index, _)| index);
+    Some(&specifier[..end])
+}
+
+fn undeclared_imports(
+    repo_root: &AbsoluteSystemPath,
+    name: &PackageName,
+    info: &PackageInfo,
+    packages: &[(PackageName, &PackageInfo)],
+) -> Result<Vec<Violation>, Error> {
+    let package_dir = info.package_path().to_unix();
+    let declared = info
+        .package_json
+        .all_dependencies()
+        .chain(info.package_json.peer_dependencies.iter().flatten())
+        .map(|(dependency, _)| dependency.as_str())
+        .collect::<HashSet<_>>();
+    let workspace_packages = packages
+        .iter()
+        .filter(|(other, _)| other != name)
+        .map(|(other, _)| other.to_string())
+        .collect::<HashSet<_>>();
+
+    // Packages nested inside this one are checked on their own
+    let mut exclude = vec![globwalk::ValidatedGlob::from_str("**/node_modules/**")?];
+    for (other, other_info) in packages {
+        let other_dir = other_info.package_path().to_unix();
+        if other == name || other_dir == package_dir {
+            continue;
+        }
+        if let Ok(nested) = other_dir.strip_prefix(&package_dir) {
+            exclude.push(globwalk::ValidatedGlob::from_str(&format!(
+                "{}/**",
+                nested.as_str()
+            ))?);
+        }
+    }
+
+    let mut files = globwalk::globwalk(
+        &repo_root.resolve(info.package_path()),
+        &[globwalk::ValidatedGlob::from_str(SOURCE_GLOB)?],
+        &exclude,
+        globwalk::WalkType::Files,
+    )?
+    .into_iter()
+    .collect::<Vec<_>>();
+    files.sort();
+
+    let mut violations = Vec::new();
+    for file in files {
+        let contents = read(&file)?;
+        for import in IMPORT.captures_iter(&contents) {
+            let specifier = import.get(1).expect("import pattern has a capture group");
+            let Some(dependency) = imported_package(specifier.as_str()) else {
+                continue;
+            };
+            if declared.contains(dependency) || !workspace_packages.contains(dependency) {
+                continue;
+            }
+            let path = repo_root
+                .anchor(&file)
+                .map_or_else(|_| file.to_string(), |path| path.to_string());
+            violations.push(Violation::UndeclaredImport {
+                package: name.to_string(),
+                dependency: dependency.to_string(),
+                package_json: info.package_json_path().to_string(),
+                span: Some((specifier.start(), specifier.len()).into()),
+                text: NamedSource::new(path, contents.clone()),
+            });
+        }
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn rules(json: serde_json::Value) -> Rules {
+        let raw = crate::turbo_json::RawTurboJson::parse_from_serde(json).unwrap();
+        let turbo_json = TurboJson::try_from(raw).unwrap();
+        Rules::new(&turbo_json.boundaries).unwrap()
+    }
+
+    fn package(name: &str, tags: &[&str]) -> TaggedPackage {
+        TaggedPackage {
+            name: name.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    #[test_case(package("web", &["app"]), package("ui", &["ui"]), None ; "allowed by tag")]
+    #[test_case(package("web", &["app"]), package("@acme/utils", &[]), None ; "allowed by name")]
+    #[test_case(
+        package("web", &["app"]),
+        package("db", &["internal"]),
+        Some(Broken::Denied { selector: "tag:app", entry: "tag:internal" }) ;
+        "denied by tag"
+    )]
+    #[test_case(
+        package("web", &["app"]),
+        package("docs", &["app"]),
+        Some(Broken::NotAllowed { selector: "tag:app", allowed: "\"tag:ui\", \"@acme/*\"".to_string() }) ;
+        "not in allow list"
+    )]
+    #[test_case(
+        package("legacy-api", &[]),
+        package("ui", &["ui"]),
+        Some(Broken::Denied { selector: "legacy-*", entry: "*" }) ;
+        "denied by name glob"
+    )]
+    #[test_case(package("docs", &[]), package("db", &["internal"]), None ; "no matching rule")]
+    #[test_case(
+        package("ui", &["ui"]),
+        package("web", &["app"]),
+        Some(Broken::NotAllowed { selector: "tag:ui", allowed: "none".to_string() }) ;
+        "empty allow list"
+    )]
+    fn test_check(package: TaggedPackage, dependency: TaggedPackage, expected: Option<Broken>) {
+        let rules = rules(serde_json::json!({
+            "boundaries": {
+                "tag:app": { "allow": ["tag:ui", "@acme/*"], "deny": ["tag:internal"] },
+                "tag:ui": { "allow": [] },
+                "legacy-*": { "deny": ["*"] },
+            }
+        }));
+
+        assert_eq!(rules.check(&package, &dependency), expected);
+    }
+
+    #[test]
+    fn test_deny_wins_over_allow() {
+        let rules = rules(serde_json::json!({
+            "boundaries": {
+                "tag:app": { "allow": ["*"] },
+                "web": { "deny": ["db"] },
+            }
+        }));
+
+        assert_eq!(
+            rules.check(&package("web", &["app"]), &package("db", &[])),
+            Some(Broken::Denied {
+                selector: "web",
+                entry: "db"
+            })
+        );
+    }
+
+    #[test_case("@acme/ui", Some("@acme/ui") ; "scoped")]
+    #[test_case("@acme/ui/button", Some("@acme/ui") ; "scoped subpath")]
+    #[test_case("lodash/get", Some("lodash") ; "subpath")]
+    #[test_case("react", Some("react") ; "bare")]
+    #[test_case("./button", None ; "relative")]
+    #[test_case("/abs/path", None ; "absolute")]
+    fn test_imported_package(specifier: &str, expected: Option<&str>) {
+        assert_eq!(imported_package(specifier), expected);
+    }
+
+    #[test]
+    fn test_import_pattern() {
+        let source = r#"
+            import React from "react";
+            import { Button } from '@acme/ui/button';
+            import "./styles.css";
+            export * from "utils";
+            const db = require("db");
+            const lazy = await import("lazy");
+        "#;
+        let specifiers = IMPORT
+            .captures_iter(source)
+            .map(|import| import.get(1).unwrap().as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            specifiers,
+            vec![
+                "react",
+                "@acme/ui/button",
+                "./styles.css",
+                "utils",
+                "db",
+                "lazy"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dependency_span() {
+        let package_json = r#"{
+  "name": "web",
+  "dependencies": {
+    "web-utils": "*",
+    "ui": "*"
+  }
+}"#;
+        let span = dependency_span(package_json, "ui").unwrap();
+        assert_eq!(
+            &package_json[span.offset()..span.offset() + span.len()],
+            "\"ui\""
+        );
+        assert_eq!(dependency_span(package_json, "db"), None);
+    }
+}
@@ -0,0 +1,501 @@
+use std::io::{BufReader, BufWriter, Write};
+
+use camino::Utf8Path;
+use chrono::{DateTime, Local};
+use miette::Diagnostic;
+use serde::Serialize;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_cache::{
+    cache_archive::ArchiveEntry,
+    fs::{CacheItem, EvictionOpts, FSCache, PruneSummary, Verification},
+    http::HTTPCache,
+    signature_authentication::ArtifactSignatureAuthenticator,
+    CacheError, CacheOpts, CacheSource, RemoteCacheOpts,
+};
+use turborepo_ui::{BOLD, GREY};
+
+use crate::{
+    cli::CacheCommand,
+    commands::CommandBase,
+    config::{self, ConfigurationOptions},
+};
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(
+        "no local cache limits configured. Pass --max-size or --max-age, or set \
+         `localCacheMaxSize` or `localCacheMaxAge` in turbo.json"
+    )]
+    NoLimits,
+    #[error("artifact {0} not found in the local or remote cache")]
+    NotFound(String),
+    #[error("failed to access {path}: {error}")]
+    Io {
+        path: AbsoluteSystemPathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemSummary<'a> {
+    hash: &'a str,
+    size: u64,
+    duration: u64,
+    last_accessed: DateTime<Local>,
+}
+
+impl<'a> From<&'a CacheItem> for ItemSummary<'a> {
+    fn from(item: &'a CacheItem) -> Self {
+        Self {
+            hash: &item.hash,
+            size: item.size,
+            duration: item.duration,
+            last_accessed: item.last_accessed.into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactDetails<'a> {
+    hash: &'a str,
+    source: &'static str,
+    duration: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<FileSummary>>,
+}
+
+#[derive(Serialize)]
+struct FileSummary {
+    path: String,
+    size: u64,
+}
+
+impl From<ArchiveEntry> for FileSummary {
+    fn from(entry: ArchiveEntry) -> Self {
+        Self {
+            path: entry.path,
+            size: entry.size,
+        }
+    }
+}
+
+pub async fn run(
+    base: &CommandBase,
+    cache_dir: Option<&Utf8Path>,
+    command: &CacheCommand,
+) -> Result<i32, Error> {
+    let cache = local_cache(base, cache_dir)?;
+
+    match command {
+        CacheCommand::Prune { max_size, max_age } => {
+            let config = base.config()?;
+            let opts = eviction_opts(config, max_size.as_deref(), max_age.as_deref())?;
+            if !opts.has_limits() {
+                return Err(Error::NoLimits);
+            }
+            print_summary(base, &cache.prune(&opts)?);
+        }
+        CacheCommand::Clean => print_summary(base, &cache.clean()?),
+        CacheCommand::Ls { json } => ls(base, &cache, *json)?,
+        CacheCommand::Show { hash, json } => show(base, &cache, hash, *json).await?,
+        CacheCommand::Verify { hashes } => return verify(base, &cache, hashes),
+        CacheCommand::Export { hashes, output } => {
+            let path = AbsoluteSystemPathBuf::from_unknown(base.cwd(), output);
+            let exported = export(&cache, hashes, &path)?;
+            println!(
+                "Exported {} {} to {}",
+                exported.len(),
+                pluralize_artifacts(exported.len()),
+                path
+            );
+        }
+        CacheCommand::Import { bundle, force } => {
+            let path = AbsoluteSystemPathBuf::from_unknown(base.cwd(), bundle);
+            let file = path.open().map_err(|error| Error::Io {
+                path: path.clone(),
+                error,
+            })?;
+            let imported = cache.import(BufReader::new(file), *force)?;
+            println!(
+                "Imported {} {}",
+                imported.len(),
+                pluralize_artifacts(imported.len())
+            );
+        }
+    }
+
+    Ok(0)
+}
+
+// Opens the local cache, checking signatures if they're enabled for the
+// remote cache.
+fn local_cache(base: &CommandBase, cache_dir: Option<&Utf8Path>) -> Result<FSCache, Error> {
+    let cache = FSCache::new(cache_dir, &base.repo_root, None)?;
+    let config = base.config()?;
+    if !config.signature() {
+        return Ok(cache);
+    }
+
+    let team_id = config.team_id().unwrap_or_default();
+    Ok(
+        cache.with_signer_verifier(ArtifactSignatureAuthenticator::new(
+            team_id.as_bytes().to_vec(),
+            None,
+        )),
+    )
+}
+
+fn ls(base: &CommandBase, cache: &FSCache, json: bool) -> Result<(), Error> {
+    let mut items = cache.list()?;
+    // Most recently used first
+    items.sort_by(|a, b| b.last_accessed.cmp(&a.last_accessed));
+
+    if json {
+        let items = items.iter().map(ItemSummary::from).collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&items)?);
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        println!(
+            "{}",
+            base.ui
+                .apply(GREY.apply_to("No artifacts in the local cache"))
+        );
+        return Ok(());
+    }
+
+    let total_size = items.iter().map(|item| item.size).sum();
+    for item in &items {
+        let last_accessed: DateTime<Local> = item.last_accessed.into();
+        let last_accessed = last_accessed.format("last used %Y-%m-%d %H:%M");
+        let duration = format!("{}ms", item.duration);
+        println!(
+            "{}  {:>10}  {:>10}  {}",
+            item.hash,
+            format_bytes(item.size),
+            duration,
+            base.ui.apply(GREY.apply_to(last_accessed.to_string()))
+        );
+    }
+    println!(
+        "\n{} {} ({})",
+        items.len(),
+        pluralize_artifacts(items.len()),
+        format_bytes(total_size)
+    );
+
+    Ok(())
+}
+
+async fn show(base: &CommandBase, cache: &FSCache, hash: &str, json: bool) -> Result<(), Error> {
+    let details = if let Some(item) = cache.item(hash)? {
+        let files = cache.files(hash)?.unwrap_or_default();
+        ArtifactDetails {
+            hash,
+            source: source_name(CacheSource::Local),
+            duration: item.duration,
+            size: Some(item.size),
+            files: Some(files.into_iter().map(FileSummary::from).collect()),
+        }
+    } else {
+        let hit = match remote_cache(base)? {
+            Some(remote) => remote.exists(hash).await?,
+            None => None,
+        };
+        let Some(hit) = hit else {
+            return Err(Error::NotFound(hash.to_string()));
+        };
+        // Listing the files of a remote artifact would require downloading it
+        ArtifactDetails {
+            hash,
+            source: source_name(hit.source),
+            duration: hit.time_saved,
+            size: None,
+            files: None,
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&details)?);
+        return Ok(());
+    }
+
+    println!("{}", base.ui.apply(BOLD.apply_to(details.hash)));
+    println!("  Source   = {}", details.source);
+    println!("  Duration = {}ms", details.duration);
+    if let Some(size) = details.size {
+        println!("  Size     = {}", format_bytes(size));
+    }
+    if let Some(files) = &details.files {
+        println!("  Files    =");
+        for file in files {
+            println!(
+                "    {} {}",
+                file.path,
+                base.ui
+                    .apply(GREY.apply_to(format!("({})", format_bytes(file.size))))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn remote_cache(base: &CommandBase) -> Result<Option<HTTPCache>, Error> {
+    let config = base.config()?;
+    if !config.enabled() {
+        return Ok(None);
+    }
+
+    let opts = CacheOpts {
+        remote_cache_opts: Some(RemoteCacheOpts::new(
+            config.team_id().map(|team_id| team_id.to_string()),
+            config.signature(),
+        )),
+        remote_cache_backend: config.remote_cache_backend(&base.repo_root)?,
+        ..CacheOpts::default()
+    };
+    Ok(HTTPCache::from_opts(
+        &opts,
+        &base.repo_root,
+        base.api_client()?,
+        base.api_auth()?,
+        None,
+    )?)
+}
+
+fn verify(base: &CommandBase, cache: &FSCache, hashes: &[String]) -> Result<i32, Error> {
+    let hashes = if hashes.is_empty() {
+        cache.list()?.into_iter().map(|item| item.hash).collect()
+    } else {
+        hashes.to_vec()
+    };
+
+    let mut failures = 0;
+    for hash in &hashes {
+        let message = match cache.verify(hash)? {
+            Some(Verification::Valid) => "ok".to_string(),
+            Some(Verification::Unchecked) => {
+                "ok (written without a checksum, only checked that it is readable)".to_string()
+            }
+            Some(Verification::ChecksumMismatch) => {
+                failures += 1;
+                "checksum mismatch".to_string()
+            }
+            Some(Verification::MissingSignature) => {
+                failures += 1;
+                "missing signature".to_string()
+            }
+            Some(Verification::InvalidSignature) => {
+                failures += 1;
+                "invalid signature".to_string()
+            }
+            Some(Verification::Corrupt(reason)) => {
+                failures += 1;
+                format!("corrupt: {reason}")
+            }
+            None => {
+                failures += 1;
+                "not found".to_string()
+            }
+        };
+        println!("{hash}: {message}");
+    }
+
+    let summary = format!(
+        "Verified {} {}, {} failed",
+        hashes.len(),
+        pluralize_artifacts(hashes.len()),
+        failures
+    );
+    println!("{}", base.ui.apply(GREY.apply_to(summary)));
+
+    Ok(if failures > 0 { 1 } else { 0 })
+}
+
+// Writes the bundle to a temporary file that is only moved to `path` once the
+// export has succeeded, so that a failed export doesn't destroy an existing
+// file
+fn export(
+    cache: &FSCache,
+    hashes: &[String],
+    path: &AbsoluteSystemPath,
+) -> Result<Vec<String>, Error> {
+    let tmp_path = AbsoluteSystemPathBuf::from_unknown(path, format!("{path}.tmp"));
+    let exported = match write_bundle(cache, hashes, &tmp_path) {
+        Ok(exported) => exported,
+        Err(err) => {
+            // The temporary file may not have been created
+            tmp_path.remove_file().ok();
+            return Err(err);
+        }
+    };
+    tmp_path.rename(path).map_err(|error| Error::Io {
+        path: path.to_owned(),
+        error,
+    })?;
+
+    Ok(exported)
+}
+
+fn write_bundle(
+    cache: &FSCache,
+    hashes: &[String],
+    path: &AbsoluteSystemPath,
+) -> Result<Vec<String>, Error> {
+    let io_error = |error| Error::Io {
+        path: path.to_owned(),
+        error,
+    };
+    let mut writer = BufWriter::new(path.create().map_err(io_error)?);
+    let exported = cache.export(hashes, &mut writer)?;
+    writer.flush().map_err(io_error)?;
+
+    Ok(exported)
+}
+
+fn source_name(source: CacheSource) -> &'static str {
+    match source {
+        CacheSource::Local => "local",
+        CacheSource::Remote => "remote",
+    }
+}
+
+fn pluralize_artifacts(count: usize) -> &'static str {
+    if count == 1 {
+        "artifact"
+    } else {
+        "artifacts"
+    }
+}
+
+// Flags take precedence over the configured limits
+fn eviction_opts(
+    config: &ConfigurationOptions,
+    max_size: Option<&str>,
+    max_age: Option<&str>,
+) -> Result<EvictionOpts, config::Error> {
+    let mut opts = config.local_cache_eviction()?;
+    if let Some(max_size) = max_size {
+        opts.max_size = Some(
+            EvictionOpts::parse_size(max_size)
+                .ok_or_else(|| config::Error::InvalidLocalCacheMaxSize(max_size.to_string()))?,
+        );
+    }
+    if let Some(max_age) = max_age {
+        opts.max_age = Some(
+            EvictionOpts::parse_age(max_age)
+                .ok_or_else(|| config::Error::InvalidLocalCacheMaxAge(max_age.to_string()))?,
+        );
+    }
+
+    Ok(opts)
+}
+
+fn print_summary(base: &CommandBase, summary: &PruneSummary) {
+    println!(
+        "Removed {} {} ({})",
+        summary.removed_entries,
+        pluralize_entries(summary.removed_entries),
+        format_bytes(summary.removed_bytes)
+    );
+    let remaining = format!(
+        "{} {} ({}) remaining",
+        summary.remaining_entries,
+        pluralize_entries(summary.remaining_entries),
+        format_bytes(summary.remaining_bytes)
+    );
+    println!("{}", base.ui.apply(GREY.apply_to(remaining)));
+}
+
+fn pluralize_entries(count: usize) -> &'static str {
+    if count == 1 {
+        "entry"
+    } else {
+        "entries"
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use tempfile::tempdir;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(0, "0 B" ; "empty")]
+    #[test_case(1023, "1023 B" ; "bytes")]
+    #[test_case(1536, "1.5 KB" ; "kilobytes")]
+    #[test_case(10 * 1024 * 1024 * 1024, "10.0 GB" ; "gigabytes")]
+    fn test_format_bytes(bytes: u64, expected: &str) {
+        assert_eq!(format_bytes(bytes), expected);
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let config = ConfigurationOptions {
+            local_cache_max_size: Some("1GB".into()),
+            local_cache_max_age: Some("7d".into()),
+            ..Default::default()
+        };
+
+        let opts = eviction_opts(&config, None, Some("1h")).unwrap();
+        assert_eq!(opts.max_size, Some(1024 * 1024 * 1024));
+        assert_eq!(opts.max_age, Some(Duration::from_secs(60 * 60)));
+
+        assert!(eviction_opts(&config, Some("big"), None).is_err());
+    }
+
+    #[test]
+    fn test_failed_export_keeps_existing_file() {
+        let dir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(dir.path()).unwrap();
+        let cache = FSCache::new(None, repo_root, None).unwrap();
+        let path = repo_root.join_component("bundle.tar");
+        path.create_with_contents("existing bundle").unwrap();
+
+        let result = export(&cache, &["missing".to_string()], &path);
+        assert!(matches!(
+            result,
+            Err(Error::Cache(CacheError::ArtifactNotFound(_)))
+        ));
+        assert_eq!(path.read_to_string().unwrap(), "existing bundle");
+        assert!(!repo_root.join_component("bundle.tar.tmp").exists());
+
+        let exported = export(&cache, &[], &path).unwrap();
+        assert!(exported.is_empty());
+        assert_ne!(path.read_to_string().unwrap(), "existing bundle");
+    }
+}
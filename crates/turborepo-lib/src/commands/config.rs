@@ -0,0 +1,164 @@
+//! Prints the resolved configuration along with the layer that set each
+//! option: package.json, turbo.json, the global and local config files,
+//! environment variables or flags. Tokens are never printed.
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use turborepo_ui::{BOLD, GREY};
+
+use crate::{
+    commands::CommandBase,
+    config::{self, ConfigSource},
+};
+
+const REDACTED: &str = "********";
+const DEFAULT_CACHE_DIR: &str = "node_modules/.cache/turbo";
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error("failed to serialize the configuration: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigOption {
+    value: Value,
+    source: ConfigSource,
+}
+
+// Serialized as a map that keeps the order the options are listed in
+#[derive(Debug)]
+struct ConfigOptions(Vec<(&'static str, ConfigOption)>);
+
+impl Serialize for ConfigOptions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(name, option)| (name, option)))
+    }
+}
+
+pub fn run(base: &CommandBase, json: bool) -> Result<(), Error> {
+    let options = resolve(base, std::env::var("TURBO_CACHE_DIR").ok())?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&options)?);
+        return Ok(());
+    }
+
+    let width = options
+        .0
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default();
+    for (name, option) in &options.0 {
+        let value = match &option.value {
+            Value::Null => base.ui.apply(GREY.apply_to("not set")).to_string(),
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        println!(
+            "{}  {value} {}",
+            base.ui.apply(BOLD.apply_to(format!("{name:width$}"))),
+            base.ui.apply(GREY.apply_to(format!("({})", option.source)))
+        );
+    }
+
+    Ok(())
+}
+
+fn resolve(base: &CommandBase, cache_dir: Option<String>) -> Result<ConfigOptions, Error> {
+    let (config, sources) = base.config_with_sources()?;
+    let redact = |token: Option<&str>| token.map(|_| REDACTED);
+
+    let mut options = [
+        ("api_url", json!(config.api_url())),
+        ("login_url", json!(config.login_url())),
+        ("team_slug", json!(config.team_slug())),
+        ("team_id", json!(config.team_id())),
+        ("token", json!(redact(config.token()))),
+        ("signature", json!(config.signature())),
+        ("preflight", json!(config.preflight())),
+        ("timeout", json!(config.timeout())),
+        ("enabled", json!(config.enabled())),
+        ("spaces_id", json!(config.spaces_id())),
+        ("experimental_ui", json!(config.experimental_ui())),
+        ("local_cache_max_size", json!(config.local_cache_max_size)),
+        ("local_cache_max_age", json!(config.local_cache_max_age)),
+        ("backend", json!(config.backend)),
+        ("endpoint", json!(config.endpoint)),
+        ("bucket", json!(config.bucket)),
+        ("region", json!(config.region)),
+        ("prefix", json!(config.prefix)),
+        ("path", json!(config.path)),
+        ("http_token", json!(redact(config.http_token()))),
+    ]
+    .into_iter()
+    .map(|(name, value)| {
+        let source = sources.get(name).copied().unwrap_or(ConfigSource::Default);
+        (name, ConfigOption { value, source })
+    })
+    .collect::<Vec<_>>();
+
+    // The cache directory is set per command rather than through the layered
+    // configuration, so only TURBO_CACHE_DIR applies to every run
+    let cache_dir = cache_dir.filter(|dir| !dir.is_empty());
+    options.push((
+        "cache_dir",
+        ConfigOption {
+            source: match cache_dir {
+                Some(_) => ConfigSource::Env,
+                None => ConfigSource::Default,
+            },
+            value: json!(cache_dir.as_deref().unwrap_or(DEFAULT_CACHE_DIR)),
+        },
+    ));
+
+    Ok(ConfigOptions(options))
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use tempfile::TempDir;
+    use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_ui::UI;
+
+    use super::*;
+    use crate::Args;
+
+    #[test]
+    fn test_tokens_are_redacted() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path())?;
+        let global_config_path =
+            AbsoluteSystemPathBuf::try_from(TempDir::new()?.path().join("nonexistent.json"))?;
+        let args = Args {
+            token: Some("my-secret-token".to_string()),
+            team: Some("my-team".to_string()),
+            ..Default::default()
+        };
+        let base = CommandBase::new(args, repo_root, "1.0.0", UI::new(true))
+            .with_global_config_path(global_config_path);
+
+        let options = resolve(&base, Some("/tmp/cache".to_string()))?;
+        let option = |name: &str| {
+            &options
+                .0
+                .iter()
+                .find(|(option, _)| *option == name)
+                .unwrap()
+                .1
+        };
+
+        assert_eq!(option("token").value, json!(REDACTED));
+        assert_eq!(option("token").source, ConfigSource::Flag);
+        assert_eq!(option("team_slug").value, json!("my-team"));
+        assert_eq!(option("api_url").source, ConfigSource::Default);
+        assert_eq!(option("cache_dir").value, json!("/tmp/cache"));
+        assert_eq!(option("cache_dir").source, ConfigSource::Env);
+        assert!(!serde_json::to_string(&options)?.contains("my-secret-token"));
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::cell::OnceCell;
+use std::{cell::OnceCell, collections::HashMap};
 
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 use turborepo_api_client::{APIAuth, APIClient};
@@ -7,11 +7,14 @@ use turborepo_dirs::config_dir;
 use turborepo_ui::UI;
 
 use crate::{
-    config::{ConfigurationOptions, Error as ConfigError, TurborepoConfigBuilder},
+    config::{ConfigSource, ConfigurationOptions, Error as ConfigError, TurborepoConfigBuilder},
     Args,
 };
 
 pub(crate) mod bin;
+pub(crate) mod boundaries;
+pub(crate) mod cache;
+pub(crate) mod config;
 pub(crate) mod daemon;
 pub(crate) mod generate;
 pub(crate) mod info;
@@ -23,6 +26,8 @@ pub(crate) mod run;
 pub(crate) mod scan;
 pub(crate) mod telemetry;
 pub(crate) mod unlink;
+pub(crate) mod watch;
+pub(crate) mod why;
 
 #[derive(Debug)]
 pub struct CommandBase {
@@ -60,6 +65,10 @@ impl CommandBase {
     }
 
     fn config_init(&self) -> Result<ConfigurationOptions, ConfigError> {
+        self.config_builder().build()
+    }
+
+    fn config_builder(&self) -> TurborepoConfigBuilder {
         TurborepoConfigBuilder::new(self)
             // The below should be deprecated and removed.
             .with_api_url(self.args.api.clone())
@@ -69,13 +78,20 @@ impl CommandBase {
             .with_timeout(self.args.remote_cache_timeout)
             .with_preflight(self.args.preflight.then_some(true))
             .with_experimental_ui(self.args.experimental_ui.then_some(true))
-            .build()
     }
 
     pub fn config(&self) -> Result<&ConfigurationOptions, ConfigError> {
         self.config.get_or_try_init(|| self.config_init())
     }
 
+    /// Resolves the configuration again, keeping track of which source set
+    /// each option.
+    pub fn config_with_sources(
+        &self,
+    ) -> Result<(ConfigurationOptions, HashMap<&'static str, ConfigSource>), ConfigError> {
+        self.config_builder().build_with_sources()
+    }
+
     // Getting all of the paths.
     fn global_config_path(&self) -> Result<AbsoluteSystemPathBuf, ConfigError> {
         #[cfg(test)]
@@ -47,8 +47,11 @@ pub enum Error {
     MissingWorkspace(PackageName),
     #[error("Cannot prune without parsed lockfile")]
     MissingLockfile,
-    #[error("Prune is not supported for Bun")]
-    BunUnsupported,
+    #[error(
+        "Prune is not supported for Bun's binary lockfile. Run `bun install --save-text-lockfile` \
+         to switch to bun.lock."
+    )]
+    BunBinaryLockfile,
 }
 
 // Files that should be copied from root and if they're required for install
@@ -98,11 +101,12 @@ pub async fn prune(
 
     let prune = Prune::new(base, scope, docker, output_dir).await?;
 
-    if matches!(
-        prune.package_graph.package_manager(),
-        turborepo_repository::package_manager::PackageManager::Bun
-    ) {
-        return Err(Error::BunUnsupported);
+    if prune
+        .package_graph
+        .package_manager()
+        .has_binary_lockfile(&prune.root)
+    {
+        return Err(Error::BunBinaryLockfile);
     }
 
     println!(
@@ -163,13 +167,19 @@ pub async fn prune(
         .subgraph(&workspace_paths, &lockfile_keys)?;
 
     let lockfile_contents = lockfile.encode()?;
-    let lockfile_name = prune.package_graph.package_manager().lockfile_name();
-    let lockfile_path = prune.out_directory.join_component(lockfile_name);
+    let lockfile_name = prune
+        .package_graph
+        .package_manager()
+        .lockfile_path(&prune.root)
+        .file_name()
+        .expect("lockfile path has a file name")
+        .to_string();
+    let lockfile_path = prune.out_directory.join_component(&lockfile_name);
     lockfile_path.create_with_contents(&lockfile_contents)?;
     if prune.docker {
         prune
             .docker_directory()
-            .join_component(lockfile_name)
+            .join_component(&lockfile_name)
             .create_with_contents(&lockfile_contents)?;
     }
 
@@ -1,21 +1,23 @@
+use std::future::Future;
+
 use turborepo_telemetry::events::command::CommandEventBuilder;
 
 use crate::{commands::CommandBase, run, run::builder::RunBuilder, signal::SignalHandler};
 
-pub async fn run(base: CommandBase, telemetry: CommandEventBuilder) -> Result<i32, run::Error> {
+pub(crate) fn get_signal() -> Result<impl Future<Output = Option<()>>, run::Error> {
     #[cfg(windows)]
-    let signal = {
+    {
         let mut ctrl_c = tokio::signal::windows::ctrl_c().map_err(run::Error::SignalHandler)?;
-        async move { ctrl_c.recv().await }
-    };
+        Ok(async move { ctrl_c.recv().await })
+    }
     #[cfg(not(windows))]
-    let signal = {
+    {
         use tokio::signal::unix;
         let mut sigint =
             unix::signal(unix::SignalKind::interrupt()).map_err(run::Error::SignalHandler)?;
         let mut sigterm =
             unix::signal(unix::SignalKind::terminate()).map_err(run::Error::SignalHandler)?;
-        async move {
+        Ok(async move {
             tokio::select! {
                 res = sigint.recv() => {
                     res
@@ -24,9 +26,12 @@ pub async fn run(base: CommandBase, telemetry: CommandEventBuilder) -> Result<i3
                     res
                 }
             }
-        }
-    };
+        })
+    }
+}
 
+pub async fn run(base: CommandBase, telemetry: CommandEventBuilder) -> Result<i32, run::Error> {
+    let signal = get_signal()?;
     let handler = SignalHandler::new(signal);
 
     let api_auth = base.api_auth()?;
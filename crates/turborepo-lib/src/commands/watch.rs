@@ -0,0 +1,32 @@
+use turborepo_telemetry::events::command::CommandEventBuilder;
+
+use crate::{
+    cli::RunArgs,
+    commands::{run::get_signal, CommandBase},
+    run::watch::{Error, WatchClient},
+    signal::SignalHandler,
+};
+
+pub async fn run(
+    base: CommandBase,
+    run_args: RunArgs,
+    telemetry: CommandEventBuilder,
+) -> Result<i32, Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+
+    let client = WatchClient::new(base, run_args, handler.clone(), telemetry)?;
+    let watch_fut = client.start();
+
+    tokio::select! {
+        biased;
+        _ = handler.done() => {
+            // We caught a signal, which already notified the subscribers
+            Ok(0)
+        }
+        result = watch_fut => {
+            handler.close().await;
+            result.map(|_| 0)
+        },
+    }
+}
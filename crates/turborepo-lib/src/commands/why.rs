@@ -0,0 +1,31 @@
+//! Explains why a task missed the cache in its last run, using the hash
+//! inputs that `turbo run` records for each task
+use turborepo_ui::{cprintln, BOLD};
+
+use crate::{
+    commands::CommandBase,
+    run::summary::{self, print_explanation, HashInputsStore},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to read the recorded task hash inputs: {0}")]
+    HashInputs(#[from] summary::Error),
+    #[error("{0} hasn't been run in this repository. Run it with `turbo run` first")]
+    NotRun(String),
+}
+
+pub fn run(base: &CommandBase, task: &str) -> Result<(), Error> {
+    let store = HashInputsStore::load(&base.repo_root)?;
+    let record = store
+        .get(task)
+        .ok_or_else(|| Error::NotRun(task.to_string()))?;
+
+    if record.hit {
+        cprintln!(base.ui, BOLD, "{task} hit the cache in its last run");
+        return Ok(());
+    }
+    print_explanation(base.ui, task, &record.explain());
+
+    Ok(())
+}
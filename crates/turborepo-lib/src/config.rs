@@ -1,12 +1,13 @@
-use std::{collections::HashMap, ffi::OsString, io};
+use std::{any::Any, collections::HashMap, ffi::OsString, fmt, io};
 
 use convert_case::{Case, Casing};
 use miette::{Diagnostic, NamedSource, SourceSpan};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use struct_iterable::Iterable;
 use thiserror::Error;
-use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPath};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath};
 use turborepo_auth::{TURBO_TOKEN_DIR, TURBO_TOKEN_FILE, VERCEL_TOKEN_DIR, VERCEL_TOKEN_FILE};
+use turborepo_cache::{fs::EvictionOpts, remote::RemoteCacheBackendOpts};
 use turborepo_dirs::{config_dir, vercel_config_dir};
 use turborepo_errors::TURBO_SITE;
 use turborepo_repository::package_json::{Error as PackageJsonError, PackageJson};
@@ -96,9 +97,26 @@ pub enum Error {
         #[source_code]
         text: NamedSource,
     },
-    #[error("You can only extend from the root workspace")]
-    ExtendFromNonRoot {
-        #[label("non-root workspace found here")]
+    #[error("Cannot extend from \"{package}\": package not found")]
+    ExtendsMissingPackage {
+        package: String,
+        #[label("extended here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("Cannot extend from \"{package}\": package has no turbo.json")]
+    ExtendsMissingTurboJson {
+        package: String,
+        #[label("extended here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("Cyclic \"extends\" found: {cycle}")]
+    ExtendsCycle {
+        cycle: String,
+        #[label("cycle closed here")]
         span: Option<SourceSpan>,
         #[source_code]
         text: NamedSource,
@@ -118,6 +136,36 @@ pub enum Error {
         #[source_code]
         text: NamedSource,
     },
+    #[error("Pool \"{pool}\" must have a size of at least 1")]
+    InvalidPoolSize {
+        pool: String,
+        #[label("size set here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("\"with\" cannot contain topological dependencies")]
+    TopologicalSidecar {
+        #[label("sidecar declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("\"readiness\" must set exactly one of \"tcp\", \"http\" or \"stdout\"")]
+    InvalidReadiness {
+        #[label("readiness set here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("Invalid \"stdout\" readiness pattern: {reason}")]
+    InvalidReadinessPattern {
+        reason: String,
+        #[label("pattern set here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
     #[error("Tasks cannot be marked as interactive and cacheable")]
     InteractiveNoCacheable {
         #[label("marked interactive here")]
@@ -137,6 +185,19 @@ pub enum Error {
     InvalidRemoteCacheTimeout(#[source] std::num::ParseIntError),
     #[error("TURBO_PREFLIGHT should be either 1 or 0.")]
     InvalidPreflight,
+    #[error("Invalid local cache max size \"{0}\". Expected a size such as 500MB or 10GB.")]
+    InvalidLocalCacheMaxSize(String),
+    #[error("Invalid local cache max age \"{0}\". Expected an age such as 12h or 7d.")]
+    InvalidLocalCacheMaxAge(String),
+    #[error(
+        "Invalid remote cache backend \"{0}\". Expected one of vercel, s3, directory or http."
+    )]
+    InvalidRemoteCacheBackend(String),
+    #[error("The {backend} remote cache backend requires `{option}` to be set.")]
+    MissingRemoteCacheOption {
+        backend: &'static str,
+        option: &'static str,
+    },
     #[error(transparent)]
     #[diagnostic(transparent)]
     TurboJsonParseError(#[from] turbo_json::parser::Error),
@@ -154,6 +215,7 @@ macro_rules! create_builder {
 const DEFAULT_API_URL: &str = "https://vercel.com/api";
 const DEFAULT_LOGIN_URL: &str = "https://vercel.com";
 const DEFAULT_TIMEOUT: u64 = 30;
+const DEFAULT_S3_REGION: &str = "us-east-1";
 
 // We intentionally don't derive Serialize so that different parts
 // of the code that want to display the config can tune how they
@@ -185,6 +247,48 @@ pub struct ConfigurationOptions {
     pub(crate) spaces_id: Option<String>,
     #[serde(rename = "experimentalUI")]
     pub(crate) experimental_ui: Option<bool>,
+    pub(crate) local_cache_max_size: Option<String>,
+    pub(crate) local_cache_max_age: Option<String>,
+    pub(crate) backend: Option<String>,
+    pub(crate) endpoint: Option<String>,
+    pub(crate) bucket: Option<String>,
+    pub(crate) region: Option<String>,
+    pub(crate) prefix: Option<String>,
+    pub(crate) path: Option<String>,
+    // The token sent to an `http` remote cache backend. It's only read from
+    // TURBO_REMOTE_CACHE_HTTP_TOKEN so that a Vercel token is never sent to a
+    // third party server.
+    #[serde(skip)]
+    pub(crate) http_token: Option<String>,
+}
+
+/// The layer of configuration that set an option
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigSource {
+    Default,
+    PackageJson,
+    TurboJson,
+    GlobalConfig,
+    GlobalAuth,
+    LocalConfig,
+    Env,
+    Flag,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConfigSource::Default => "default",
+            ConfigSource::PackageJson => "package.json",
+            ConfigSource::TurboJson => "turbo.json",
+            ConfigSource::GlobalConfig => "global config",
+            ConfigSource::GlobalAuth => "global auth",
+            ConfigSource::LocalConfig => ".turbo/config.json",
+            ConfigSource::Env => "environment variable",
+            ConfigSource::Flag => "flag",
+        })
+    }
 }
 
 #[derive(Default)]
@@ -222,6 +326,10 @@ impl ConfigurationOptions {
         non_empty_str(self.token.as_deref())
     }
 
+    pub fn http_token(&self) -> Option<&str> {
+        non_empty_str(self.http_token.as_deref())
+    }
+
     pub fn signature(&self) -> bool {
         self.signature.unwrap_or_default()
     }
@@ -245,6 +353,67 @@ impl ConfigurationOptions {
     pub fn experimental_ui(&self) -> bool {
         self.experimental_ui.unwrap_or_default() && atty::is(atty::Stream::Stdout)
     }
+
+    pub fn local_cache_eviction(&self) -> Result<EvictionOpts, Error> {
+        let max_size = non_empty_str(self.local_cache_max_size.as_deref())
+            .map(|size| {
+                EvictionOpts::parse_size(size)
+                    .ok_or_else(|| Error::InvalidLocalCacheMaxSize(size.to_string()))
+            })
+            .transpose()?;
+        let max_age = non_empty_str(self.local_cache_max_age.as_deref())
+            .map(|age| {
+                EvictionOpts::parse_age(age)
+                    .ok_or_else(|| Error::InvalidLocalCacheMaxAge(age.to_string()))
+            })
+            .transpose()?;
+        Ok(EvictionOpts { max_size, max_age })
+    }
+
+    /// Resolves the backend the remote cache should use. Relative directory
+    /// paths are resolved against the repository root.
+    pub fn remote_cache_backend(
+        &self,
+        repo_root: &AbsoluteSystemPath,
+    ) -> Result<RemoteCacheBackendOpts, Error> {
+        let endpoint = non_empty_str(self.endpoint.as_deref()).map(|s| s.to_string());
+        match non_empty_str(self.backend.as_deref()).unwrap_or("vercel") {
+            "vercel" => Ok(RemoteCacheBackendOpts::Vercel),
+            "s3" => Ok(RemoteCacheBackendOpts::S3 {
+                endpoint,
+                bucket: non_empty_str(self.bucket.as_deref())
+                    .ok_or(Error::MissingRemoteCacheOption {
+                        backend: "s3",
+                        option: "bucket",
+                    })?
+                    .to_string(),
+                region: non_empty_str(self.region.as_deref())
+                    .unwrap_or(DEFAULT_S3_REGION)
+                    .to_string(),
+                prefix: non_empty_str(self.prefix.as_deref()).map(|s| s.to_string()),
+                timeout: self.timeout(),
+            }),
+            "directory" => {
+                let path =
+                    non_empty_str(self.path.as_deref()).ok_or(Error::MissingRemoteCacheOption {
+                        backend: "directory",
+                        option: "path",
+                    })?;
+                Ok(RemoteCacheBackendOpts::Directory {
+                    path: AbsoluteSystemPathBuf::from_unknown(repo_root, path),
+                })
+            }
+            "http" => Ok(RemoteCacheBackendOpts::Http {
+                url: endpoint.ok_or(Error::MissingRemoteCacheOption {
+                    backend: "http",
+                    option: "endpoint",
+                })?,
+                token: self.http_token().map(|token| token.to_string()),
+                timeout: self.timeout(),
+            }),
+            backend => Err(Error::InvalidRemoteCacheBackend(backend.to_string())),
+        }
+    }
 }
 
 // Maps Some("") to None to emulate how Go handles empty strings
@@ -314,6 +483,24 @@ fn get_env_var_config(
     turbo_mapping.insert(OsString::from("turbo_remote_cache_timeout"), "timeout");
     turbo_mapping.insert(OsString::from("turbo_experimental_ui"), "experimental_ui");
     turbo_mapping.insert(OsString::from("turbo_preflight"), "preflight");
+    turbo_mapping.insert(
+        OsString::from("turbo_local_cache_max_size"),
+        "local_cache_max_size",
+    );
+    turbo_mapping.insert(
+        OsString::from("turbo_local_cache_max_age"),
+        "local_cache_max_age",
+    );
+    turbo_mapping.insert(OsString::from("turbo_remote_cache_backend"), "backend");
+    turbo_mapping.insert(OsString::from("turbo_remote_cache_endpoint"), "endpoint");
+    turbo_mapping.insert(OsString::from("turbo_remote_cache_bucket"), "bucket");
+    turbo_mapping.insert(OsString::from("turbo_remote_cache_region"), "region");
+    turbo_mapping.insert(OsString::from("turbo_remote_cache_prefix"), "prefix");
+    turbo_mapping.insert(OsString::from("turbo_remote_cache_path"), "path");
+    turbo_mapping.insert(
+        OsString::from("turbo_remote_cache_http_token"),
+        "http_token",
+    );
 
     // We do not enable new config sources:
     // turbo_mapping.insert(String::from("turbo_signature"), "signature"); // new
@@ -403,6 +590,15 @@ fn get_env_var_config(
         team_slug: output_map.get("team_slug").cloned(),
         team_id: output_map.get("team_id").cloned(),
         token: output_map.get("token").cloned(),
+        local_cache_max_size: output_map.get("local_cache_max_size").cloned(),
+        local_cache_max_age: output_map.get("local_cache_max_age").cloned(),
+        backend: output_map.get("backend").cloned(),
+        endpoint: output_map.get("endpoint").cloned(),
+        bucket: output_map.get("bucket").cloned(),
+        region: output_map.get("region").cloned(),
+        prefix: output_map.get("prefix").cloned(),
+        path: output_map.get("path").cloned(),
+        http_token: output_map.get("http_token").cloned(),
 
         // Processed booleans
         signature,
@@ -458,6 +654,15 @@ fn get_override_env_var_config(
         experimental_ui: None,
         timeout: None,
         spaces_id: None,
+        local_cache_max_size: None,
+        local_cache_max_age: None,
+        backend: None,
+        endpoint: None,
+        bucket: None,
+        region: None,
+        prefix: None,
+        path: None,
+        http_token: None,
     };
 
     Ok(output)
@@ -593,8 +798,19 @@ impl TurborepoConfigBuilder {
     create_builder!(with_preflight, preflight, Option<bool>);
     create_builder!(with_timeout, timeout, Option<u64>);
     create_builder!(with_experimental_ui, experimental_ui, Option<bool>);
-
-    pub fn build(&self) -> Result<ConfigurationOptions, Error> {
+    create_builder!(
+        with_local_cache_max_size,
+        local_cache_max_size,
+        Option<String>
+    );
+    create_builder!(
+        with_local_cache_max_age,
+        local_cache_max_age,
+        Option<String>
+    );
+
+    // Each layer of configuration along with where it came from
+    fn sources(&self) -> Result<Vec<(ConfigSource, ConfigurationOptions)>, Error> {
         // Priority, from least significant to most significant:
         // - shared configuration (package.json .turbo)
         // - shared configuration (turbo.json)
@@ -634,71 +850,156 @@ impl TurborepoConfigBuilder {
         let env_var_config = get_env_var_config(&env_vars)?;
         let override_env_var_config = get_override_env_var_config(&env_vars)?;
 
-        let sources = [
-            root_package_json.get_configuration_options(),
-            turbo_json.get_configuration_options(),
-            global_config.get_configuration_options(),
-            global_auth.get_configuration_options(),
-            local_config.get_configuration_options(),
-            env_var_config.get_configuration_options(),
-            Ok(self.override_config.clone()),
-            override_env_var_config.get_configuration_options(),
-        ];
-
-        sources.into_iter().try_fold(
+        Ok(vec![
+            (
+                ConfigSource::PackageJson,
+                root_package_json.get_configuration_options()?,
+            ),
+            (
+                ConfigSource::TurboJson,
+                turbo_json.get_configuration_options()?,
+            ),
+            (
+                ConfigSource::GlobalConfig,
+                global_config.get_configuration_options()?,
+            ),
+            (
+                ConfigSource::GlobalAuth,
+                global_auth.get_configuration_options()?,
+            ),
+            (
+                ConfigSource::LocalConfig,
+                local_config.get_configuration_options()?,
+            ),
+            (
+                ConfigSource::Env,
+                env_var_config.get_configuration_options()?,
+            ),
+            (ConfigSource::Flag, self.override_config.clone()),
+            (
+                ConfigSource::Env,
+                override_env_var_config.get_configuration_options()?,
+            ),
+        ])
+    }
+
+    pub fn build(&self) -> Result<ConfigurationOptions, Error> {
+        Ok(self
+            .sources()?
+            .into_iter()
+            .fold(ConfigurationOptions::default(), |acc, (_, config)| {
+                acc.merge(config)
+            }))
+    }
+
+    /// Builds the configuration along with the source that set each option.
+    /// Options that aren't in the returned map weren't set by any source.
+    pub fn build_with_sources(
+        &self,
+    ) -> Result<(ConfigurationOptions, HashMap<&'static str, ConfigSource>), Error> {
+        let mut option_sources = HashMap::new();
+        let config = self.sources()?.into_iter().fold(
             ConfigurationOptions::default(),
-            |mut acc, current_source| {
-                current_source.map(|current_source_config| {
-                    if let Some(api_url) = current_source_config.api_url.clone() {
-                        acc.api_url = Some(api_url);
-                    }
-                    if let Some(login_url) = current_source_config.login_url.clone() {
-                        acc.login_url = Some(login_url);
-                    }
-                    if let Some(team_slug) = current_source_config.team_slug.clone() {
-                        acc.team_slug = Some(team_slug);
-                    }
-                    if let Some(team_id) = current_source_config.team_id.clone() {
-                        acc.team_id = Some(team_id);
-                    }
-                    if let Some(token) = current_source_config.token.clone() {
-                        acc.token = Some(token);
-                    }
-                    if let Some(signature) = current_source_config.signature {
-                        acc.signature = Some(signature);
-                    }
-                    if let Some(enabled) = current_source_config.enabled {
-                        acc.enabled = Some(enabled);
-                    }
-                    if let Some(preflight) = current_source_config.preflight {
-                        acc.preflight = Some(preflight);
-                    }
-                    if let Some(timeout) = current_source_config.timeout {
-                        acc.timeout = Some(timeout);
+            |acc, (source, config)| {
+                for (option, value) in config.iter() {
+                    if is_set(value) {
+                        option_sources.insert(option, source);
                     }
-                    if let Some(spaces_id) = current_source_config.spaces_id {
-                        acc.spaces_id = Some(spaces_id);
-                    }
-                    if let Some(experimental_ui) = current_source_config.experimental_ui {
-                        acc.experimental_ui = Some(experimental_ui);
-                    }
-
-                    acc
-                })
+                }
+                acc.merge(config)
             },
-        )
+        );
+        Ok((config, option_sources))
     }
 }
 
+impl ConfigurationOptions {
+    // Overrides the options that are set in `other`
+    fn merge(mut self, other: ConfigurationOptions) -> Self {
+        if let Some(api_url) = other.api_url {
+            self.api_url = Some(api_url);
+        }
+        if let Some(login_url) = other.login_url {
+            self.login_url = Some(login_url);
+        }
+        if let Some(team_slug) = other.team_slug {
+            self.team_slug = Some(team_slug);
+        }
+        if let Some(team_id) = other.team_id {
+            self.team_id = Some(team_id);
+        }
+        if let Some(token) = other.token {
+            self.token = Some(token);
+        }
+        if let Some(signature) = other.signature {
+            self.signature = Some(signature);
+        }
+        if let Some(enabled) = other.enabled {
+            self.enabled = Some(enabled);
+        }
+        if let Some(preflight) = other.preflight {
+            self.preflight = Some(preflight);
+        }
+        if let Some(timeout) = other.timeout {
+            self.timeout = Some(timeout);
+        }
+        if let Some(spaces_id) = other.spaces_id {
+            self.spaces_id = Some(spaces_id);
+        }
+        if let Some(experimental_ui) = other.experimental_ui {
+            self.experimental_ui = Some(experimental_ui);
+        }
+        if let Some(local_cache_max_size) = other.local_cache_max_size {
+            self.local_cache_max_size = Some(local_cache_max_size);
+        }
+        if let Some(local_cache_max_age) = other.local_cache_max_age {
+            self.local_cache_max_age = Some(local_cache_max_age);
+        }
+        if let Some(backend) = other.backend {
+            self.backend = Some(backend);
+        }
+        if let Some(endpoint) = other.endpoint {
+            self.endpoint = Some(endpoint);
+        }
+        if let Some(bucket) = other.bucket {
+            self.bucket = Some(bucket);
+        }
+        if let Some(region) = other.region {
+            self.region = Some(region);
+        }
+        if let Some(prefix) = other.prefix {
+            self.prefix = Some(prefix);
+        }
+        if let Some(path) = other.path {
+            self.path = Some(path);
+        }
+        if let Some(http_token) = other.http_token {
+            self.http_token = Some(http_token);
+        }
+
+        self
+    }
+}
+
+fn is_set(value: &dyn Any) -> bool {
+    value
+        .downcast_ref::<Option<String>>()
+        .map(Option::is_some)
+        .or_else(|| value.downcast_ref::<Option<bool>>().map(Option::is_some))
+        .or_else(|| value.downcast_ref::<Option<u64>>().map(Option::is_some))
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod test {
-    use std::{collections::HashMap, ffi::OsString};
+    use std::{collections::HashMap, ffi::OsString, time::Duration};
 
     use tempfile::TempDir;
     use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_cache::remote::RemoteCacheBackendOpts;
 
     use crate::config::{
-        get_env_var_config, get_override_env_var_config, ConfigurationOptions,
+        get_env_var_config, get_override_env_var_config, ConfigSource, ConfigurationOptions,
         TurborepoConfigBuilder, DEFAULT_API_URL, DEFAULT_LOGIN_URL, DEFAULT_TIMEOUT,
     };
 
@@ -739,9 +1040,11 @@ mod test {
         );
         env.insert("turbo_experimental_ui".into(), "true".into());
         env.insert("turbo_preflight".into(), "true".into());
+        env.insert("turbo_remote_cache_http_token".into(), "http-token".into());
 
         let config = get_env_var_config(&env).unwrap();
         assert!(config.preflight());
+        assert_eq!(config.http_token(), Some("http-token"));
         assert_eq!(turbo_api, config.api_url.unwrap());
         assert_eq!(turbo_login, config.login_url.unwrap());
         assert_eq!(turbo_team, config.team_slug.unwrap());
@@ -842,4 +1145,172 @@ mod test {
         assert_eq!(config.token().unwrap(), vercel_artifacts_token);
         assert_eq!(config.spaces_id().unwrap(), "my-spaces-id");
     }
+
+    #[test]
+    fn test_build_with_sources() {
+        let tmp_dir = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+        let global_config_path = AbsoluteSystemPathBuf::try_from(
+            TempDir::new().unwrap().path().join("nonexistent.json"),
+        )
+        .unwrap();
+
+        repo_root
+            .join_component("turbo.json")
+            .create_with_contents(r#"{"remoteCache": {"timeout": 10, "signature": true}}"#)
+            .unwrap();
+        repo_root
+            .join_components(&[".turbo", "config.json"])
+            .ensure_dir()
+            .unwrap();
+        repo_root
+            .join_components(&[".turbo", "config.json"])
+            .create_with_contents(r#"{"teamslug": "local-team", "timeout": 20}"#)
+            .unwrap();
+
+        let mut env: HashMap<OsString, OsString> = HashMap::new();
+        env.insert("turbo_token".into(), "abcdef1234567890abcdef".into());
+
+        let builder = TurborepoConfigBuilder {
+            repo_root,
+            override_config: ConfigurationOptions {
+                team_slug: Some("flag-team".into()),
+                ..Default::default()
+            },
+            global_config_path: Some(global_config_path),
+            environment: env,
+        };
+
+        let (config, sources) = builder.build_with_sources().unwrap();
+        assert_eq!(config, builder.build().unwrap());
+        assert_eq!(config.timeout(), 20);
+        assert_eq!(sources.get("timeout"), Some(&ConfigSource::LocalConfig));
+        assert_eq!(sources.get("signature"), Some(&ConfigSource::TurboJson));
+        assert_eq!(sources.get("team_slug"), Some(&ConfigSource::Flag));
+        assert_eq!(sources.get("token"), Some(&ConfigSource::Env));
+        assert_eq!(sources.get("api_url"), None);
+    }
+
+    #[test]
+    fn test_local_cache_eviction() {
+        let tmp_dir = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+        let global_config_path = AbsoluteSystemPathBuf::try_from(
+            TempDir::new().unwrap().path().join("nonexistent.json"),
+        )
+        .unwrap();
+
+        repo_root
+            .join_component("turbo.json")
+            .create_with_contents(
+                r#"{"remoteCache": {"localCacheMaxSize": "1GB", "localCacheMaxAge": "1d"}}"#,
+            )
+            .unwrap();
+
+        let mut env: HashMap<OsString, OsString> = HashMap::new();
+        env.insert("turbo_local_cache_max_age".into(), "2h".into());
+
+        let builder = TurborepoConfigBuilder {
+            repo_root,
+            override_config: Default::default(),
+            global_config_path: Some(global_config_path),
+            environment: env,
+        };
+
+        let config = builder.build().unwrap();
+        let eviction = config.local_cache_eviction().unwrap();
+        assert_eq!(eviction.max_size, Some(1024 * 1024 * 1024));
+        assert_eq!(eviction.max_age, Some(Duration::from_secs(2 * 60 * 60)));
+
+        let invalid = ConfigurationOptions {
+            local_cache_max_size: Some("lots".into()),
+            ..Default::default()
+        };
+        assert!(invalid.local_cache_eviction().is_err());
+    }
+
+    #[test]
+    fn test_remote_cache_backend() {
+        let tmp_dir = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+
+        assert_eq!(
+            ConfigurationOptions::default()
+                .remote_cache_backend(&repo_root)
+                .unwrap(),
+            RemoteCacheBackendOpts::Vercel
+        );
+
+        let s3 = ConfigurationOptions {
+            backend: Some("s3".into()),
+            bucket: Some("turbo-cache".into()),
+            timeout: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            s3.remote_cache_backend(&repo_root).unwrap(),
+            RemoteCacheBackendOpts::S3 {
+                endpoint: None,
+                bucket: "turbo-cache".into(),
+                region: "us-east-1".into(),
+                prefix: None,
+                timeout: 10,
+            }
+        );
+
+        let directory = ConfigurationOptions {
+            backend: Some("directory".into()),
+            path: Some("shared/cache".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            directory.remote_cache_backend(&repo_root).unwrap(),
+            RemoteCacheBackendOpts::Directory {
+                path: repo_root.join_components(&["shared", "cache"]),
+            }
+        );
+
+        let http = ConfigurationOptions {
+            backend: Some("http".into()),
+            endpoint: Some("https://cache.example.com".into()),
+            http_token: Some("secret".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            http.remote_cache_backend(&repo_root).unwrap(),
+            RemoteCacheBackendOpts::Http {
+                url: "https://cache.example.com".into(),
+                token: Some("secret".into()),
+                timeout: DEFAULT_TIMEOUT,
+            }
+        );
+
+        // The Vercel token is never sent to an http backend
+        let vercel_token = ConfigurationOptions {
+            backend: Some("http".into()),
+            endpoint: Some("https://cache.example.com".into()),
+            token: Some("vercel-token".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            vercel_token.remote_cache_backend(&repo_root).unwrap(),
+            RemoteCacheBackendOpts::Http {
+                url: "https://cache.example.com".into(),
+                token: None,
+                timeout: DEFAULT_TIMEOUT,
+            }
+        );
+
+        let missing_bucket = ConfigurationOptions {
+            backend: Some("s3".into()),
+            ..Default::default()
+        };
+        assert!(missing_bucket.remote_cache_backend(&repo_root).is_err());
+
+        let unknown = ConfigurationOptions {
+            backend: Some("ftp".into()),
+            ..Default::default()
+        };
+        assert!(unknown.remote_cache_backend(&repo_root).is_err());
+    }
 }
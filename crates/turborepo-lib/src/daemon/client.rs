@@ -144,6 +144,17 @@ impl<T> DaemonClient<T> {
 
         Ok(response)
     }
+
+    /// Subscribes to the changes the daemon sees to files in the repository.
+    pub async fn file_changes(
+        &mut self,
+    ) -> Result<tonic::Streaming<proto::FileChangesEvent>, DaemonError> {
+        Ok(self
+            .client
+            .file_changes(proto::FileChangesRequest {})
+            .await?
+            .into_inner())
+    }
 }
 
 impl DaemonClient<DaemonConnector> {
@@ -14,7 +14,7 @@
 //!    'non-blocking' calls (requests in the hot path for
 //!    a run of turbo), and falling back to the server
 //!    limit for blocking ones (useful in cases like the
-//!    LSP)
+//!    LSP) and for subscriptions (used by `turbo watch`)
 //!
 //! With this in place, it means that clients can specify
 //! a timeout that it wants (as long as it is less than 30s),
@@ -25,6 +25,10 @@ use std::time::Duration;
 use tonic::{codegen::http::Request, server::NamedService, transport::Body};
 use tower::{Layer, Service};
 
+/// Streaming calls that are expected to stay open for as long as the client
+/// is interested in them
+const SUBSCRIPTIONS: &[&str] = &["/FileChanges"];
+
 #[derive(Clone, Debug)]
 pub struct DefaultTimeoutService<S> {
     inner: S,
@@ -46,7 +50,8 @@ where
     }
 
     fn call(&mut self, mut req: Request<Body>) -> Self::Future {
-        if !req.uri().path().ends_with("Blocking") {
+        let path = req.uri().path();
+        if !path.ends_with("Blocking") && !SUBSCRIPTIONS.iter().any(|rpc| path.ends_with(rpc)) {
             req.headers_mut()
                 .entry("grpc-timeout")
                 .or_insert_with(move || {
@@ -93,6 +98,7 @@ mod test {
     #[test_case("/ExampleBlocking", None, None ; "no default for blocking calls")]
     #[test_case("/Example", None, Some("100000u") ; "default for non-blocking calls")]
     #[test_case("/Example", Some("200u"), Some("200u") ; "respect client preference")]
+    #[test_case("/turbodprotocol.Turbod/FileChanges", None, None ; "no default for subscriptions")]
     #[tokio::test]
     async fn overrides_timeout_for_non_blocking(
         path: &str,
@@ -18,9 +18,10 @@ use semver::Version;
 use thiserror::Error;
 use tokio::{
     select,
-    sync::{mpsc, oneshot},
+    sync::{broadcast, mpsc, oneshot},
     task::JoinHandle,
 };
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{server::NamedService, transport::Server};
 use tower::ServiceBuilder;
 use tracing::{error, info, trace, warn};
@@ -122,6 +123,13 @@ impl FileWatching {
 /// Timeout for every RPC the server handles
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// How many file change events are buffered for a subscriber that isn't
+/// keeping up
+const FILE_CHANGES_BUFFER: usize = 64;
+
+/// How often an open file changes subscription keeps the daemon alive
+const FILE_CHANGES_KEEP_ALIVE: Duration = Duration::from_secs(60);
+
 pub struct TurboGrpcService<S> {
     repo_root: AbsoluteSystemPathBuf,
     paths: Paths,
@@ -174,8 +182,15 @@ where
         // well as available to the gRPC server itself to handle the shutdown RPC.
         let (trigger_shutdown, mut shutdown_signal) = mpsc::channel::<()>(1);
 
-        let (service, exit_root_watch, watch_root_handle) =
-            TurboGrpcServiceInner::new(repo_root.clone(), trigger_shutdown, paths.log_file);
+        let bump_timeout = Arc::new(BumpTimeout::new(timeout));
+        let timeout_fut = bump_timeout.wait();
+
+        let (service, exit_root_watch, watch_root_handle) = TurboGrpcServiceInner::new(
+            repo_root.clone(),
+            trigger_shutdown,
+            paths.log_file,
+            bump_timeout.clone(),
+        );
 
         let running = Arc::new(AtomicBool::new(true));
         let (_pid_lock, stream) =
@@ -185,9 +200,6 @@ where
             };
         trace!("acquired connection stream for socket");
 
-        let bump_timeout = Arc::new(BumpTimeout::new(timeout));
-        let timeout_fut = bump_timeout.wait();
-
         // when one of these futures complete, let the server gracefully shutdown
         let (grpc_shutdown_tx, shutdown_reason) = oneshot::channel();
         let shutdown_fut = async move {
@@ -237,12 +249,14 @@ where
 }
 
 struct TurboGrpcServiceInner {
+    repo_root: AbsoluteSystemPathBuf,
     shutdown: mpsc::Sender<()>,
     file_watching: FileWatching,
     times_saved: Arc<Mutex<HashMap<String, u64>>>,
     start_time: Instant,
     log_file: AbsoluteSystemPathBuf,
     package_watcher: Arc<PackageWatcher>,
+    bump_timeout: Arc<BumpTimeout>,
 }
 
 // we have a grpc service that uses watching package discovery, and where the
@@ -253,6 +267,7 @@ impl TurboGrpcServiceInner {
         repo_root: AbsoluteSystemPathBuf,
         trigger_shutdown: mpsc::Sender<()>,
         log_file: AbsoluteSystemPathBuf,
+        bump_timeout: Arc<BumpTimeout>,
     ) -> (
         Self,
         oneshot::Sender<()>,
@@ -277,12 +292,14 @@ impl TurboGrpcServiceInner {
 
         (
             TurboGrpcServiceInner {
+                repo_root,
                 package_watcher,
                 shutdown: trigger_shutdown,
                 file_watching,
                 times_saved: Arc::new(Mutex::new(HashMap::new())),
                 start_time: Instant::now(),
                 log_file,
+                bump_timeout,
             },
             exit_root_watch,
             watch_root_handle,
@@ -329,6 +346,64 @@ impl TurboGrpcServiceInner {
             .await?;
         Ok((changed_globs, time_saved))
     }
+
+    async fn file_changes(
+        &self,
+    ) -> Result<mpsc::Receiver<Result<proto::FileChangesEvent, tonic::Status>>, RpcError> {
+        let mut events = self
+            .file_watching
+            .watcher
+            .subscribe()
+            .await
+            .map_err(|_| RpcError::NoFileWatching)?;
+        let (tx, rx) = mpsc::channel(FILE_CHANGES_BUFFER);
+        let repo_root = self.repo_root.clone();
+        let bump_timeout = self.bump_timeout.clone();
+
+        tokio::spawn(async move {
+            let mut keep_alive = tokio::time::interval(FILE_CHANGES_KEEP_ALIVE);
+            loop {
+                let event = select! {
+                    _ = tx.closed() => break,
+                    _ = keep_alive.tick() => {
+                        bump_timeout.reset();
+                        continue;
+                    }
+                    event = events.recv() => event,
+                };
+                let event = match event {
+                    Ok(Ok(event)) => proto::FileChangesEvent {
+                        paths: event
+                            .paths
+                            .into_iter()
+                            .filter_map(|path| {
+                                let path = AbsoluteSystemPathBuf::try_from(path).ok()?;
+                                Some(repo_root.anchor(&path).ok()?.to_string())
+                            })
+                            .collect(),
+                        lagged: false,
+                    },
+                    Ok(Err(e)) => {
+                        warn!("file watching error: {e}");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => proto::FileChangesEvent {
+                        paths: Vec::new(),
+                        lagged: true,
+                    },
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if event.paths.is_empty() && !event.lagged {
+                    continue;
+                }
+                if tx.send(Ok(event)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(rx)
+    }
 }
 
 async fn watch_root(
@@ -381,6 +456,8 @@ async fn watch_root(
 
 #[tonic::async_trait]
 impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
+    type FileChangesStream = ReceiverStream<Result<proto::FileChangesEvent, tonic::Status>>;
+
     async fn hello(
         &self,
         request: tonic::Request<proto::HelloRequest>,
@@ -513,6 +590,14 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
             }
         }
     }
+
+    async fn file_changes(
+        &self,
+        _request: tonic::Request<proto::FileChangesRequest>,
+    ) -> Result<tonic::Response<Self::FileChangesStream>, tonic::Status> {
+        let events = self.file_changes().await?;
+        Ok(tonic::Response::new(ReceiverStream::new(events)))
+    }
 }
 
 /// Determine whether a server can serve a client's request based on its
@@ -324,6 +324,22 @@ impl<'a> EngineBuilder<'a> {
                 traversal_queue.push_back(from_task_id);
             }
 
+            // Sidecars are added as dependencies, the engine treats them as done
+            // once they're ready rather than once they exit
+            for sidecar in &task_definition.sidecars {
+                has_deps = true;
+                let from_task_id = sidecar
+                    .task_id()
+                    .unwrap_or_else(|| TaskId::new(to_task_id.package(), sidecar.task()))
+                    .into_owned();
+                let from_task_index = engine.get_index(&from_task_id);
+                engine
+                    .task_graph
+                    .add_edge(to_task_index, from_task_index, ());
+                let from_task_id = sidecar.to(from_task_id);
+                traversal_queue.push_back(from_task_id);
+            }
+
             engine.add_definition(task_id.as_inner().clone().into_owned(), task_definition);
             if !has_deps && !has_topo_deps {
                 engine.connect_to_root(&to_task_id);
@@ -363,12 +379,107 @@ impl<'a> EngineBuilder<'a> {
         if turbo_json.pipeline.contains_key(&task_id_as_name)
             || turbo_json.pipeline.contains_key(task_name)
         {
-            Ok(true)
-        } else if !matches!(workspace, PackageName::Root) {
-            self.has_task_definition(turbo_jsons, &PackageName::Root, task_name, task_id)
-        } else {
-            Ok(false)
+            return Ok(true);
+        }
+        if matches!(workspace, PackageName::Root) {
+            return Ok(false);
+        }
+
+        for extended in self.extends_chain(turbo_jsons, workspace)? {
+            if let Some(extended_json) = self.turbo_json(turbo_jsons, &extended)? {
+                if extended_json.pipeline.contains_key(task_name) {
+                    return Ok(true);
+                }
+            }
+        }
+        self.has_task_definition(turbo_jsons, &PackageName::Root, task_name, task_id)
+    }
+
+    // Returns the packages that a workspace's turbo.json extends from, other
+    // than the root, in the order their task definitions are merged. Each
+    // package's own `extends` are resolved before it so that a package
+    // always overrides the presets it builds on.
+    fn extends_chain(
+        &self,
+        turbo_jsons: &mut HashMap<PackageName, TurboJson>,
+        workspace: &PackageName,
+    ) -> Result<Vec<PackageName>, Error> {
+        let mut chain = Vec::new();
+        self.resolve_extends(
+            turbo_jsons,
+            workspace,
+            &mut vec![workspace.clone()],
+            &mut chain,
+        )?;
+        Ok(chain)
+    }
+
+    fn resolve_extends(
+        &self,
+        turbo_jsons: &mut HashMap<PackageName, TurboJson>,
+        workspace: &PackageName,
+        stack: &mut Vec<PackageName>,
+        chain: &mut Vec<PackageName>,
+    ) -> Result<(), Error> {
+        let extends = match self.turbo_json(turbo_jsons, workspace) {
+            Ok(Some(turbo_json)) => turbo_json.extends.value.clone(),
+            Ok(None) => return Ok(()),
+            Err(e) if e.is_missing_turbo_json() => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        for extended in extends {
+            let package = PackageName::from(extended.as_str());
+            // The root turbo.json is always the base of the chain
+            if matches!(package, PackageName::Root) || chain.contains(&package) {
+                continue;
+            }
+
+            let (span, text) = extended.span_and_text("turbo.json");
+            if let Some(start) = stack.iter().position(|p| p == &package) {
+                let cycle = stack[start..]
+                    .iter()
+                    .chain(std::iter::once(&package))
+                    .join(" -> ");
+                return Err(config::Error::ExtendsCycle { cycle, span, text }.into());
+            }
+            if self.package_graph.package_json(&package).is_none() {
+                return Err(config::Error::ExtendsMissingPackage {
+                    package: package.to_string(),
+                    span,
+                    text,
+                }
+                .into());
+            }
+            match self.turbo_json(turbo_jsons, &package) {
+                Ok(Some(extended_json)) => {
+                    let validation_errors = extended_json
+                        .validate(&[validate_no_package_task_syntax, validate_extends]);
+                    if !validation_errors.is_empty() {
+                        return Err(Error::Validation {
+                            errors: validation_errors,
+                        });
+                    }
+                }
+                Ok(None) => (),
+                Err(e) if e.is_missing_turbo_json() => {
+                    return Err(config::Error::ExtendsMissingTurboJson {
+                        package: package.to_string(),
+                        span,
+                        text,
+                    }
+                    .into());
+                }
+                Err(e) => return Err(e),
+            }
+
+            stack.push(package.clone());
+            self.resolve_extends(turbo_jsons, &package, stack, chain)?;
+            stack.pop();
+            chain.push(package);
         }
+
+        Ok(())
     }
 
     fn task_definition_chain(
@@ -402,7 +513,8 @@ impl<'a> EngineBuilder<'a> {
         }
 
         if task_id.package() != ROOT_PKG_NAME {
-            match self.turbo_json(turbo_jsons, &PackageName::from(task_id.package())) {
+            let workspace = PackageName::from(task_id.package());
+            match self.turbo_json(turbo_jsons, &workspace) {
                 Ok(Some(workspace_json)) => {
                     let validation_errors = workspace_json
                         .validate(&[validate_no_package_task_syntax, validate_extends]);
@@ -412,9 +524,23 @@ impl<'a> EngineBuilder<'a> {
                         });
                     }
 
-                    if let Some(workspace_def) = workspace_json.pipeline.get(task_name) {
-                        task_definitions.push(workspace_def.value.clone());
+                    let workspace_def = workspace_json
+                        .pipeline
+                        .get(task_name)
+                        .map(|def| def.value.clone());
+
+                    // Definitions from extended packages apply between the root and
+                    // the workspace's own definition
+                    for extended in self.extends_chain(turbo_jsons, &workspace)? {
+                        if let Some(extended_def) = self
+                            .turbo_json(turbo_jsons, &extended)?
+                            .and_then(|json| json.pipeline.get(task_name))
+                        {
+                            task_definitions.push(extended_def.value.clone());
+                        }
                     }
+
+                    task_definitions.extend(workspace_def);
                 }
                 Ok(None) => (),
                 // swallow the error where the config file doesn't exist, but bubble up other things
@@ -838,6 +964,49 @@ mod test {
         assert_eq!(all_dependencies(&engine), expected);
     }
 
+    #[test]
+    fn test_sidecars() {
+        let repo_root_dir = TempDir::new("repo").unwrap();
+        let repo_root = AbsoluteSystemPathBuf::new(repo_root_dir.path().to_str().unwrap()).unwrap();
+        let package_graph = mock_package_graph(
+            &repo_root,
+            package_jsons! {
+                repo_root,
+                "web" => [],
+                "api" => []
+            },
+        );
+        let turbo_jsons = vec![(
+            PackageName::Root,
+            turbo_json(json!({
+                "pipeline": {
+                    "e2e": { "with": ["api#serve", "preview"] },
+                    "serve": { "persistent": true },
+                    "preview": { "persistent": true },
+                }
+            })),
+        )]
+        .into_iter()
+        .collect();
+        let engine = EngineBuilder::new(&repo_root, &package_graph, false)
+            .with_turbo_jsons(Some(turbo_jsons))
+            .with_tasks(Some(Spanned::new(TaskName::from("e2e"))))
+            .with_workspaces(vec![PackageName::from("web")])
+            .build()
+            .unwrap();
+
+        let expected = deps! {
+            "web#e2e" => ["api#serve", "web#preview"],
+            "api#serve" => ["___ROOT___"],
+            "web#preview" => ["___ROOT___"]
+        };
+        assert_eq!(all_dependencies(&engine), expected);
+        assert_eq!(
+            engine.sidecars(&TaskId::new("web", "e2e")),
+            vec![TaskId::new("api", "serve"), TaskId::new("web", "preview")]
+        );
+    }
+
     #[test]
     fn test_run_package_task() {
         let repo_root_dir = TempDir::new("repo").unwrap();
@@ -1154,4 +1323,111 @@ mod test {
             .err();
         assert_eq!(result.as_deref(), reason);
     }
+
+    #[test]
+    fn test_extends_chain() {
+        let repo_root_dir = TempDir::new("repo").unwrap();
+        let repo_root = AbsoluteSystemPathBuf::new(repo_root_dir.path().to_str().unwrap()).unwrap();
+        let package_graph = mock_package_graph(
+            &repo_root,
+            package_jsons! {
+                repo_root,
+                "a" => [],
+                "b" => [],
+                "c" => []
+            },
+        );
+        let mut turbo_jsons: HashMap<_, _> = vec![
+            (
+                PackageName::Root,
+                turbo_json(json!({
+                    "pipeline": {
+                        "build": { "inputs": ["root"], "outputs": ["dist/**"] },
+                    }
+                })),
+            ),
+            (
+                PackageName::from("a"),
+                turbo_json(json!({
+                    "extends": ["//"],
+                    "pipeline": {
+                        "build": { "inputs": ["a"] },
+                    }
+                })),
+            ),
+            (
+                PackageName::from("b"),
+                turbo_json(json!({
+                    "extends": ["//", "a"],
+                    "pipeline": {
+                        "build": { "env": ["B"] },
+                    }
+                })),
+            ),
+            (
+                PackageName::from("c"),
+                turbo_json(json!({
+                    "extends": ["//", "b", "a"],
+                    "pipeline": {
+                        "build": { "env": ["C"] },
+                    }
+                })),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let engine_builder = EngineBuilder::new(&repo_root, &package_graph, false);
+
+        let chain = engine_builder
+            .extends_chain(&mut turbo_jsons, &PackageName::from("c"))
+            .unwrap();
+        assert_eq!(chain, vec![PackageName::from("a"), PackageName::from("b")]);
+
+        let task_id = Spanned::new(TaskId::try_from("c#build").unwrap());
+        let definitions = engine_builder
+            .task_definition_chain(&mut turbo_jsons, &task_id, &TaskName::from("build"))
+            .unwrap();
+        let expected = [PackageName::Root, "a".into(), "b".into(), "c".into()]
+            .iter()
+            .map(|package| {
+                turbo_jsons[package].pipeline[&TaskName::from("build")]
+                    .value
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(definitions, expected);
+    }
+
+    #[test_case(json!({"extends": ["//", "c"]}), "Cyclic \"extends\" found: a -> c -> a" ; "cycle")]
+    #[test_case(json!({"extends": ["//", "a"]}), "Cyclic \"extends\" found: a -> a" ; "self")]
+    #[test_case(json!({"extends": ["//", "missing"]}), "Cannot extend from \"missing\": package not found" ; "missing package")]
+    #[test_case(json!({"extends": ["//", "b"]}), "Cannot extend from \"b\": package has no turbo.json" ; "missing turbo.json")]
+    fn test_extends_errors(a_turbo_json: serde_json::Value, expected: &str) {
+        let repo_root_dir = TempDir::new("repo").unwrap();
+        let repo_root = AbsoluteSystemPathBuf::new(repo_root_dir.path().to_str().unwrap()).unwrap();
+        let package_graph = mock_package_graph(
+            &repo_root,
+            package_jsons! {
+                repo_root,
+                "a" => [],
+                "b" => [],
+                "c" => []
+            },
+        );
+        let mut turbo_jsons: HashMap<_, _> = vec![
+            (PackageName::from("a"), turbo_json(a_turbo_json)),
+            (
+                PackageName::from("c"),
+                turbo_json(json!({ "extends": ["//", "a"] })),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let engine_builder = EngineBuilder::new(&repo_root, &package_graph, false);
+
+        let error = engine_builder
+            .extends_chain(&mut turbo_jsons, &PackageName::from("a"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), expected);
+    }
 }
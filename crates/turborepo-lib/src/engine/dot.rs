@@ -3,9 +3,21 @@ use std::io;
 use petgraph::{visit::EdgeRef, Graph};
 
 use super::{Built, Engine, TaskNode};
+use crate::run::task_id::TaskId;
 
 impl Engine<Built> {
     pub fn dot_graph<W: io::Write>(&self, writer: W, is_single: bool) -> Result<(), io::Error> {
+        self.dot_graph_with_attributes(writer, is_single, |_| None)
+    }
+
+    /// Renders the graph along with the dot attributes returned for each
+    /// task, such as a label or a color
+    pub fn dot_graph_with_attributes<W: io::Write>(
+        &self,
+        writer: W,
+        is_single: bool,
+        mut attributes: impl FnMut(&TaskId) -> Option<String>,
+    ) -> Result<(), io::Error> {
         let display_node = match is_single {
             true => |node: &TaskNode| match node {
                 TaskNode::Root => node.to_string(),
@@ -13,7 +25,15 @@ impl Engine<Built> {
             },
             false => |node: &TaskNode| node.to_string(),
         };
-        render_graph(&self.task_graph, display_node, writer)
+        render_graph(
+            &self.task_graph,
+            display_node,
+            |node| match node {
+                TaskNode::Root => None,
+                TaskNode::Task(task) => attributes(task),
+            },
+            writer,
+        )
     }
 }
 
@@ -25,6 +45,7 @@ const GRAPH_PRELUDE: &str = "\ndigraph {\n\tcompound = \"true\"
 fn render_graph<N>(
     graph: &Graph<N, ()>,
     mut display_node: impl FnMut(&N) -> String,
+    mut node_attributes: impl FnMut(&N) -> Option<String>,
     mut writer: impl io::Write,
 ) -> Result<(), io::Error> {
     let mut get_node = |i| {
@@ -48,6 +69,19 @@ fn render_graph<N>(
         .collect::<Vec<_>>();
     edges.sort();
 
+    let mut attributes = graph
+        .node_weights()
+        .filter_map(|node| {
+            let attributes = node_attributes(node)?;
+            Some(format!(
+                "\t\t\"[root] {}\" [{attributes}]",
+                display_node(node)
+            ))
+        })
+        .collect::<Vec<_>>();
+    attributes.sort();
+    edges.extend(attributes);
+
     writer.write_all(edges.join("\n").as_bytes())?;
 
     writer.write_all("\n\t}\n}\n\n".as_bytes())?;
@@ -67,7 +101,7 @@ mod test {
         let root = graph.add_node("___ROOT___");
         let build = graph.add_node("build");
         graph.add_edge(root, build, ());
-        render_graph(&graph, |n| n.to_string(), &mut bytes).unwrap();
+        render_graph(&graph, |n| n.to_string(), |_| None, &mut bytes).unwrap();
         assert_eq!(
             String::from_utf8(bytes).unwrap(),
             "\ndigraph {
@@ -76,6 +110,33 @@ mod test {
 \tsubgraph \"root\" {
 \t\t\"[root] ___ROOT___\" -> \"[root] build\"
 \t}
+}\n\n"
+        );
+    }
+
+    #[test]
+    fn test_graph_with_attributes() {
+        let mut bytes = Vec::new();
+        let mut graph = Graph::new();
+        let root = graph.add_node("___ROOT___");
+        let build = graph.add_node("build");
+        graph.add_edge(build, root, ());
+        render_graph(
+            &graph,
+            |n| n.to_string(),
+            |n| (*n == "build").then(|| "color=\"red\"".to_string()),
+            &mut bytes,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "\ndigraph {
+\tcompound = \"true\"
+\tnewrank = \"true\"
+\tsubgraph \"root\" {
+\t\t\"[root] build\" -> \"[root] ___ROOT___\"
+\t\t\"[root] build\" [color=\"red\"]
+\t}
 }\n\n"
         );
     }
@@ -1,7 +1,10 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
 
 use futures::{stream::FuturesUnordered, StreamExt};
-use tokio::sync::{mpsc, oneshot, Semaphore};
+use tokio::sync::{mpsc, oneshot, OwnedSemaphorePermit, Semaphore};
 use tracing::log::debug;
 use turborepo_graph_utils::Walker;
 
@@ -18,10 +21,12 @@ pub struct Message<T, U> {
 type VisitorData = TaskId<'static>;
 type VisitorResult = Result<(), StopExecution>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExecutionOptions {
     parallel: bool,
     concurrency: usize,
+    // Sizes of the named pools that tasks can be assigned to
+    pools: BTreeMap<String, u32>,
 }
 
 impl ExecutionOptions {
@@ -29,8 +34,42 @@ impl ExecutionOptions {
         Self {
             parallel,
             concurrency,
+            pools: BTreeMap::new(),
         }
     }
+
+    pub fn with_pools(mut self, pools: BTreeMap<String, u32>) -> Self {
+        self.pools = pools;
+        self
+    }
+}
+
+// A semaphore along with the number of permits it was created with
+struct Slots {
+    semaphore: Arc<Semaphore>,
+    size: u32,
+}
+
+impl Slots {
+    fn new(size: u32) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(size as usize)),
+            size,
+        }
+    }
+
+    // A task that weighs more than there are slots takes all of them instead of
+    // waiting forever
+    async fn acquire(&self, weight: u32) -> OwnedSemaphorePermit {
+        self.semaphore
+            .clone()
+            .acquire_many_owned(weight.max(1).min(self.size))
+            .await
+            .expect(
+                "Graph concurrency semaphore closed while tasks are still attempting to acquire \
+                 permits",
+            )
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -72,8 +111,15 @@ impl Engine {
         let ExecutionOptions {
             parallel,
             concurrency,
+            pools,
         } = options;
-        let sema = Arc::new(Semaphore::new(concurrency));
+        let sema = Arc::new(Slots::new(u32::try_from(concurrency).unwrap_or(u32::MAX)));
+        let pools = Arc::new(
+            pools
+                .into_iter()
+                .map(|(name, size)| (name, Slots::new(size)))
+                .collect::<HashMap<_, _>>(),
+        );
         let mut tasks: FuturesUnordered<tokio::task::JoinHandle<Result<(), ExecuteError>>> =
             FuturesUnordered::new();
 
@@ -83,6 +129,7 @@ impl Engine {
         while let Some((node_id, done)) = nodes.recv().await {
             let visitor = visitor.clone();
             let sema = sema.clone();
+            let pools = pools.clone();
             let walker = walker.clone();
             let this = self.clone();
 
@@ -102,12 +149,23 @@ impl Engine {
                     return Ok(());
                 };
 
+                let definition = this.task_definition(task_id);
+                let weight = definition.map_or(1, |definition| definition.weight);
+
+                // Wait for a slot in the task's pool first so that we don't hold on to
+                // slots that other tasks could be using in the meantime. Pools apply
+                // even when running in parallel as they guard shared resources.
+                let pool = definition
+                    .and_then(|definition| definition.pool.as_ref())
+                    .and_then(|pool| pools.get(pool));
+                let _pool_permit = match pool {
+                    Some(pool) => Some(pool.acquire(weight).await),
+                    None => None,
+                };
+
                 // Acquire the semaphore unless parallel
                 let _permit = match parallel {
-                    false => Some(sema.acquire().await.expect(
-                        "Graph concurrency semaphore closed while tasks are still attempting to \
-                         acquire permits",
-                    )),
+                    false => Some(sema.acquire(weight).await),
                     true => None,
                 };
 
@@ -149,3 +207,125 @@ impl<T, U> Message<T, U> {
         (Self { info, callback }, receiver)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::task_graph::TaskDefinition;
+
+    // Runs independent tasks with the given definitions and returns the most
+    // tasks that were running at once
+    async fn max_running(definitions: Vec<TaskDefinition>, options: ExecutionOptions) -> usize {
+        let mut engine = Engine::new();
+        for (i, definition) in definitions.into_iter().enumerate() {
+            let task_id = TaskId::new(&format!("pkg{i}"), "build").into_owned();
+            engine.connect_to_root(&task_id);
+            engine.add_definition(task_id, definition);
+        }
+        let engine = Arc::new(engine.seal());
+
+        let (sender, mut receiver) = mpsc::channel::<Message<VisitorData, VisitorResult>>(1);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let visitor = {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            tokio::spawn(async move {
+                while let Some(Message { callback, .. }) = receiver.recv().await {
+                    let running = running.clone();
+                    let max_running = max_running.clone();
+                    tokio::spawn(async move {
+                        let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_running.fetch_max(now_running, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        callback.send(Ok(())).ok();
+                    });
+                }
+            })
+        };
+
+        engine.execute(options, sender).await.unwrap();
+        visitor.await.unwrap();
+        max_running.load(Ordering::SeqCst)
+    }
+
+    fn in_pool(pool: &str) -> TaskDefinition {
+        TaskDefinition {
+            pool: Some(pool.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn weighing(weight: u32) -> TaskDefinition {
+        TaskDefinition {
+            weight,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pool_limits_concurrency() {
+        let options = ExecutionOptions::new(false, 10)
+            .with_pools(BTreeMap::from([("browser".to_string(), 2)]));
+        assert_eq!(max_running(vec![in_pool("browser"); 6], options).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pool_applies_when_parallel() {
+        let options = ExecutionOptions::new(true, 10)
+            .with_pools(BTreeMap::from([("browser".to_string(), 1)]));
+        assert_eq!(max_running(vec![in_pool("browser"); 3], options).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_weight_uses_more_slots() {
+        let options = ExecutionOptions::new(false, 4);
+        assert_eq!(max_running(vec![weighing(2); 6], options).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_weight_larger_than_concurrency() {
+        let options = ExecutionOptions::new(false, 2);
+        assert_eq!(max_running(vec![weighing(5); 3], options).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stopped_task_blocks_dependents() {
+        // app#build depends on lib#build, which gets stopped
+        let mut engine = Engine::new();
+        let lib_build = TaskId::new("lib", "build").into_owned();
+        let app_build = TaskId::new("app", "build").into_owned();
+        engine.connect_to_root(&lib_build);
+        let lib_index = engine.get_index(&lib_build);
+        let app_index = engine.get_index(&app_build);
+        engine.task_graph.add_edge(app_index, lib_index, ());
+        for task_id in [&lib_build, &app_build] {
+            engine.add_definition(task_id.clone(), TaskDefinition::default());
+        }
+        let engine = Arc::new(engine.seal());
+
+        let (sender, mut receiver) = mpsc::channel::<Message<VisitorData, VisitorResult>>(1);
+        let visitor = tokio::spawn(async move {
+            let mut visited = Vec::new();
+            while let Some(Message { info, callback }) = receiver.recv().await {
+                visited.push(info.to_string());
+                // The visitor sends the same result for a stopped task that isn't
+                // persistent
+                callback.send(Err(StopExecution)).ok();
+            }
+            visited
+        });
+
+        engine
+            .execute(ExecutionOptions::new(false, 10), sender)
+            .await
+            .unwrap();
+        assert_eq!(visitor.await.unwrap(), vec!["lib#build"]);
+    }
+}
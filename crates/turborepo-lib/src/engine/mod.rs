@@ -5,7 +5,7 @@ mod dot;
 mod mermaid;
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt,
 };
 
@@ -154,6 +154,26 @@ impl Engine<Built> {
         self.task_definitions.get(task_id)
     }
 
+    /// Returns the sidecars that have to be ready before the given task can
+    /// start
+    pub fn sidecars(&self, task_id: &TaskId) -> Vec<TaskId<'static>> {
+        self.task_definitions
+            .get(task_id)
+            .map(|definition| {
+                definition
+                    .sidecars
+                    .iter()
+                    .map(|sidecar| {
+                        sidecar
+                            .task_id()
+                            .unwrap_or_else(|| TaskId::new(task_id.package(), sidecar.task()))
+                            .into_owned()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn tasks(&self) -> impl Iterator<Item = &TaskNode> {
         self.task_graph.node_weights()
     }
@@ -173,19 +193,210 @@ impl Engine<Built> {
             .collect()
     }
 
+    /// Return the direct dependencies of each task, keyed by task id
+    pub fn task_dependencies(&self) -> HashMap<String, Vec<String>> {
+        self.task_lookup
+            .keys()
+            .map(|task_id| {
+                let mut dependencies = self
+                    .dependencies(task_id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|node| match node {
+                        TaskNode::Root => None,
+                        TaskNode::Task(dependency) => Some(dependency.to_string()),
+                    })
+                    .collect::<Vec<_>>();
+                dependencies.sort();
+                (task_id.to_string(), dependencies)
+            })
+            .collect()
+    }
+
     pub fn task_definitions(&self) -> &HashMap<TaskId<'static>, TaskDefinition> {
         &self.task_definitions
     }
 
+    /// Returns true if the engine has at least one task to execute
+    pub fn has_tasks(&self) -> bool {
+        !self.task_lookup.is_empty()
+    }
+
+    /// Creates an engine containing the tasks affected by changes to the given
+    /// packages: every task of those packages and every task that
+    /// transitively depends on them. Persistent tasks are left out as they
+    /// are expected to keep running across changes.
+    pub fn create_engine_for_subgraph(
+        &self,
+        changed_packages: &HashSet<PackageName>,
+    ) -> Engine<Built> {
+        let affected = self.with_dependents(
+            self.task_lookup
+                .iter()
+                .filter(|(task_id, _)| {
+                    changed_packages.contains(&PackageName::from(task_id.package()))
+                })
+                .map(|(_, index)| *index),
+        );
+
+        self.subgraph(self.task_lookup.iter().filter_map(|(task_id, index)| {
+            (affected.contains(index) && !self.is_persistent(task_id)).then_some(*index)
+        }))
+    }
+
+    /// Creates an engine containing the tasks for which `is_affected` returns
+    /// true and every task that transitively depends on them.
+    pub fn create_engine_for_affected_tasks(
+        &self,
+        mut is_affected: impl FnMut(&TaskId<'static>, &TaskDefinition) -> bool,
+    ) -> Engine<Built> {
+        let affected =
+            self.with_dependents(self.task_lookup.iter().filter_map(|(task_id, index)| {
+                let definition = self.task_definitions.get(task_id)?;
+                is_affected(task_id, definition).then_some(*index)
+            }));
+
+        self.subgraph(affected)
+    }
+
+    /// Returns the tasks that no other task depends on. These are the tasks
+    /// that were asked for rather than pulled in as a dependency.
+    pub fn entry_tasks(&self) -> Vec<&TaskId<'static>> {
+        let mut tasks = self
+            .task_lookup
+            .iter()
+            .filter(|(_, index)| {
+                self.task_graph
+                    .neighbors_directed(**index, petgraph::Direction::Incoming)
+                    .next()
+                    .is_none()
+            })
+            .map(|(task_id, _)| task_id)
+            .collect::<Vec<_>>();
+        tasks.sort();
+        tasks
+    }
+
+    /// Creates an engine containing the given tasks along with the tasks they
+    /// depend on.
+    pub fn create_engine_for_tasks(&self, tasks: &HashSet<TaskId<'static>>) -> Engine<Built> {
+        self.subgraph(
+            self.task_lookup
+                .iter()
+                .filter_map(|(task_id, index)| tasks.contains(task_id).then_some(*index)),
+        )
+    }
+
+    // Returns the given tasks along with every task that transitively depends
+    // on them.
+    fn with_dependents(
+        &self,
+        indices: impl IntoIterator<Item = petgraph::graph::NodeIndex>,
+    ) -> HashSet<petgraph::graph::NodeIndex> {
+        let mut affected = HashSet::new();
+        let mut stack = indices.into_iter().collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            if index != self.root_index && affected.insert(index) {
+                stack.extend(
+                    self.task_graph
+                        .neighbors_directed(index, petgraph::Direction::Incoming),
+                );
+            }
+        }
+        affected
+    }
+
+    /// Creates an engine containing only the persistent tasks along with the
+    /// tasks they depend on.
+    pub fn create_engine_for_persistent_tasks(&self) -> Engine<Built> {
+        self.subgraph(
+            self.task_lookup
+                .iter()
+                .filter_map(|(task_id, index)| self.is_persistent(task_id).then_some(*index)),
+        )
+    }
+
+    /// Creates an engine containing every task except the persistent ones.
+    pub fn create_engine_without_persistent_tasks(&self) -> Engine<Built> {
+        self.subgraph(
+            self.task_lookup
+                .iter()
+                .filter_map(|(task_id, index)| (!self.is_persistent(task_id)).then_some(*index)),
+        )
+    }
+
+    fn is_persistent(&self, task_id: &TaskId<'static>) -> bool {
+        self.task_definitions
+            .get(task_id)
+            .map_or(false, |definition| definition.persistent)
+    }
+
+    // Builds a new engine out of the given tasks. The dependencies of those tasks
+    // are always included so that their hashes can still be calculated.
+    fn subgraph(
+        &self,
+        indices: impl IntoIterator<Item = petgraph::graph::NodeIndex>,
+    ) -> Engine<Built> {
+        let mut included = HashSet::from([self.root_index]);
+        let mut stack = indices.into_iter().collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            if included.insert(index) {
+                stack.extend(
+                    self.task_graph
+                        .neighbors_directed(index, petgraph::Direction::Outgoing),
+                );
+            }
+        }
+
+        let task_graph = self.task_graph.filter_map(
+            |index, node| included.contains(&index).then(|| node.clone()),
+            |_, _| Some(()),
+        );
+
+        let mut root_index = None;
+        let mut task_lookup = HashMap::new();
+        for index in task_graph.node_indices() {
+            match &task_graph[index] {
+                TaskNode::Root => root_index = Some(index),
+                TaskNode::Task(task_id) => {
+                    task_lookup.insert(task_id.clone(), index);
+                }
+            }
+        }
+
+        let task_definitions = self
+            .task_definitions
+            .iter()
+            .filter(|(task_id, _)| task_lookup.contains_key(*task_id))
+            .map(|(task_id, definition)| (task_id.clone(), definition.clone()))
+            .collect();
+        let task_locations = self
+            .task_locations
+            .iter()
+            .filter(|(task_id, _)| task_lookup.contains_key(*task_id))
+            .map(|(task_id, location)| (task_id.clone(), location.clone()))
+            .collect();
+
+        Engine {
+            marker: std::marker::PhantomData,
+            task_graph,
+            root_index: root_index.expect("root node is always included"),
+            task_lookup,
+            task_definitions,
+            task_locations,
+        }
+    }
+
     pub fn validate(
         &self,
         package_graph: &PackageGraph,
         concurrency: u32,
         experimental_ui: bool,
     ) -> Result<(), Vec<ValidateError>> {
+        let sidecars = self.all_sidecars();
         // TODO(olszewski) once this is hooked up to a real run, we should
         // see if using rayon to parallelize would provide a speedup
-        let (persistent_count, mut validation_errors) = self
+        let (persistent_tasks, mut validation_errors) = self
             .task_graph
             .node_indices()
             .map(|node_index| {
@@ -195,9 +406,10 @@ impl Engine<Built> {
                     .expect("graph should contain weight for node index")
                 else {
                     // No need to check the root node if that's where we are.
-                    return Ok(false);
+                    return Ok(None);
                 };
 
+                let task_sidecars = self.sidecars(task_id);
                 for dep_index in self
                     .task_graph
                     .neighbors_directed(node_index, petgraph::Direction::Outgoing)
@@ -225,6 +437,7 @@ impl Engine<Built> {
                         })?;
                     if task_definition.persistent
                         && package_json.scripts.contains_key(dep_id.task())
+                        && !task_sidecars.contains(dep_id)
                     {
                         let (span, text) = self
                             .task_locations
@@ -241,40 +454,37 @@ impl Engine<Built> {
                     }
                 }
 
-                // check if the package for the task has that task in its package.json
-                let info = package_graph
-                    .package_info(&PackageName::from(task_id.package().to_string()))
-                    .expect("package graph should contain workspace info for task package");
-
-                let package_has_task = info
-                    .package_json
-                    .scripts
-                    .get(task_id.task())
-                    // handle legacy behaviour from go where an empty string may appear
-                    .map_or(false, |script| !script.is_empty());
-
-                let task_is_persistent = self
-                    .task_definitions
-                    .get(task_id)
-                    .map_or(false, |task_def| task_def.persistent);
-
-                Ok(task_is_persistent && package_has_task)
+                Ok(self
+                    .holds_slots(package_graph, task_id, &sidecars)
+                    .then_some(task_id))
             })
-            .fold((0, Vec::new()), |(mut count, mut errs), result| {
+            .fold((Vec::new(), Vec::new()), |(mut tasks, mut errs), result| {
                 match result {
-                    Ok(true) => count += 1,
-                    Ok(false) => (),
+                    Ok(Some(task_id)) => tasks.push(task_id),
+                    Ok(None) => (),
                     Err(e) => errs.push(e),
                 }
-                (count, errs)
+                (tasks, errs)
             });
 
         // there must always be at least one concurrency 'slot' available for
         // non-persistent tasks otherwise we get race conditions
-        if persistent_count >= concurrency {
-            validation_errors.push(ValidateError::PersistentTasksExceedConcurrency {
-                persistent_count,
-                concurrency,
+        let persistent_count = persistent_tasks.len() as u32;
+        let weights = persistent_tasks
+            .iter()
+            .map(|task_id| self.weight(task_id))
+            .collect::<Vec<_>>();
+        if slots_held(&weights, concurrency) >= concurrency {
+            let persistent_weight = weights.iter().fold(0u32, |sum, w| sum.saturating_add(*w));
+            validation_errors.push(match persistent_weight == persistent_count {
+                true => ValidateError::PersistentTasksExceedConcurrency {
+                    persistent_count,
+                    concurrency,
+                },
+                false => ValidateError::PersistentTaskWeightExceedsConcurrency {
+                    persistent_weight,
+                    concurrency,
+                },
             })
         }
 
@@ -286,6 +496,120 @@ impl Engine<Built> {
         }
     }
 
+    /// Validates that every task assigned to a pool uses one of the pools
+    /// defined in the root turbo.json, and that the persistent tasks in a pool
+    /// leave room for its other tasks
+    pub fn validate_pools(
+        &self,
+        package_graph: &PackageGraph,
+        pools: &BTreeMap<String, u32>,
+    ) -> Result<(), Vec<ValidateError>> {
+        let mut validation_errors = self
+            .task_definitions
+            .iter()
+            .filter_map(|(task_id, definition)| {
+                let pool = definition.pool.as_ref()?;
+                if pools.contains_key(pool) {
+                    return None;
+                }
+                let (span, text) = self
+                    .task_locations
+                    .get(task_id)
+                    .map(|spanned| spanned.span_and_text("turbo.json"))
+                    .unwrap_or((None, NamedSource::new("", "")));
+                Some((
+                    task_id,
+                    ValidateError::UnknownPool {
+                        span,
+                        text,
+                        task: task_id.to_string(),
+                        pool: pool.clone(),
+                    },
+                ))
+            })
+            .collect::<Vec<_>>();
+        // Sort the errors so that they're reported in a consistent order
+        validation_errors.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut validation_errors = validation_errors
+            .into_iter()
+            .map(|(_, error)| error)
+            .collect::<Vec<_>>();
+
+        let sidecars = self.all_sidecars();
+        for (pool, &size) in pools {
+            let (persistent, other): (Vec<_>, Vec<_>) = self
+                .task_definitions
+                .iter()
+                .filter(|(_, definition)| definition.pool.as_ref() == Some(pool))
+                .partition(|(task_id, _)| self.holds_slots(package_graph, task_id, &sidecars));
+            if persistent.is_empty() {
+                continue;
+            }
+            let weights = persistent
+                .iter()
+                .map(|(task_id, _)| self.weight(task_id))
+                .collect::<Vec<_>>();
+            // A persistent task that can't get its slots would never start, and
+            // the other tasks in the pool need at least one slot to run
+            let persistent_weight = slots_held(&weights, size);
+            if persistent_weight > size || (persistent_weight == size && !other.is_empty()) {
+                let required = weights
+                    .iter()
+                    .fold(0u32, |sum, w| sum.saturating_add(*w))
+                    .saturating_add(u32::from(!other.is_empty()));
+                validation_errors.push(ValidateError::PersistentTasksExceedPool {
+                    pool: pool.clone(),
+                    persistent_weight,
+                    size,
+                    required,
+                });
+            }
+        }
+
+        match validation_errors.is_empty() {
+            true => Ok(()),
+            false => Err(validation_errors),
+        }
+    }
+
+    // Sidecars only hold on to their slots until they're ready, so they don't
+    // count towards the persistent tasks
+    fn all_sidecars(&self) -> HashSet<TaskId<'static>> {
+        self.task_definitions
+            .keys()
+            .flat_map(|task_id| self.sidecars(task_id))
+            .collect()
+    }
+
+    // Whether a task holds on to its concurrency and pool slots for the whole
+    // run, which is the case for persistent tasks that have a script to run
+    fn holds_slots(
+        &self,
+        package_graph: &PackageGraph,
+        task_id: &TaskId<'static>,
+        sidecars: &HashSet<TaskId<'static>>,
+    ) -> bool {
+        // check if the package for the task has that task in its package.json
+        let package_has_task = package_graph
+            .package_json(&PackageName::from(task_id.package()))
+            .and_then(|package_json| package_json.scripts.get(task_id.task()))
+            // handle legacy behaviour from go where an empty string may appear
+            .map_or(false, |script| !script.is_empty());
+
+        let task_is_persistent = self
+            .task_definitions
+            .get(task_id)
+            .map_or(false, |task_def| task_def.persistent);
+
+        task_is_persistent && package_has_task && !sidecars.contains(task_id)
+    }
+
+    fn weight(&self, task_id: &TaskId<'static>) -> u32 {
+        self.task_definitions
+            .get(task_id)
+            .map_or(1, |definition| definition.weight.max(1))
+    }
+
     // Validates that UI is setup if any interactive tasks will be executed
     fn validate_interactive(&self, experimental_ui: bool) -> Vec<ValidateError> {
         // If experimental_ui is being used, then we don't need check for interactive
@@ -308,6 +632,14 @@ impl Engine<Built> {
     }
 }
 
+// The slots that tasks with the given weights hold on to when they all run at
+// once. A task that weighs more than there are slots takes all of them.
+fn slots_held(weights: &[u32], slots: u32) -> u32 {
+    weights
+        .iter()
+        .fold(0u32, |sum, weight| sum.saturating_add(*weight.min(&slots)))
+}
+
 #[derive(Debug, Error, Diagnostic)]
 pub enum ValidateError {
     #[error("Cannot find task definition for {task_id} in package {package_name}")]
@@ -334,11 +666,43 @@ pub enum ValidateError {
         persistent_count: u32,
         concurrency: u32,
     },
+    #[error(
+        "Your persistent tasks have a total weight of {persistent_weight} but `turbo` is \
+         configured for concurrency of {concurrency}. Set --concurrency to at least {}",
+        persistent_weight.saturating_add(1)
+    )]
+    PersistentTaskWeightExceedsConcurrency {
+        persistent_weight: u32,
+        concurrency: u32,
+    },
+    #[error(
+        "The persistent tasks using the pool \"{pool}\" hold on to {persistent_weight} of its \
+         {size} slots for the whole run, so not every task in the pool can run. Set the size of \
+         \"{pool}\" to at least {required} in the root turbo.json"
+    )]
+    PersistentTasksExceedPool {
+        pool: String,
+        persistent_weight: u32,
+        size: u32,
+        required: u32,
+    },
     #[error(
         "Cannot run interactive task \"{task}\" without experimental UI. Set `\"experimentalUI\": \
          true` in `turbo.json` or `TURBO_EXPERIMENTAL_UI=true` as an environment variable"
     )]
     InteractiveNeedsUI { task: String },
+    #[error(
+        "\"{task}\" uses the pool \"{pool}\", which isn't defined in \"pools\" in the root \
+         turbo.json"
+    )]
+    UnknownPool {
+        #[label("task defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+        task: String,
+        pool: String,
+    },
 }
 
 impl fmt::Display for TaskNode {
@@ -363,6 +727,7 @@ mod test {
     };
 
     use super::*;
+    use crate::run::task_id::TaskName;
 
     struct DummyDiscovery<'a>(&'a TempDir);
 
@@ -468,4 +833,273 @@ mod test {
         // if our limit is greater, then it should pass
         engine.validate(&graph, 4, false).expect("ok");
     }
+
+    #[tokio::test]
+    async fn test_validate_persistent_sidecar() {
+        let tmp = tempdir::TempDir::new("persistent_sidecar").unwrap();
+
+        let engine_with = |sidecars: Vec<Spanned<TaskName<'static>>>| {
+            let mut engine = Engine::new();
+            let sidecar = TaskId::new("a", "build");
+            let dependent = TaskId::new("b", "build");
+            let sidecar_index = engine.get_index(&sidecar);
+            let dependent_index = engine.get_index(&dependent);
+            engine
+                .task_graph
+                .add_edge(dependent_index, sidecar_index, ());
+            engine.add_definition(
+                sidecar,
+                TaskDefinition {
+                    persistent: true,
+                    ..Default::default()
+                },
+            );
+            engine.add_definition(
+                dependent,
+                TaskDefinition {
+                    sidecars,
+                    ..Default::default()
+                },
+            );
+            engine.seal()
+        };
+
+        let graph = PackageGraph::builder(
+            AbsoluteSystemPath::from_std_path(tmp.path()).unwrap(),
+            PackageJson::default(),
+        )
+        .with_package_discovery(DummyDiscovery(&tmp))
+        .build()
+        .await
+        .unwrap();
+
+        // A persistent sidecar doesn't count towards the persistent tasks
+        engine_with(vec![Spanned::new(TaskName::from("a#build"))])
+            .validate(&graph, 1, false)
+            .expect("ok");
+
+        let errors = engine_with(vec![])
+            .validate(&graph, 2, false)
+            .expect_err("depends on a persistent task");
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec!["\"a#build\" is a persistent task, \"b#build\" cannot depend on it"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_pools() {
+        let tmp = tempdir::TempDir::new("validate_pools").unwrap();
+        let graph = PackageGraph::builder(
+            AbsoluteSystemPath::from_std_path(tmp.path()).unwrap(),
+            PackageJson::default(),
+        )
+        .with_package_discovery(DummyDiscovery(&tmp))
+        .build()
+        .await
+        .unwrap();
+
+        let mut engine = Engine::new();
+        for (package, pool) in [("web", "browser"), ("docs", "browser"), ("api", "database")] {
+            engine.add_definition(
+                TaskId::new(package, "e2e").into_owned(),
+                TaskDefinition {
+                    pool: Some(pool.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        let engine = engine.seal();
+
+        let errors = engine
+            .validate_pools(&graph, &BTreeMap::from([("database".to_string(), 1)]))
+            .expect_err("browser pool isn't defined");
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec![
+                "\"docs#e2e\" uses the pool \"browser\", which isn't defined in \"pools\" in the \
+                 root turbo.json",
+                "\"web#e2e\" uses the pool \"browser\", which isn't defined in \"pools\" in the \
+                 root turbo.json",
+            ]
+        );
+
+        engine
+            .validate_pools(
+                &graph,
+                &BTreeMap::from([("browser".to_string(), 2), ("database".to_string(), 1)]),
+            )
+            .expect("all pools are defined");
+    }
+
+    // Persistent build tasks in packages a and b with the given weights and
+    // pool, and a build task in package c that isn't persistent in `c_pool`
+    fn persistent_engine(weights: [u32; 2], pool: Option<&str>, c_pool: Option<&str>) -> Engine {
+        let mut engine = Engine::new();
+        for (package, weight, pool) in [
+            ("a", weights[0], pool),
+            ("b", weights[1], pool),
+            ("c", 1, c_pool),
+        ] {
+            let task_id = TaskId::new(package, "build");
+            engine.get_index(&task_id);
+            engine.add_definition(
+                task_id,
+                TaskDefinition {
+                    persistent: package != "c",
+                    weight,
+                    pool: pool.map(|pool| pool.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        engine.seal()
+    }
+
+    #[tokio::test]
+    async fn test_validate_persistent_weight() {
+        let tmp = tempdir::TempDir::new("persistent_weight").unwrap();
+        let graph = PackageGraph::builder(
+            AbsoluteSystemPath::from_std_path(tmp.path()).unwrap(),
+            PackageJson::default(),
+        )
+        .with_package_discovery(DummyDiscovery(&tmp))
+        .build()
+        .await
+        .unwrap();
+        let engine = persistent_engine([2, 1], None, None);
+
+        // The persistent tasks hold on to 3 slots, leaving none for the others
+        let errors = engine.validate(&graph, 3, false).expect_err("not enough");
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Your persistent tasks have a total weight of 3 but `turbo` is configured for \
+                 concurrency of 3. Set --concurrency to at least 4"
+            ]
+        );
+        // A task that weighs more than the concurrency takes all of the slots
+        engine.validate(&graph, 2, false).expect_err("not enough");
+
+        engine.validate(&graph, 4, false).expect("ok");
+    }
+
+    #[tokio::test]
+    async fn test_validate_persistent_pool() {
+        let tmp = tempdir::TempDir::new("persistent_pool").unwrap();
+        let graph = PackageGraph::builder(
+            AbsoluteSystemPath::from_std_path(tmp.path()).unwrap(),
+            PackageJson::default(),
+        )
+        .with_package_discovery(DummyDiscovery(&tmp))
+        .build()
+        .await
+        .unwrap();
+        let pool = |size| BTreeMap::from([("dev".to_string(), size)]);
+        let engine = persistent_engine([1, 2], Some("dev"), Some("dev"));
+
+        // The persistent tasks hold on to the whole pool, so c#build would never run
+        let errors = engine
+            .validate_pools(&graph, &pool(3))
+            .expect_err("no room for c#build");
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec![
+                "The persistent tasks using the pool \"dev\" hold on to 3 of its 3 slots for the \
+                 whole run, so not every task in the pool can run. Set the size of \"dev\" to at \
+                 least 4 in the root turbo.json"
+            ]
+        );
+        engine.validate_pools(&graph, &pool(4)).expect("ok");
+
+        // Even without other tasks, the persistent tasks need to fit in the pool
+        // to all start
+        let engine = persistent_engine([1, 2], Some("dev"), None);
+        engine
+            .validate_pools(&graph, &pool(2))
+            .expect_err("b#build would never start");
+        engine.validate_pools(&graph, &pool(3)).expect("ok");
+    }
+
+    fn task_ids(engine: &Engine) -> Vec<String> {
+        let mut tasks = engine
+            .tasks()
+            .filter_map(|node| match node {
+                TaskNode::Root => None,
+                TaskNode::Task(task_id) => Some(task_id.to_string()),
+            })
+            .collect::<Vec<_>>();
+        tasks.sort();
+        tasks
+    }
+
+    #[test]
+    fn test_subgraph_engines() {
+        // a#build <- b#build <- b#dev (persistent)
+        // c#build
+        let mut engine = Engine::new();
+        let a_build = TaskId::new("a", "build");
+        let b_build = TaskId::new("b", "build");
+        let b_dev = TaskId::new("b", "dev");
+        let c_build = TaskId::new("c", "build");
+
+        for task_id in [&a_build, &b_build, &b_dev, &c_build] {
+            engine.add_definition(
+                task_id.clone(),
+                TaskDefinition {
+                    persistent: task_id == &b_dev,
+                    ..Default::default()
+                },
+            );
+        }
+        engine.connect_to_root(&a_build);
+        engine.connect_to_root(&c_build);
+        let a_build_index = engine.get_index(&a_build);
+        let b_build_index = engine.get_index(&b_build);
+        let b_dev_index = engine.get_index(&b_dev);
+        engine.task_graph.add_edge(b_build_index, a_build_index, ());
+        engine.task_graph.add_edge(b_dev_index, b_build_index, ());
+
+        let engine = engine.seal();
+
+        assert_eq!(
+            task_ids(&engine.create_engine_for_subgraph(&HashSet::from([PackageName::from("a")]))),
+            vec!["a#build", "b#build"]
+        );
+        assert_eq!(
+            task_ids(&engine.create_engine_for_subgraph(&HashSet::from([PackageName::from("b")]))),
+            vec!["a#build", "b#build"]
+        );
+        assert_eq!(
+            task_ids(&engine.create_engine_for_persistent_tasks()),
+            vec!["a#build", "b#build", "b#dev"]
+        );
+        assert_eq!(
+            task_ids(&engine.create_engine_without_persistent_tasks()),
+            vec!["a#build", "b#build", "c#build"]
+        );
+        assert!(!engine
+            .create_engine_for_subgraph(&HashSet::new())
+            .has_tasks());
+        assert_eq!(
+            task_ids(&engine.create_engine_for_affected_tasks(|task_id, _| task_id == &a_build)),
+            vec!["a#build", "b#build", "b#dev"]
+        );
+        assert_eq!(
+            task_ids(&engine.create_engine_for_affected_tasks(|task_id, _| task_id == &c_build)),
+            vec!["c#build"]
+        );
+        assert_eq!(
+            engine
+                .entry_tasks()
+                .into_iter()
+                .map(|task_id| task_id.to_string())
+                .collect::<Vec<_>>(),
+            vec!["b#dev", "c#build"]
+        );
+        assert_eq!(
+            task_ids(&engine.create_engine_for_tasks(&HashSet::from([b_build.clone()]))),
+            vec!["a#build", "b#build"]
+        );
+    }
 }
@@ -168,18 +168,22 @@ pub fn infer_framework(workspace: &PackageInfo, is_monorepo: bool) -> Option<&'s
         .find(|framework| framework.dependency_match.test(workspace, is_monorepo))
 }
 
+/// Looks up a framework by the slug that `infer_framework` found for a task
+pub fn framework_by_slug(slug: &str) -> Option<&'static Framework> {
+    get_frameworks()
+        .iter()
+        .find(|framework| framework.slug == slug)
+}
+
 #[cfg(test)]
 mod tests {
     use test_case::test_case;
     use turborepo_repository::{package_graph::PackageInfo, package_json::PackageJson};
 
-    use crate::framework::{get_frameworks, infer_framework, Framework};
+    use crate::framework::{framework_by_slug, infer_framework, Framework};
 
     fn get_framework_by_slug(slug: &str) -> &'static Framework {
-        get_frameworks()
-            .iter()
-            .find(|framework| framework.slug == slug)
-            .expect("framework not found")
+        framework_by_slug(slug).expect("framework not found")
     }
 
     #[test_case(PackageInfo::default(), None, true; "empty dependencies")]
@@ -37,12 +37,18 @@ mod task_hash;
 mod tracing;
 mod turbo_json;
 mod unescape;
+mod validate;
 
 pub use crate::{
     child::spawn_child,
     cli::Args,
     daemon::{DaemonClient, DaemonConnector, Paths as DaemonPaths},
     run::package_discovery::DaemonPackageDiscovery,
+    turbo_json::{
+        parser::{task_definition_keys, turbo_json_keys},
+        resolve_task_definition,
+    },
+    validate::{validate_turbo_jsons, ConfigProblem, Error as ValidateError},
 };
 
 pub fn get_version() -> &'static str {
@@ -1,12 +1,16 @@
 use std::backtrace;
 
+use camino::Utf8PathBuf;
 use thiserror::Error;
 use turbopath::AnchoredSystemPathBuf;
 use turborepo_cache::CacheOpts;
 
 use crate::{
-    cli::{Command, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode, RunArgs},
-    run::task_id::TaskId,
+    cli::{
+        Command, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputFormat, OutputLogsMode, RunArgs,
+        Shard, SummarizeMode,
+    },
+    run::{scope::AffectedRange, task_id::TaskId},
     Args,
 };
 
@@ -30,7 +34,7 @@ pub enum Error {
     Path(#[from] turbopath::PathError),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Opts {
     pub cache_opts: CacheOpts,
     pub run_opts: RunOpts,
@@ -70,6 +74,14 @@ impl Opts {
             cmd.push_str(" --only");
         }
 
+        if let Some(shard) = self.run_opts.shard {
+            cmd.push_str(&format!(" --shard={shard}"));
+        }
+
+        if let Some(shard_durations) = &self.run_opts.shard_durations {
+            cmd.push_str(&format!(" --shard-durations={shard_durations}"));
+        }
+
         if !self.run_opts.pass_through_args.is_empty() {
             cmd.push_str(" -- ");
             cmd.push_str(&self.run_opts.pass_through_args.join(" "));
@@ -100,7 +112,7 @@ impl<'a> TryFrom<&'a Args> for Opts {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct RunCacheOpts {
     pub(crate) skip_reads: bool,
     pub(crate) skip_writes: bool,
@@ -117,7 +129,7 @@ impl<'a> From<&'a RunArgs> for RunCacheOpts {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RunOpts {
     pub(crate) tasks: Vec<String>,
     pub(crate) concurrency: u32,
@@ -127,6 +139,14 @@ pub struct RunOpts {
     pub(crate) framework_inference: bool,
     pub profile: Option<String>,
     pub(crate) continue_on_error: bool,
+    // Overrides the number of retries set for each task in turbo.json
+    pub(crate) retries: Option<u32>,
+    // Overrides the timeout set for each task in turbo.json
+    pub(crate) task_timeout: Option<u64>,
+    // Only run the tasks of this shard of the run
+    pub(crate) shard: Option<Shard>,
+    // A run summary to balance the shards by
+    pub(crate) shard_durations: Option<Utf8PathBuf>,
     pub(crate) pass_through_args: Vec<String>,
     pub(crate) only: bool,
     pub(crate) dry_run: Option<DryRunMode>,
@@ -135,7 +155,16 @@ pub struct RunOpts {
     pub(crate) single_package: bool,
     pub log_prefix: ResolvedLogPrefix,
     pub log_order: ResolvedLogOrder,
-    pub summarize: Option<Option<bool>>,
+    pub summarize: Option<Option<SummarizeMode>>,
+    pub(crate) output_format: OutputFormat,
+    // File to write run events to, alongside the regular output
+    pub(crate) events_file: Option<String>,
+    // Print why tasks missed the cache
+    pub(crate) explain: bool,
+    // Report env vars that tasks read without declaring them
+    pub(crate) audit_env: bool,
+    // Re-run cache hits to check that their outputs are reproducible
+    pub(crate) verify_cache: bool,
     pub(crate) experimental_space_id: Option<String>,
     pub is_github_actions: bool,
 }
@@ -155,7 +184,7 @@ impl RunOpts {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum GraphOpts {
     Stdout,
     File(String),
@@ -213,6 +242,11 @@ impl<'a> TryFrom<&'a RunArgs> for RunOpts {
             log_prefix,
             log_order,
             summarize: args.summarize,
+            output_format: args.output_format.unwrap_or_default(),
+            events_file: args.events_file.clone(),
+            explain: args.explain,
+            audit_env: args.audit_env,
+            verify_cache: args.verify_cache,
             experimental_space_id: args.experimental_space_id.clone(),
             framework_inference: args.framework_inference,
             env_mode: args.env_mode,
@@ -220,6 +254,10 @@ impl<'a> TryFrom<&'a RunArgs> for RunOpts {
             parallel: args.parallel,
             profile: args.profile.clone(),
             continue_on_error: args.continue_execution,
+            retries: args.retries,
+            task_timeout: args.task_timeout,
+            shard: args.shard,
+            shard_durations: args.shard_durations.clone(),
             pass_through_args: args.pass_through_args.clone(),
             only: args.only,
             daemon: args.daemon(),
@@ -264,7 +302,7 @@ impl From<LogPrefix> for ResolvedLogPrefix {
 
 // LegacyFilter holds the options in use before the filter syntax. They have
 // their own rules for how they are compiled into filter expressions.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct LegacyFilter {
     // include_dependencies is whether to include pkg.dependencies in execution (defaults to false)
     include_dependencies: bool,
@@ -306,13 +344,14 @@ impl LegacyFilter {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ScopeOpts {
     pub pkg_inference_root: Option<AnchoredSystemPathBuf>,
     pub legacy_filter: LegacyFilter,
     pub global_deps: Vec<String>,
     pub filter_patterns: Vec<String>,
     pub ignore_patterns: Vec<String>,
+    pub affected_range: Option<AffectedRange>,
 }
 
 impl<'a> TryFrom<&'a RunArgs> for ScopeOpts {
@@ -337,6 +376,7 @@ impl<'a> TryFrom<&'a RunArgs> for ScopeOpts {
             legacy_filter,
             filter_patterns: args.filter.clone(),
             ignore_patterns: args.ignore.clone(),
+            affected_range: args.affected.then(AffectedRange::infer),
         })
     }
 }
@@ -521,6 +561,10 @@ mod test {
             framework_inference: true,
             profile: None,
             continue_on_error: opts_input.continue_on_error,
+            retries: None,
+            task_timeout: None,
+            shard: None,
+            shard_durations: None,
             pass_through_args: opts_input.pass_through_args,
             only: opts_input.only,
             dry_run: opts_input.dry_run,
@@ -530,6 +574,11 @@ mod test {
             log_prefix: crate::opts::ResolvedLogPrefix::Task,
             log_order: crate::opts::ResolvedLogOrder::Stream,
             summarize: None,
+            output_format: crate::cli::OutputFormat::Text,
+            events_file: None,
+            explain: false,
+            audit_env: false,
+            verify_cache: false,
             experimental_space_id: None,
             is_github_actions: false,
         };
@@ -542,6 +591,7 @@ mod test {
             global_deps: vec![],
             filter_patterns: opts_input.filter_patterns,
             ignore_patterns: vec![],
+            affected_range: None,
         };
         let opts = Opts {
             run_opts,
@@ -59,6 +59,16 @@ impl ProcessManager {
         let use_pty = !cfg!(windows) && atty::is(atty::Stream::Stdout);
         Self::new(use_pty)
     }
+
+    /// Whether children of this manager are attached to a pty
+    pub fn use_pty(&self) -> bool {
+        self.use_pty
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_closing(&self) -> bool {
+        self.state.lock().expect("not poisoned").is_closing
+    }
 }
 
 impl ProcessManager {
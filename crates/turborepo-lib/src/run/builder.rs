@@ -1,5 +1,5 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     io::{ErrorKind, IsTerminal},
     sync::Arc,
     time::SystemTime,
@@ -38,12 +38,18 @@ use {
 };
 
 use crate::{
-    cli::{DryRunMode, EnvMode},
+    cli::{DryRunMode, EnvMode, OutputFormat},
     commands::CommandBase,
     engine::{Engine, EngineBuilder},
     opts::Opts,
     process::ProcessManager,
-    run::{scope, task_access::TaskAccess, task_id::TaskName, Error, Run, RunCache},
+    run::{
+        scope,
+        shard::{self, ShardPlan},
+        task_access::TaskAccess,
+        task_id::TaskName,
+        Error, Run, RunCache,
+    },
     shim::TurboState,
     signal::{SignalHandler, SignalSubscriber},
     task_hash::PackageInputsHashes,
@@ -65,8 +71,10 @@ impl RunBuilder {
     pub fn new(base: CommandBase, api_auth: Option<APIAuth>) -> Result<Self, Error> {
         let mut opts: Opts = base.args().try_into()?;
         let config = base.config()?;
+        let remote_cache_backend = config.remote_cache_backend(&base.repo_root)?;
         let is_linked = turborepo_api_client::is_linked(&api_auth);
-        if !is_linked {
+        // Only the Vercel backend needs the repository to be linked
+        if !is_linked && remote_cache_backend.requires_login() {
             opts.cache_opts.skip_remote = true;
         } else if let Some(enabled) = config.enabled {
             // We're linked, but if the user has explicitly enabled or disabled, use that
@@ -82,11 +90,15 @@ impl RunBuilder {
             unused_remote_cache_opts_team_id,
             signature,
         ));
+        opts.cache_opts.eviction_opts = config.local_cache_eviction()?;
+        opts.cache_opts.remote_cache_backend = remote_cache_backend;
         if opts.run_opts.experimental_space_id.is_none() {
             opts.run_opts.experimental_space_id = config.spaces_id().map(|s| s.to_owned());
         }
         let version = base.version();
-        let experimental_ui = config.experimental_ui();
+        // The UI can't share stdout with run events
+        let experimental_ui =
+            config.experimental_ui() && opts.run_opts.output_format == OutputFormat::Text;
         let processes = ProcessManager::new(
             // We currently only use a pty if the following are met:
             // - we're attached to a tty
@@ -341,8 +353,45 @@ impl RunBuilder {
             filtered_pkgs
         };
 
+        let affected_tasks = self
+            .opts
+            .scope_opts
+            .affected_range
+            .as_ref()
+            .map(|range| {
+                scope::AffectedTasks::new(
+                    range,
+                    &self.opts.scope_opts,
+                    &self.repo_root,
+                    &pkg_dep_graph,
+                    &scm,
+                    &root_turbo_json,
+                )
+            })
+            .transpose()?;
+
         let env_at_execution_start = EnvironmentVariableMap::infer();
-        let mut engine = self.build_engine(&pkg_dep_graph, &root_turbo_json, &filtered_pkgs)?;
+        let mut engine = self.build_engine(
+            &pkg_dep_graph,
+            &root_turbo_json,
+            &filtered_pkgs,
+            affected_tasks.as_ref(),
+        )?;
+
+        // Only durations that every shard reads the same way can be used to
+        // balance them, the run summaries in .turbo/runs differ between machines
+        let shard_durations = match &self.opts.run_opts.shard_durations {
+            Some(path) => shard::read_durations(&AbsoluteSystemPathBuf::from_cwd(path)?)?,
+            None => HashMap::new(),
+        };
+        let shard_plan = self
+            .opts
+            .run_opts
+            .shard
+            .map(|shard| ShardPlan::new(&engine, shard, &shard_durations));
+        if let Some(shard_plan) = &shard_plan {
+            engine = engine.create_engine_for_tasks(&shard_plan.tasks());
+        }
 
         let workspaces = pkg_dep_graph.packages().collect();
         let package_inputs_hashes = PackageInputsHashes::calculate_file_hashes(
@@ -356,7 +405,15 @@ impl RunBuilder {
 
         if self.opts.run_opts.parallel {
             pkg_dep_graph.remove_package_dependencies();
-            engine = self.build_engine(&pkg_dep_graph, &root_turbo_json, &filtered_pkgs)?;
+            engine = self.build_engine(
+                &pkg_dep_graph,
+                &root_turbo_json,
+                &filtered_pkgs,
+                affected_tasks.as_ref(),
+            )?;
+            if let Some(shard_plan) = &shard_plan {
+                engine = engine.create_engine_for_tasks(&shard_plan.tasks());
+            }
         }
 
         let color_selector = ColorSelector::default();
@@ -397,8 +454,10 @@ impl RunBuilder {
             package_inputs_hashes,
             scm,
             engine: Arc::new(engine),
+            shard_plan,
             run_cache,
             signal_handler: signal_handler.clone(),
+            should_print_prelude: true,
         })
     }
 
@@ -407,8 +466,9 @@ impl RunBuilder {
         pkg_dep_graph: &PackageGraph,
         root_turbo_json: &TurboJson,
         filtered_pkgs: &HashSet<PackageName>,
+        affected_tasks: Option<&scope::AffectedTasks>,
     ) -> Result<Engine, Error> {
-        let engine = EngineBuilder::new(
+        let mut engine = EngineBuilder::new(
             &self.repo_root,
             pkg_dep_graph,
             self.opts.run_opts.single_package,
@@ -427,6 +487,16 @@ impl RunBuilder {
         }))
         .build()?;
 
+        if let Some(affected_tasks) = affected_tasks {
+            engine = engine.create_engine_for_affected_tasks(|task_id, definition| {
+                affected_tasks.is_affected(task_id, definition)
+            });
+        }
+
+        engine
+            .validate_pools(pkg_dep_graph, &root_turbo_json.pools)
+            .map_err(Error::EngineValidation)?;
+
         if !self.opts.run_opts.parallel {
             engine
                 .validate(
@@ -1,6 +1,6 @@
 use std::{io::Write, sync::Arc, time::Duration};
 
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 use turbopath::{
     AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
 };
@@ -113,6 +113,19 @@ impl RunCache {
         }
     }
 
+    /// Evicts local cache entries that exceed the configured limits
+    pub async fn prune_cache(&self) {
+        match self.cache.prune().await {
+            Ok(Some(summary)) if summary.removed_entries > 0 => debug!(
+                "evicted {} entries ({} bytes) from the local cache",
+                summary.removed_entries, summary.removed_bytes
+            ),
+            Ok(_) => {}
+            // Eviction failing shouldn't fail the run
+            Err(e) => warn!("failed to prune local cache: {e}"),
+        }
+    }
+
     pub async fn shutdown_cache(&self) {
         // Ignore errors coming from cache already shutting down
         self.cache.shutdown().await.ok();
@@ -327,13 +340,15 @@ impl TaskCache {
         Ok(cache_status)
     }
 
+    /// Saves the outputs of the task to the cache, returning whether they were
+    /// written
     pub async fn save_outputs(
         &mut self,
         duration: Duration,
         telemetry: &PackageTaskEventBuilder,
-    ) -> Result<(), Error> {
+    ) -> Result<bool, Error> {
         if self.caching_disabled || self.run_cache.writes_disabled {
-            return Ok(());
+            return Ok(false);
         }
 
         debug!("caching outputs: outputs: {:?}", &self.repo_relative_globs);
@@ -384,12 +399,17 @@ impl TaskCache {
 
         self.expanded_outputs = relative_paths;
 
-        Ok(())
+        Ok(true)
     }
 
     pub fn expanded_outputs(&self) -> &[AnchoredSystemPathBuf] {
         &self.expanded_outputs
     }
+
+    /// The repo relative globs for the outputs of the task
+    pub fn output_globs(&self) -> &TaskOutputs {
+        &self.repo_relative_globs
+    }
 }
 
 #[derive(Clone)]
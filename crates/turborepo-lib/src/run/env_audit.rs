@@ -0,0 +1,281 @@
+//! Finds environment variables that tasks read without declaring them in
+//! turbo.json for `--audit-env`. Tasks report the variables they read in the
+//! trace file that turbo points them to with `TURBOREPO_TRACE_FILE`, see
+//! `task_access`. A variable that affects a task's outputs without being part
+//! of its hash poisons the cache.
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use serde_json::json;
+use tracing::warn;
+use turbopath::AbsoluteSystemPathBuf;
+use turborepo_env::EnvironmentVariableMap;
+use turborepo_ui::{cprintln, BOLD, BOLD_GREEN, GREY, UI};
+
+use crate::{
+    framework::framework_by_slug,
+    run::task_access::{TaskAccessTraceFile, TASK_ACCESS_ENV_KEY},
+    task_graph::TaskDefinition,
+};
+
+// Variables that are passed to every task or set by turbo itself
+const ALWAYS_AVAILABLE: &[&str] = &[
+    "SHELL",
+    "PATH",
+    "SYSTEMROOT",
+    "Path",
+    "SystemRoot",
+    "TURBO_HASH",
+    TASK_ACCESS_ENV_KEY,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Audit {
+    // The task wrote a trace, `undeclared` are the variables it read that
+    // aren't covered by turbo.json
+    Traced {
+        task_name: String,
+        env: Vec<String>,
+        undeclared: Vec<String>,
+    },
+    // The task ran, but didn't write a trace
+    Untraced,
+    // The task was restored from the cache, so it didn't read anything
+    Cached,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnvAudit {
+    repo_root: AbsoluteSystemPathBuf,
+    // `globalEnv` and `globalPassThroughEnv` of the root turbo.json
+    global_env: Vec<String>,
+    audits: Arc<Mutex<BTreeMap<String, Audit>>>,
+}
+
+impl EnvAudit {
+    pub fn new(repo_root: AbsoluteSystemPathBuf, global_env: Vec<String>) -> Self {
+        Self {
+            repo_root,
+            global_env,
+            audits: Arc::default(),
+        }
+    }
+
+    pub fn cached(&self, task_id: &str) {
+        self.insert(task_id, Audit::Cached);
+    }
+
+    /// Reads the trace of a task that was run and records the variables it
+    /// read that aren't covered by its definition, the global env or the env
+    /// wildcards of its framework
+    pub fn record(
+        &self,
+        task_id: &str,
+        task_name: &str,
+        task_hash: &str,
+        definition: &TaskDefinition,
+        framework: Option<&str>,
+    ) {
+        let Some(trace) = TaskAccessTraceFile::read(&self.repo_root, task_hash) else {
+            self.insert(task_id, Audit::Untraced);
+            return;
+        };
+
+        let read = trace
+            .accessed
+            .env_var_keys
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<_>>();
+        let framework_wildcards = framework
+            .and_then(framework_by_slug)
+            .map_or(&[][..], |framework| framework.env_wildcards());
+        let declared = definition
+            .env
+            .iter()
+            .chain(definition.pass_through_env.iter().flatten())
+            .chain(&self.global_env)
+            .map(String::as_str)
+            .chain(framework_wildcards.iter().copied())
+            .collect::<Vec<_>>();
+
+        self.insert(
+            task_id,
+            Audit::Traced {
+                task_name: task_name.to_string(),
+                env: definition.env.clone(),
+                undeclared: undeclared(&read, &declared),
+            },
+        );
+    }
+
+    fn insert(&self, task_id: &str, audit: Audit) {
+        self.audits
+            .lock()
+            .expect("env audit lock poisoned")
+            .insert(task_id.to_string(), audit);
+    }
+
+    pub fn print(&self, ui: UI) {
+        let audits = self.audits.lock().expect("env audit lock poisoned");
+        if audits.is_empty() {
+            return;
+        }
+
+        let findings = audits
+            .iter()
+            .filter_map(|(task_id, audit)| match audit {
+                Audit::Traced { undeclared, .. } if !undeclared.is_empty() => {
+                    Some((task_id, undeclared))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let traced = audits
+            .values()
+            .filter(|audit| matches!(audit, Audit::Traced { .. }))
+            .count();
+
+        println!();
+        if !findings.is_empty() {
+            cprintln!(
+                ui,
+                BOLD,
+                "Environment variables read by tasks without being declared in turbo.json:"
+            );
+            for (task_id, undeclared) in findings {
+                println!("  {task_id}: {}", undeclared.join(", "));
+            }
+            println!();
+            println!("To declare them, add them to \"env\" in turbo.json:");
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&suggestion(&audits))
+                    .expect("suggestion is valid json")
+            );
+        } else if traced > 0 {
+            cprintln!(
+                ui,
+                BOLD_GREEN,
+                "No undeclared environment variables found in {traced} traced tasks"
+            );
+        }
+
+        let not_audited = audits.len() - traced;
+        if not_audited > 0 {
+            cprintln!(
+                ui,
+                GREY,
+                "{not_audited} tasks couldn't be audited because they were restored from the \
+                 cache or didn't write a trace"
+            );
+        }
+    }
+}
+
+/// The variables in `read` that aren't matched by any of the `declared` env
+/// var names and wildcards
+fn undeclared(read: &[String], declared: &[&str]) -> Vec<String> {
+    let read_map = EnvironmentVariableMap::from(
+        read.iter()
+            .map(|key| (key.clone(), String::new()))
+            .collect::<HashMap<_, _>>(),
+    );
+    let covered = match read_map.from_wildcards(declared) {
+        Ok(covered) => covered,
+        Err(err) => {
+            warn!("unable to match declared environment variables: {err}");
+            return Vec::new();
+        }
+    };
+
+    read.iter()
+        .filter(|key| !covered.contains_key(*key) && !ALWAYS_AVAILABLE.contains(&key.as_str()))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+// The "env" of each task with undeclared variables, extended by them. Tasks are
+// keyed by name, so the env of every package's task of the same name is
+// combined.
+fn suggestion(audits: &BTreeMap<String, Audit>) -> serde_json::Value {
+    let mut env_by_task = BTreeMap::<&str, BTreeSet<&str>>::new();
+    for audit in audits.values() {
+        let Audit::Traced {
+            task_name,
+            env,
+            undeclared,
+        } = audit
+        else {
+            continue;
+        };
+        if undeclared.is_empty() {
+            continue;
+        }
+        env_by_task
+            .entry(task_name.as_str())
+            .or_default()
+            .extend(env.iter().chain(undeclared).map(String::as_str));
+    }
+
+    let pipeline = env_by_task
+        .into_iter()
+        .map(|(task_name, env)| (task_name, json!({ "env": env })))
+        .collect::<BTreeMap<_, _>>();
+    json!({ "pipeline": pipeline })
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test_case(&["API_URL", "NODE_ENV"], &["NODE_ENV"], &["API_URL"] ; "missing from env")]
+    #[test_case(&["NEXT_PUBLIC_URL", "NEXT_TELEMETRY"], &["NEXT_PUBLIC_*"], &["NEXT_TELEMETRY"] ; "wildcard")]
+    #[test_case(&["NEXT_PUBLIC_SECRET"], &["NEXT_PUBLIC_*", "!NEXT_PUBLIC_SECRET"], &["NEXT_PUBLIC_SECRET"] ; "excluded")]
+    #[test_case(&["PATH", "TURBO_HASH", "SHELL"], &[], &[] ; "always available")]
+    #[test_case(&["B", "A", "B"], &[], &["A", "B"] ; "sorted and deduplicated")]
+    fn test_undeclared(read: &[&str], declared: &[&str], expected: &[&str]) {
+        assert_eq!(undeclared(&strings(read), declared), strings(expected));
+    }
+
+    #[test]
+    fn test_suggestion() {
+        let traced = |task_name: &str, env: &[&str], undeclared: &[&str]| Audit::Traced {
+            task_name: task_name.to_string(),
+            env: strings(env),
+            undeclared: strings(undeclared),
+        };
+        let audits = BTreeMap::from([
+            (
+                "docs#build".to_string(),
+                traced("build", &["NODE_ENV"], &["API_URL"]),
+            ),
+            (
+                "web#build".to_string(),
+                traced("build", &[], &["SENTRY_DSN"]),
+            ),
+            ("web#lint".to_string(), traced("lint", &["CI"], &[])),
+            ("web#test".to_string(), Audit::Untraced),
+            ("ui#build".to_string(), Audit::Cached),
+        ]);
+
+        assert_eq!(
+            suggestion(&audits),
+            json!({
+                "pipeline": {
+                    "build": { "env": ["API_URL", "NODE_ENV", "SENTRY_DSN"] }
+                }
+            })
+        );
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     config, daemon, engine,
     engine::ValidateError,
     opts,
-    run::{global_hash, scope},
+    run::{global_hash, scope, shard},
     task_graph, task_hash,
 };
 
@@ -49,4 +49,8 @@ pub enum Error {
     Visitor(#[from] task_graph::VisitorError),
     #[error("error registering signal handler: {0}")]
     SignalHandler(std::io::Error),
+    #[error("unable to create events file {0}: {1}")]
+    EventsFile(String, #[source] std::io::Error),
+    #[error(transparent)]
+    Shard(#[from] shard::Error),
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::{HashMap, HashSet},
     fs::OpenOptions,
     io::{self, Write},
     process::{Command, Stdio},
@@ -9,7 +10,15 @@ use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 use turborepo_ui::{cprintln, cwrite, cwriteln, BOLD, BOLD_YELLOW_REVERSE, UI, YELLOW};
 use which::which;
 
-use crate::{engine::Engine, opts::GraphOpts, spawn_child};
+use crate::{
+    engine::{Engine, TaskNode},
+    opts::GraphOpts,
+    run::{
+        shard,
+        summary::{estimated_critical_path, TurboDuration},
+    },
+    spawn_child,
+};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -38,7 +47,7 @@ pub(crate) fn write_graph(
             if extension == "mermaid" {
                 render_mermaid_graph(&filename, engine, single_package)?;
             } else if extension == "html" {
-                render_html(&filename, engine, single_package)?;
+                render_html(&filename, engine, single_package, cwd)?;
             } else if let Ok(dot_path) = which("dot") {
                 let mut cmd = Command::new(dot_path);
                 cmd.stdin(Stdio::piped())
@@ -109,18 +118,52 @@ const HTML_SUFFIX: &str = r#"
 </html>
 "#;
 
+// Tasks are labeled with how long they took in their last run and the tasks
+// on the critical path are highlighted, if there are run summaries
 fn render_html(
     filename: &AbsoluteSystemPath,
     engine: &Engine,
     single_package: bool,
+    repo_root: &AbsoluteSystemPath,
 ) -> Result<(), Error> {
     let mut opts = OpenOptions::new();
     opts.truncate(true).create(true).write(true);
     let mut file = filename
         .open_with_options(opts)
         .map_err(Error::GraphOutput)?;
+
+    let durations = shard::load_durations(repo_root);
+    let tasks = engine
+        .task_definitions()
+        .keys()
+        .map(|task_id| {
+            let dependencies = engine
+                .dependencies(task_id)
+                .into_iter()
+                .flatten()
+                .filter_map(|node| match node {
+                    TaskNode::Root => None,
+                    TaskNode::Task(dependency) => Some(dependency.to_string()),
+                })
+                .collect();
+            (task_id.to_string(), dependencies)
+        })
+        .collect::<Vec<_>>();
+    let critical_path = estimated_critical_path(&tasks, &durations)
+        .into_iter()
+        .collect::<HashSet<_>>();
+
     let mut graph_buffer = Vec::new();
-    render_dot_graph(&mut graph_buffer, engine, single_package)?;
+    engine
+        .dot_graph_with_attributes(&mut graph_buffer, single_package, |task_id| {
+            timing_attributes(
+                task_id.to_string(),
+                single_package,
+                &durations,
+                &critical_path,
+            )
+        })
+        .map_err(Error::GraphOutput)?;
     let graph_string = String::from_utf8(graph_buffer).expect("graph rendering should be UTF-8");
 
     file.write_all(HTML_PREFIX.as_bytes())
@@ -137,6 +180,27 @@ fn render_html(
     Ok(())
 }
 
+fn timing_attributes(
+    task_id: String,
+    single_package: bool,
+    durations: &HashMap<String, u64>,
+    critical_path: &HashSet<String>,
+) -> Option<String> {
+    let duration = durations.get(&task_id)?;
+    let name = match single_package {
+        true => task_id
+            .split_once('#')
+            .map_or(task_id.as_str(), |(_, task)| task),
+        false => task_id.as_str(),
+    };
+    let duration = TurboDuration::from(chrono::Duration::milliseconds(*duration as i64));
+    let mut attributes = format!("label=\"{name} ({duration})\"");
+    if critical_path.contains(&task_id) {
+        attributes.push_str(", color=\"red\", penwidth=2");
+    }
+    Some(attributes)
+}
+
 fn filename_and_extension(
     cwd: &AbsoluteSystemPath,
     raw_filename: &str,
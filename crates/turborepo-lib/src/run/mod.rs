@@ -2,16 +2,25 @@
 
 pub mod builder;
 mod cache;
+pub(crate) mod env_audit;
 mod error;
 pub(crate) mod global_hash;
 mod graph_visualizer;
 pub(crate) mod package_discovery;
-mod scope;
+pub(crate) mod scope;
+pub(crate) mod shard;
 pub(crate) mod summary;
 pub mod task_access;
 pub mod task_id;
+pub(crate) mod verify_cache;
+pub mod watch;
 
-use std::{collections::HashSet, io::Write, sync::Arc};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    sync::Arc,
+};
 
 pub use cache::{ConfigCache, RunCache, TaskCache};
 use chrono::{DateTime, Local};
@@ -19,6 +28,7 @@ use tracing::debug;
 use turbopath::AbsoluteSystemPathBuf;
 use turborepo_analytics::AnalyticsHandle;
 use turborepo_api_client::{APIAuth, APIClient};
+use turborepo_cache::fs::FSCache;
 use turborepo_ci::Vendor;
 use turborepo_env::EnvironmentVariableMap;
 use turborepo_repository::package_graph::{PackageGraph, PackageName};
@@ -28,11 +38,18 @@ use turborepo_ui::{cprint, cprintln, BOLD_GREY, GREY, UI};
 
 pub use crate::run::error::Error;
 use crate::{
-    cli::EnvMode,
+    cli::{EnvMode, OutputFormat},
     engine::Engine,
     opts::Opts,
     process::ProcessManager,
-    run::{global_hash::get_global_hash_inputs, summary::RunTracker, task_access::TaskAccess},
+    run::{
+        env_audit::EnvAudit,
+        global_hash::get_global_hash_inputs,
+        shard::ShardPlan,
+        summary::{EventsClientHandle, RunTracker},
+        task_access::TaskAccess,
+        verify_cache::CacheVerifier,
+    },
     signal::SignalHandler,
     task_graph::Visitor,
     task_hash::{get_external_deps_hash, PackageInputsHashes},
@@ -59,11 +76,91 @@ pub struct Run {
     run_cache: Arc<RunCache>,
     signal_handler: SignalHandler,
     engine: Arc<Engine>,
+    // The tasks of each shard when running with --shard
+    shard_plan: Option<ShardPlan>,
     task_access: TaskAccess,
     analytics_handle: Option<AnalyticsHandle>,
+    should_print_prelude: bool,
 }
 
 impl Run {
+    /// Restricts the run to the tasks affected by changes to the given
+    /// packages, see `Engine::create_engine_for_subgraph`.
+    pub fn with_changed_packages(mut self, changed_packages: &HashSet<PackageName>) -> Self {
+        self.engine = Arc::new(self.engine.create_engine_for_subgraph(changed_packages));
+        self
+    }
+
+    /// Creates a run of the persistent tasks and their dependencies that
+    /// shares the configuration and package graph of this run.
+    ///
+    /// The new run gets its own process manager, so that a task failing in
+    /// this run doesn't stop the persistent tasks.
+    pub fn create_run_for_persistent_tasks(&self) -> Self {
+        let processes = ProcessManager::new(self.processes.use_pty());
+        if let Some(subscriber) = self.signal_handler.subscribe() {
+            let manager = processes.clone();
+            tokio::spawn(async move {
+                let _guard = subscriber.listen().await;
+                manager.stop().await;
+            });
+        }
+
+        Self {
+            version: self.version,
+            ui: self.ui,
+            experimental_ui: self.experimental_ui,
+            start_at: self.start_at,
+            processes,
+            run_telemetry: self.run_telemetry.clone(),
+            repo_root: self.repo_root.clone(),
+            opts: self.opts.clone(),
+            api_client: self.api_client.clone(),
+            api_auth: self.api_auth.clone(),
+            env_at_execution_start: self.env_at_execution_start.clone(),
+            filtered_pkgs: self.filtered_pkgs.clone(),
+            pkg_dep_graph: self.pkg_dep_graph.clone(),
+            root_turbo_json: self.root_turbo_json.clone(),
+            package_inputs_hashes: self.package_inputs_hashes.clone(),
+            scm: self.scm.clone(),
+            run_cache: self.run_cache.clone(),
+            signal_handler: self.signal_handler.clone(),
+            engine: Arc::new(self.engine.create_engine_for_persistent_tasks()),
+            shard_plan: self.shard_plan.clone(),
+            task_access: self.task_access.clone(),
+            // Analytics are sent once, when the run that owns the handle finishes
+            analytics_handle: None,
+            should_print_prelude: false,
+        }
+    }
+
+    /// Removes the persistent tasks from the run.
+    pub fn without_persistent_tasks(mut self) -> Self {
+        self.engine = Arc::new(self.engine.create_engine_without_persistent_tasks());
+        self
+    }
+
+    pub fn hide_prelude(mut self) -> Self {
+        self.should_print_prelude = false;
+        self
+    }
+
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    pub fn pkg_dep_graph(&self) -> &Arc<PackageGraph> {
+        &self.pkg_dep_graph
+    }
+
+    pub fn root_turbo_json(&self) -> &TurboJson {
+        &self.root_turbo_json
+    }
+
+    pub fn processes(&self) -> &ProcessManager {
+        &self.processes
+    }
+
     fn print_run_prelude(&self) {
         let targets_list = self.opts.run_opts.tasks.join(", ");
         if self.opts.run_opts.single_package {
@@ -87,6 +184,10 @@ impl Run {
             cprint!(self.ui, GREY, " in {} packages\n", self.filtered_pkgs.len());
         }
 
+        if let Some(range) = &self.opts.scope_opts.affected_range {
+            cprintln!(self.ui, GREY, "• Affected by changes since {}", range.base);
+        }
+
         let use_http_cache = !self.opts.cache_opts.skip_remote;
         if use_http_cache {
             cprintln!(self.ui, GREY, "• Remote caching enabled");
@@ -95,6 +196,20 @@ impl Run {
         }
     }
 
+    // Where run events should be written, if anywhere
+    fn events_writer(&self) -> Result<Option<Box<dyn Write + Send>>, Error> {
+        let run_opts = &self.opts.run_opts;
+        let writer: Box<dyn Write + Send> = match (&run_opts.events_file, run_opts.output_format) {
+            (Some(path), _) => {
+                let file = File::create(path).map_err(|e| Error::EventsFile(path.clone(), e))?;
+                Box::new(BufWriter::new(file))
+            }
+            (None, OutputFormat::Ndjson) => Box::new(std::io::stdout()),
+            (None, OutputFormat::Text) => return Ok(None),
+        };
+        Ok(Some(writer))
+    }
+
     pub async fn run(mut self) -> Result<i32, Error> {
         let analytics_handle = self.analytics_handle.take();
         let result = self.run_with_analytics().await;
@@ -109,7 +224,11 @@ impl Run {
     // We split this into a separate function because we need
     // to close the AnalyticsHandle regardless of whether the run succeeds or not
     async fn run_with_analytics(self) -> Result<i32, Error> {
-        if self.opts.run_opts.dry_run.is_none() && self.opts.run_opts.graph.is_none() {
+        if self.should_print_prelude
+            && self.opts.run_opts.dry_run.is_none()
+            && self.opts.run_opts.graph.is_none()
+            && self.opts.run_opts.output_format == OutputFormat::Text
+        {
             self.print_run_prelude();
         }
 
@@ -206,7 +325,16 @@ impl Run {
             Vendor::get_user(),
             &self.scm,
         );
+        let run_tracker = match self.events_writer()? {
+            Some(writer) => run_tracker.with_events(EventsClientHandle::start(writer)),
+            None => run_tracker,
+        };
+        let run_tracker = match self.shard_plan {
+            Some(shard_plan) => run_tracker.with_shard_plan(shard_plan),
+            None => run_tracker,
+        };
 
+        let run_cache = self.run_cache.clone();
         let mut visitor = Visitor::new(
             self.pkg_dep_graph.clone(),
             self.run_cache,
@@ -222,12 +350,38 @@ impl Run {
             &self.repo_root,
             global_env,
             self.experimental_ui,
+            self.root_turbo_json.pools.clone(),
         );
 
         if self.opts.run_opts.dry_run.is_some() {
             visitor.dry_run();
         }
 
+        if self.opts.run_opts.audit_env {
+            let global_env = self
+                .root_turbo_json
+                .global_env
+                .iter()
+                .chain(
+                    self.root_turbo_json
+                        .global_pass_through_env
+                        .iter()
+                        .flatten(),
+                )
+                .cloned()
+                .collect();
+            visitor.audit_env(EnvAudit::new(self.repo_root.clone(), global_env));
+        }
+
+        if self.opts.run_opts.verify_cache {
+            let cache = FSCache::new(
+                self.opts.cache_opts.override_dir.as_deref(),
+                &self.repo_root,
+                None,
+            )?;
+            visitor.verify_cache(CacheVerifier::new(self.repo_root.clone(), cache));
+        }
+
         // we look for this log line to mark the start of the run
         // in benchmarks, so please don't remove it
         debug!("running visitor");
@@ -263,6 +417,10 @@ impl Run {
             )
             .await?;
 
+        if self.opts.run_opts.dry_run.is_none() {
+            run_cache.prune_cache().await;
+        }
+
         Ok(exit_code)
     }
 }
@@ -0,0 +1,276 @@
+//! Support for `--affected`, which only runs the tasks whose inputs changed
+//! since a base git ref.
+
+use std::collections::HashMap;
+
+use tracing::{debug, warn};
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf, RelativeUnixPathBuf};
+use turborepo_ci::{non_empty_var, Vendor};
+use turborepo_repository::{
+    change_mapper::{ChangedPackageFiles, PackageFileChanges},
+    package_graph::{PackageGraph, PackageName},
+};
+use turborepo_scm::SCM;
+use wax::{Glob, Program};
+
+use super::{change_detector::ScopeChangeDetector, ResolutionError};
+use crate::{
+    opts::ScopeOpts, run::task_id::TaskId, task_graph::TaskDefinition, turbo_json::TurboJson,
+};
+
+const DEFAULT_BASE: &str = "main";
+const INPUT_INCLUDE_DEFAULT_FILES: &str = "$TURBO_DEFAULT$";
+
+/// The git refs that changes are calculated between
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffectedRange {
+    pub base: String,
+    /// `None` compares against the working tree
+    pub head: Option<String>,
+}
+
+impl AffectedRange {
+    /// Reads the range from `TURBO_SCM_BASE` and `TURBO_SCM_HEAD`. If no base
+    /// is set we use the one exposed by the CI vendor, falling back to `main`.
+    pub fn infer() -> Self {
+        let base = if let Some(base) = non_empty_var("TURBO_SCM_BASE") {
+            debug!("using TURBO_SCM_BASE as the base for --affected: {base}");
+            base
+        } else if let Some(base) = Vendor::get_base_ref() {
+            debug!("using the base exposed by the CI vendor for --affected: {base}");
+            base
+        } else {
+            // On a build of `main` itself nothing is affected, so we make sure
+            // this doesn't go unnoticed in CI
+            if turborepo_ci::is_ci() {
+                warn!(
+                    "unable to infer a base for --affected from the CI environment, comparing \
+                     against `{DEFAULT_BASE}`. Set TURBO_SCM_BASE to choose the base."
+                );
+            }
+            DEFAULT_BASE.to_string()
+        };
+        let head = non_empty_var("TURBO_SCM_HEAD");
+
+        Self { base, head }
+    }
+}
+
+/// The files that changed in each package over an `AffectedRange`
+pub struct AffectedTasks {
+    changes: Option<HashMap<PackageName, (AnchoredSystemPathBuf, ChangedPackageFiles)>>,
+}
+
+impl AffectedTasks {
+    pub fn new(
+        range: &AffectedRange,
+        opts: &ScopeOpts,
+        turbo_root: &AbsoluteSystemPath,
+        pkg_graph: &PackageGraph,
+        scm: &SCM,
+        root_turbo_json: &TurboJson,
+    ) -> Result<Self, ResolutionError> {
+        let global_deps = opts
+            .global_deps
+            .iter()
+            .map(|s| s.as_str())
+            .chain(root_turbo_json.global_deps.iter().map(|s| s.as_str()));
+        let change_detector = ScopeChangeDetector::new(
+            turbo_root,
+            scm,
+            pkg_graph,
+            global_deps,
+            opts.ignore_patterns.clone(),
+        )?;
+
+        let changes =
+            match change_detector.changed_package_files(&range.base, range.head.as_deref())? {
+                PackageFileChanges::All => {
+                    debug!("a global change affects every task");
+                    None
+                }
+                PackageFileChanges::Some(changes) => Some(
+                    changes
+                        .into_iter()
+                        .map(|(package, files)| (package.name, (package.path, files)))
+                        .collect(),
+                ),
+            };
+
+        Ok(Self { changes })
+    }
+
+    /// Whether any of the task's inputs changed
+    pub fn is_affected(&self, task_id: &TaskId, definition: &TaskDefinition) -> bool {
+        let Some(changes) = &self.changes else {
+            return true;
+        };
+        let Some((package_path, changed)) = changes.get(&PackageName::from(task_id.package()))
+        else {
+            return false;
+        };
+
+        if changed.dependencies_changed {
+            return true;
+        }
+
+        let package_path = package_path.to_unix();
+        match inputs_matcher(&definition.inputs) {
+            Ok(matcher) => changed
+                .files
+                .iter()
+                .filter_map(|file| file.to_unix().strip_prefix(&package_path).ok())
+                .any(|file| matcher.is_match(&file)),
+            Err(err) => {
+                // The same globs will fail when hashing the task, so we let that
+                // report the error
+                debug!("unable to build inputs matcher for {task_id}: {err}");
+                true
+            }
+        }
+    }
+}
+
+// Matches package-relative paths the same way that inputs are resolved when
+// hashing a package's files.
+struct InputsMatcher {
+    include_default_files: bool,
+    includes: Option<wax::Any<'static>>,
+    excludes: Option<wax::Any<'static>>,
+}
+
+impl InputsMatcher {
+    fn is_match(&self, file: &RelativeUnixPathBuf) -> bool {
+        let included = self.include_default_files
+            || self
+                .includes
+                .as_ref()
+                .map_or(true, |includes| includes.is_match(file.as_str()));
+        let excluded = self
+            .excludes
+            .as_ref()
+            .map_or(false, |excludes| excludes.is_match(file.as_str()));
+
+        included && !excluded
+    }
+}
+
+fn inputs_matcher(inputs: &[String]) -> Result<InputsMatcher, wax::BuildError> {
+    let mut include_default_files = false;
+    let mut includes = Vec::new();
+    let mut excludes = Vec::new();
+    for input in inputs {
+        if input == INPUT_INCLUDE_DEFAULT_FILES {
+            include_default_files = true;
+        } else if let Some(exclusion) = input.strip_prefix('!') {
+            excludes.push(to_glob(exclusion)?);
+        } else {
+            includes.push(to_glob(input)?);
+        }
+    }
+
+    let includes = if includes.is_empty() {
+        None
+    } else {
+        // package.json and turbo.json are always inputs of a task
+        includes.push(to_glob("package.json")?);
+        includes.push(to_glob("turbo.json")?);
+        Some(wax::any(includes)?)
+    };
+    let excludes = if excludes.is_empty() {
+        None
+    } else {
+        Some(wax::any(excludes)?)
+    };
+
+    Ok(InputsMatcher {
+        include_default_files,
+        includes,
+        excludes,
+    })
+}
+
+fn to_glob(input: &str) -> Result<Glob<'static>, wax::BuildError> {
+    Ok(Glob::new(&globwalk::fix_glob_pattern(input))?.into_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(&[], "src/index.ts", true ; "no inputs")]
+    #[test_case(&["src/**"], "src/index.ts", true ; "matching input")]
+    #[test_case(&["src/**"], "README.md", false ; "unmatched input")]
+    #[test_case(&["src/**"], "package.json", true ; "package json")]
+    #[test_case(&["!README.md"], "README.md", false ; "excluded")]
+    #[test_case(&["$TURBO_DEFAULT$", "!**/*.md"], "docs/guide.md", false ; "default with exclusion")]
+    #[test_case(&["$TURBO_DEFAULT$", "!**/*.md"], "src/index.ts", true ; "default files")]
+    fn test_inputs_matcher(inputs: &[&str], file: &str, expected: bool) {
+        let inputs = inputs.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let matcher = inputs_matcher(&inputs).unwrap();
+        assert_eq!(
+            matcher.is_match(&RelativeUnixPathBuf::new(file).unwrap()),
+            expected
+        );
+    }
+
+    fn affected_tasks(dependencies_changed: bool) -> AffectedTasks {
+        let changes = |path: &str, files: &[&str]| {
+            (
+                AnchoredSystemPathBuf::from_raw(path).unwrap(),
+                ChangedPackageFiles {
+                    files: files
+                        .iter()
+                        .map(|f| AnchoredSystemPathBuf::from_raw(f).unwrap())
+                        .collect(),
+                    dependencies_changed,
+                },
+            )
+        };
+        AffectedTasks {
+            changes: Some(HashMap::from([
+                (
+                    PackageName::from("web"),
+                    changes("apps/web", &["apps/web/src/index.ts"]),
+                ),
+                (
+                    PackageName::from("docs"),
+                    changes("apps/docs", &["apps/docs/README.md"]),
+                ),
+                (PackageName::Root, changes("", &["scripts/lint.js"])),
+            ])),
+        }
+    }
+
+    #[test_case("web", &["src/**"], false, true ; "input within package")]
+    #[test_case("web", &["apps/web/src/**"], false, false ; "inputs are package relative")]
+    #[test_case("docs", &["src/**"], false, false ; "unmatched input")]
+    #[test_case("docs", &["src/**"], true, true ; "dependencies changed")]
+    #[test_case("ui", &[], true, false ; "unchanged package")]
+    #[test_case("//", &["scripts/**"], false, true ; "root task")]
+    #[test_case("//", &["src/**"], false, false ; "root task unmatched input")]
+    fn test_is_affected(
+        package: &str,
+        inputs: &[&str],
+        dependencies_changed: bool,
+        expected: bool,
+    ) {
+        let definition = TaskDefinition {
+            inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            affected_tasks(dependencies_changed)
+                .is_affected(&TaskId::new(package, "build"), &definition),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_global_change_affects_every_task() {
+        let affected = AffectedTasks { changes: None };
+        assert!(affected.is_affected(&TaskId::new("ui", "build"), &TaskDefinition::default()));
+    }
+}
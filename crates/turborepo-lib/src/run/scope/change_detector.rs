@@ -4,6 +4,7 @@ use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf};
 use turborepo_repository::{
     change_mapper::{
         ChangeMapError, ChangeMapper, DefaultPackageChangeMapper, LockfileChange, PackageChanges,
+        PackageFileChanges,
     },
     package_graph::{PackageGraph, PackageName},
 };
@@ -46,6 +47,19 @@ impl<'a> ScopeChangeDetector<'a> {
         })
     }
 
+    /// Determines which files changed in each package between two git refs.
+    pub fn changed_package_files(
+        &self,
+        from_ref: &str,
+        to_ref: Option<&str>,
+    ) -> Result<PackageFileChanges, ChangeMapError> {
+        let changed_files = self.scm.changed_files(self.turbo_root, from_ref, to_ref)?;
+        let lockfile_contents = self.get_lockfile_contents(from_ref, &changed_files);
+
+        self.change_mapper
+            .changed_package_files(changed_files, lockfile_contents)
+    }
+
     /// Gets the lockfile content from SCM if it has changed.
     /// Does *not* error if cannot get content, instead just
     /// returns an empty lockfile change
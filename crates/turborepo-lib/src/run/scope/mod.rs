@@ -1,3 +1,4 @@
+mod affected;
 mod change_detector;
 mod filter;
 mod simple_glob;
@@ -6,11 +7,15 @@ mod target_selector;
 use std::collections::HashSet;
 
 use filter::{FilterResolver, PackageInference};
+pub(crate) use simple_glob::{Match, SimpleGlob};
 use turbopath::AbsoluteSystemPath;
 use turborepo_repository::package_graph::{PackageGraph, PackageName};
 use turborepo_scm::SCM;
 
-pub use crate::run::scope::filter::ResolutionError;
+pub use crate::run::scope::{
+    affected::{AffectedRange, AffectedTasks},
+    filter::ResolutionError,
+};
 use crate::{opts::ScopeOpts, turbo_json::TurboJson};
 
 #[tracing::instrument(skip(opts, pkg_graph, scm))]
@@ -0,0 +1,416 @@
+//! Splits the tasks of a run across machines for `--shard`. The tasks that
+//! were asked for are divided evenly between the shards, or balanced by how
+//! long they took in the run summary given with `--shard-durations`. Every
+//! shard also runs the dependencies of its tasks, which are cache hits once
+//! another shard has run them.
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::debug;
+use turbopath::AbsoluteSystemPath;
+
+use crate::{cli::Shard, engine::Engine, run::task_id::TaskId};
+
+// Only the most recent summaries are read, older runs are unlikely to still
+// be representative
+const MAX_SUMMARIES: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("unable to read shard durations from {0}: {1}")]
+    Io(String, #[source] std::io::Error),
+    #[error("shard durations in {0} aren't a run summary: {1}")]
+    Json(String, #[source] serde_json::Error),
+}
+
+/// The tasks of every shard of a run, reported by `--dry=json`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardPlan {
+    pub index: usize,
+    pub count: usize,
+    pub shards: Vec<ShardTasks>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardTasks {
+    pub index: usize,
+    pub tasks: Vec<String>,
+    // Expected duration of the tasks in milliseconds. Missing if there are no
+    // previous runs to estimate it from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_duration: Option<u64>,
+}
+
+impl ShardPlan {
+    /// Splits the entry tasks of the engine into shards. `durations` holds
+    /// the durations of previous runs of tasks in milliseconds, see
+    /// `read_durations`. They must be the same on every machine, otherwise
+    /// the shards won't agree on which tasks each of them runs. Without any
+    /// durations every shard gets the same number of tasks.
+    pub fn new(engine: &Engine, shard: Shard, durations: &HashMap<String, u64>) -> Self {
+        let tasks = engine
+            .entry_tasks()
+            .into_iter()
+            .map(|task_id| {
+                let task_id = task_id.to_string();
+                let duration = durations.get(&task_id).copied();
+                (task_id, duration)
+            })
+            .collect::<Vec<_>>();
+
+        // Tasks that haven't been run before are expected to take as long as an
+        // average task
+        let known = tasks
+            .iter()
+            .filter_map(|(_, duration)| *duration)
+            .collect::<Vec<_>>();
+        let has_durations = !known.is_empty();
+        let fallback = match has_durations {
+            true => known.iter().sum::<u64>() / known.len() as u64,
+            false => 1,
+        };
+
+        let mut tasks = tasks
+            .into_iter()
+            .map(|(task_id, duration)| (task_id, duration.unwrap_or(fallback)))
+            .collect::<Vec<_>>();
+        // Longest tasks first, ties are broken by task id to keep the split the
+        // same on every machine
+        tasks.sort_by(|(a_id, a_cost), (b_id, b_cost)| b_cost.cmp(a_cost).then(a_id.cmp(b_id)));
+
+        let mut shards = (1..=shard.count)
+            .map(|index| (index, Vec::new(), 0u64))
+            .collect::<Vec<_>>();
+        for (task_id, cost) in tasks {
+            let (_, shard_tasks, load) = shards
+                .iter_mut()
+                .min_by_key(|(index, _, load)| (*load, *index))
+                .expect("there is at least one shard");
+            shard_tasks.push(task_id);
+            *load += cost;
+        }
+
+        Self {
+            index: shard.index,
+            count: shard.count,
+            shards: shards
+                .into_iter()
+                .map(|(index, mut tasks, load)| {
+                    tasks.sort();
+                    ShardTasks {
+                        index,
+                        tasks,
+                        estimated_duration: has_durations.then_some(load),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The entry tasks of the shard being run
+    pub fn tasks(&self) -> HashSet<TaskId<'static>> {
+        self.shards
+            .iter()
+            .find(|shard| shard.index == self.index)
+            .into_iter()
+            .flat_map(|shard| &shard.tasks)
+            .filter_map(|task_id| TaskId::try_from(task_id.as_str()).ok())
+            .map(TaskId::into_owned)
+            .collect()
+    }
+
+    /// Names the tasks by their task name alone, as is done for single
+    /// package repositories
+    pub fn for_single_package(&self) -> Self {
+        let mut plan = self.clone();
+        for shard in &mut plan.shards {
+            for task_id in &mut shard.tasks {
+                if let Ok(id) = TaskId::try_from(task_id.as_str()) {
+                    *task_id = id.task().to_string();
+                }
+            }
+        }
+        plan
+    }
+}
+
+#[derive(Deserialize)]
+struct PastRun {
+    monorepo: bool,
+    tasks: Vec<PastTask>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PastTask {
+    task_id: String,
+    cache: PastCache,
+    execution: Option<PastExecution>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PastCache {
+    status: String,
+    time_saved: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PastExecution {
+    start_time: i64,
+    end_time: i64,
+}
+
+impl PastTask {
+    fn duration(&self) -> Option<u64> {
+        let execution = self.execution.as_ref()?;
+        // A cache hit only takes as long as restoring the outputs, the time it
+        // saved is how long the task took when it was run
+        if self.cache.status == "HIT" && self.cache.time_saved > 0 {
+            return Some(self.cache.time_saved);
+        }
+        u64::try_from(execution.end_time - execution.start_time).ok()
+    }
+}
+
+impl PastRun {
+    fn add_durations(self, durations: &mut HashMap<String, u64>) {
+        for task in self.tasks {
+            let Some(duration) = task.duration() else {
+                continue;
+            };
+            let task_id = match self.monorepo {
+                true => task.task_id,
+                false => TaskId::new("//", &task.task_id).to_string(),
+            };
+            durations.entry(task_id).or_insert(duration);
+        }
+    }
+}
+
+/// Reads the duration of each task in milliseconds from a run summary, as
+/// written by `--summarize`. Used to balance shards, where every machine has
+/// to read the same durations.
+pub fn read_durations(path: &AbsoluteSystemPath) -> Result<HashMap<String, u64>, Error> {
+    let contents = path
+        .read_to_string()
+        .map_err(|err| Error::Io(path.to_string(), err))?;
+    let run = serde_json::from_str::<PastRun>(&contents)
+        .map_err(|err| Error::Json(path.to_string(), err))?;
+    let mut durations = HashMap::new();
+    run.add_durations(&mut durations);
+    Ok(durations)
+}
+
+/// Reads the duration of each task in milliseconds from the most recent run
+/// summaries in `.turbo/runs`. Summaries that can't be read are skipped. These
+/// differ between machines, so they aren't used to split shards.
+pub fn load_durations(repo_root: &AbsoluteSystemPath) -> HashMap<String, u64> {
+    let runs_dir = repo_root.join_components(&[".turbo", "runs"]);
+    let Ok(entries) = std::fs::read_dir(runs_dir.as_std_path()) else {
+        return HashMap::new();
+    };
+
+    // Summaries are named after their KSUID, which sorts by creation time
+    let mut summaries = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == "json")
+        })
+        .collect::<Vec<_>>();
+    summaries.sort();
+
+    let mut durations = HashMap::new();
+    for path in summaries.iter().rev().take(MAX_SUMMARIES) {
+        let run = match std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| {
+                serde_json::from_str::<PastRun>(&contents).map_err(|err| err.to_string())
+            }) {
+            Ok(run) => run,
+            Err(err) => {
+                debug!("skipping run summary {}: {}", path.display(), err);
+                continue;
+            }
+        };
+
+        run.add_durations(&mut durations);
+    }
+
+    durations
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use tempfile::TempDir;
+    use test_case::test_case;
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::*;
+    use crate::task_graph::TaskDefinition;
+
+    fn engine(tasks: &[&'static str]) -> Engine {
+        let mut engine = Engine::new();
+        for task in tasks {
+            let task_id = TaskId::try_from(*task).unwrap();
+            engine.connect_to_root(&task_id);
+            engine.add_definition(task_id, TaskDefinition::default());
+        }
+        engine.seal()
+    }
+
+    fn shard_tasks(plan: &ShardPlan) -> Vec<Vec<&str>> {
+        plan.shards
+            .iter()
+            .map(|shard| shard.tasks.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test_case(
+        &[],
+        vec![vec!["a#test", "d#test"], vec!["b#test", "e#test"], vec!["c#test"]] ;
+        "equal counts without durations"
+    )]
+    #[test_case(
+        &[("a#test", 100), ("b#test", 10), ("c#test", 10), ("d#test", 10), ("e#test", 10)],
+        vec![vec!["a#test"], vec!["b#test", "d#test"], vec!["c#test", "e#test"]] ;
+        "balanced by duration"
+    )]
+    #[test_case(
+        &[("a#test", 30), ("b#test", 10)],
+        vec![vec!["a#test"], vec!["c#test", "e#test"], vec!["b#test", "d#test"]] ;
+        "average duration for unknown tasks"
+    )]
+    fn test_shard_plan(durations: &[(&str, u64)], expected: Vec<Vec<&str>>) {
+        let engine = engine(&["a#test", "b#test", "c#test", "d#test", "e#test"]);
+        let durations = durations
+            .iter()
+            .map(|(task_id, duration)| (task_id.to_string(), *duration))
+            .collect();
+
+        let plan = ShardPlan::new(&engine, Shard { index: 2, count: 3 }, &durations);
+
+        assert_eq!(shard_tasks(&plan), expected);
+        let tasks = plan.tasks();
+        assert_eq!(tasks.len(), expected[1].len());
+        assert!(expected[1]
+            .iter()
+            .all(|task| tasks.contains(&TaskId::try_from(*task).unwrap())));
+    }
+
+    #[test]
+    fn test_more_shards_than_tasks() {
+        let engine = engine(&["a#test"]);
+        let plan = ShardPlan::new(&engine, Shard { index: 3, count: 3 }, &HashMap::new());
+
+        assert_eq!(shard_tasks(&plan), vec![vec!["a#test"], vec![], vec![]]);
+        assert!(plan.tasks().is_empty());
+    }
+
+    #[test]
+    fn test_every_task_runs_once() {
+        let tasks = ["a#test", "b#test", "c#test", "d#test", "e#test"];
+        let engine = engine(&tasks);
+        let durations = [
+            HashMap::from([("a#test".to_string(), 100), ("b#test".to_string(), 10)]),
+            HashMap::from([("c#test".to_string(), 5), ("e#test".to_string(), 300)]),
+        ];
+
+        for durations in &durations {
+            // Every shard reads the same durations, so each task is run by one of them
+            let mut runs = HashMap::<TaskId, usize>::new();
+            for index in 1..=3 {
+                let plan = ShardPlan::new(&engine, Shard { index, count: 3 }, durations);
+                for task_id in plan.tasks() {
+                    *runs.entry(task_id).or_default() += 1;
+                }
+            }
+            for task in tasks {
+                assert_eq!(
+                    runs.get(&TaskId::try_from(task).unwrap()),
+                    Some(&1),
+                    "{task} should run on exactly one shard"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_durations() {
+        let tmp = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let summary = repo_root.join_component("summary.json");
+        summary
+            .create_with_contents(
+                json!({
+                    "monorepo": true,
+                    "tasks": [{
+                        "taskId": "a#test",
+                        "cache": { "status": "MISS", "timeSaved": 0 },
+                        "execution": { "startTime": 1000, "endTime": 1400 },
+                    }],
+                })
+                .to_string(),
+            )
+            .unwrap();
+        assert_eq!(
+            read_durations(&summary).unwrap(),
+            HashMap::from([("a#test".to_string(), 400)])
+        );
+
+        // Unlike the summaries in .turbo/runs, a file that was asked for has to be
+        // readable
+        assert!(read_durations(&repo_root.join_component("missing.json")).is_err());
+        summary.create_with_contents("not json").unwrap();
+        assert!(read_durations(&summary).is_err());
+    }
+
+    #[test]
+    fn test_load_durations() {
+        let tmp = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let runs = repo_root.join_components(&[".turbo", "runs"]);
+        runs.create_dir_all().unwrap();
+
+        let task = |task_id: &str, status: &str, time_saved: u64, duration: i64| {
+            json!({
+                "taskId": task_id,
+                "cache": { "status": status, "timeSaved": time_saved },
+                "execution": { "startTime": 1000, "endTime": 1000 + duration },
+            })
+        };
+        let older = json!({
+            "monorepo": true,
+            "tasks": [task("a#test", "MISS", 0, 500), task("b#test", "MISS", 0, 700)],
+        });
+        let newer = json!({
+            "monorepo": true,
+            "tasks": [
+                task("a#test", "MISS", 0, 300),
+                task("b#test", "HIT", 800, 5),
+                { "taskId": "c#test", "cache": { "status": "MISS", "timeSaved": 0 } },
+            ],
+        });
+        runs.join_component("2aaaaaaaaaaaaaaaaaaaaaaaaaa.json")
+            .create_with_contents(older.to_string())
+            .unwrap();
+        runs.join_component("2bbbbbbbbbbbbbbbbbbbbbbbbbb.json")
+            .create_with_contents(newer.to_string())
+            .unwrap();
+        runs.join_component("2ccccccccccccccccccccccccccc.json")
+            .create_with_contents("not json")
+            .unwrap();
+
+        assert_eq!(
+            load_durations(&repo_root),
+            HashMap::from([("a#test".to_string(), 300), ("b#test".to_string(), 800)])
+        );
+    }
+}
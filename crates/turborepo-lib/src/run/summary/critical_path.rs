@@ -0,0 +1,496 @@
+//! Works out what bounded the wall-clock time of a run: the chain of
+//! dependent tasks that took the longest (the critical path), how long each
+//! task could have been delayed without making the run longer (its slack),
+//! how many tasks ran at once and which tasks would shorten the run the most
+//! if they were faster.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::Duration;
+use serde::Serialize;
+use turborepo_ui::{color, BOLD, UI};
+
+use super::TurboDuration;
+
+// The number of tasks reported as bottlenecks
+const MAX_BOTTLENECKS: usize = 5;
+
+/// When a task ran and the tasks it had to wait for
+#[derive(Debug, Clone)]
+pub struct TaskTiming {
+    pub task_id: String,
+    // Milliseconds since the epoch
+    pub start: i64,
+    pub end: i64,
+    pub dependencies: Vec<String>,
+}
+
+impl TaskTiming {
+    fn duration(&self) -> u64 {
+        u64::try_from(self.end - self.start).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunAnalysis {
+    pub critical_path: Vec<String>,
+    // Milliseconds the critical path took
+    pub critical_path_duration: u64,
+    // Milliseconds each task could have been delayed by without delaying the
+    // run, if only its dependencies held it back
+    pub slack: BTreeMap<String, u64>,
+    pub bottlenecks: Vec<Bottleneck>,
+    pub concurrency: Concurrency,
+}
+
+/// A task on the critical path along with how much shorter the critical path
+/// would be if the task took no time
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bottleneck {
+    pub task_id: String,
+    pub duration: u64,
+    pub savings: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Concurrency {
+    // The number of tasks that are allowed to run at once
+    pub available: u32,
+    pub peak: usize,
+    pub average: f64,
+    // The number of running tasks every time it changed, in milliseconds
+    // since the first task started
+    pub timeline: Vec<ConcurrencySample>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencySample {
+    pub time: u64,
+    pub running: usize,
+}
+
+impl RunAnalysis {
+    /// Analyzes the tasks that ran. Returns `None` if no tasks ran.
+    pub fn new(mut tasks: Vec<TaskTiming>, available: u32) -> Option<Self> {
+        if tasks.is_empty() {
+            return None;
+        }
+        // Ties are broken by the order of the tasks, so sort them to get the
+        // same analysis for the same run
+        tasks.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+
+        let durations = tasks.iter().map(TaskTiming::duration).collect::<Vec<_>>();
+        let graph = Graph::new(&tasks);
+        let schedule = graph.schedule(&durations);
+        let critical_path = schedule.critical_path(&graph, &durations);
+
+        let mut bottlenecks = critical_path
+            .iter()
+            .map(|&index| {
+                let mut without_task = durations.clone();
+                without_task[index] = 0;
+                let savings = schedule.length - graph.schedule(&without_task).length;
+                Bottleneck {
+                    task_id: tasks[index].task_id.clone(),
+                    duration: durations[index],
+                    savings,
+                }
+            })
+            .filter(|bottleneck| bottleneck.savings > 0)
+            .collect::<Vec<_>>();
+        bottlenecks.sort_by(|a, b| {
+            b.savings
+                .cmp(&a.savings)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        bottlenecks.truncate(MAX_BOTTLENECKS);
+
+        Some(Self {
+            critical_path_duration: schedule.length,
+            critical_path: critical_path
+                .iter()
+                .map(|&index| tasks[index].task_id.clone())
+                .collect(),
+            slack: tasks
+                .iter()
+                .enumerate()
+                .map(|(index, task)| (task.task_id.clone(), schedule.slack(index)))
+                .collect(),
+            bottlenecks,
+            concurrency: Concurrency::new(&tasks, available),
+        })
+    }
+
+    /// Names the tasks by their task name alone, as is done for single
+    /// package repositories
+    pub fn for_single_package(&self) -> Self {
+        let task_name = |task_id: &String| match task_id.split_once('#') {
+            Some((_, task)) => task.to_string(),
+            None => task_id.clone(),
+        };
+        Self {
+            critical_path: self.critical_path.iter().map(task_name).collect(),
+            critical_path_duration: self.critical_path_duration,
+            slack: self
+                .slack
+                .iter()
+                .map(|(task_id, slack)| (task_name(task_id), *slack))
+                .collect(),
+            bottlenecks: self
+                .bottlenecks
+                .iter()
+                .map(|bottleneck| Bottleneck {
+                    task_id: task_name(&bottleneck.task_id),
+                    ..bottleneck.clone()
+                })
+                .collect(),
+            concurrency: self.concurrency.clone(),
+        }
+    }
+
+    pub fn print(&self, ui: UI) {
+        let duration = |ms: u64| TurboDuration::from(Duration::milliseconds(ms as i64));
+
+        let lines = [
+            (
+                "Critical path",
+                format!(
+                    "{} ({})",
+                    self.critical_path.join(" > "),
+                    color!(ui, BOLD, "{}", duration(self.critical_path_duration))
+                ),
+            ),
+            (
+                "Concurrency",
+                format!(
+                    "{} average, {} peak, {} available",
+                    self.concurrency.average, self.concurrency.peak, self.concurrency.available
+                ),
+            ),
+            (
+                "Bottlenecks",
+                self.bottlenecks
+                    .iter()
+                    .map(|bottleneck| {
+                        format!(
+                            "{} (took {}, up to {} faster)",
+                            bottleneck.task_id,
+                            duration(bottleneck.duration),
+                            duration(bottleneck.savings)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        ];
+
+        for (header, trailer) in lines {
+            if trailer.is_empty() {
+                continue;
+            }
+            println!("{}", color!(ui, BOLD, "{header:>13}:    {trailer}"));
+        }
+        println!();
+    }
+}
+
+impl Concurrency {
+    fn new(tasks: &[TaskTiming], available: u32) -> Self {
+        let origin = tasks
+            .iter()
+            .map(|task| task.start)
+            .min()
+            .unwrap_or_default();
+        let end = tasks.iter().map(|task| task.end).max().unwrap_or_default();
+
+        // Tasks that finish at the same time as others start don't overlap
+        let mut events = tasks
+            .iter()
+            .flat_map(|task| [(task.start, 1), (task.end, -1)])
+            .collect::<Vec<(i64, i64)>>();
+        events.sort();
+
+        let mut timeline: Vec<ConcurrencySample> = Vec::new();
+        let mut running = 0i64;
+        for (time, change) in events {
+            running += change;
+            let sample = ConcurrencySample {
+                time: u64::try_from(time - origin).unwrap_or_default(),
+                running: usize::try_from(running).unwrap_or_default(),
+            };
+            match timeline.last_mut() {
+                Some(last) if last.time == sample.time => *last = sample,
+                _ => timeline.push(sample),
+            }
+        }
+
+        let busy = tasks.iter().map(TaskTiming::duration).sum::<u64>();
+        let average = match end - origin {
+            wall_time if wall_time > 0 => busy as f64 / wall_time as f64,
+            _ => 0.0,
+        };
+
+        Self {
+            available,
+            peak: timeline
+                .iter()
+                .map(|sample| sample.running)
+                .max()
+                .unwrap_or_default(),
+            // Two decimals is plenty to compare against the available concurrency
+            average: (average * 100.0).round() / 100.0,
+            timeline,
+        }
+    }
+}
+
+/// Finds the critical path of tasks that haven't run yet, using the
+/// durations of previous runs. Tasks without a duration are assumed to take
+/// no time.
+pub fn estimated_critical_path(
+    tasks: &[(String, Vec<String>)],
+    durations: &HashMap<String, u64>,
+) -> Vec<String> {
+    let timings = tasks
+        .iter()
+        .map(|(task_id, dependencies)| TaskTiming {
+            task_id: task_id.clone(),
+            start: 0,
+            end: durations
+                .get(task_id)
+                .map_or(0, |duration| *duration as i64),
+            dependencies: dependencies.clone(),
+        })
+        .collect::<Vec<_>>();
+    RunAnalysis::new(timings, 0)
+        .filter(|analysis| analysis.critical_path_duration > 0)
+        .map(|analysis| analysis.critical_path)
+        .unwrap_or_default()
+}
+
+// The dependencies between tasks by their index
+struct Graph {
+    dependencies: Vec<Vec<usize>>,
+    dependents: Vec<Vec<usize>>,
+    // Tasks ordered so that dependencies come before the tasks that depend
+    // on them
+    order: Vec<usize>,
+}
+
+impl Graph {
+    fn new(tasks: &[TaskTiming]) -> Self {
+        let indices = tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| (task.task_id.as_str(), index))
+            .collect::<HashMap<_, _>>();
+        // Dependencies that didn't run aren't part of the analysis
+        let dependencies = tasks
+            .iter()
+            .map(|task| {
+                task.dependencies
+                    .iter()
+                    .filter_map(|dependency| indices.get(dependency.as_str()).copied())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut dependents = vec![Vec::new(); tasks.len()];
+        for (index, task_dependencies) in dependencies.iter().enumerate() {
+            for dependency in task_dependencies {
+                dependents[*dependency].push(index);
+            }
+        }
+
+        let mut remaining = dependencies.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut ready = (0..tasks.len())
+            .filter(|index| remaining[*index] == 0)
+            .collect::<BTreeSet<_>>();
+        let mut order = Vec::with_capacity(tasks.len());
+        while let Some(index) = ready.pop_first() {
+            order.push(index);
+            for dependent in &dependents[index] {
+                remaining[*dependent] -= 1;
+                if remaining[*dependent] == 0 {
+                    ready.insert(*dependent);
+                }
+            }
+        }
+
+        Self {
+            dependencies,
+            dependents,
+            order,
+        }
+    }
+
+    // Schedules every task as early and as late as its dependencies and
+    // dependents allow
+    fn schedule(&self, durations: &[u64]) -> Schedule {
+        let mut earliest_start = vec![0; durations.len()];
+        for &index in &self.order {
+            earliest_start[index] = self.dependencies[index]
+                .iter()
+                .map(|&dependency| earliest_start[dependency] + durations[dependency])
+                .max()
+                .unwrap_or_default();
+        }
+        let length = (0..durations.len())
+            .map(|index| earliest_start[index] + durations[index])
+            .max()
+            .unwrap_or_default();
+
+        let mut latest_start = vec![length; durations.len()];
+        for &index in self.order.iter().rev() {
+            let latest_finish = self.dependents[index]
+                .iter()
+                .map(|&dependent| latest_start[dependent])
+                .min()
+                .unwrap_or(length);
+            latest_start[index] = latest_finish.saturating_sub(durations[index]);
+        }
+
+        Schedule {
+            earliest_start,
+            latest_start,
+            length,
+        }
+    }
+}
+
+struct Schedule {
+    earliest_start: Vec<u64>,
+    latest_start: Vec<u64>,
+    length: u64,
+}
+
+impl Schedule {
+    fn slack(&self, index: usize) -> u64 {
+        self.latest_start[index].saturating_sub(self.earliest_start[index])
+    }
+
+    // Follows the dependencies that held back each task, starting from the
+    // task that finished last
+    fn critical_path(&self, graph: &Graph, durations: &[u64]) -> Vec<usize> {
+        let finish = |index: usize| self.earliest_start[index] + durations[index];
+        let Some(mut current) = (0..durations.len())
+            .rev()
+            .max_by_key(|index| finish(*index))
+        else {
+            return Vec::new();
+        };
+
+        let mut path = vec![current];
+        while let Some(dependency) = graph.dependencies[current]
+            .iter()
+            .copied()
+            .filter(|dependency| finish(*dependency) == self.earliest_start[current])
+            .min()
+        {
+            path.push(dependency);
+            current = dependency;
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn task(task_id: &str, start: i64, end: i64, dependencies: &[&str]) -> TaskTiming {
+        TaskTiming {
+            task_id: task_id.to_string(),
+            start,
+            end,
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_analysis() {
+        // util#build -> app#build -> app#test
+        //            \-> docs#build
+        // lint runs on its own
+        let tasks = vec![
+            task("util#build", 0, 100, &[]),
+            task("app#build", 100, 400, &["util#build"]),
+            task("app#test", 400, 500, &["app#build"]),
+            task("docs#build", 100, 200, &["util#build"]),
+            task("lint#lint", 0, 50, &[]),
+        ];
+
+        let analysis = RunAnalysis::new(tasks, 4).unwrap();
+
+        assert_eq!(
+            analysis.critical_path,
+            vec!["util#build", "app#build", "app#test"]
+        );
+        assert_eq!(analysis.critical_path_duration, 500);
+        assert_eq!(
+            analysis.slack.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("app#build".to_string(), 0),
+                ("app#test".to_string(), 0),
+                ("docs#build".to_string(), 300),
+                ("lint#lint".to_string(), 450),
+                ("util#build".to_string(), 0),
+            ]
+        );
+        assert_eq!(
+            analysis
+                .bottlenecks
+                .iter()
+                .map(|b| (b.task_id.as_str(), b.duration, b.savings))
+                .collect::<Vec<_>>(),
+            vec![
+                ("app#build", 300, 300),
+                ("app#test", 100, 100),
+                ("util#build", 100, 100),
+            ]
+        );
+        assert_eq!(analysis.concurrency.peak, 2);
+        assert_eq!(analysis.concurrency.available, 4);
+        // 650ms of work over 500ms
+        assert_eq!(analysis.concurrency.average, 1.3);
+        assert_eq!(
+            analysis
+                .concurrency
+                .timeline
+                .iter()
+                .map(|sample| (sample.time, sample.running))
+                .collect::<Vec<_>>(),
+            vec![(0, 2), (50, 1), (100, 2), (200, 1), (400, 1), (500, 0)]
+        );
+    }
+
+    #[test]
+    fn test_no_tasks() {
+        assert!(RunAnalysis::new(Vec::new(), 10).is_none());
+    }
+
+    #[test]
+    fn test_estimated_critical_path() {
+        let tasks = vec![
+            ("a#build".to_string(), vec![]),
+            ("b#build".to_string(), vec!["a#build".to_string()]),
+            ("c#build".to_string(), vec!["a#build".to_string()]),
+        ];
+        let durations = HashMap::from([
+            ("a#build".to_string(), 10),
+            ("b#build".to_string(), 10),
+            ("c#build".to_string(), 50),
+        ]);
+
+        assert_eq!(
+            estimated_critical_path(&tasks, &durations),
+            vec!["a#build", "c#build"]
+        );
+        assert!(estimated_critical_path(&tasks, &HashMap::new()).is_empty());
+    }
+}
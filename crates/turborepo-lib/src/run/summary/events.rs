@@ -0,0 +1,454 @@
+//! A stream of events describing a run as it happens, written as newline
+//! delimited JSON so that other tools don't need to parse turbo's output.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, Write},
+};
+
+use chrono::Local;
+use serde::Serialize;
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+};
+use turborepo_cache::CacheHitMetadata;
+
+use super::task::CacheSource;
+
+// NOTE: Bump this whenever an event or one of its fields is changed or
+// removed. Adding new events or fields is not a breaking change.
+const EVENTS_SCHEMA_VERSION: &str = "1";
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RunEvent {
+    RunStarted {
+        command: String,
+        turbo_version: &'static str,
+    },
+    TaskScheduled {
+        task_id: String,
+        hash: String,
+    },
+    CacheHit {
+        task_id: String,
+        hash: String,
+        source: CacheSource,
+        time_saved: u64,
+    },
+    CacheMiss {
+        task_id: String,
+        hash: String,
+    },
+    // Sent every time the task's command is started, including retries
+    TaskStarted {
+        task_id: String,
+        attempt: u32,
+    },
+    // The task's stdout and stderr are combined, the same way they are in its logs
+    TaskOutput {
+        task_id: String,
+        data: String,
+    },
+    TaskFinished {
+        task_id: String,
+        status: TaskStatus,
+        exit_code: Option<i32>,
+        // Duration of the task in milliseconds
+        duration: i64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    RunFinished {
+        exit_code: i32,
+        // The same summary that is written by `--summarize`
+        summary: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskStatus {
+    Succeeded,
+    Cached,
+    Failed,
+    TimedOut,
+    Stopped,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventLine {
+    version: &'static str,
+    // Milliseconds since the epoch
+    timestamp: i64,
+    #[serde(flatten)]
+    event: RunEvent,
+}
+
+/// Handle on the events writer, lets you send events to the worker thread and
+/// eventually await on it to finish writing them
+pub struct EventsClientHandle {
+    handle: JoinHandle<io::Result<()>>,
+    tx: UnboundedSender<EventLine>,
+}
+
+/// An events client limited to sending the events of a single task
+#[derive(Clone)]
+pub struct EventsTaskClient {
+    task_id: String,
+    tx: UnboundedSender<EventLine>,
+}
+
+impl Debug for EventsClientHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventsClientHandle").finish()
+    }
+}
+
+impl EventsClientHandle {
+    /// Starts writing events to the given writer. Each event is flushed as
+    /// soon as it is written so the stream can be followed while the run is
+    /// still going.
+    pub fn start(mut writer: impl Write + Send + 'static) -> Self {
+        // Events are unbounded so that sending one never holds up a task
+        let (tx, mut rx) = mpsc::unbounded_channel::<EventLine>();
+        let handle = tokio::task::spawn_blocking(move || {
+            while let Some(line) = rx.blocking_recv() {
+                serde_json::to_writer(&mut writer, &line)?;
+                writer.write_all(b"\n")?;
+                writer.flush()?;
+            }
+            Ok(())
+        });
+
+        Self { handle, tx }
+    }
+
+    pub fn send(&self, event: RunEvent) {
+        send(&self.tx, event);
+    }
+
+    pub fn task_client(&self, task_id: String) -> EventsTaskClient {
+        EventsTaskClient {
+            task_id,
+            tx: self.tx.clone(),
+        }
+    }
+
+    pub async fn close(self) -> io::Result<()> {
+        // Dropping the transmitter lets the worker thread finish once it has
+        // written all of the events
+        drop(self.tx);
+        self.handle.await?
+    }
+}
+
+impl EventsTaskClient {
+    pub fn scheduled(&self, hash: &str) {
+        self.send(RunEvent::TaskScheduled {
+            task_id: self.task_id.clone(),
+            hash: hash.to_string(),
+        });
+    }
+
+    pub fn cache_status(&self, hash: &str, status: Option<CacheHitMetadata>) {
+        let task_id = self.task_id.clone();
+        let hash = hash.to_string();
+        self.send(match status {
+            Some(CacheHitMetadata { source, time_saved }) => RunEvent::CacheHit {
+                task_id,
+                hash,
+                source: source.into(),
+                time_saved,
+            },
+            None => RunEvent::CacheMiss { task_id, hash },
+        });
+    }
+
+    pub fn started(&self, attempt: u32) {
+        self.send(RunEvent::TaskStarted {
+            task_id: self.task_id.clone(),
+            attempt,
+        });
+    }
+
+    pub fn finished(
+        &self,
+        status: TaskStatus,
+        exit_code: Option<i32>,
+        duration: i64,
+        error: Option<String>,
+    ) {
+        self.send(RunEvent::TaskFinished {
+            task_id: self.task_id.clone(),
+            status,
+            exit_code,
+            duration,
+            error,
+        });
+    }
+
+    /// Wraps a writer of the task's output so that everything written to it
+    /// is also sent as output events
+    pub fn output_writer<W: Write>(&self, writer: W) -> OutputEventsWriter<W> {
+        OutputEventsWriter {
+            writer,
+            client: self.clone(),
+            incomplete: Vec::new(),
+        }
+    }
+
+    fn send(&self, event: RunEvent) {
+        send(&self.tx, event);
+    }
+}
+
+fn send(tx: &UnboundedSender<EventLine>, event: RunEvent) {
+    // If the writer has stopped there's nobody to report the event to
+    tx.send(EventLine {
+        version: EVENTS_SCHEMA_VERSION,
+        timestamp: Local::now().timestamp_millis(),
+        event,
+    })
+    .ok();
+}
+
+pub struct OutputEventsWriter<W> {
+    writer: W,
+    client: EventsTaskClient,
+    // Output is read in chunks, which can end partway through a character
+    incomplete: Vec<u8>,
+}
+
+impl<W> OutputEventsWriter<W> {
+    fn send_data(&self, data: String) {
+        self.client.send(RunEvent::TaskOutput {
+            task_id: self.client.task_id.clone(),
+            data,
+        });
+    }
+}
+
+impl<W: Write> OutputEventsWriter<W> {
+    fn send_output(&mut self, buf: &[u8]) {
+        self.incomplete.extend_from_slice(buf);
+        let valid_up_to = match std::str::from_utf8(&self.incomplete) {
+            Ok(_) => self.incomplete.len(),
+            // Hold on to a character that's cut off until the rest of it is written
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            // Anything else isn't text, so we send it as best we can
+            Err(_) => self.incomplete.len(),
+        };
+        if valid_up_to == 0 {
+            return;
+        }
+        let rest = self.incomplete.split_off(valid_up_to);
+        let data = String::from_utf8_lossy(&self.incomplete).into_owned();
+        self.incomplete = rest;
+        self.send_data(data);
+    }
+}
+
+impl<W> Drop for OutputEventsWriter<W> {
+    // The output can end partway through a character, which is sent as best we
+    // can rather than dropped
+    fn drop(&mut self) {
+        if !self.incomplete.is_empty() {
+            let data = String::from_utf8_lossy(&self.incomplete).into_owned();
+            self.send_data(data);
+        }
+    }
+}
+
+impl<W: Write> Write for OutputEventsWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.send_output(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use serde_json::json;
+    use test_case::test_case;
+    use turborepo_cache::{CacheHitMetadata, CacheSource};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            let contents = self.0.lock().unwrap();
+            std::str::from_utf8(&contents)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test_case(
+        RunEvent::CacheHit {
+            task_id: "web#build".into(),
+            hash: "abc123".into(),
+            source: CacheSource::Remote.into(),
+            time_saved: 100,
+        },
+        json!({
+            "type": "cacheHit",
+            "taskId": "web#build",
+            "hash": "abc123",
+            "source": "REMOTE",
+            "timeSaved": 100,
+        })
+        ; "cache hit"
+    )]
+    #[test_case(
+        RunEvent::TaskFinished {
+            task_id: "web#build".into(),
+            status: TaskStatus::Succeeded,
+            exit_code: Some(0),
+            duration: 1234,
+            error: None,
+        },
+        json!({
+            "type": "taskFinished",
+            "taskId": "web#build",
+            "status": "succeeded",
+            "exitCode": 0,
+            "duration": 1234,
+        })
+        ; "task finished"
+    )]
+    #[test_case(
+        RunEvent::TaskFinished {
+            task_id: "web#test".into(),
+            status: TaskStatus::TimedOut,
+            exit_code: None,
+            duration: 60000,
+            error: Some("command (apps/web) npm run test timed out after 60s".into()),
+        },
+        json!({
+            "type": "taskFinished",
+            "taskId": "web#test",
+            "status": "timedOut",
+            "exitCode": null,
+            "duration": 60000,
+            "error": "command (apps/web) npm run test timed out after 60s",
+        })
+        ; "task timed out"
+    )]
+    fn test_serialization(event: RunEvent, expected: serde_json::Value) {
+        assert_eq!(serde_json::to_value(event).unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_events_are_written_in_order() {
+        let buffer = SharedBuffer::default();
+        let handle = EventsClientHandle::start(buffer.clone());
+        handle.send(RunEvent::RunStarted {
+            command: "turbo run build".into(),
+            turbo_version: "1.0.0",
+        });
+        let task = handle.task_client("web#build".into());
+        task.scheduled("abc123");
+        task.cache_status(
+            "abc123",
+            Some(CacheHitMetadata {
+                source: CacheSource::Local,
+                time_saved: 5,
+            }),
+        );
+        task.finished(TaskStatus::Cached, Some(0), 1, None);
+        handle.close().await.unwrap();
+
+        let lines = buffer.lines();
+        assert_eq!(
+            lines.iter().map(|line| &line["type"]).collect::<Vec<_>>(),
+            ["runStarted", "taskScheduled", "cacheHit", "taskFinished"]
+        );
+        assert!(lines.iter().all(|line| line["version"] == "1"));
+        assert!(lines.iter().all(|line| line["timestamp"].is_i64()));
+    }
+
+    #[tokio::test]
+    async fn test_output_writer() {
+        let buffer = SharedBuffer::default();
+        let handle = EventsClientHandle::start(buffer.clone());
+        let mut output = Vec::new();
+        {
+            let mut writer = handle
+                .task_client("web#build".into())
+                .output_writer(&mut output);
+            writer.write_all(b"hello\n").unwrap();
+            // A character split across two writes is only sent once it is complete
+            let snowman = "\u{2603}\n".as_bytes();
+            writer.write_all(&snowman[..1]).unwrap();
+            writer.write_all(&snowman[1..]).unwrap();
+        }
+        handle.close().await.unwrap();
+
+        assert_eq!(output, "hello\n\u{2603}\n".as_bytes());
+        let data = buffer
+            .lines()
+            .into_iter()
+            .map(|line| {
+                assert_eq!(line["type"], "taskOutput");
+                assert_eq!(line["taskId"], "web#build");
+                line["data"].as_str().unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(data, ["hello\n", "\u{2603}\n"]);
+    }
+
+    #[tokio::test]
+    async fn test_output_writer_flushes_incomplete_character() {
+        let buffer = SharedBuffer::default();
+        let handle = EventsClientHandle::start(buffer.clone());
+        let mut output = Vec::new();
+        {
+            let mut writer = handle
+                .task_client("web#build".into())
+                .output_writer(&mut output);
+            writer.write_all(b"done ").unwrap();
+            // The output ends partway through a character
+            writer.write_all(&"\u{2603}".as_bytes()[..1]).unwrap();
+        }
+        handle.close().await.unwrap();
+
+        let data = buffer
+            .lines()
+            .into_iter()
+            .map(|line| line["data"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(data, ["done ", "\u{FFFD}"]);
+    }
+}
@@ -6,7 +6,10 @@ use tokio::sync::mpsc;
 use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPath};
 use turborepo_ui::{color, cprintln, BOLD, BOLD_GREEN, BOLD_RED, MAGENTA, UI, YELLOW};
 
-use super::TurboDuration;
+use super::{
+    events::{EventsTaskClient, TaskStatus},
+    TurboDuration,
+};
 use crate::run::{summary::task::TaskSummary, task_id::TaskId};
 
 // Just used to make changing the type that gets passed to the state management
@@ -26,7 +29,7 @@ pub struct ExecutionTracker {
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionSummary<'a> {
     // a synthesized turbo command to produce this invocation
-    command: String,
+    pub(crate) command: String,
     // the (possibly empty) path from the turborepo root to where the command was run
     #[serde(rename = "repoPath")]
     repo_path: &'a AnchoredSystemPath,
@@ -195,6 +198,8 @@ pub struct TaskTracker<T> {
     sender: mpsc::Sender<Message>,
     started_at: T,
     task_id: TaskId<'static>,
+    attempts: Vec<TaskAttempt>,
+    events: Option<EventsTaskClient>,
 }
 
 #[derive(Debug, Clone)]
@@ -222,6 +227,24 @@ pub struct TaskExecutionSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    // Only present if the task was retried
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<TaskAttempt>,
+    // Whether the task's outputs are in the cache once it has finished, either
+    // because it was restored from the cache or because its outputs were saved
+    #[serde(skip)]
+    pub outputs_cached: bool,
+}
+
+/// A single run of a task's command
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAttempt {
+    pub exit_code: Option<i32>,
+    // Duration of the attempt in milliseconds
+    pub duration: u64,
 }
 
 impl TaskExecutionSummary {
@@ -230,6 +253,17 @@ impl TaskExecutionSummary {
         // or was killed in a manner where we didn't collect an exit code.
         !matches!(self.exit_code, Some(0))
     }
+
+    fn send_finished(&self, events: Option<EventsTaskClient>, status: TaskStatus) {
+        if let Some(events) = events {
+            events.finished(
+                status,
+                self.exit_code,
+                self.end_time - self.start_time,
+                self.error.clone(),
+            );
+        }
+    }
 }
 
 impl ExecutionTracker {
@@ -264,6 +298,8 @@ impl ExecutionTracker {
             sender: self.sender.clone(),
             task_id,
             started_at: (),
+            attempts: Vec::new(),
+            events: None,
         }
     }
 
@@ -284,11 +320,27 @@ impl ExecutionTracker {
     }
 }
 
+impl<T> TaskTracker<T> {
+    // Also report the task's progress as run events
+    pub fn with_events(mut self, events: Option<EventsTaskClient>) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn events(&self) -> Option<&EventsTaskClient> {
+        self.events.as_ref()
+    }
+}
+
 impl TaskTracker<()> {
     // Start the tracker
     pub async fn start(self) -> TaskTracker<DateTime<Local>> {
         let TaskTracker {
-            sender, task_id, ..
+            sender,
+            task_id,
+            attempts,
+            events,
+            ..
         } = self;
         let started_at = Local::now();
         sender
@@ -302,6 +354,8 @@ impl TaskTracker<()> {
             sender,
             started_at,
             task_id,
+            attempts,
+            events,
         }
     }
 
@@ -329,11 +383,18 @@ impl TaskTracker<chrono::DateTime<Local>> {
     // internal turbo error
     pub fn cancel(self) {}
 
+    // Record the attempts of a task that had to be retried
+    pub fn record_attempts(&mut self, attempts: Vec<TaskAttempt>) {
+        self.attempts = attempts;
+    }
+
     pub async fn cached(self) -> TaskExecutionSummary {
         let Self {
             sender,
             started_at,
             task_id,
+            events,
+            ..
         } = self;
 
         let ended_at = Local::now();
@@ -343,7 +404,11 @@ impl TaskTracker<chrono::DateTime<Local>> {
             // Go synthesizes a zero exit code on cache hits
             exit_code: Some(0),
             error: None,
+            timed_out: false,
+            attempts: Vec::new(),
+            outputs_cached: true,
         };
+        execution.send_finished(events, TaskStatus::Cached);
 
         let state = TaskState {
             task_id,
@@ -359,11 +424,17 @@ impl TaskTracker<chrono::DateTime<Local>> {
         execution
     }
 
-    pub async fn build_succeeded(self, exit_code: i32) -> TaskExecutionSummary {
+    pub async fn build_succeeded(
+        self,
+        exit_code: i32,
+        outputs_cached: bool,
+    ) -> TaskExecutionSummary {
         let Self {
             sender,
             started_at,
             task_id,
+            attempts,
+            events,
         } = self;
 
         let ended_at = Local::now();
@@ -372,7 +443,11 @@ impl TaskTracker<chrono::DateTime<Local>> {
             end_time: ended_at.timestamp_millis(),
             exit_code: Some(exit_code),
             error: None,
+            timed_out: false,
+            attempts,
+            outputs_cached,
         };
+        execution.send_finished(events, TaskStatus::Succeeded);
 
         let state = TaskState {
             task_id,
@@ -392,11 +467,32 @@ impl TaskTracker<chrono::DateTime<Local>> {
         self,
         exit_code: Option<i32>,
         error: impl fmt::Display,
+    ) -> TaskExecutionSummary {
+        self.failed(exit_code, error, TaskStatus::Failed).await
+    }
+
+    pub async fn build_timed_out(self, error: impl fmt::Display) -> TaskExecutionSummary {
+        self.failed(None, error, TaskStatus::TimedOut).await
+    }
+
+    // The task was stopped by the user before it finished, so it didn't succeed
+    pub async fn build_stopped(self) -> TaskExecutionSummary {
+        self.failed(None, "task was stopped", TaskStatus::Stopped)
+            .await
+    }
+
+    async fn failed(
+        self,
+        exit_code: Option<i32>,
+        error: impl fmt::Display,
+        status: TaskStatus,
     ) -> TaskExecutionSummary {
         let Self {
             sender,
             started_at,
             task_id,
+            attempts,
+            events,
         } = self;
 
         let ended_at = Local::now();
@@ -405,7 +501,11 @@ impl TaskTracker<chrono::DateTime<Local>> {
             end_time: ended_at.timestamp_millis(),
             exit_code,
             error: Some(error.to_string()),
+            timed_out: status == TaskStatus::TimedOut,
+            attempts,
+            outputs_cached: false,
         };
+        execution.send_finished(events, status);
 
         let state = TaskState {
             task_id,
@@ -437,12 +537,13 @@ mod test {
         let bar = TaskId::new("bar", "build");
         let baz = TaskId::new("baz", "build");
         let boo = TaskId::new("boo", "build");
+        let qux = TaskId::new("qux", "build");
         let mut tasks = Vec::new();
         {
             let tracker = summary.task_tracker(foo.clone());
             tasks.push(tokio::spawn(async move {
                 let tracker = tracker.start().await;
-                tracker.build_succeeded(0).await;
+                tracker.build_succeeded(0, true).await;
             }));
         }
         {
@@ -466,14 +567,21 @@ mod test {
                 tracker.cancel();
             }));
         }
+        {
+            let tracker = summary.task_tracker(qux.clone());
+            tasks.push(tokio::spawn(async move {
+                let tracker = tracker.start().await;
+                tracker.build_stopped().await;
+            }));
+        }
         for task in tasks {
             task.await.unwrap();
         }
 
         let state = summary.finish().await.unwrap();
-        assert_eq!(state.attempted, 4);
+        assert_eq!(state.attempted, 5);
         assert_eq!(state.cached, 1);
-        assert_eq!(state.failed, 1);
+        assert_eq!(state.failed, 2);
         assert_eq!(state.success, 1);
         let foo_state = state.tasks.iter().find(|task| task.task_id == foo).unwrap();
         assert_eq!(foo_state.execution.as_ref().unwrap().exit_code, Some(0));
@@ -486,6 +594,11 @@ mod test {
             boo_state.is_none(),
             "canceling doesn't produce execution data"
         );
+        let qux_state = state.tasks.iter().find(|task| task.task_id == qux).unwrap();
+        assert!(
+            qux_state.execution.as_ref().unwrap().is_failure(),
+            "a stopped task didn't succeed"
+        );
     }
 
     #[tokio::test]
@@ -499,7 +612,7 @@ mod test {
         let tracker = tracker.start().await;
 
         tokio::time::sleep(sleep_duration.to_std().unwrap()).await;
-        tracker.build_succeeded(0).await;
+        tracker.build_succeeded(0, true).await;
         let mut state = summary.finish().await.unwrap();
         assert_eq!(state.tasks.len(), 1);
         let summary = state.tasks.pop().unwrap().execution.unwrap();
@@ -518,7 +631,10 @@ mod test {
             start_time: 123,
             end_time: 234,
             exit_code: Some(0),
-            error: None
+            error: None,
+            timed_out: false,
+            attempts: vec![],
+            outputs_cached: false,
         },
         json!({ "startTime": 123, "endTime": 234, "exitCode": 0 })
         ; "success"
@@ -529,10 +645,56 @@ mod test {
             end_time: 234,
             exit_code: Some(1),
             error: Some("cannot find anything".into()),
+            timed_out: false,
+            attempts: vec![],
+            outputs_cached: false,
         },
         json!({ "startTime": 123, "endTime": 234, "exitCode": 1, "error": "cannot find anything" })
         ; "failure"
     )]
+    #[test_case(
+        TaskExecutionSummary {
+            start_time: 123,
+            end_time: 234,
+            exit_code: Some(0),
+            error: None,
+            timed_out: false,
+            attempts: vec![
+                TaskAttempt { exit_code: Some(1), duration: 50 },
+                TaskAttempt { exit_code: Some(0), duration: 60 },
+            ],
+            outputs_cached: false,
+        },
+        json!({
+            "startTime": 123,
+            "endTime": 234,
+            "exitCode": 0,
+            "attempts": [
+                { "exitCode": 1, "duration": 50 },
+                { "exitCode": 0, "duration": 60 },
+            ]
+        })
+        ; "retried"
+    )]
+    #[test_case(
+        TaskExecutionSummary {
+            start_time: 123,
+            end_time: 234,
+            exit_code: None,
+            error: Some("command (apps/web) npm run test timed out after 60s".into()),
+            timed_out: true,
+            attempts: vec![],
+            outputs_cached: false,
+        },
+        json!({
+            "startTime": 123,
+            "endTime": 234,
+            "exitCode": null,
+            "error": "command (apps/web) npm run test timed out after 60s",
+            "timedOut": true,
+        })
+        ; "timed out"
+    )]
     fn test_serialization(value: impl serde::Serialize, expected: serde_json::Value) {
         assert_eq!(serde_json::to_value(value).unwrap(), expected);
     }
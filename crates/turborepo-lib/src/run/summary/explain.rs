@@ -0,0 +1,599 @@
+//! Records the inputs to each task's hash so that cache misses can be
+//! explained by comparing them against the last time the task was cached.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use itertools::{EitherOrBoth, Itertools};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_ui::{cprintln, BOLD, UI};
+
+use super::{global_hash::GlobalHashSummary, task::TaskSummary, EnvMode, Error};
+use crate::run::task_id::TaskId;
+
+/// The inputs to the global hash, which are shared by every task in a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalHashInputs {
+    files: BTreeMap<String, String>,
+    hash_of_external_dependencies: String,
+    // Env var names mapped to hashes of their values
+    env: BTreeMap<String, String>,
+    // Configuration that is part of the global hash, such as the env mode
+    settings: BTreeMap<String, Value>,
+}
+
+/// The inputs to a task's hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashInputs {
+    hash: String,
+    global: GlobalHashInputs,
+    files: BTreeMap<String, String>,
+    hash_of_external_dependencies: String,
+    // Env var names mapped to hashes of their values
+    env: BTreeMap<String, String>,
+    // Hashes of the tasks that this task depends on
+    dependencies: BTreeMap<String, String>,
+    task_definition: BTreeMap<String, Value>,
+    cli_arguments: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRecord {
+    // The inputs of the last run of the task
+    pub last_run: HashInputs,
+    // Whether the last run of the task was a cache hit
+    pub hit: bool,
+    // The inputs of the last cached run before the last run, which is what the
+    // last run is explained against
+    pub previously_cached: Option<HashInputs>,
+    // The inputs of the last run that the task was cached in
+    pub last_cached: Option<HashInputs>,
+}
+
+/// The hash inputs of every task that has been run in the repository, stored
+/// in `.turbo/hash-inputs.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HashInputsStore {
+    tasks: BTreeMap<String, TaskRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+    GlobalFile,
+    GlobalExternalDependencies,
+    GlobalEnv,
+    GlobalSetting,
+    File,
+    ExternalDependencies,
+    Env,
+    Dependency,
+    TaskDefinition,
+    Arguments,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub input: Input,
+    // The file, env var, task or field that changed. Inputs that are a single
+    // value don't have a name.
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Explanation {
+    // The task hasn't been cached before, so there's nothing to compare against
+    NeverCached,
+    // The inputs match the last time the task was cached
+    Unchanged,
+    Changed(Vec<Change>),
+}
+
+impl GlobalHashInputs {
+    pub fn new(
+        summary: &GlobalHashSummary,
+        env_mode: EnvMode,
+        framework_inference: bool,
+    ) -> Result<Self, Error> {
+        let env_vars = &summary.environment_variables;
+        let env = env_vars
+            .configured
+            .iter()
+            .chain(env_vars.inferred.iter())
+            .flatten();
+
+        let settings = BTreeMap::from([
+            ("envMode".to_string(), serde_json::to_value(env_mode)?),
+            (
+                "frameworkInference".to_string(),
+                Value::Bool(framework_inference),
+            ),
+            (
+                "globalEnv".to_string(),
+                serde_json::to_value(env_vars.specified.env)?,
+            ),
+            (
+                "globalPassThroughEnv".to_string(),
+                serde_json::to_value(env_vars.specified.pass_through_env)?,
+            ),
+            (
+                "globalDotEnv".to_string(),
+                serde_json::to_value(summary.global_dot_env)?,
+            ),
+        ]);
+
+        Ok(Self {
+            files: summary
+                .files
+                .iter()
+                .map(|(path, hash)| (path.to_string(), hash.clone()))
+                .collect(),
+            hash_of_external_dependencies: summary.hash_of_external_dependencies.to_string(),
+            env: env_hashes(env),
+            settings,
+        })
+    }
+}
+
+impl HashInputs {
+    /// Collects the inputs of a task, looking up the hashes of its
+    /// dependencies in `task_hashes`
+    pub fn new(
+        task: &TaskSummary,
+        global: &GlobalHashInputs,
+        task_hashes: &HashMap<&TaskId<'static>, &str>,
+    ) -> Result<Self, Error> {
+        let env_vars = &task.shared.environment_variables;
+        let task_definition = match serde_json::to_value(&task.shared.resolved_task_definition)? {
+            Value::Object(fields) => fields.into_iter().collect(),
+            _ => BTreeMap::new(),
+        };
+
+        Ok(Self {
+            hash: task.shared.hash.clone(),
+            global: global.clone(),
+            files: task
+                .shared
+                .inputs
+                .iter()
+                .map(|(path, hash)| (path.to_string(), hash.clone()))
+                .collect(),
+            hash_of_external_dependencies: task.shared.hash_of_external_dependencies.clone(),
+            env: env_hashes(env_vars.configured.iter().chain(&env_vars.inferred)),
+            dependencies: task
+                .shared
+                .dependencies
+                .iter()
+                .filter_map(|dependency| {
+                    let hash = task_hashes.get(&dependency)?;
+                    Some((dependency.to_string(), hash.to_string()))
+                })
+                .collect(),
+            task_definition,
+            cli_arguments: task.shared.cli_arguments.clone(),
+        })
+    }
+}
+
+impl HashInputsStore {
+    fn path(repo_root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
+        repo_root.join_components(&[".turbo", "hash-inputs.json"])
+    }
+
+    pub fn load(repo_root: &AbsoluteSystemPath) -> Result<Self, Error> {
+        let path = Self::path(repo_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&path.read_to_string()?)?)
+    }
+
+    pub fn save(&self, repo_root: &AbsoluteSystemPath) -> Result<(), Error> {
+        let path = Self::path(repo_root);
+        path.ensure_dir()?;
+        Ok(path.create_with_contents(serde_json::to_string(self)?)?)
+    }
+
+    /// Looks up a task by its id. In single package repositories tasks can be
+    /// looked up by their name alone.
+    pub fn get(&self, task_id: &str) -> Option<&TaskRecord> {
+        self.tasks.get(task_id).or_else(|| {
+            let task_id = TaskId::new("//", task_id).to_string();
+            self.tasks.get(&task_id)
+        })
+    }
+
+    /// Records a run of a task. `cached` is whether the outputs of the task are
+    /// in the cache after the run. Returns whether the task's record changed.
+    pub fn record(&mut self, task_id: String, inputs: HashInputs, hit: bool, cached: bool) -> bool {
+        let existing = self.tasks.get(&task_id);
+        let previously_cached = existing.and_then(|record| record.last_cached.clone());
+        let last_cached = match cached {
+            true => Some(inputs.clone()),
+            false => previously_cached.clone(),
+        };
+        let record = TaskRecord {
+            last_run: inputs,
+            hit,
+            previously_cached,
+            last_cached,
+        };
+        if existing == Some(&record) {
+            return false;
+        }
+        self.tasks.insert(task_id, record);
+        true
+    }
+}
+
+impl TaskRecord {
+    /// Explains the last run of the task
+    pub fn explain(&self) -> Explanation {
+        explain(self.previously_cached.as_ref(), &self.last_run)
+    }
+}
+
+pub fn explain(last_cached: Option<&HashInputs>, current: &HashInputs) -> Explanation {
+    match last_cached {
+        None => Explanation::NeverCached,
+        Some(last_cached) if last_cached.hash == current.hash => Explanation::Unchanged,
+        Some(last_cached) => Explanation::Changed(changes(last_cached, current)),
+    }
+}
+
+/// Prints why a task missed the cache
+pub fn print_explanation(ui: UI, task_id: &str, explanation: &Explanation) {
+    match explanation {
+        Explanation::NeverCached => {
+            cprintln!(ui, BOLD, "{task_id} missed the cache");
+            println!("  It hasn't been cached before");
+        }
+        Explanation::Unchanged => {
+            cprintln!(ui, BOLD, "{task_id} missed the cache");
+            println!(
+                "  Its inputs haven't changed since it was last cached. The cache entry may have \
+                 been evicted or the cache was bypassed"
+            );
+        }
+        Explanation::Changed(changes) if changes.is_empty() => {
+            cprintln!(ui, BOLD, "{task_id} missed the cache");
+            println!("  Its hash changed, but none of its recorded inputs did");
+        }
+        Explanation::Changed(changes) => {
+            cprintln!(
+                ui,
+                BOLD,
+                "{task_id} missed the cache. Changes since it was last cached:"
+            );
+            for change in changes {
+                println!("  {change}");
+            }
+        }
+    }
+    println!();
+}
+
+fn changes(previous: &HashInputs, current: &HashInputs) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_maps(
+        &mut changes,
+        Input::GlobalFile,
+        &previous.global.files,
+        &current.global.files,
+    );
+    diff_value(
+        &mut changes,
+        Input::GlobalExternalDependencies,
+        &previous.global.hash_of_external_dependencies,
+        &current.global.hash_of_external_dependencies,
+    );
+    diff_maps(
+        &mut changes,
+        Input::GlobalEnv,
+        &previous.global.env,
+        &current.global.env,
+    );
+    diff_maps(
+        &mut changes,
+        Input::GlobalSetting,
+        &previous.global.settings,
+        &current.global.settings,
+    );
+    diff_maps(&mut changes, Input::File, &previous.files, &current.files);
+    diff_value(
+        &mut changes,
+        Input::ExternalDependencies,
+        &previous.hash_of_external_dependencies,
+        &current.hash_of_external_dependencies,
+    );
+    diff_maps(&mut changes, Input::Env, &previous.env, &current.env);
+    diff_maps(
+        &mut changes,
+        Input::Dependency,
+        &previous.dependencies,
+        &current.dependencies,
+    );
+    diff_maps(
+        &mut changes,
+        Input::TaskDefinition,
+        &previous.task_definition,
+        &current.task_definition,
+    );
+    diff_value(
+        &mut changes,
+        Input::Arguments,
+        &previous.cli_arguments,
+        &current.cli_arguments,
+    );
+    changes
+}
+
+fn diff_maps<V: PartialEq>(
+    changes: &mut Vec<Change>,
+    input: Input,
+    previous: &BTreeMap<String, V>,
+    current: &BTreeMap<String, V>,
+) {
+    for entry in previous
+        .iter()
+        .merge_join_by(current.iter(), |(a, _), (b, _)| a.cmp(b))
+    {
+        let (name, kind) = match entry {
+            EitherOrBoth::Left((name, _)) => (name, ChangeKind::Removed),
+            EitherOrBoth::Right((name, _)) => (name, ChangeKind::Added),
+            EitherOrBoth::Both((name, previous), (_, current)) if previous != current => {
+                (name, ChangeKind::Changed)
+            }
+            EitherOrBoth::Both(..) => continue,
+        };
+        changes.push(Change {
+            kind,
+            input,
+            name: Some(name.clone()),
+        });
+    }
+}
+
+fn diff_value<V: PartialEq + ?Sized>(
+    changes: &mut Vec<Change>,
+    input: Input,
+    previous: &V,
+    current: &V,
+) {
+    if previous != current {
+        changes.push(Change {
+            kind: ChangeKind::Changed,
+            input,
+            name: None,
+        });
+    }
+}
+
+// Env vars are summarized as `NAME=<hash of value>`
+fn env_hashes<'a>(pairs: impl IntoIterator<Item = &'a String>) -> BTreeMap<String, String> {
+    pairs
+        .into_iter()
+        .map(|pair| {
+            let (name, hash) = pair.split_once('=').unwrap_or((pair, ""));
+            (name.to_string(), hash.to_string())
+        })
+        .collect()
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Input::GlobalFile => "global file",
+            Input::GlobalExternalDependencies => "global external dependencies",
+            Input::GlobalEnv => "global env var",
+            Input::GlobalSetting => "global setting",
+            Input::File => "file",
+            Input::ExternalDependencies => "external dependencies",
+            Input::Env => "env var",
+            Input::Dependency => "dependency",
+            Input::TaskDefinition => "turbo.json",
+            Input::Arguments => "arguments",
+        })
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Changed => '~',
+        };
+        match &self.name {
+            Some(name) => write!(f, "{symbol} {}: {name}", self.input),
+            None => write!(f, "{symbol} {}", self.input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+
+    fn inputs() -> HashInputs {
+        HashInputs {
+            hash: "abc123".to_string(),
+            global: GlobalHashInputs {
+                files: BTreeMap::from([("package-lock.json".to_string(), "1".to_string())]),
+                hash_of_external_dependencies: "ext".to_string(),
+                env: BTreeMap::new(),
+                settings: BTreeMap::from([("envMode".to_string(), Value::from("infer"))]),
+            },
+            files: BTreeMap::from([
+                ("src/index.ts".to_string(), "1".to_string()),
+                ("src/util.ts".to_string(), "1".to_string()),
+            ]),
+            hash_of_external_dependencies: "ext".to_string(),
+            env: BTreeMap::from([("API_URL".to_string(), "1".to_string())]),
+            dependencies: BTreeMap::from([("ui#build".to_string(), "def456".to_string())]),
+            task_definition: BTreeMap::from([("outputs".to_string(), Value::from("dist/**"))]),
+            cli_arguments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let previous = inputs();
+        let mut current = inputs();
+        current.hash = "changed".to_string();
+        current
+            .files
+            .insert("src/index.ts".to_string(), "2".to_string());
+        current.files.remove("src/util.ts");
+        current
+            .files
+            .insert("src/new.ts".to_string(), "1".to_string());
+        current.env.insert("NODE_ENV".to_string(), String::new());
+        current
+            .dependencies
+            .insert("ui#build".to_string(), "789abc".to_string());
+        current.cli_arguments.push("--watch".to_string());
+
+        assert_eq!(
+            explain(Some(&previous), &current),
+            Explanation::Changed(vec![
+                Change {
+                    kind: ChangeKind::Changed,
+                    input: Input::File,
+                    name: Some("src/index.ts".to_string())
+                },
+                Change {
+                    kind: ChangeKind::Added,
+                    input: Input::File,
+                    name: Some("src/new.ts".to_string())
+                },
+                Change {
+                    kind: ChangeKind::Removed,
+                    input: Input::File,
+                    name: Some("src/util.ts".to_string())
+                },
+                Change {
+                    kind: ChangeKind::Added,
+                    input: Input::Env,
+                    name: Some("NODE_ENV".to_string())
+                },
+                Change {
+                    kind: ChangeKind::Changed,
+                    input: Input::Dependency,
+                    name: Some("ui#build".to_string())
+                },
+                Change {
+                    kind: ChangeKind::Changed,
+                    input: Input::Arguments,
+                    name: None
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_global_changes() {
+        let previous = inputs();
+        let mut current = inputs();
+        current.hash = "changed".to_string();
+        current.global.hash_of_external_dependencies = "other".to_string();
+        current
+            .global
+            .settings
+            .insert("envMode".to_string(), Value::from("strict"));
+
+        assert_eq!(
+            explain(Some(&previous), &current),
+            Explanation::Changed(vec![
+                Change {
+                    kind: ChangeKind::Changed,
+                    input: Input::GlobalExternalDependencies,
+                    name: None
+                },
+                Change {
+                    kind: ChangeKind::Changed,
+                    input: Input::GlobalSetting,
+                    name: Some("envMode".to_string())
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        assert_eq!(explain(None, &inputs()), Explanation::NeverCached);
+        assert_eq!(explain(Some(&inputs()), &inputs()), Explanation::Unchanged);
+    }
+
+    #[test]
+    fn test_record_keeps_last_cached() {
+        let mut store = HashInputsStore::default();
+        store.record("web#build".to_string(), inputs(), false, true);
+
+        let mut failed = inputs();
+        failed.hash = "failed".to_string();
+        store.record("web#build".to_string(), failed.clone(), false, false);
+
+        let record = store.get("web#build").unwrap();
+        assert_eq!(record.last_run, failed);
+        assert_eq!(record.last_cached, Some(inputs()));
+
+        let mut fixed = inputs();
+        fixed.hash = "fixed".to_string();
+        store.record("web#build".to_string(), fixed.clone(), false, true);
+
+        // The run is explained against the run that was cached before it
+        let record = store.get("web#build").unwrap();
+        assert_eq!(record.previously_cached, Some(inputs()));
+        assert_eq!(record.last_cached, Some(fixed));
+        assert!(matches!(record.explain(), Explanation::Changed(_)));
+    }
+
+    #[test]
+    fn test_record_unchanged() {
+        let mut store = HashInputsStore::default();
+        assert!(store.record("web#build".to_string(), inputs(), false, true));
+        assert!(store.record("web#build".to_string(), inputs(), true, true));
+        // Hitting the cache again with the same inputs doesn't change anything
+        assert!(!store.record("web#build".to_string(), inputs(), true, true));
+    }
+
+    #[test]
+    fn test_get_single_package_task() {
+        let mut store = HashInputsStore::default();
+        store.record("//#build".to_string(), inputs(), true, true);
+        assert!(store.get("build").is_some());
+        assert!(store.get("//#build").is_some());
+        assert!(store.get("lint").is_none());
+    }
+
+    #[test_case(ChangeKind::Added, Input::Env, Some("API_URL"), "+ env var: API_URL" ; "added")]
+    #[test_case(ChangeKind::Removed, Input::File, Some("src/a.ts"), "- file: src/a.ts" ; "removed")]
+    #[test_case(ChangeKind::Changed, Input::TaskDefinition, Some("outputs"), "~ turbo.json: outputs" ; "changed")]
+    #[test_case(ChangeKind::Changed, Input::ExternalDependencies, None, "~ external dependencies" ; "unnamed")]
+    fn test_display_change(kind: ChangeKind, input: Input, name: Option<&str>, expected: &str) {
+        let change = Change {
+            kind,
+            input,
+            name: name.map(|name| name.to_string()),
+        };
+        assert_eq!(change.to_string(), expected);
+    }
+}
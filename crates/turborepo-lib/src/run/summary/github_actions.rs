@@ -0,0 +1,200 @@
+//! Reporting for runs on GitHub Actions: failed tasks are annotated with
+//! workflow commands and the run is added to the job summary.
+
+use std::fmt::Write;
+
+use chrono::Duration;
+
+use super::{
+    report::{TaskReport, TaskReportStatus},
+    TurboDuration,
+};
+
+// The file that the job summary is appended to
+pub const STEP_SUMMARY_ENV_VAR: &str = "GITHUB_STEP_SUMMARY";
+
+/// An `::error` workflow command for each failed task, which GitHub displays
+/// as an annotation on the workflow run
+pub fn annotations(tasks: &[TaskReport]) -> String {
+    let mut output = String::new();
+    for task in tasks
+        .iter()
+        .filter(|task| task.status == TaskReportStatus::Failed)
+    {
+        let mut message = task
+            .error
+            .clone()
+            .unwrap_or_else(|| format!("{} failed", task.task_id));
+        if let Some(log_tail) = &task.log_tail {
+            message.push_str("\n\n");
+            message.push_str(log_tail);
+        }
+        output.push_str(&format!(
+            "::error title={}::{}\n",
+            escape_property(&format!("{} failed", task.task_id)),
+            escape_data(&message)
+        ));
+    }
+    output
+}
+
+/// Markdown for the job summary, with a row for each task and the logs of
+/// the tasks that failed
+pub fn job_summary(command: &str, tasks: &[TaskReport]) -> String {
+    let mut markdown = String::new();
+    // Writing to a String can't fail
+    write_job_summary(&mut markdown, command, tasks).expect("writing to a string");
+    markdown
+}
+
+fn write_job_summary(
+    markdown: &mut String,
+    command: &str,
+    tasks: &[TaskReport],
+) -> std::fmt::Result {
+    let count = |status| tasks.iter().filter(|task| task.status == status).count();
+
+    writeln!(markdown, "### `{command}`")?;
+    writeln!(markdown)?;
+    write!(
+        markdown,
+        "{} successful, {} cached, {} failed",
+        count(TaskReportStatus::Succeeded),
+        count(TaskReportStatus::Cached),
+        count(TaskReportStatus::Failed)
+    )?;
+    let skipped = count(TaskReportStatus::Skipped);
+    if skipped > 0 {
+        write!(markdown, ", {skipped} not run")?;
+    }
+    writeln!(markdown, ", {} total", tasks.len())?;
+    writeln!(markdown)?;
+
+    writeln!(markdown, "| Task | Status | Duration |")?;
+    writeln!(markdown, "| ---- | ------ | -------- |")?;
+    for task in tasks {
+        let status = match task.status {
+            TaskReportStatus::Succeeded => ":white_check_mark: Succeeded",
+            TaskReportStatus::Cached => ":zap: Cached",
+            TaskReportStatus::Failed => ":x: Failed",
+            TaskReportStatus::Skipped => ":heavy_minus_sign: Not run",
+        };
+        writeln!(
+            markdown,
+            "| `{}` | {} | {} |",
+            task.task_id,
+            status,
+            TurboDuration::from(Duration::milliseconds(task.duration))
+        )?;
+    }
+
+    for task in tasks
+        .iter()
+        .filter(|task| task.status == TaskReportStatus::Failed)
+    {
+        writeln!(markdown)?;
+        writeln!(markdown, "<details>")?;
+        writeln!(markdown, "<summary><code>{}</code></summary>", task.task_id)?;
+        writeln!(markdown)?;
+        if let Some(error) = &task.error {
+            writeln!(markdown, "{error}")?;
+            writeln!(markdown)?;
+        }
+        if let Some(log_tail) = &task.log_tail {
+            writeln!(markdown, "```")?;
+            writeln!(markdown, "{log_tail}")?;
+            writeln!(markdown, "```")?;
+            writeln!(markdown)?;
+        }
+        writeln!(markdown, "</details>")?;
+    }
+
+    Ok(())
+}
+
+// See https://github.com/actions/toolkit/blob/main/packages/core/src/command.ts
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn tasks() -> Vec<TaskReport> {
+        vec![
+            TaskReport {
+                task_id: "docs#build".to_string(),
+                package: "docs".to_string(),
+                task: "build".to_string(),
+                status: TaskReportStatus::Cached,
+                duration: 12,
+                error: None,
+                log_tail: None,
+            },
+            TaskReport {
+                task_id: "web#test".to_string(),
+                package: "web".to_string(),
+                task: "test".to_string(),
+                status: TaskReportStatus::Failed,
+                duration: 1500,
+                error: Some("command (apps/web) npm run test exited (1)".to_string()),
+                log_tail: Some("FAIL src/app.test.ts\n100% of 1 test failed".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_annotations() {
+        assert_eq!(
+            annotations(&tasks()),
+            "::error title=web#test failed::command (apps/web) npm run test exited (1)%0A%0AFAIL \
+             src/app.test.ts%0A100%25 of 1 test failed\n"
+        );
+    }
+
+    #[test]
+    fn test_annotation_title_is_escaped() {
+        let mut tasks = tasks();
+        tasks[1].task_id = "web#test:unit,e2e".to_string();
+        assert!(annotations(&tasks).starts_with("::error title=web#test%3Aunit%2Ce2e failed::"));
+    }
+
+    #[test]
+    fn test_job_summary() {
+        assert_eq!(
+            job_summary("turbo run build test", &tasks()),
+            "### `turbo run build test`
+
+0 successful, 1 cached, 1 failed, 2 total
+
+| Task | Status | Duration |
+| ---- | ------ | -------- |
+| `docs#build` | :zap: Cached | 12ms |
+| `web#test` | :x: Failed | 1.5s |
+
+<details>
+<summary><code>web#test</code></summary>
+
+command (apps/web) npm run test exited (1)
+
+```
+FAIL src/app.test.ts
+100% of 1 test failed
+```
+
+</details>
+"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Renders a run as JUnit XML, with a test suite for each package and a test
+//! case for each of its tasks, so that CI systems can display the results of
+//! a run the same way they display test results.
+
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use super::report::{TaskReport, TaskReportStatus};
+
+pub fn render(name: &str, tasks: &[TaskReport]) -> String {
+    let mut xml = String::new();
+    // Writing to a String can't fail
+    write_testsuites(&mut xml, name, tasks).expect("writing to a string");
+    xml
+}
+
+fn write_testsuites(xml: &mut String, name: &str, tasks: &[TaskReport]) -> std::fmt::Result {
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        xml,
+        r#"<testsuites name="{}" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+        escape(name),
+        tasks.len(),
+        count(tasks, TaskReportStatus::Failed),
+        count(tasks, TaskReportStatus::Skipped),
+        seconds(tasks.iter().map(|task| task.duration).sum()),
+    )?;
+
+    let packages = tasks
+        .iter()
+        .sorted_by(|a, b| a.package.cmp(&b.package))
+        .group_by(|task| task.package.as_str());
+    for (package, tasks) in &packages {
+        let tasks = tasks.collect::<Vec<_>>();
+        writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+            escape(package),
+            tasks.len(),
+            count(tasks.iter().copied(), TaskReportStatus::Failed),
+            count(tasks.iter().copied(), TaskReportStatus::Skipped),
+            seconds(tasks.iter().map(|task| task.duration).sum()),
+        )?;
+        for task in tasks {
+            write_testcase(xml, task)?;
+        }
+        writeln!(xml, "  </testsuite>")?;
+    }
+
+    writeln!(xml, "</testsuites>")
+}
+
+fn write_testcase(xml: &mut String, task: &TaskReport) -> std::fmt::Result {
+    let attributes = format!(
+        r#"name="{}" classname="{}" time="{}""#,
+        escape(&task.task),
+        escape(&task.package),
+        seconds(task.duration)
+    );
+    match task.status {
+        TaskReportStatus::Succeeded | TaskReportStatus::Cached => {
+            writeln!(xml, "    <testcase {attributes}/>")
+        }
+        TaskReportStatus::Skipped => {
+            writeln!(xml, "    <testcase {attributes}>")?;
+            writeln!(xml, r#"      <skipped message="task was not run"/>"#)?;
+            writeln!(xml, "    </testcase>")
+        }
+        TaskReportStatus::Failed => {
+            let message = task.error.as_deref().unwrap_or("task failed");
+            writeln!(xml, "    <testcase {attributes}>")?;
+            write!(xml, r#"      <failure message="{}">"#, escape(message))?;
+            if let Some(log_tail) = &task.log_tail {
+                write!(xml, "{}", escape(log_tail))?;
+            }
+            writeln!(xml, "</failure>")?;
+            writeln!(xml, "    </testcase>")
+        }
+    }
+}
+
+fn count<'a>(tasks: impl IntoIterator<Item = &'a TaskReport>, status: TaskReportStatus) -> usize {
+    tasks
+        .into_iter()
+        .filter(|task| task.status == status)
+        .count()
+}
+
+fn seconds(milliseconds: i64) -> String {
+    format!("{:.3}", milliseconds as f64 / 1000.0)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters aren't allowed in XML, even escaped
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+
+    fn report(task_id: &str, status: TaskReportStatus, duration: i64) -> TaskReport {
+        let (package, task) = task_id.split_once('#').unwrap();
+        TaskReport {
+            task_id: task_id.to_string(),
+            package: package.to_string(),
+            task: task.to_string(),
+            status,
+            duration,
+            error: None,
+            log_tail: None,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let tasks = vec![
+            report("web#build", TaskReportStatus::Succeeded, 1500),
+            report("docs#build", TaskReportStatus::Cached, 12),
+            TaskReport {
+                error: Some("command (apps/web) npm run test exited (1)".to_string()),
+                log_tail: Some("expected <div> & got nothing".to_string()),
+                ..report("web#test", TaskReportStatus::Failed, 2000)
+            },
+            report("web#lint", TaskReportStatus::Skipped, 0),
+        ];
+
+        assert_eq!(
+            render("turbo run build test lint", &tasks),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="turbo run build test lint" tests="4" failures="1" skipped="1" time="3.512">
+  <testsuite name="docs" tests="1" failures="0" skipped="0" time="0.012">
+    <testcase name="build" classname="docs" time="0.012"/>
+  </testsuite>
+  <testsuite name="web" tests="3" failures="1" skipped="1" time="3.500">
+    <testcase name="build" classname="web" time="1.500"/>
+    <testcase name="test" classname="web" time="2.000">
+      <failure message="command (apps/web) npm run test exited (1)">expected &lt;div&gt; &amp; got nothing</failure>
+    </testcase>
+    <testcase name="lint" classname="web" time="0.000">
+      <skipped message="task was not run"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test_case("plain", "plain" ; "plain text")]
+    #[test_case(r#"a "quoted" <tag>"#, "a &quot;quoted&quot; &lt;tag&gt;" ; "markup")]
+    #[test_case("line\nbreak", "line\nbreak" ; "newlines are kept")]
+    #[test_case("bell\u{7}", "bell" ; "control characters are removed")]
+    fn test_escape(text: &str, expected: &str) {
+        assert_eq!(escape(text), expected);
+    }
+}
@@ -3,25 +3,40 @@
 //! A tracker tracks the live data and then gets turned into a summary for
 //! displaying it We have this split because the tracker representation is not
 //! exactly what we want to display to the user.
+mod critical_path;
 #[allow(dead_code)]
 mod duration;
+mod events;
 mod execution;
+mod explain;
+mod github_actions;
 mod global_hash;
+mod junit;
+mod report;
 mod scm;
 mod spaces;
 mod task;
 mod task_factory;
-use std::{collections::HashSet, io, io::Write};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::OpenOptions,
+    io,
+    io::Write,
+};
 
 use chrono::{DateTime, Local};
+pub use critical_path::estimated_critical_path;
 pub use duration::TurboDuration;
-pub use execution::{TaskExecutionSummary, TaskTracker};
+pub use events::{EventsClientHandle, EventsTaskClient};
+pub use execution::{TaskAttempt, TaskExecutionSummary, TaskTracker};
+pub use explain::{print_explanation, HashInputsStore};
 pub use global_hash::GlobalHashSummary;
 use itertools::Itertools;
 use serde::Serialize;
 pub use spaces::{SpacesTaskClient, SpacesTaskInformation};
 use svix_ksuid::{Ksuid, KsuidLike};
 use tabwriter::TabWriter;
+pub use task::TaskSummaryTaskDefinition;
 use thiserror::Error;
 use tracing::{error, log::warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath};
@@ -32,19 +47,28 @@ use turborepo_scm::SCM;
 use turborepo_ui::{color, cprintln, cwriteln, BOLD, BOLD_CYAN, GREY, UI};
 
 use self::{
-    execution::TaskState, task::SinglePackageTaskSummary, task_factory::TaskSummaryFactory,
+    critical_path::{RunAnalysis, TaskTiming},
+    execution::TaskState,
+    explain::{GlobalHashInputs, HashInputs},
+    report::TaskReport,
+    task::SinglePackageTaskSummary,
+    task_factory::TaskSummaryFactory,
 };
 use super::task_id::TaskId;
 use crate::{
     cli,
-    cli::DryRunMode,
+    cli::{DryRunMode, OutputFormat, SummarizeMode},
     engine::Engine,
     opts::RunOpts,
-    run::summary::{
-        execution::{ExecutionSummary, ExecutionTracker},
-        scm::SCMState,
-        spaces::{SpaceRequest, SpacesClient, SpacesClientHandle},
-        task::TaskSummary,
+    run::{
+        shard::ShardPlan,
+        summary::{
+            events::RunEvent,
+            execution::{ExecutionSummary, ExecutionTracker},
+            scm::SCMState,
+            spaces::{SpaceRequest, SpacesClient, SpacesClientHandle},
+            task::TaskSummary,
+        },
     },
     task_hash::TaskHashTracker,
 };
@@ -118,16 +142,30 @@ pub struct RunSummary<'a> {
     env_mode: EnvMode,
     framework_inference: bool,
     tasks: Vec<TaskSummary>,
+    // The tasks of each shard when running with --shard
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shard: Option<ShardPlan>,
+    // What bounded the duration of the run, missing for dry runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    analysis: Option<RunAnalysis>,
     user: String,
     scm: SCMState,
     #[serde(skip)]
     repo_root: &'a AbsoluteSystemPath,
     #[serde(skip)]
-    should_save: bool,
+    summarize: Option<SummarizeMode>,
     #[serde(skip)]
     run_type: RunType,
     #[serde(skip)]
     spaces_client_handle: Option<SpacesClientHandle>,
+    #[serde(skip)]
+    events_client_handle: Option<EventsClientHandle>,
+    // The execution summary isn't printed when run events are written to stdout
+    #[serde(skip)]
+    should_print: bool,
+    // Print why tasks missed the cache
+    #[serde(skip)]
+    explain: bool,
 }
 
 /// We use this to track the run, so it's constructed before the run.
@@ -138,6 +176,8 @@ pub struct RunTracker {
     started_at: DateTime<Local>,
     execution_tracker: ExecutionTracker,
     spaces_client_handle: Option<SpacesClientHandle>,
+    events_client_handle: Option<EventsClientHandle>,
+    shard_plan: Option<ShardPlan>,
     user: String,
     synthesized_command: String,
 }
@@ -183,9 +223,27 @@ impl RunTracker {
             user,
             synthesized_command,
             spaces_client_handle,
+            events_client_handle: None,
+            shard_plan: None,
         }
     }
 
+    /// Reports the progress of the run as events sent to the given handle
+    pub fn with_events(mut self, events_client_handle: EventsClientHandle) -> Self {
+        events_client_handle.send(RunEvent::RunStarted {
+            command: self.synthesized_command.clone(),
+            turbo_version: self.version,
+        });
+        self.events_client_handle = Some(events_client_handle);
+        self
+    }
+
+    /// Includes the tasks of each shard in the summary
+    pub fn with_shard_plan(mut self, shard_plan: ShardPlan) -> Self {
+        self.shard_plan = Some(shard_plan);
+        self
+    }
+
     #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(skip(
         repo_root,
@@ -208,7 +266,10 @@ impl RunTracker {
         task_factory: TaskSummaryFactory<'a>,
     ) -> Result<RunSummary<'a>, Error> {
         let single_package = run_opts.single_package;
-        let should_save = run_opts.summarize.flatten().is_some_and(|s| s);
+        let summarize = run_opts
+            .summarize
+            .flatten()
+            .filter(|mode| *mode != SummarizeMode::Disabled);
 
         let run_type = match run_opts.dry_run {
             None => RunType::Real,
@@ -224,6 +285,29 @@ impl RunTracker {
             .cloned()
             .map(|TaskState { task_id, execution }| task_factory.task_summary(task_id, execution))
             .collect::<Result<Vec<_>, task_factory::Error>>()?;
+        let analysis = match run_type {
+            RunType::Real => RunAnalysis::new(
+                tasks
+                    .iter()
+                    .filter_map(|task| {
+                        let execution = task.shared.execution.as_ref()?;
+                        Some(TaskTiming {
+                            task_id: task.task_id.to_string(),
+                            start: execution.start_time,
+                            end: execution.end_time,
+                            dependencies: task
+                                .shared
+                                .dependencies
+                                .iter()
+                                .map(|dependency| dependency.to_string())
+                                .collect(),
+                        })
+                    })
+                    .collect(),
+                run_opts.concurrency,
+            ),
+            RunType::DryText | RunType::DryJson => None,
+        };
         let execution_summary = ExecutionSummary::new(
             self.synthesized_command.clone(),
             summary_state,
@@ -242,14 +326,19 @@ impl RunTracker {
             env_mode: global_env_mode,
             framework_inference: run_opts.framework_inference,
             tasks,
+            shard: self.shard_plan,
+            analysis,
             global_hash_summary,
             scm: self.scm,
             user: self.user,
             monorepo: !single_package,
             repo_root,
-            should_save,
+            summarize,
             run_type,
             spaces_client_handle: self.spaces_client_handle,
+            events_client_handle: self.events_client_handle,
+            should_print: run_opts.output_format == OutputFormat::Text,
+            explain: run_opts.explain,
         })
     }
 
@@ -310,7 +399,13 @@ impl RunTracker {
     }
 
     pub fn track_task(&self, task_id: TaskId<'static>) -> TaskTracker<()> {
-        self.execution_tracker.task_tracker(task_id)
+        let events = self
+            .events_client_handle
+            .as_ref()
+            .map(|handle| handle.task_client(task_id.to_string()));
+        self.execution_tracker
+            .task_tracker(task_id)
+            .with_events(events)
     }
 
     pub fn spaces_enabled(&self) -> bool {
@@ -343,6 +438,10 @@ struct SinglePackageRunSummary<'a> {
     env_mode: EnvMode,
     framework_inference: bool,
     tasks: Vec<SinglePackageTaskSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shard: Option<ShardPlan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    analysis: Option<RunAnalysis>,
     user: &'a str,
     pub scm: &'a SCMState,
 }
@@ -366,6 +465,14 @@ impl<'a> From<&'a RunSummary<'a>> for SinglePackageRunSummary<'a> {
             env_mode: run_summary.env_mode,
             framework_inference: run_summary.framework_inference,
             tasks,
+            shard: run_summary
+                .shard
+                .as_ref()
+                .map(ShardPlan::for_single_package),
+            analysis: run_summary
+                .analysis
+                .as_ref()
+                .map(RunAnalysis::for_single_package),
             user: &run_summary.user,
             scm: &run_summary.scm,
         }
@@ -385,16 +492,37 @@ impl<'a> RunSummary<'a> {
             return self.close_dry_run(pkg_dep_graph, ui);
         }
 
-        if self.should_save {
+        if self.summarize.is_some() {
             if let Err(err) = self.save() {
                 warn!("Error writing run summary: {}", err)
             }
         }
 
-        if let Some(execution) = &self.execution {
+        if let Err(err) = self.record_hash_inputs(ui) {
+            warn!("Error recording task hash inputs: {}", err)
+        }
+
+        if let Some(execution) = self.execution.as_ref().filter(|_| self.should_print) {
             let path = self.get_path();
             let failed_tasks = self.get_failed_tasks();
             execution.print(ui, path, failed_tasks);
+
+            // The analysis is only printed alongside a saved summary to keep the
+            // output of regular runs short
+            if let Some(analysis) = self.analysis.as_ref().filter(|_| self.summarize.is_some()) {
+                match self.monorepo {
+                    true => analysis.print(ui),
+                    false => analysis.for_single_package().print(ui),
+                }
+            }
+        }
+
+        if turborepo_ci::Vendor::get_constant() == Some("GITHUB_ACTIONS") {
+            self.report_to_github_actions();
+        }
+
+        if let Some(events_client_handle) = self.events_client_handle.take() {
+            self.send_events(events_client_handle, exit_code).await;
         }
 
         if let Some(spaces_client_handle) = self.spaces_client_handle.take() {
@@ -434,6 +562,57 @@ impl<'a> RunSummary<'a> {
         }
     }
 
+    async fn send_events(&mut self, events_client_handle: EventsClientHandle, exit_code: i32) {
+        match self.to_json_value() {
+            Ok(summary) => events_client_handle.send(RunEvent::RunFinished { exit_code, summary }),
+            Err(err) => warn!("Error serializing run summary for events: {}", err),
+        }
+
+        if let Err(err) = events_client_handle.close().await {
+            warn!("Error writing run events: {}", err);
+        }
+    }
+
+    fn report_to_github_actions(&mut self) {
+        self.normalize();
+        let tasks = self.task_reports();
+
+        if self.should_print {
+            print!("{}", github_actions::annotations(&tasks));
+        }
+
+        // The job summary is shared with the other steps of the job, so it's only
+        // added to when a summary was asked for
+        if self.summarize.is_none() {
+            return;
+        }
+        let Some(step_summary) = turborepo_ci::non_empty_var(github_actions::STEP_SUMMARY_ENV_VAR)
+        else {
+            return;
+        };
+        let command = self
+            .execution
+            .as_ref()
+            .map_or("turbo run", |execution| execution.command.as_str());
+        let job_summary = github_actions::job_summary(command, &tasks);
+        // Other steps can add to the job summary too, so we append to it
+        if let Err(err) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(step_summary)
+            .and_then(|mut file| file.write_all(job_summary.as_bytes()))
+        {
+            warn!("Error writing GitHub Actions job summary: {}", err);
+        }
+    }
+
+    fn task_reports(&self) -> Vec<TaskReport> {
+        self.tasks
+            .iter()
+            .map(|task| TaskReport::new(task, self.monorepo, self.repo_root))
+            .collect()
+    }
+
     fn print_errors(errors: &[Error]) {
         if errors.is_empty() {
             return;
@@ -757,6 +936,16 @@ impl<'a> RunSummary<'a> {
         Ok(rendered_json)
     }
 
+    fn to_json_value(&mut self) -> Result<serde_json::Value, Error> {
+        self.normalize();
+
+        Ok(if self.monorepo {
+            serde_json::to_value(&self)
+        } else {
+            serde_json::to_value(SinglePackageRunSummary::from(&*self))
+        }?)
+    }
+
     fn normalize(&mut self) {
         // Remove execution summary for dry runs
         if matches!(self.run_type, RunType::DryJson) {
@@ -779,12 +968,74 @@ impl<'a> RunSummary<'a> {
     }
 
     fn get_path(&self) -> AbsoluteSystemPathBuf {
-        let filename = format!("{}.json", self.id);
+        let extension = match self.summarize {
+            Some(SummarizeMode::Junit) => "xml",
+            _ => "json",
+        };
+        let filename = format!("{}.{}", self.id, extension);
 
         self.repo_root
             .join_components(&[".turbo", "runs", &filename])
     }
 
+    // Records the hash inputs of each task that was run, explaining why tasks
+    // missed the cache if requested. The recorded inputs are only written if
+    // they changed.
+    fn record_hash_inputs(&self, ui: UI) -> Result<(), Error> {
+        let mut store = HashInputsStore::load(self.repo_root).unwrap_or_else(|err| {
+            warn!(
+                "Error reading task hash inputs, they will be overwritten: {}",
+                err
+            );
+            HashInputsStore::default()
+        });
+        let global = GlobalHashInputs::new(
+            &self.global_hash_summary,
+            self.env_mode,
+            self.framework_inference,
+        )?;
+        let task_hashes = self
+            .tasks
+            .iter()
+            .map(|task| (&task.task_id, task.shared.hash.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        let mut changed = false;
+        for task in self
+            .tasks
+            .iter()
+            .sorted_by(|a, b| a.task_id.cmp(&b.task_id))
+        {
+            // Tasks that weren't run don't have anything to record
+            let Some(execution) = &task.shared.execution else {
+                continue;
+            };
+            let inputs = HashInputs::new(task, &global, &task_hashes)?;
+            let hit = task.shared.cache.is_hit();
+            if self.explain && self.should_print && !hit {
+                let task_id = match self.monorepo {
+                    true => task.task_id.to_string(),
+                    false => task.task.clone(),
+                };
+                let last_cached = store
+                    .get(&task.task_id.to_string())
+                    .and_then(|record| record.last_cached.as_ref());
+                print_explanation(ui, &task_id, &explain::explain(last_cached, &inputs));
+            }
+            changed |= store.record(
+                task.task_id.to_string(),
+                inputs,
+                hit,
+                execution.outputs_cached,
+            );
+        }
+
+        match changed {
+            true => store.save(self.repo_root),
+            false => Ok(()),
+        }
+    }
+
     fn get_failed_tasks(&self) -> Vec<&TaskSummary> {
         self.tasks
             .iter()
@@ -798,11 +1049,24 @@ impl<'a> RunSummary<'a> {
     }
 
     fn save(&mut self) -> Result<(), Error> {
-        let json = self.format_json()?;
+        let contents = match self.summarize {
+            Some(SummarizeMode::Junit) => self.format_junit(),
+            _ => self.format_json()?,
+        };
 
         let summary_path = self.get_path();
         summary_path.ensure_dir()?;
 
-        Ok(summary_path.create_with_contents(json)?)
+        Ok(summary_path.create_with_contents(contents)?)
+    }
+
+    fn format_junit(&mut self) -> String {
+        self.normalize();
+
+        let name = self
+            .execution
+            .as_ref()
+            .map_or("turbo run", |execution| execution.command.as_str());
+        junit::render(name, &self.task_reports())
     }
 }
@@ -0,0 +1,98 @@
+//! The outcome of each task in a run, in the shape that CI reporters such as
+//! JUnit and GitHub Actions need it.
+
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf};
+
+use super::task::TaskSummary;
+
+// Number of lines from the end of a failed task's log that are included in
+// reports
+const LOG_TAIL_LINES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskReportStatus {
+    Succeeded,
+    Cached,
+    Failed,
+    // The task was never started because the run stopped early
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskReport {
+    pub task_id: String,
+    pub package: String,
+    pub task: String,
+    pub status: TaskReportStatus,
+    // Duration of the task in milliseconds
+    pub duration: i64,
+    pub error: Option<String>,
+    // The end of the task's log, only collected for failed tasks
+    pub log_tail: Option<String>,
+}
+
+impl TaskReport {
+    pub fn new(task: &TaskSummary, monorepo: bool, repo_root: &AbsoluteSystemPath) -> Self {
+        let status = match &task.shared.execution {
+            None => TaskReportStatus::Skipped,
+            Some(execution) if execution.is_failure() => TaskReportStatus::Failed,
+            Some(_) if task.shared.cache.is_hit() => TaskReportStatus::Cached,
+            Some(_) => TaskReportStatus::Succeeded,
+        };
+        let log_tail = match status {
+            TaskReportStatus::Failed => read_log_tail(repo_root, &task.shared.log_file),
+            _ => None,
+        };
+
+        Self {
+            task_id: match monorepo {
+                true => task.task_id.to_string(),
+                false => task.task.clone(),
+            },
+            package: task.package.clone(),
+            task: task.task.clone(),
+            status,
+            duration: task
+                .shared
+                .execution
+                .as_ref()
+                .map_or(0, |execution| execution.end_time - execution.start_time),
+            error: task
+                .shared
+                .execution
+                .as_ref()
+                .and_then(|execution| execution.error.clone()),
+            log_tail,
+        }
+    }
+}
+
+fn read_log_tail(repo_root: &AbsoluteSystemPath, log_file: &str) -> Option<String> {
+    let log_file = repo_root.resolve(&AnchoredSystemPathBuf::from_raw(log_file).ok()?);
+    // Tasks that aren't cached don't write a log, in which case there's nothing
+    // to include
+    let contents = log_file.read().ok()?;
+    let contents = String::from_utf8_lossy(&contents);
+    let tail = log_tail(&console::strip_ansi_codes(&contents), LOG_TAIL_LINES);
+    (!tail.is_empty()).then_some(tail)
+}
+
+fn log_tail(contents: &str, lines: usize) -> String {
+    let all_lines = contents.trim_end().lines().collect::<Vec<_>>();
+    all_lines[all_lines.len().saturating_sub(lines)..].join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::log_tail;
+
+    #[test_case("", 2, "" ; "empty")]
+    #[test_case("one\ntwo\n", 3, "one\ntwo" ; "fewer lines than the limit")]
+    #[test_case("one\ntwo\nthree\nfour\n", 2, "three\nfour" ; "more lines than the limit")]
+    #[test_case("one\r\ntwo\r\n\n\n", 1, "two" ; "trailing blank lines")]
+    fn test_log_tail(contents: &str, lines: usize, expected: &str) {
+        assert_eq!(log_tail(contents, lines), expected);
+    }
+}
@@ -36,7 +36,7 @@ enum CacheStatus {
 
 #[derive(Debug, Serialize, Copy, Clone)]
 #[serde(rename_all = "UPPERCASE")]
-enum CacheSource {
+pub enum CacheSource {
     Local,
     Remote,
 }
@@ -106,6 +106,7 @@ pub struct TaskSummaryTaskDefinition {
     pass_through_env: Option<Vec<String>>,
     dot_env: Option<Vec<RelativeUnixPathBuf>>,
     interactive: bool,
+    retries: u32,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -118,6 +119,12 @@ pub struct TaskEnvVarSummary {
     pub pass_through: Option<Vec<String>>,
 }
 
+impl TaskSummaryTaskDefinition {
+    pub fn cache(&self) -> bool {
+        self.cache
+    }
+}
+
 impl TaskCacheSummary {
     pub fn cache_miss() -> Self {
         Self {
@@ -128,6 +135,10 @@ impl TaskCacheSummary {
             source: None,
         }
     }
+
+    pub fn is_hit(&self) -> bool {
+        matches!(self.status, CacheStatus::Hit)
+    }
 }
 
 impl From<Option<CacheHitMetadata>> for TaskCacheSummary {
@@ -284,6 +295,14 @@ impl From<TaskDefinition> for TaskSummaryTaskDefinition {
             output_mode,
             persistent,
             interactive,
+            retries,
+            // These only affect how a task is run, not what it produces
+            cache_retried: _,
+            timeout: _,
+            weight: _,
+            pool: _,
+            sidecars: _,
+            readiness: _,
         } = value;
 
         let mut outputs = inclusions;
@@ -316,6 +335,7 @@ impl From<TaskDefinition> for TaskSummaryTaskDefinition {
             output_mode,
             persistent,
             interactive,
+            retries,
             env,
             pass_through_env,
             // This should _not_ be sorted.
@@ -376,6 +396,7 @@ mod test {
             "outputMode": "full",
             "persistent": false,
             "interactive": false,
+            "retries": 0,
             "env": [],
             "passThroughEnv": null,
             "dotEnv": null,
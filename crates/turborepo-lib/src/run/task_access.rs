@@ -18,7 +18,7 @@ use crate::{
 
 // Environment variable key that will be used to enable, and set the expected
 // trace location
-const TASK_ACCESS_ENV_KEY: &str = "TURBOREPO_TRACE_FILE";
+pub(crate) const TASK_ACCESS_ENV_KEY: &str = "TURBOREPO_TRACE_FILE";
 /// File name where the task is expected to leave a trace result
 const TASK_ACCESS_TRACE_NAME: &str = "trace.json";
 // Path to the config file that will be used to store the trace results
@@ -0,0 +1,495 @@
+//! Checks that cached tasks are deterministic for `--verify-cache`. A task
+//! that hits the cache is run again in a scratch copy of its package, and the
+//! files it produces are compared against the ones stored in its artifact. A
+//! task that doesn't reproduce its outputs can't be trusted to share them
+//! through the remote cache.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+};
+
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+use turborepo_cache::{cache_archive::content_hash, fs::FSCache, CacheError};
+use turborepo_ui::{cprintln, BOLD, BOLD_GREEN, BOLD_RED, GREY, UI};
+
+use crate::task_graph::{TaskDefinition, TaskOutputs};
+
+// Scratch copies are created under `.turbo` so they're on the same filesystem
+// as the workspace and ignored by the same rules
+const SCRATCH_DIR: &[&str] = &[".turbo", "verify"];
+// Left out of scratch copies. `.turbo` holds turbo's own state, including the
+// scratch copies, and the re-run shouldn't be able to change the repository
+const SKIPPED: &[&str] = &[".turbo", ".git"];
+// The packages in node_modules are linked into scratch copies instead of being
+// copied, as they're large and tasks shouldn't be writing to them
+const NODE_MODULES: &str = "node_modules";
+// Directories in node_modules that package managers install to. Tools keep
+// their caches in the other ones starting with a dot, like `.cache` or `.vite`,
+// so those aren't linked.
+const INSTALLED: &[&str] = &[".bin", ".pnpm"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("the artifact isn't in the local cache")]
+    NotCached,
+    #[error("unable to read the artifact: {0}")]
+    Cache(#[from] CacheError),
+    #[error("unable to set up the scratch directory: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unable to set up the scratch directory: {0}")]
+    Path(#[from] turbopath::PathError),
+    #[error("unable to set up the scratch directory: {0}")]
+    Copy(#[from] turborepo_fs::Error),
+    #[error("invalid output glob: {0}")]
+    Glob(#[from] globwalk::GlobError),
+    #[error("unable to find the outputs of the re-run: {0}")]
+    Walk(#[from] globwalk::WalkError),
+    #[error("the re-run failed: {0}")]
+    Rerun(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Reproduced {
+        files: usize,
+    },
+    // Paths of the files that differ, relative to the repo root
+    Differs {
+        changed: Vec<String>,
+        missing: Vec<String>,
+        added: Vec<String>,
+    },
+    Unverified(String),
+}
+
+/// A copy of the repository to re-run a task in, so that the re-run can't
+/// change the workspace. Only the packages in `node_modules` are linked. The
+/// scratch copy is removed once it's dropped.
+pub struct Scratch {
+    root: AbsoluteSystemPathBuf,
+    package_dir: AbsoluteSystemPathBuf,
+    package_path: AnchoredSystemPathBuf,
+    // Content hashes of the files in the artifact, keyed by repo relative path
+    artifact: BTreeMap<String, String>,
+}
+
+impl Scratch {
+    pub fn package_dir(&self) -> &AbsoluteSystemPath {
+        &self.package_dir
+    }
+
+    pub fn root(&self) -> &AbsoluteSystemPath {
+        &self.root
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        self.root.remove_dir_all().ok();
+    }
+}
+
+#[derive(Clone)]
+pub struct CacheVerifier {
+    repo_root: AbsoluteSystemPathBuf,
+    cache: Arc<FSCache>,
+    outcomes: Arc<Mutex<BTreeMap<String, Outcome>>>,
+}
+
+impl CacheVerifier {
+    pub fn new(repo_root: AbsoluteSystemPathBuf, cache: FSCache) -> Self {
+        Self {
+            repo_root,
+            cache: Arc::new(cache),
+            outcomes: Arc::default(),
+        }
+    }
+
+    /// Creates a scratch copy of the repository to re-run the task with the
+    /// given hash in. Files stored in the artifact, and any other files that
+    /// match the task's outputs, aren't copied, so the task has to produce
+    /// them again.
+    pub fn scratch(
+        &self,
+        task_hash: &str,
+        package_dir: &AbsoluteSystemPath,
+        outputs: &TaskOutputs,
+    ) -> Result<Scratch, Error> {
+        let artifact = self.cache.file_hashes(task_hash)?.ok_or(Error::NotCached)?;
+        // Outputs left behind by earlier runs would otherwise be reported as
+        // added by the re-run
+        let stale_outputs = globwalk::globwalk(
+            &self.repo_root,
+            &outputs.validated_inclusions()?,
+            &outputs.validated_exclusions()?,
+            globwalk::WalkType::Files,
+        )?
+        .into_iter()
+        .map(|path| {
+            AnchoredSystemPathBuf::relative_path_between(&self.repo_root, &path)
+                .to_unix()
+                .into_inner()
+        });
+        let excluded = artifact
+            .keys()
+            .cloned()
+            .chain(stale_outputs)
+            .collect::<BTreeSet<_>>();
+        let package_path =
+            AnchoredSystemPathBuf::relative_path_between(&self.repo_root, package_dir);
+        let root = self
+            .repo_root
+            .join_components(SCRATCH_DIR)
+            .join_component(task_hash);
+        if root.exists() {
+            root.remove_dir_all()?;
+        }
+
+        let scratch = Scratch {
+            package_dir: root.resolve(&package_path),
+            root,
+            package_path,
+            artifact,
+        };
+        create_scratch(&self.repo_root, &scratch, &excluded)?;
+        Ok(scratch)
+    }
+
+    /// Compares the files the re-run produced in `scratch` against the
+    /// artifact and records the outcome for the task
+    pub fn compare(
+        &self,
+        task_id: &str,
+        task_name: &str,
+        scratch: Scratch,
+        outputs: &TaskOutputs,
+    ) -> Result<(), Error> {
+        let produced = globwalk::globwalk(
+            &scratch.root,
+            &outputs.validated_inclusions()?,
+            &outputs.validated_exclusions()?,
+            globwalk::WalkType::Files,
+        )?
+        .into_iter()
+        .map(|path| {
+            let relative = AnchoredSystemPathBuf::relative_path_between(&scratch.root, &path);
+            Ok((relative.to_unix().into_inner(), content_hash(path.open()?)?))
+        })
+        .collect::<Result<BTreeMap<_, _>, Error>>()?;
+
+        // turbo writes the log file, not the task, so it's never reproduced
+        let log_file = scratch
+            .package_path
+            .join(&TaskDefinition::workspace_relative_log_file(task_name))
+            .to_unix()
+            .into_inner();
+        self.insert(task_id, compare(&scratch.artifact, produced, &log_file));
+        Ok(())
+    }
+
+    pub fn unverified(&self, task_id: &str, error: Error) {
+        self.insert(task_id, Outcome::Unverified(error.to_string()));
+    }
+
+    fn insert(&self, task_id: &str, outcome: Outcome) {
+        self.outcomes
+            .lock()
+            .expect("cache verifier lock poisoned")
+            .insert(task_id.to_string(), outcome);
+    }
+
+    pub fn print(&self, ui: UI) {
+        let outcomes = self.outcomes.lock().expect("cache verifier lock poisoned");
+        if outcomes.is_empty() {
+            return;
+        }
+
+        println!();
+        cprintln!(ui, BOLD, "Cache verification:");
+        let mut differing = 0;
+        for (task_id, outcome) in outcomes.iter() {
+            match outcome {
+                Outcome::Reproduced { files } => {
+                    let noun = if *files == 1 { "file" } else { "files" };
+                    println!("  {task_id}: reproduced {files} {noun}");
+                }
+                Outcome::Differs {
+                    changed,
+                    missing,
+                    added,
+                } => {
+                    differing += 1;
+                    println!("  {task_id}: outputs differ from the cache");
+                    let differences = changed
+                        .iter()
+                        .map(|path| ("changed", path))
+                        .chain(missing.iter().map(|path| ("missing", path)))
+                        .chain(added.iter().map(|path| ("added", path)));
+                    for (difference, path) in differences {
+                        println!("    {difference:<7} {path}");
+                    }
+                }
+                Outcome::Unverified(reason) => {
+                    cprintln!(ui, GREY, "  {task_id}: couldn't be verified, {reason}");
+                }
+            }
+        }
+
+        let verified = outcomes
+            .values()
+            .filter(|outcome| !matches!(outcome, Outcome::Unverified(_)))
+            .count();
+        if differing > 0 {
+            cprintln!(
+                ui,
+                BOLD_RED,
+                "{differing} of {verified} verified tasks didn't reproduce their cached outputs"
+            );
+        } else if verified > 0 {
+            cprintln!(
+                ui,
+                BOLD_GREEN,
+                "All {verified} verified tasks reproduced their cached outputs"
+            );
+        }
+    }
+}
+
+fn compare(
+    artifact: &BTreeMap<String, String>,
+    mut produced: BTreeMap<String, String>,
+    log_file: &str,
+) -> Outcome {
+    produced.remove(log_file);
+    let mut files = 0;
+    let mut changed = Vec::new();
+    let mut missing = Vec::new();
+    for (path, hash) in artifact.iter().filter(|(path, _)| *path != log_file) {
+        match produced.remove(path) {
+            Some(produced_hash) if produced_hash == *hash => files += 1,
+            Some(_) => changed.push(path.clone()),
+            None => missing.push(path.clone()),
+        }
+    }
+    let added = produced.into_keys().collect::<Vec<_>>();
+
+    if changed.is_empty() && missing.is_empty() && added.is_empty() {
+        Outcome::Reproduced { files }
+    } else {
+        Outcome::Differs {
+            changed,
+            missing,
+            added,
+        }
+    }
+}
+
+// Copies the repository, apart from the `excluded` files, which are given as
+// repo relative unix paths
+fn create_scratch(
+    repo_root: &AbsoluteSystemPath,
+    scratch: &Scratch,
+    excluded: &BTreeSet<String>,
+) -> Result<(), Error> {
+    scratch.root.create_dir_all()?;
+    copy_dir(repo_root, &scratch.root, "", excluded)
+}
+
+// Copies `dir`, `relative` is the repo relative unix path of `dir`
+fn copy_dir(
+    dir: &AbsoluteSystemPath,
+    scratch_dir: &AbsoluteSystemPath,
+    relative: &str,
+    excluded: &BTreeSet<String>,
+) -> Result<(), Error> {
+    for (name, path) in entries(dir)? {
+        let target = scratch_dir.join_component(&name);
+        let relative = match relative {
+            "" => name.clone(),
+            relative => format!("{relative}/{name}"),
+        };
+        if SKIPPED.contains(&name.as_str()) || excluded.contains(&relative) {
+            continue;
+        }
+        if name == NODE_MODULES {
+            link_node_modules(&path, &target)?;
+        } else if path.symlink_metadata()?.is_dir() {
+            target.create_dir_all()?;
+            copy_dir(&path, &target, &relative, excluded)?;
+        } else {
+            turborepo_fs::copy_file(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+// Links the installed packages in `node_modules`, leaving out the caches that
+// tools write to
+fn link_node_modules(
+    node_modules: &AbsoluteSystemPath,
+    target: &AbsoluteSystemPath,
+) -> Result<(), Error> {
+    target.create_dir_all()?;
+    for (name, path) in entries(node_modules)? {
+        let is_cache = name.starts_with('.')
+            && !INSTALLED.contains(&name.as_str())
+            && path.symlink_metadata()?.is_dir();
+        if !is_cache {
+            link(&path, &target.join_component(&name))?;
+        }
+    }
+    Ok(())
+}
+
+fn entries(dir: &AbsoluteSystemPath) -> Result<Vec<(String, AbsoluteSystemPathBuf)>, Error> {
+    let mut entries = Vec::new();
+    for entry in dir.as_std_path().read_dir()? {
+        // Names that aren't valid UTF-8 can't be part of the package path or its
+        // outputs, so there's nothing to copy them for
+        let Ok(name) = entry?.file_name().into_string() else {
+            continue;
+        };
+        let path = dir.join_component(&name);
+        entries.push((name, path));
+    }
+    Ok(entries)
+}
+
+fn link(path: &AbsoluteSystemPath, link: &AbsoluteSystemPath) -> Result<(), Error> {
+    if path.stat().is_ok_and(|metadata| metadata.is_dir()) {
+        link.symlink_to_dir(path.as_str())?;
+    } else {
+        link.symlink_to_file(path.as_str())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn hashes(files: &[(&str, &str)]) -> BTreeMap<String, String> {
+        files
+            .iter()
+            .map(|(path, hash)| (path.to_string(), hash.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_compare() {
+        let log_file = "apps/web/.turbo/turbo-build.log";
+        let artifact = hashes(&[
+            (log_file, "log"),
+            ("apps/web/dist/index.js", "a"),
+            ("apps/web/dist/time.txt", "b"),
+            ("apps/web/dist/old.js", "c"),
+        ]);
+
+        assert_eq!(
+            compare(
+                &artifact,
+                hashes(&[
+                    ("apps/web/dist/index.js", "a"),
+                    ("apps/web/dist/time.txt", "b"),
+                    ("apps/web/dist/old.js", "c"),
+                ]),
+                log_file
+            ),
+            Outcome::Reproduced { files: 3 }
+        );
+        assert_eq!(
+            compare(
+                &artifact,
+                hashes(&[
+                    ("apps/web/dist/index.js", "a"),
+                    ("apps/web/dist/time.txt", "d"),
+                    ("apps/web/dist/new.js", "e"),
+                ]),
+                log_file
+            ),
+            Outcome::Differs {
+                changed: vec!["apps/web/dist/time.txt".to_string()],
+                missing: vec!["apps/web/dist/old.js".to_string()],
+                added: vec!["apps/web/dist/new.js".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_scratch() -> Result<(), Error> {
+        let tmp = tempdir()?;
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path())?;
+        for file in [
+            "package.json",
+            ".turbo/runs/run.json",
+            ".git/HEAD",
+            "node_modules/.bin/tool",
+            "node_modules/.cache/tool/data",
+            "apps/docs/package.json",
+            "apps/web/package.json",
+            "apps/web/src/index.js",
+            "apps/web/dist/index.js",
+            "apps/web/dist/stale.js",
+            "apps/web/node_modules/dep/index.js",
+            "apps/web/.turbo/turbo-build.log",
+        ] {
+            let path = repo_root.join_unix_path(turbopath::RelativeUnixPath::new(file)?);
+            path.ensure_dir()?;
+            path.create_with_contents(file)?;
+        }
+
+        let root = repo_root
+            .join_components(SCRATCH_DIR)
+            .join_component("hash");
+        let scratch = Scratch {
+            package_dir: root.join_components(&["apps", "web"]),
+            root,
+            package_path: AnchoredSystemPathBuf::from_raw("apps/web")?,
+            artifact: hashes(&[("apps/web/dist/index.js", "a")]),
+        };
+        let excluded = BTreeSet::from([
+            "apps/web/dist/index.js".to_string(),
+            "apps/web/dist/stale.js".to_string(),
+        ]);
+        create_scratch(&repo_root, &scratch, &excluded)?;
+
+        let scratch_path = |path: &str| {
+            scratch
+                .root
+                .join_unix_path(turbopath::RelativeUnixPath::new(path).unwrap())
+        };
+        // The repository is copied, so the re-run can't write to the workspace
+        for file in [
+            "package.json",
+            "apps/docs/package.json",
+            "apps/web/src/index.js",
+        ] {
+            assert!(!scratch_path(file).symlink_metadata()?.is_symlink());
+            assert_eq!(scratch_path(file).read_to_string()?, file);
+        }
+        // Installed packages are linked, but the caches in node_modules aren't
+        assert!(!scratch_path("node_modules")
+            .symlink_metadata()?
+            .is_symlink());
+        assert!(scratch_path("node_modules/.bin")
+            .symlink_metadata()?
+            .is_symlink());
+        assert!(scratch_path("apps/web/node_modules/dep")
+            .symlink_metadata()?
+            .is_symlink());
+        assert!(!scratch_path("node_modules/.cache").exists());
+        // Outputs and turbo's own files aren't copied
+        assert!(!scratch_path("apps/web/dist/index.js").exists());
+        assert!(!scratch_path("apps/web/dist/stale.js").exists());
+        assert!(!scratch_path("apps/web/.turbo").exists());
+        assert!(!scratch_path(".turbo").exists());
+        assert!(!scratch_path(".git").exists());
+
+        let root = scratch.root.clone();
+        drop(scratch);
+        assert!(!root.exists());
+        Ok(())
+    }
+}
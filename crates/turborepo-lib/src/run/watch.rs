@@ -0,0 +1,657 @@
+//! Watch mode
+//!
+//! Runs the requested tasks once and then subscribes to file system events,
+//! through the daemon unless `--no-daemon` is passed. Whenever files change,
+//! the changed packages are computed and only the affected part of the task
+//! graph is re-run. Persistent tasks are started once and kept alive across
+//! changes, unless a change invalidates the entire repository (e.g. the root
+//! `turbo.json`), in which case everything is restarted.
+
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    time::Duration,
+};
+
+use thiserror::Error;
+use tokio::{select, sync::broadcast, task::JoinHandle};
+use tracing::{debug, error, warn};
+use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+use turborepo_filewatch::{FileSystemWatcher, NotifyError, WatchError};
+use turborepo_repository::{
+    change_mapper::{ChangeMapError, ChangeMapper, PackageChanges},
+    package_graph::{PackageGraph, PackageName},
+};
+use turborepo_telemetry::events::command::CommandEventBuilder;
+use turborepo_ui::UI;
+
+use crate::{
+    cli::{Command, RunArgs},
+    commands::CommandBase,
+    daemon::{proto, DaemonConnector, DaemonConnectorError, DaemonError},
+    global_deps_package_change_mapper::{self, GlobalDepsPackageChangeMapper},
+    process::ProcessManager,
+    run::{self, builder::RunBuilder, task_id::TaskId, Run},
+    signal::SignalHandler,
+    task_graph::TaskDefinition,
+    Args,
+};
+
+/// How long we wait for the file system to settle before re-running tasks.
+/// Saving a file in an editor or switching branches produces a burst of
+/// events that should only trigger a single run.
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(100);
+
+/// Directories whose contents never affect task inputs
+const IGNORED_DIRECTORIES: [&str; 3] = [".git", ".turbo", "node_modules"];
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("`turbo watch` does not support --{0}")]
+    UnsupportedFlag(&'static str),
+    #[error("failed to connect to the daemon, pass --no-daemon to watch without it: {0}")]
+    DaemonConnect(#[from] DaemonConnectorError),
+    #[error("failed to subscribe to file changes from the daemon: {0}")]
+    Daemon(#[from] DaemonError),
+    #[error("failed to start file watching: {0}")]
+    Watch(#[from] WatchError),
+    #[error("file watching stopped unexpectedly")]
+    WatchStopped,
+    #[error("failed to compute changed packages: {0}")]
+    ChangeMapper(#[from] ChangeMapError),
+    #[error(transparent)]
+    GlobalDeps(#[from] global_deps_package_change_mapper::Error),
+    #[error(transparent)]
+    Config(#[from] crate::config::Error),
+    #[error(transparent)]
+    Run(#[from] run::Error),
+}
+
+/// The packages affected by a batch of file changes
+#[derive(Debug, PartialEq)]
+enum ChangedPackages {
+    All,
+    Some(HashSet<PackageName>),
+}
+
+/// A batch of changes reported by the file watcher
+#[derive(Debug)]
+enum FileEvent {
+    Changed(Vec<AnchoredSystemPathBuf>),
+    /// Events were dropped, so any file may have changed
+    Lagged,
+}
+
+/// Where file changes come from
+enum FileEvents {
+    Daemon(tonic::Streaming<proto::FileChangesEvent>),
+    // Only used with `--no-daemon`. The watcher is kept alive for as long as
+    // we are subscribed to it.
+    Local {
+        _watcher: FileSystemWatcher,
+        events: broadcast::Receiver<Result<notify::Event, NotifyError>>,
+        repo_root: AbsoluteSystemPathBuf,
+    },
+}
+
+impl FileEvents {
+    async fn recv(&mut self) -> Result<FileEvent, Error> {
+        match self {
+            FileEvents::Daemon(stream) => match stream.message().await {
+                Ok(Some(event)) if event.lagged => Ok(FileEvent::Lagged),
+                Ok(Some(event)) => Ok(FileEvent::Changed(
+                    event
+                        .paths
+                        .into_iter()
+                        .filter_map(|path| AnchoredSystemPathBuf::from_raw(path).ok())
+                        .collect(),
+                )),
+                Ok(None) => Err(Error::WatchStopped),
+                Err(status) => Err(DaemonError::from(status).into()),
+            },
+            FileEvents::Local {
+                events, repo_root, ..
+            } => loop {
+                match events.recv().await {
+                    Ok(Ok(event)) => {
+                        return Ok(FileEvent::Changed(
+                            event
+                                .paths
+                                .into_iter()
+                                .filter_map(|path| {
+                                    let path = AbsoluteSystemPathBuf::try_from(path).ok()?;
+                                    // Paths outside of the repository are dropped
+                                    repo_root.anchor(&path).ok()
+                                })
+                                .collect(),
+                        ));
+                    }
+                    Ok(Err(e)) => warn!("file watching error: {e}"),
+                    Err(broadcast::error::RecvError::Lagged(_)) => return Ok(FileEvent::Lagged),
+                    Err(broadcast::error::RecvError::Closed) => return Err(Error::WatchStopped),
+                }
+            },
+        }
+    }
+}
+
+/// A run of persistent tasks that is kept alive in the background
+struct PersistentRun {
+    processes: ProcessManager,
+    handle: JoinHandle<Result<i32, run::Error>>,
+}
+
+impl PersistentRun {
+    async fn stop(self) {
+        self.processes.stop().await;
+        match self.handle.await {
+            Ok(Err(e)) => warn!("persistent tasks exited with an error: {e}"),
+            Err(e) => warn!("persistent tasks panicked: {e}"),
+            Ok(Ok(_)) => {}
+        }
+    }
+}
+
+/// The parts of a `Run` that watch mode relies on
+trait WatchRun: Sized {
+    fn with_changed_packages(self, packages: &HashSet<PackageName>) -> Self;
+    fn without_persistent_tasks(self) -> Self;
+    fn create_run_for_persistent_tasks(&self) -> Self;
+    fn has_tasks(&self) -> bool;
+    fn processes(&self) -> &ProcessManager;
+    fn run(self) -> impl Future<Output = Result<i32, run::Error>> + Send + 'static;
+}
+
+impl WatchRun for Run {
+    fn with_changed_packages(self, packages: &HashSet<PackageName>) -> Self {
+        Run::with_changed_packages(self, packages)
+    }
+
+    fn without_persistent_tasks(self) -> Self {
+        Run::without_persistent_tasks(self)
+    }
+
+    fn create_run_for_persistent_tasks(&self) -> Self {
+        Run::create_run_for_persistent_tasks(self)
+    }
+
+    fn has_tasks(&self) -> bool {
+        self.engine().has_tasks()
+    }
+
+    fn processes(&self) -> &ProcessManager {
+        Run::processes(self)
+    }
+
+    fn run(self) -> impl Future<Output = Result<i32, run::Error>> + Send + 'static {
+        Run::run(self)
+    }
+}
+
+pub struct WatchClient {
+    args: Args,
+    repo_root: AbsoluteSystemPathBuf,
+    ui: UI,
+    version: &'static str,
+    daemon: Option<bool>,
+    handler: SignalHandler,
+    telemetry: CommandEventBuilder,
+}
+
+impl WatchClient {
+    pub fn new(
+        base: CommandBase,
+        run_args: RunArgs,
+        handler: SignalHandler,
+        telemetry: CommandEventBuilder,
+    ) -> Result<Self, Error> {
+        if run_args.dry_run.is_some() {
+            return Err(Error::UnsupportedFlag("dry-run"));
+        }
+        if run_args.graph.is_some() {
+            return Err(Error::UnsupportedFlag("graph"));
+        }
+
+        let daemon = run_args.daemon();
+        // Watch mode is built out of regular runs, so we pretend to be one.
+        let mut args = base.args().clone();
+        args.command = Some(Command::Run(Box::new(run_args)));
+
+        Ok(Self {
+            version: base.version(),
+            args,
+            repo_root: base.repo_root,
+            ui: base.ui,
+            daemon,
+            handler,
+            telemetry,
+        })
+    }
+
+    pub async fn start(self) -> Result<(), Error> {
+        let mut events = self.subscribe().await?;
+
+        let run = self.build_run().await?;
+        let mut persistent_run = execute(ChangedPackages::All, run, None).await?;
+
+        'watch: loop {
+            // Wait for the next batch of changed files, or for the user to stop us
+            let mut changed_files = HashSet::new();
+            select! {
+                biased;
+                _ = self.handler.done() => break 'watch,
+                event = events.recv() => add_changed_files(&mut changed_files, event?),
+            }
+            // Collect all of the events that come in during the debounce window
+            loop {
+                select! {
+                    biased;
+                    _ = self.handler.done() => break 'watch,
+                    _ = tokio::time::sleep(DEBOUNCE_DURATION) => break,
+                    event = events.recv() => add_changed_files(&mut changed_files, event?),
+                }
+            }
+            if changed_files.is_empty() {
+                continue;
+            }
+            debug!("changed files: {:?}", changed_files);
+
+            // We build a fresh run for every change to pick up package and
+            // configuration changes. If the repository is in a broken state we
+            // report it and wait for the next change.
+            let result = match self.build_run().await {
+                Ok(run) => match Self::changed_packages_for_run(&run, changed_files) {
+                    Ok(changes) => execute(changes, run, persistent_run.take()).await,
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(run) => persistent_run = run,
+                Err(e) => error!("{e}"),
+            }
+        }
+
+        if let Some(persistent_run) = persistent_run {
+            persistent_run.stop().await;
+        }
+
+        Ok(())
+    }
+
+    async fn subscribe(&self) -> Result<FileEvents, Error> {
+        if self.daemon == Some(false) {
+            debug!("watching files without the daemon");
+            let watcher = FileSystemWatcher::new_with_default_cookie_dir(&self.repo_root)?;
+            let events = watcher.subscribe().await.map_err(|_| Error::WatchStopped)?;
+            return Ok(FileEvents::Local {
+                _watcher: watcher,
+                events,
+                repo_root: self.repo_root.clone(),
+            });
+        }
+
+        let can_start_server = true;
+        let can_kill_server = true;
+        let mut client = DaemonConnector::new(can_start_server, can_kill_server, &self.repo_root)
+            .connect()
+            .await?;
+        Ok(FileEvents::Daemon(client.file_changes().await?))
+    }
+
+    async fn build_run(&self) -> Result<Run, Error> {
+        let base = CommandBase::new(
+            self.args.clone(),
+            self.repo_root.clone(),
+            self.version,
+            self.ui,
+        );
+        let api_auth = base.api_auth()?;
+        let api_client = base.api_client()?;
+        Ok(RunBuilder::new(base, api_auth)?
+            .build(&self.handler, self.telemetry.clone(), api_client)
+            .await?)
+    }
+
+    fn changed_packages_for_run(
+        run: &Run,
+        changed_files: HashSet<AnchoredSystemPathBuf>,
+    ) -> Result<ChangedPackages, Error> {
+        let pkg_dep_graph = run.pkg_dep_graph();
+        changed_packages(
+            pkg_dep_graph,
+            run.root_turbo_json()
+                .global_deps
+                .iter()
+                .map(|dep| dep.as_str()),
+            output_globs(run.engine().task_definitions(), pkg_dep_graph),
+            changed_files,
+        )
+    }
+}
+
+// Runs the tasks affected by `changes`. If everything changed, any running
+// persistent tasks are restarted.
+async fn execute<R: WatchRun>(
+    changes: ChangedPackages,
+    run: R,
+    persistent_run: Option<PersistentRun>,
+) -> Result<Option<PersistentRun>, Error> {
+    match changes {
+        ChangedPackages::All => {
+            if let Some(persistent_run) = persistent_run {
+                persistent_run.stop().await;
+            }
+
+            let persistent = run.create_run_for_persistent_tasks();
+            run.without_persistent_tasks().run().await?;
+
+            if !persistent.has_tasks() {
+                return Ok(None);
+            }
+
+            let processes = persistent.processes().clone();
+            let handle = tokio::spawn(persistent.run());
+            Ok(Some(PersistentRun { processes, handle }))
+        }
+        ChangedPackages::Some(packages) => {
+            let run = run.with_changed_packages(&packages);
+            if run.has_tasks() {
+                // The persistent tasks are still running, so we report the
+                // failure instead of bubbling it up and losing track of them.
+                if let Err(e) = run.run().await {
+                    error!("{e}");
+                }
+            }
+            Ok(persistent_run)
+        }
+    }
+}
+
+fn add_changed_files(changed_files: &mut HashSet<AnchoredSystemPathBuf>, event: FileEvent) {
+    match event {
+        FileEvent::Changed(paths) => changed_files.extend(paths.into_iter().filter(|path| {
+            !path
+                .components()
+                .any(|component| IGNORED_DIRECTORIES.contains(&component.as_str()))
+        })),
+        // We missed some events, so we no longer know what changed. Report
+        // the root package.json to invalidate everything.
+        FileEvent::Lagged => {
+            changed_files.insert(
+                AnchoredSystemPathBuf::from_raw("package.json")
+                    .expect("package.json is a valid anchored path"),
+            );
+        }
+    }
+}
+
+// Task outputs are written by the runs we trigger and must not trigger
+// another run.
+fn output_globs(
+    task_definitions: &HashMap<TaskId<'static>, TaskDefinition>,
+    pkg_dep_graph: &PackageGraph,
+) -> Vec<String> {
+    task_definitions
+        .iter()
+        .filter_map(|(task_id, definition)| {
+            let info = pkg_dep_graph.package_info(&PackageName::from(task_id.package()))?;
+            Some(
+                definition
+                    .repo_relative_hashable_outputs(task_id, info.package_path())
+                    .inclusions,
+            )
+        })
+        .flatten()
+        .map(|glob| glob.replace('\\', "/").trim_start_matches('/').to_string())
+        .collect()
+}
+
+fn changed_packages<'a>(
+    pkg_dep_graph: &PackageGraph,
+    global_deps: impl Iterator<Item = &'a str>,
+    ignore_patterns: Vec<String>,
+    changed_files: HashSet<AnchoredSystemPathBuf>,
+) -> Result<ChangedPackages, Error> {
+    let package_detector = GlobalDepsPackageChangeMapper::new(pkg_dep_graph, global_deps)?;
+    let change_mapper = ChangeMapper::new(pkg_dep_graph, ignore_patterns, package_detector);
+
+    Ok(match change_mapper.changed_packages(changed_files, None)? {
+        PackageChanges::All => ChangedPackages::All,
+        PackageChanges::Some(packages) => {
+            ChangedPackages::Some(packages.into_iter().map(|package| package.name).collect())
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use tempdir::TempDir;
+    use turbopath::AbsoluteSystemPath;
+    use turborepo_repository::{
+        discovery::{DiscoveryResponse, PackageDiscovery, WorkspaceData},
+        package_json::PackageJson,
+        package_manager::PackageManager,
+    };
+
+    use super::*;
+    use crate::task_graph::TaskOutputs;
+
+    // web depends on nothing and lives in apps/web, ui lives in packages/ui
+    struct DummyDiscovery<'a>(&'a TempDir);
+
+    impl<'a> PackageDiscovery for DummyDiscovery<'a> {
+        async fn discover_packages(
+            &self,
+        ) -> Result<DiscoveryResponse, turborepo_repository::discovery::Error> {
+            let root = AbsoluteSystemPath::from_std_path(self.0.path()).unwrap();
+            let workspaces = [("web", "apps/web"), ("ui", "packages/ui")]
+                .into_iter()
+                .map(|(name, path)| {
+                    let package_json = root
+                        .join_components(&path.split('/').collect::<Vec<_>>())
+                        .join_component("package.json");
+                    package_json.ensure_dir().unwrap();
+                    let package = PackageJson {
+                        name: Some(name.to_string()),
+                        ..Default::default()
+                    };
+                    package_json
+                        .create_with_contents(serde_json::to_string(&package).unwrap())
+                        .unwrap();
+
+                    WorkspaceData {
+                        package_json,
+                        turbo_json: None,
+                    }
+                })
+                .collect();
+
+            Ok(DiscoveryResponse {
+                package_manager: PackageManager::Pnpm,
+                workspaces,
+            })
+        }
+
+        async fn discover_packages_blocking(
+            &self,
+        ) -> Result<DiscoveryResponse, turborepo_repository::discovery::Error> {
+            self.discover_packages().await
+        }
+    }
+
+    async fn package_graph(tmp: &TempDir) -> PackageGraph {
+        PackageGraph::builder(
+            AbsoluteSystemPath::from_std_path(tmp.path()).unwrap(),
+            PackageJson::default(),
+        )
+        .with_package_discovery(DummyDiscovery(tmp))
+        .build()
+        .await
+        .unwrap()
+    }
+
+    fn files(paths: &[&str]) -> HashSet<AnchoredSystemPathBuf> {
+        paths
+            .iter()
+            .map(|path| AnchoredSystemPathBuf::from_raw(path).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_outputs_are_ignored() {
+        let tmp = TempDir::new("watch").unwrap();
+        let graph = package_graph(&tmp).await;
+        let task_definitions = HashMap::from([(
+            TaskId::new("web", "build"),
+            TaskDefinition {
+                outputs: TaskOutputs {
+                    inclusions: vec!["dist/**".to_string()],
+                    exclusions: vec![],
+                },
+                ..Default::default()
+            },
+        )]);
+        let ignore_patterns = output_globs(&task_definitions, &graph);
+
+        let changes = changed_packages(
+            &graph,
+            std::iter::empty(),
+            ignore_patterns.clone(),
+            files(&["apps/web/dist/index.js"]),
+        )
+        .unwrap();
+        assert_eq!(changes, ChangedPackages::Some(HashSet::new()));
+
+        let changes = changed_packages(
+            &graph,
+            std::iter::empty(),
+            ignore_patterns,
+            files(&["apps/web/dist/index.js", "packages/ui/dist/index.js"]),
+        )
+        .unwrap();
+        assert_eq!(
+            changes,
+            ChangedPackages::Some(HashSet::from([PackageName::from("ui")]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lagged_invalidates_everything() {
+        let tmp = TempDir::new("watch").unwrap();
+        let graph = package_graph(&tmp).await;
+
+        let mut changed_files = HashSet::new();
+        add_changed_files(
+            &mut changed_files,
+            FileEvent::Changed(
+                files(&["node_modules/ui/index.js", ".turbo/turbo-build.log"])
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+        assert!(changed_files.is_empty());
+
+        add_changed_files(&mut changed_files, FileEvent::Lagged);
+        let changes =
+            changed_packages(&graph, std::iter::empty(), Vec::new(), changed_files).unwrap();
+        assert_eq!(changes, ChangedPackages::All);
+    }
+
+    struct MockRun {
+        persistent: bool,
+        packages: Option<HashSet<PackageName>>,
+        processes: ProcessManager,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockRun {
+        fn new(log: Arc<Mutex<Vec<String>>>) -> Self {
+            Self {
+                persistent: false,
+                packages: None,
+                processes: ProcessManager::new(false),
+                log,
+            }
+        }
+    }
+
+    impl WatchRun for MockRun {
+        fn with_changed_packages(mut self, packages: &HashSet<PackageName>) -> Self {
+            self.packages = Some(packages.clone());
+            self
+        }
+
+        fn without_persistent_tasks(self) -> Self {
+            self
+        }
+
+        fn create_run_for_persistent_tasks(&self) -> Self {
+            Self {
+                persistent: true,
+                packages: None,
+                processes: ProcessManager::new(false),
+                log: self.log.clone(),
+            }
+        }
+
+        fn has_tasks(&self) -> bool {
+            true
+        }
+
+        fn processes(&self) -> &ProcessManager {
+            &self.processes
+        }
+
+        fn run(self) -> impl Future<Output = Result<i32, run::Error>> + Send + 'static {
+            let entry = match (self.persistent, &self.packages) {
+                (true, _) => "persistent".to_string(),
+                (false, None) => "all".to_string(),
+                (false, Some(packages)) => {
+                    let mut packages = packages.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+                    packages.sort();
+                    packages.join(",")
+                }
+            };
+            self.log.lock().unwrap().push(entry);
+            async { Ok(0) }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_persistent_tasks_survive_partial_rerun() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let persistent_run = execute(ChangedPackages::All, MockRun::new(log.clone()), None)
+            .await
+            .unwrap()
+            .expect("persistent tasks are started");
+        let processes = persistent_run.processes.clone();
+
+        let persistent_run = execute(
+            ChangedPackages::Some(HashSet::from([PackageName::from("web")])),
+            MockRun::new(log.clone()),
+            Some(persistent_run),
+        )
+        .await
+        .unwrap()
+        .expect("persistent tasks are kept");
+        assert!(!processes.is_closing());
+
+        execute(
+            ChangedPackages::All,
+            MockRun::new(log.clone()),
+            Some(persistent_run),
+        )
+        .await
+        .unwrap();
+        assert!(processes.is_closing());
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["all", "persistent", "web", "all", "persistent"]
+        );
+    }
+}
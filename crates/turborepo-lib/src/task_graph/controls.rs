@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+};
+use tracing::debug;
+use turborepo_ui::tui::{TaskRequest, TaskRequests};
+
+/// Routes the requests the user makes in the TUI to restart or stop a task to
+/// the task they're for, and keeps track of the tasks that handle restarts
+/// once the task's first run has finished.
+pub struct TaskControls {
+    state: Mutex<ControlState>,
+}
+
+#[derive(Default)]
+struct ControlState {
+    senders: HashMap<String, UnboundedSender<TaskRequest>>,
+    restarts: Vec<JoinHandle<()>>,
+}
+
+impl TaskControls {
+    pub fn new(requests: TaskRequests) -> Arc<Self> {
+        let this = Arc::new(Self {
+            state: Mutex::default(),
+        });
+        // Requests are received until the TUI stops
+        let controls = this.clone();
+        tokio::task::spawn_blocking(move || {
+            while let Some(request) = requests.recv() {
+                controls.send(request);
+            }
+        });
+        this
+    }
+
+    /// Returns the receiver for the requests made for the given task
+    pub fn register(&self, task: String) -> UnboundedReceiver<TaskRequest> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.state
+            .lock()
+            .expect("task controls lock poisoned")
+            .senders
+            .insert(task, tx);
+        rx
+    }
+
+    /// Spawns the handling of restarts for a task whose first run has finished
+    pub fn spawn_restarts(&self, restarts: impl Future<Output = ()> + Send + 'static) {
+        let handle = tokio::spawn(restarts);
+        self.state
+            .lock()
+            .expect("task controls lock poisoned")
+            .restarts
+            .push(handle);
+    }
+
+    /// Stops accepting requests and waits for any restarted tasks to be
+    /// dropped
+    pub async fn shutdown(&self) {
+        let restarts = {
+            let mut state = self.state.lock().expect("task controls lock poisoned");
+            state.senders.clear();
+            std::mem::take(&mut state.restarts)
+        };
+        for restart in restarts {
+            restart.abort();
+            // We only care that the task has been dropped
+            restart.await.ok();
+        }
+    }
+
+    fn send(&self, request: TaskRequest) {
+        let state = self.state.lock().expect("task controls lock poisoned");
+        match state.senders.get(request.task()) {
+            Some(sender) => {
+                sender.send(request).ok();
+            }
+            None => debug!("no task to send {request:?} to"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use turborepo_ui::tui::AppSender;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_are_routed() {
+        let (_handle, receiver, requests) = AppSender::new();
+        let controls = TaskControls::new(requests);
+        let mut web = controls.register("web#dev".into());
+        let mut docs = controls.register("docs#dev".into());
+
+        receiver.request(TaskRequest::Stop {
+            task: "docs#dev".into(),
+        });
+        receiver.request(TaskRequest::Restart {
+            task: "web#dev".into(),
+        });
+        // Requests for unknown tasks are dropped
+        receiver.request(TaskRequest::Restart {
+            task: "api#dev".into(),
+        });
+
+        assert_eq!(
+            web.recv().await,
+            Some(TaskRequest::Restart {
+                task: "web#dev".into()
+            })
+        );
+        assert_eq!(
+            docs.recv().await,
+            Some(TaskRequest::Stop {
+                task: "docs#dev".into()
+            })
+        );
+
+        controls.shutdown().await;
+        assert_eq!(web.recv().await, None);
+        drop(receiver);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drops_restarts() {
+        let (_handle, _receiver, requests) = AppSender::new();
+        let controls = TaskControls::new(requests);
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        controls.spawn_restarts(async move {
+            let _tx = tx;
+            std::future::pending::<()>().await;
+        });
+        controls.shutdown().await;
+        assert!(rx.await.is_err(), "restart should be dropped on shutdown");
+    }
+}
@@ -1,3 +1,5 @@
+mod controls;
+mod sidecar;
 mod visitor;
 
 use std::str::FromStr;
@@ -78,6 +80,55 @@ pub struct TaskDefinition {
     // Tasks that take stdin input cannot be cached as their outputs may depend on the
     // input.
     pub interactive: bool,
+
+    // Retries is the number of times a failing task is run again before it is
+    // considered failed.
+    pub retries: u32,
+
+    // CacheRetried allows saving the outputs of a task that only succeeded after
+    // being retried. These aren't cached by default as the task may be flaky.
+    pub cache_retried: bool,
+
+    // Timeout is the number of seconds a task can run for before it is stopped
+    // and considered failed. 0 means the task can run indefinitely.
+    pub timeout: u64,
+
+    // Weight is the number of concurrency slots the task takes up while it runs
+    pub weight: u32,
+
+    // Pool is the name of a pool from the root turbo.json that limits how many
+    // of its tasks can run at the same time, in addition to --concurrency
+    pub pool: Option<String>,
+
+    // Sidecars are tasks that are started alongside this one. The task only
+    // starts once they are ready and they are stopped once all of the tasks
+    // that use them are finished.
+    // This field is custom-marshalled from rawTask.With
+    pub sidecars: Vec<Spanned<TaskName<'static>>>,
+
+    // Readiness determines when the task is ready when it is used as a sidecar.
+    // Without a readiness check a sidecar is ready as soon as it has started.
+    pub readiness: Option<Readiness>,
+}
+
+// The number of seconds a sidecar has to become ready before it is considered
+// failed
+pub const DEFAULT_READINESS_TIMEOUT: u64 = 60;
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Eq)]
+pub struct Readiness {
+    pub probe: ReadinessProbe,
+    pub timeout: u64,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Eq)]
+pub enum ReadinessProbe {
+    // Ready once a connection can be opened to the port on localhost
+    Tcp(u16),
+    // Ready once a GET request to the URL responds with a 200
+    Http(String),
+    // Ready once a line of the task's output matches the pattern
+    Stdout(String),
 }
 
 impl Default for TaskDefinition {
@@ -94,6 +145,13 @@ impl Default for TaskDefinition {
             persistent: Default::default(),
             dot_env: Default::default(),
             interactive: Default::default(),
+            retries: Default::default(),
+            cache_retried: Default::default(),
+            timeout: Default::default(),
+            weight: 1,
+            pool: Default::default(),
+            sidecars: Default::default(),
+            readiness: Default::default(),
         }
     }
 }
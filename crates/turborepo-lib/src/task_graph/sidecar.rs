@@ -0,0 +1,311 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    sync::Mutex,
+    time::Duration,
+};
+
+use futures::future::join_all;
+use regex::Regex;
+use tokio::{net::TcpStream, sync::oneshot};
+use tracing::debug;
+
+use super::{Readiness, ReadinessProbe};
+use crate::{engine::Engine, process::Child, run::task_id::TaskId};
+
+// How long to wait between attempts of network based readiness probes
+const PROBE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Keeps track of the sidecars in a run so that they can be stopped once all
+/// of the tasks that use them have finished.
+pub struct Sidecars {
+    // The sidecars of each task
+    sidecars: HashMap<TaskId<'static>, Vec<TaskId<'static>>>,
+    state: Mutex<SidecarState>,
+}
+
+#[derive(Default)]
+struct SidecarState {
+    // The number of tasks that use each sidecar and haven't finished yet
+    pending: HashMap<TaskId<'static>, usize>,
+    running: HashMap<TaskId<'static>, Child>,
+    torn_down: HashSet<TaskId<'static>>,
+}
+
+impl Sidecars {
+    pub fn new(engine: &Engine) -> Self {
+        let sidecars = engine
+            .task_definitions()
+            .keys()
+            .map(|task_id| (task_id.clone(), engine.sidecars(task_id)))
+            .filter(|(_, sidecars)| !sidecars.is_empty())
+            .collect::<HashMap<_, _>>();
+
+        let mut pending = HashMap::new();
+        for sidecar in sidecars.values().flatten() {
+            *pending.entry(sidecar.clone()).or_default() += 1;
+        }
+
+        Self {
+            sidecars,
+            state: Mutex::new(SidecarState {
+                pending,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn is_sidecar(&self, task_id: &TaskId) -> bool {
+        self.state
+            .lock()
+            .expect("lock poisoned")
+            .pending
+            .contains_key(task_id)
+    }
+
+    /// Registers the process of a sidecar so it can be stopped later on
+    pub fn started(&self, task_id: &TaskId<'static>, child: Child) {
+        self.state
+            .lock()
+            .expect("lock poisoned")
+            .running
+            .insert(task_id.clone(), child);
+    }
+
+    /// Returns true if the sidecar was stopped because every task that used
+    /// it has finished
+    pub fn is_torn_down(&self, task_id: &TaskId) -> bool {
+        self.state
+            .lock()
+            .expect("lock poisoned")
+            .torn_down
+            .contains(task_id)
+    }
+
+    /// Marks a task as finished, stopping any of its sidecars that are no
+    /// longer used by other tasks
+    pub async fn finished(&self, task_id: &TaskId<'static>) {
+        let Some(sidecars) = self.sidecars.get(task_id) else {
+            return;
+        };
+
+        let to_stop = {
+            let mut state = self.state.lock().expect("lock poisoned");
+            let state = &mut *state;
+            let mut to_stop = Vec::new();
+            for sidecar in sidecars {
+                let Some(pending) = state.pending.get_mut(sidecar) else {
+                    continue;
+                };
+                *pending = pending.saturating_sub(1);
+                if *pending > 0 {
+                    continue;
+                }
+                state.torn_down.insert(sidecar.clone());
+                if let Some(child) = state.running.remove(sidecar) {
+                    debug!("stopping sidecar {sidecar} as its dependents have finished");
+                    to_stop.push(child);
+                }
+            }
+            to_stop
+        };
+
+        join_all(to_stop.into_iter().map(|mut child| async move {
+            child.stop().await;
+        }))
+        .await;
+    }
+}
+
+/// Waits for a sidecar to pass its readiness probe. Returns false if it
+/// didn't become ready in time.
+///
+/// `stdout_match` is notified by a `ReadinessWriter` when the sidecar's
+/// output matches its pattern.
+pub async fn wait_until_ready(
+    readiness: Option<&Readiness>,
+    stdout_match: Option<oneshot::Receiver<()>>,
+) -> bool {
+    // Sidecars without a probe are ready as soon as they've started
+    let Some(readiness) = readiness else {
+        return true;
+    };
+
+    let probe = async {
+        match &readiness.probe {
+            ReadinessProbe::Tcp(port) => {
+                while TcpStream::connect(("localhost", *port)).await.is_err() {
+                    tokio::time::sleep(PROBE_INTERVAL).await;
+                }
+            }
+            ReadinessProbe::Http(url) => {
+                let client = reqwest::Client::new();
+                loop {
+                    let response = client.get(url).timeout(PROBE_INTERVAL * 4).send().await;
+                    if response.map_or(false, |response| {
+                        response.status() == reqwest::StatusCode::OK
+                    }) {
+                        break;
+                    }
+                    tokio::time::sleep(PROBE_INTERVAL).await;
+                }
+            }
+            ReadinessProbe::Stdout(_) => match stdout_match {
+                Some(stdout_match) => {
+                    // If the sender is dropped the output ended without a match, so the
+                    // sidecar can't become ready anymore
+                    if stdout_match.await.is_err() {
+                        std::future::pending::<()>().await;
+                    }
+                }
+                None => std::future::pending::<()>().await,
+            },
+        }
+    };
+
+    tokio::time::timeout(Duration::from_secs(readiness.timeout), probe)
+        .await
+        .is_ok()
+}
+
+/// Passes a task's output through while looking for a line that matches the
+/// pattern of a `stdout` readiness probe.
+pub struct ReadinessWriter<W> {
+    inner: W,
+    matcher: Option<(Regex, oneshot::Sender<()>)>,
+    line: Vec<u8>,
+}
+
+impl<W: Write> ReadinessWriter<W> {
+    /// Creates a writer along with the receiver that is notified once the
+    /// output matches. The receiver is only returned for `stdout` probes.
+    pub fn new(inner: W, readiness: Option<&Readiness>) -> (Self, Option<oneshot::Receiver<()>>) {
+        let pattern = readiness
+            .and_then(|readiness| match &readiness.probe {
+                ReadinessProbe::Stdout(pattern) => Some(pattern),
+                _ => None,
+            })
+            .and_then(|pattern| Regex::new(pattern).ok());
+        let (matcher, receiver) = match pattern {
+            Some(pattern) => {
+                let (sender, receiver) = oneshot::channel();
+                (Some((pattern, sender)), Some(receiver))
+            }
+            None => (None, None),
+        };
+
+        (
+            Self {
+                inner,
+                matcher,
+                line: Vec::new(),
+            },
+            receiver,
+        )
+    }
+
+    fn is_match(&mut self, output: &[u8]) -> bool {
+        let Some((pattern, _)) = &self.matcher else {
+            return false;
+        };
+        self.line.extend_from_slice(output);
+        // Check every complete line along with the last partial one as servers
+        // don't always end their ready message with a newline
+        let is_match = self
+            .line
+            .split(|byte| *byte == b'\n')
+            .any(|line| pattern.is_match(&String::from_utf8_lossy(line)));
+        if let Some(end) = self.line.iter().rposition(|byte| *byte == b'\n') {
+            self.line.drain(..=end);
+        }
+        is_match
+    }
+}
+
+impl<W: Write> Write for ReadinessWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if self.is_match(&buf[..written]) {
+            if let Some((_, sender)) = self.matcher.take() {
+                sender.send(()).ok();
+            }
+            self.line.clear();
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use test_case::test_case;
+
+    use super::*;
+
+    fn stdout_readiness(pattern: &str) -> Readiness {
+        Readiness {
+            probe: ReadinessProbe::Stdout(pattern.to_string()),
+            timeout: 1,
+        }
+    }
+
+    #[test_case(&["listening on port 3000\n"], true ; "single line")]
+    #[test_case(&["starting\nlisten", "ing on port 3000\n"], true ; "split across writes")]
+    #[test_case(&["listening on port 3000"], true ; "without newline")]
+    #[test_case(&["listening on\nport 3000\n"], false ; "split across lines")]
+    #[test_case(&["starting\n", "compiling\n"], false ; "no match")]
+    fn test_readiness_writer(writes: &[&str], expected: bool) {
+        let readiness = stdout_readiness("listening on port \\d+");
+        let mut output = Vec::new();
+        let (mut writer, receiver) = ReadinessWriter::new(&mut output, Some(&readiness));
+        let mut receiver = receiver.expect("stdout probes have a receiver");
+        for write in writes {
+            writer.write_all(write.as_bytes()).unwrap();
+        }
+        drop(writer);
+
+        assert_eq!(receiver.try_recv().is_ok(), expected);
+        assert_eq!(String::from_utf8(output).unwrap(), writes.concat());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_stdout() {
+        let readiness = stdout_readiness("ready");
+        let (mut writer, receiver) = ReadinessWriter::new(std::io::sink(), Some(&readiness));
+        writer.write_all(b"ready\n").unwrap();
+
+        assert!(wait_until_ready(Some(&readiness), receiver).await);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_times_out() {
+        let readiness = stdout_readiness("ready");
+        let (mut writer, receiver) = ReadinessWriter::new(std::io::sink(), Some(&readiness));
+        writer.write_all(b"starting\n").unwrap();
+
+        assert!(!wait_until_ready(Some(&readiness), receiver).await);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_tcp() {
+        let listener = tokio::net::TcpListener::bind(("localhost", 0))
+            .await
+            .unwrap();
+        let readiness = Readiness {
+            probe: ReadinessProbe::Tcp(listener.local_addr().unwrap().port()),
+            timeout: 1,
+        };
+
+        assert!(wait_until_ready(Some(&readiness), None).await);
+    }
+
+    #[tokio::test]
+    async fn test_no_readiness_is_ready() {
+        assert!(wait_until_ready(None, None).await);
+    }
+}
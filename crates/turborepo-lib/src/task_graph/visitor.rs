@@ -1,11 +1,13 @@
 use std::{
     borrow::Cow,
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
     io::Write,
+    path::Path,
     sync::{Arc, Mutex, OnceLock},
     time::{Duration, Instant},
 };
 
+use chrono::{DateTime, Local};
 use console::{Style, StyledObject};
 use either::Either;
 use futures::{stream::FuturesUnordered, StreamExt};
@@ -13,6 +15,7 @@ use regex::Regex;
 use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, Instrument, Span};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath};
+use turborepo_cache::{CacheHitMetadata, CacheSource};
 use turborepo_ci::{Vendor, VendorBehavior};
 use turborepo_env::{EnvironmentVariableMap, ResolvedEnvMode};
 use turborepo_repository::{
@@ -29,20 +32,27 @@ use turborepo_ui::{
 use which::which;
 
 use crate::{
-    cli::EnvMode,
+    cli::{EnvMode, OutputFormat},
     engine::{Engine, ExecutionOptions, StopExecution},
     opts::RunOpts,
     process::{ChildExit, Command, ProcessManager},
     run::{
+        env_audit::EnvAudit,
         global_hash::GlobalHashableInputs,
         summary::{
             self, GlobalHashSummary, RunTracker, SpacesTaskClient, SpacesTaskInformation,
-            TaskExecutionSummary, TaskTracker,
+            TaskAttempt, TaskExecutionSummary, TaskTracker,
         },
-        task_access::TaskAccess,
+        task_access::{TaskAccess, TASK_ACCESS_ENV_KEY},
         task_id::TaskId,
+        verify_cache::{self, CacheVerifier, Scratch},
         RunCache, TaskCache,
     },
+    task_graph::{
+        controls::TaskControls,
+        sidecar::{self, ReadinessWriter, Sidecars},
+        Readiness, DEFAULT_READINESS_TIMEOUT,
+    },
     task_hash::{self, PackageInputsHashes, TaskHashTracker, TaskHashTrackerState, TaskHasher},
 };
 
@@ -63,6 +73,9 @@ pub struct Visitor<'a> {
     task_hasher: TaskHasher<'a>,
     ui: UI,
     experimental_ui: bool,
+    pools: BTreeMap<String, u32>,
+    env_audit: Option<EnvAudit>,
+    cache_verifier: Option<CacheVerifier>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -106,6 +119,7 @@ impl<'a> Visitor<'a> {
         repo_root: &'a AbsoluteSystemPath,
         global_env: EnvironmentVariableMap,
         experimental_ui: bool,
+        pools: BTreeMap<String, u32>,
     ) -> Self {
         let task_hasher = TaskHasher::new(
             package_inputs_hashes,
@@ -133,6 +147,9 @@ impl<'a> Visitor<'a> {
             ui,
             global_env,
             experimental_ui,
+            pools,
+            env_audit: None,
+            cache_verifier: None,
         }
     }
 
@@ -145,25 +162,38 @@ impl<'a> Visitor<'a> {
         let concurrency = self.run_opts.concurrency as usize;
         let (node_sender, mut node_stream) = mpsc::channel(concurrency);
 
-        let (ui, render_thread_handle) = if self.experimental_ui {
+        let (ui, render_thread_handle, controls) = if self.experimental_ui {
             let task_names = engine.tasks_with_command(&self.package_graph);
+            let timeline = tui::Timeline::new(
+                engine.task_dependencies(),
+                self.repo_root.join_components(&[".turbo", "timeline.json"]),
+            );
 
-            let (handle, receiver) = tui::AppSender::new();
-            let app = tokio::task::spawn_blocking(move || tui::run_app(task_names, receiver));
-            (Some(handle), Some(app))
+            let (handle, receiver, requests) = tui::AppSender::new();
+            let app =
+                tokio::task::spawn_blocking(move || tui::run_app(task_names, timeline, receiver));
+            (Some(handle), Some(app), Some(TaskControls::new(requests)))
         } else {
-            (None, None)
+            (None, None, None)
         };
 
         let engine_handle = {
             let engine = engine.clone();
-            tokio::spawn(engine.execute(ExecutionOptions::new(false, concurrency), node_sender))
+            let options = ExecutionOptions::new(false, concurrency).with_pools(self.pools.clone());
+            tokio::spawn(engine.execute(options, node_sender))
         };
         let mut tasks = FuturesUnordered::new();
         let errors = Arc::new(Mutex::new(Vec::new()));
         let span = Span::current();
 
-        let factory = ExecContextFactory::new(self, errors.clone(), self.manager.clone(), &engine);
+        let sidecars = Arc::new(Sidecars::new(&engine));
+        let factory = ExecContextFactory::new(
+            self,
+            errors.clone(),
+            self.manager.clone(),
+            &engine,
+            sidecars.clone(),
+        );
 
         while let Some(message) = node_stream.recv().await {
             let span = tracing::debug_span!(parent: &span, "queue_task", task = %message.info);
@@ -263,12 +293,34 @@ impl<'a> Visitor<'a> {
                     //
                     // bail if the script doesn't exist or is empty
                     if command.map_or(true, |s| s.is_empty()) {
+                        // The task still counts as finished for any sidecars it uses
+                        let sidecars = sidecars.clone();
+                        tasks.push(tokio::spawn(async move {
+                            sidecars.finished(&info.into_owned()).await;
+                        }));
                         continue;
                     }
 
+                    let tracker = self.run_tracker.track_task(info.clone().into_owned());
+                    if let Some(events) = tracker.events() {
+                        events.scheduled(&task_hash);
+                    }
+
                     let workspace_directory = self.repo_root.resolve(workspace_info.package_path());
 
                     let takes_input = task_definition.interactive || task_definition.persistent;
+                    let retries = self.run_opts.retries.unwrap_or(task_definition.retries);
+                    // Persistent tasks are expected to run until turbo exits and sidecars
+                    // until the tasks using them are finished
+                    let timeout = match self
+                        .run_opts
+                        .task_timeout
+                        .unwrap_or(task_definition.timeout)
+                    {
+                        0 => None,
+                        _ if task_definition.persistent || sidecars.is_sidecar(&info) => None,
+                        timeout => Some(Duration::from_secs(timeout)),
+                    };
                     let mut exec_context = factory.exec_context(
                         info.clone(),
                         task_hash,
@@ -277,6 +329,9 @@ impl<'a> Visitor<'a> {
                         execution_env,
                         takes_input,
                         self.task_access.clone(),
+                        retries,
+                        task_definition.cache_retried,
+                        timeout,
                     );
 
                     let vendor_behavior =
@@ -287,7 +342,15 @@ impl<'a> Visitor<'a> {
                     } else {
                         TaskOutput::Direct(self.output_client(&info, vendor_behavior))
                     };
-                    let tracker = self.run_tracker.track_task(info.clone().into_owned());
+                    // Tasks can be restarted and stopped from the TUI, except for sidecars
+                    // which are managed by the tasks that use them
+                    let restarts = match (&controls, &output_client) {
+                        (Some(controls), TaskOutput::UI(task)) if !sidecars.is_sidecar(&info) => {
+                            exec_context.requests = Some(controls.register(info.to_string()));
+                            Some((controls.clone(), task.clone()))
+                        }
+                        _ => None,
+                    };
                     let spaces_client = self.run_tracker.spaces_task_client();
                     let parent_span = Span::current();
                     let execution_telemetry = package_task_event.child();
@@ -303,6 +366,9 @@ impl<'a> Visitor<'a> {
                                 &execution_telemetry,
                             )
                             .await;
+                        if let Some((controls, task)) = restarts {
+                            controls.spawn_restarts(exec_context.handle_requests(task));
+                        }
                     }));
                 }
             }
@@ -325,10 +391,21 @@ impl<'a> Visitor<'a> {
                 error!("error encountered rendering tui: {e}");
             }
         }
+        if let Some(controls) = controls {
+            controls.shutdown().await;
+        }
 
         // Write out the traced-config.json file if we have one
         self.task_access.save().await;
 
+        if let Some(env_audit) = &self.env_audit {
+            env_audit.print(self.ui);
+        }
+
+        if let Some(cache_verifier) = &self.cache_verifier {
+            cache_verifier.print(self.ui);
+        }
+
         let errors = Arc::into_inner(errors)
             .expect("only one strong reference to errors should remain")
             .into_inner()
@@ -387,7 +464,10 @@ impl<'a> Visitor<'a> {
     }
 
     fn sink(run_opts: &RunOpts) -> OutputSink<StdWriter> {
-        let (out, err) = if run_opts.should_redirect_stderr_to_stdout() {
+        let (out, err) = if run_opts.output_format == OutputFormat::Ndjson {
+            // stdout is reserved for run events
+            (std::io::stderr().into(), std::io::stderr().into())
+        } else if run_opts.should_redirect_stderr_to_stdout() {
             (std::io::stdout().into(), std::io::stdout().into())
         } else {
             (std::io::stdout().into(), std::io::stderr().into())
@@ -485,6 +565,19 @@ impl<'a> Visitor<'a> {
         // No need to start a TUI on dry run
         self.experimental_ui = false;
     }
+
+    /// Has tasks write a trace of the env vars they read, and reports the
+    /// ones that aren't declared in turbo.json once the run is finished
+    pub fn audit_env(&mut self, env_audit: EnvAudit) {
+        self.env_audit = Some(env_audit);
+    }
+
+    /// Re-runs tasks that hit the cache in a scratch directory, and reports
+    /// the ones that didn't reproduce their cached outputs once the run is
+    /// finished
+    pub fn verify_cache(&mut self, cache_verifier: CacheVerifier) {
+        self.cache_verifier = Some(cache_verifier);
+    }
 }
 
 // A tiny enum that allows us to use the same type for stdout and stderr without
@@ -560,6 +653,12 @@ enum TaskErrorCause {
     Spawn { msg: String },
     #[error("command {command} exited ({exit_code})")]
     Exit { command: String, exit_code: i32 },
+    #[error("command {command} timed out after {}s", timeout.as_secs())]
+    Timeout { command: String, timeout: Duration },
+    #[error("command {command} was not ready after {}s", timeout.as_secs())]
+    NotReady { command: String, timeout: Duration },
+    #[error("command {command} was stopped before it finished")]
+    Stopped { command: String },
 }
 
 impl TaskError {
@@ -597,6 +696,35 @@ impl TaskErrorCause {
     fn from_execution(command: String, exit_code: i32) -> Self {
         TaskErrorCause::Exit { command, exit_code }
     }
+
+    fn from_timeout(command: String, timeout: Duration) -> Self {
+        TaskErrorCause::Timeout { command, timeout }
+    }
+
+    fn from_not_ready(command: String, timeout: Duration) -> Self {
+        TaskErrorCause::NotReady { command, timeout }
+    }
+}
+
+// Sends the result of a task to the engine unless it has already been sent,
+// which is the case for sidecars that were reported as ready
+fn send_result(
+    callback: &mut Option<oneshot::Sender<Result<(), StopExecution>>>,
+    result: Result<(), StopExecution>,
+) {
+    if let Some(callback) = callback.take() {
+        callback.send(result).ok();
+    }
+}
+
+// The result sent to the engine for a task that was stopped from the TUI. The
+// run stops scheduling tasks as the ones that depend on it would use incomplete
+// outputs, but persistent tasks can't be depended on so the run carries on.
+fn stopped_result(persistent: bool) -> Result<(), StopExecution> {
+    match persistent {
+        true => Ok(()),
+        false => Err(StopExecution),
+    }
 }
 
 struct ExecContextFactory<'a> {
@@ -604,6 +732,7 @@ struct ExecContextFactory<'a> {
     errors: Arc<Mutex<Vec<TaskError>>>,
     manager: ProcessManager,
     engine: &'a Arc<Engine>,
+    sidecars: Arc<Sidecars>,
 }
 
 impl<'a> ExecContextFactory<'a> {
@@ -612,12 +741,14 @@ impl<'a> ExecContextFactory<'a> {
         errors: Arc<Mutex<Vec<TaskError>>>,
         manager: ProcessManager,
         engine: &'a Arc<Engine>,
+        sidecars: Arc<Sidecars>,
     ) -> Self {
         Self {
             visitor,
             errors,
             manager,
             engine,
+            sidecars,
         }
     }
 
@@ -631,9 +762,16 @@ impl<'a> ExecContextFactory<'a> {
         execution_env: EnvironmentVariableMap,
         takes_input: bool,
         task_access: TaskAccess,
+        retries: u32,
+        cache_retried: bool,
+        timeout: Option<Duration>,
     ) -> ExecContext {
         let task_id_for_display = self.visitor.display_task_id(&task_id);
         let pass_through_args = self.visitor.run_opts.args_for_task(&task_id);
+        let is_sidecar = self.sidecars.is_sidecar(&task_id);
+        let definition = self.engine.task_definition(&task_id);
+        let readiness = definition.and_then(|definition| definition.readiness.clone());
+        let persistent = definition.map_or(false, |definition| definition.persistent);
         ExecContext {
             engine: self.engine.clone(),
             ui: self.visitor.ui,
@@ -657,6 +795,20 @@ impl<'a> ExecContextFactory<'a> {
             errors: self.errors.clone(),
             takes_input,
             task_access,
+            retries,
+            cache_retried,
+            attempts: Vec::new(),
+            outputs_cached: false,
+            timeout,
+            sidecars: self.sidecars.clone(),
+            is_sidecar,
+            readiness,
+            env_audit: self.visitor.env_audit.clone(),
+            cache_verifier: self.visitor.cache_verifier.clone(),
+            persistent,
+            requests: None,
+            stopped: false,
+            restart: false,
         }
     }
 
@@ -693,6 +845,23 @@ struct ExecContext {
     errors: Arc<Mutex<Vec<TaskError>>>,
     takes_input: bool,
     task_access: TaskAccess,
+    retries: u32,
+    cache_retried: bool,
+    attempts: Vec<TaskAttempt>,
+    // Whether the outputs of the task were saved to the cache
+    outputs_cached: bool,
+    timeout: Option<Duration>,
+    sidecars: Arc<Sidecars>,
+    is_sidecar: bool,
+    readiness: Option<Readiness>,
+    env_audit: Option<EnvAudit>,
+    cache_verifier: Option<CacheVerifier>,
+    persistent: bool,
+    // Requests from the user to restart or stop the task, only set if the TUI is used
+    requests: Option<mpsc::UnboundedReceiver<tui::TaskRequest>>,
+    // If the user stopped the task, and if they asked for it to be run again
+    stopped: bool,
+    restart: bool,
 }
 
 enum ExecOutcome {
@@ -704,7 +873,10 @@ enum ExecOutcome {
     Task {
         exit_code: Option<i32>,
         message: String,
+        timed_out: bool,
     },
+    // The task was stopped from the TUI before it finished
+    Stopped,
 }
 
 enum SuccessOutcome {
@@ -730,18 +902,23 @@ impl ExecContext {
         spaces_client: Option<SpacesTaskClient>,
         telemetry: &PackageTaskEventBuilder,
     ) {
-        let tracker = tracker.start().await;
+        let mut tracker = tracker.start().await;
         let span = tracing::debug_span!("execute_task", task = %self.task_id.task());
         span.follows_from(parent_span_id);
+        // Sidecars report back to the engine as soon as they're ready
+        let mut callback = Some(callback);
         let mut result = self
-            .execute_inner(&output_client, telemetry)
+            .execute_inner(&output_client, &tracker, telemetry, &mut callback)
             .instrument(span)
             .await;
+        tracker.record_attempts(std::mem::take(&mut self.attempts));
+        self.audit_env(&result);
 
         // If the task resulted in an error, do not group in order to better highlight
         // the error.
-        let is_error = matches!(result, ExecOutcome::Task { .. });
-        let logs = match output_client.finish(is_error) {
+        let is_error = matches!(result, ExecOutcome::Task { .. } | ExecOutcome::Stopped);
+        let task_result = self.task_result(&result);
+        let logs = match output_client.finish(is_error, task_result) {
             Ok(logs) => logs,
             Err(e) => {
                 telemetry.track_error(TrackedErrors::DaemonFailedToMarkOutputsAsCached);
@@ -751,13 +928,17 @@ impl ExecContext {
             }
         };
 
+        let cache_hit = matches!(result, ExecOutcome::Success(SuccessOutcome::CacheHit));
         match result {
             ExecOutcome::Success(outcome) => {
                 let task_summary = match outcome {
                     SuccessOutcome::CacheHit => tracker.cached().await,
-                    SuccessOutcome::Run => tracker.build_succeeded(0).await,
+                    SuccessOutcome::Run => {
+                        let outputs_cached = std::mem::take(&mut self.outputs_cached);
+                        tracker.build_succeeded(0, outputs_cached).await
+                    }
                 };
-                callback.send(Ok(())).ok();
+                send_result(&mut callback, Ok(()));
                 if let Some(client) = spaces_client {
                     let logs = logs.expect("spaces enabled logs should be collected");
                     let info = self.spaces_task_info(self.task_id.clone(), task_summary, logs);
@@ -766,19 +947,40 @@ impl ExecContext {
             }
             ExecOutcome::Internal => {
                 tracker.cancel();
-                callback.send(Err(StopExecution)).ok();
+                send_result(&mut callback, Err(StopExecution));
                 self.manager.stop().await;
             }
-            ExecOutcome::Task { exit_code, message } => {
-                let task_summary = tracker.build_failed(exit_code, message).await;
-                callback
-                    .send(match self.continue_on_error {
+            ExecOutcome::Stopped => {
+                let task_summary = tracker.build_stopped().await;
+                send_result(&mut callback, stopped_result(self.persistent));
+                if let Some(client) = spaces_client {
+                    let logs = logs.unwrap_or_default();
+                    let info = self.spaces_task_info(self.task_id.clone(), task_summary, logs);
+                    client.finish_task(info).await.ok();
+                }
+            }
+            ExecOutcome::Task {
+                exit_code,
+                message,
+                timed_out,
+            } => {
+                let task_summary = match timed_out {
+                    true => tracker.build_timed_out(message).await,
+                    false => tracker.build_failed(exit_code, message).await,
+                };
+                // A persistent task that crashed can be restarted from the TUI, so we keep
+                // the rest of the run going
+                let continue_on_error =
+                    self.continue_on_error || (self.persistent && self.requests.is_some());
+                send_result(
+                    &mut callback,
+                    match continue_on_error {
                         true => Ok(()),
                         false => Err(StopExecution),
-                    })
-                    .ok();
+                    },
+                );
 
-                match (spaces_client, self.continue_on_error) {
+                match (spaces_client, continue_on_error) {
                     // Nothing to do
                     (None, true) => (),
                     // Shut down manager
@@ -801,6 +1003,101 @@ impl ExecContext {
                 }
             }
         }
+
+        self.sidecars.finished(&self.task_id).await;
+
+        // The task's result has already been reported, so the tasks that depend on it
+        // don't wait for it to be verified
+        if let Some(cache_verifier) = self.cache_verifier.as_ref().filter(|_| cache_hit) {
+            self.verify_cache(cache_verifier).await;
+        }
+    }
+
+    fn audit_env(&self, result: &ExecOutcome) {
+        let Some(env_audit) = &self.env_audit else {
+            return;
+        };
+        match result {
+            ExecOutcome::Success(SuccessOutcome::CacheHit) => {
+                env_audit.cached(&self.task_id_for_display)
+            }
+            ExecOutcome::Success(SuccessOutcome::Run)
+            | ExecOutcome::Task { .. }
+            | ExecOutcome::Stopped => {
+                if let Some(definition) = self.engine.task_definition(&self.task_id) {
+                    env_audit.record(
+                        &self.task_id_for_display,
+                        self.task_id.task(),
+                        &self.task_hash,
+                        definition,
+                        self.hash_tracker.framework(&self.task_id).as_deref(),
+                    );
+                }
+            }
+            ExecOutcome::Internal => (),
+        }
+    }
+
+    // Re-runs a task that hit the cache in a scratch copy of its package to
+    // check that it reproduces its cached outputs
+    async fn verify_cache(&self, cache_verifier: &CacheVerifier) {
+        let result = match cache_verifier.scratch(
+            &self.task_hash,
+            &self.workspace_directory,
+            self.task_cache.output_globs(),
+        ) {
+            Ok(scratch) => match self.rerun(&scratch).await {
+                Ok(()) => cache_verifier.compare(
+                    &self.task_id_for_display,
+                    self.task_id.task(),
+                    scratch,
+                    self.task_cache.output_globs(),
+                ),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            cache_verifier.unverified(&self.task_id_for_display, e);
+        }
+    }
+
+    async fn rerun(&self, scratch: &Scratch) -> Result<(), verify_cache::Error> {
+        let package_manager_binary = which(self.package_manager.command())
+            .map_err(|e| verify_cache::Error::Rerun(e.to_string()))?;
+        let mut cmd = self.command(&package_manager_binary);
+        cmd.current_dir(scratch.package_dir().to_owned());
+        // The scratch copy is inside the repository, so git would otherwise find
+        // the repository's own .git
+        cmd.env("GIT_CEILING_DIRECTORIES", scratch.root().as_str());
+        // The trace of the re-run shouldn't replace the one of the cached run
+        if self.writes_trace() {
+            cmd.env(
+                TASK_ACCESS_ENV_KEY,
+                scratch.root().join_component("trace.json").as_str(),
+            );
+        }
+
+        let mut process = match self.manager.spawn(cmd, Duration::from_millis(500)) {
+            Some(Ok(child)) => child,
+            Some(Err(e)) => return Err(verify_cache::Error::Rerun(e.to_string())),
+            None => {
+                return Err(verify_cache::Error::Rerun(
+                    "turbo is shutting down".to_string(),
+                ))
+            }
+        };
+        // The outputs of the re-run are compared, not its logs
+        match process.wait_with_piped_outputs(std::io::sink()).await {
+            Ok(Some(ChildExit::Finished(Some(0)))) => Ok(()),
+            Ok(Some(ChildExit::Finished(Some(code)))) => Err(verify_cache::Error::Rerun(format!(
+                "exited with code {code}"
+            ))),
+            Ok(_) => Err(verify_cache::Error::Rerun(
+                "the process was stopped".to_string(),
+            )),
+            Err(e) => Err(verify_cache::Error::Rerun(e.to_string())),
+        }
     }
 
     fn prefixed_ui<W: Write>(&self, stdout: W, stderr: W) -> PrefixedUI<W> {
@@ -816,7 +1113,9 @@ impl ExecContext {
     async fn execute_inner(
         &mut self,
         output_client: &TaskOutput<impl std::io::Write>,
+        tracker: &TaskTracker<DateTime<Local>>,
         telemetry: &PackageTaskEventBuilder,
+        callback: &mut Option<oneshot::Sender<Result<(), StopExecution>>>,
     ) -> ExecOutcome {
         let task_start = Instant::now();
         let mut prefixed_ui = self.prefixed_ui(output_client.stdout(), output_client.stderr());
@@ -833,15 +1132,23 @@ impl ExecContext {
             TaskOutput::UI(task) => Some(task.clone()),
             TaskOutput::Direct(_) => None,
         };
-        match self
-            .task_cache
-            .restore_outputs(
-                prefixed_ui.output_prefixed_writer(),
-                alt_log_replay_writer,
-                telemetry,
-            )
-            .await
-        {
+        // Sidecars always run as the tasks using them need them to be up
+        let restored = match self.is_sidecar {
+            true => Ok(None),
+            false => {
+                self.task_cache
+                    .restore_outputs(
+                        prefixed_ui.output_prefixed_writer(),
+                        alt_log_replay_writer,
+                        telemetry,
+                    )
+                    .await
+            }
+        };
+        if let Some(events) = tracker.events() {
+            events.cache_status(&self.task_hash, restored.as_ref().ok().copied().flatten());
+        }
+        match restored {
             Ok(Some(status)) => {
                 // we need to set expanded outputs
                 self.hash_tracker.insert_expanded_outputs(
@@ -863,114 +1170,264 @@ impl ExecContext {
             return ExecOutcome::Internal;
         };
 
-        let mut cmd = Command::new(package_manager_binary);
-        let mut args = vec!["run".to_string(), self.task_id.task().to_string()];
-        if let Some(pass_through_args) = &self.pass_through_args {
-            args.extend(
-                self.package_manager
-                    .arg_separator(pass_through_args.as_slice())
-                    .map(|s| s.to_string()),
-            );
-            args.extend(pass_through_args.iter().cloned());
-        }
-        cmd.args(args);
-        cmd.current_dir(self.workspace_directory.clone());
-
-        // We clear the env before populating it with variables we expect
-        cmd.env_clear();
-        cmd.envs(self.execution_env.iter());
-        // Always last to make sure it overwrites any user configured env var.
-        cmd.env("TURBO_HASH", &self.task_hash);
-        // enable task access tracing
-
-        // set the trace file env var - frameworks that support this can use it to
-        // write out a trace file that we will use to automatically cache the task
-        if self.task_access.is_enabled() {
-            let (task_access_trace_key, trace_file) = self.task_access.get_env_var(&self.task_hash);
-            cmd.env(task_access_trace_key, trace_file.to_string());
-        }
-
-        cmd.open_stdin();
+        let (process, mut stdout_writer, exit_status, timed_out, not_ready) = loop {
+            let attempt_start = Instant::now();
+            let cmd = self.command(&package_manager_binary);
+
+            let mut process = match self.manager.spawn(cmd, Duration::from_millis(500)) {
+                Some(Ok(child)) => child,
+                // Turbo was unable to spawn a process
+                Some(Err(e)) => {
+                    // Note: we actually failed to spawn, but this matches the Go output
+                    prefixed_ui.error(format!("command finished with error: {e}"));
+                    let error_string = e.to_string();
+                    self.errors
+                        .lock()
+                        .expect("lock poisoned")
+                        .push(TaskError::from_spawn(self.task_id_for_display.clone(), e));
+                    return ExecOutcome::Task {
+                        exit_code: None,
+                        message: error_string,
+                        timed_out: false,
+                    };
+                }
+                // Turbo is shutting down
+                None => {
+                    return ExecOutcome::Internal;
+                }
+            };
 
-        let mut process = match self.manager.spawn(cmd, Duration::from_millis(500)) {
-            Some(Ok(child)) => child,
-            // Turbo was unable to spawn a process
-            Some(Err(e)) => {
-                // Note: we actually failed to spawn, but this matches the Go output
-                prefixed_ui.error(format!("command finished with error: {e}"));
-                let error_string = e.to_string();
-                self.errors
-                    .lock()
-                    .expect("lock poisoned")
-                    .push(TaskError::from_spawn(self.task_id_for_display.clone(), e));
-                return ExecOutcome::Task {
-                    exit_code: None,
-                    message: error_string,
-                };
+            if let Some(events) = tracker.events() {
+                events.started(self.attempts.len() as u32 + 1);
             }
-            // Turbo is shutting down
-            None => {
-                return ExecOutcome::Internal;
-            }
-        };
 
-        if self.experimental_ui && self.takes_input {
-            if let TaskOutput::UI(task) = output_client {
-                if let Some(stdin) = process.stdin() {
-                    task.set_stdin(stdin);
+            if self.experimental_ui && self.takes_input {
+                if let TaskOutput::UI(task) = output_client {
+                    if let Some(stdin) = process.stdin() {
+                        task.set_stdin(stdin);
+                    }
                 }
             }
-        }
 
-        let mut stdout_writer = match self.task_cache.output_writer(if self.experimental_ui {
-            Either::Left(output_client.stdout())
-        } else {
-            Either::Right(prefixed_ui.output_prefixed_writer())
-        }) {
-            Ok(w) => w,
-            Err(e) => {
-                telemetry.track_error(TrackedErrors::FailedToCaptureOutputs);
-                error!("failed to capture outputs for \"{}\": {e}", self.task_id);
-                return ExecOutcome::Internal;
+            let mut stdout_writer = match self.task_cache.output_writer(if self.experimental_ui {
+                Either::Left(output_client.stdout())
+            } else {
+                Either::Right(prefixed_ui.output_prefixed_writer())
+            }) {
+                Ok(w) => match tracker.events() {
+                    Some(events) => Either::Left(events.output_writer(w)),
+                    None => Either::Right(w),
+                },
+                Err(e) => {
+                    telemetry.track_error(TrackedErrors::FailedToCaptureOutputs);
+                    error!("failed to capture outputs for \"{}\": {e}", self.task_id);
+                    return ExecOutcome::Internal;
+                }
+            };
+
+            let mut timed_out = false;
+            let mut not_ready = false;
+            let mut child = process.clone();
+            let readiness = self.readiness.as_ref().filter(|_| self.is_sidecar);
+            let (output_writer, stdout_match) = ReadinessWriter::new(&mut stdout_writer, readiness);
+            let wait = process.wait_with_piped_outputs(output_writer);
+            tokio::pin!(wait);
+            let result = match self.timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, &mut wait).await {
+                    Ok(result) => result,
+                    // Stopping the child lets us finish piping its outputs
+                    Err(_) => {
+                        timed_out = true;
+                        child.stop().await;
+                        wait.await
+                    }
+                },
+                None if self.is_sidecar => {
+                    self.sidecars.started(&self.task_id, child.clone());
+                    let ready = tokio::select! {
+                        // The sidecar exited before it became ready
+                        result = &mut wait => Err(result),
+                        ready = sidecar::wait_until_ready(readiness, stdout_match) => Ok(ready),
+                    };
+                    match ready {
+                        Err(result) => result,
+                        Ok(true) => {
+                            debug!("sidecar {} is ready", self.task_id);
+                            send_result(callback, Ok(()));
+                            wait.await
+                        }
+                        Ok(false) => {
+                            not_ready = true;
+                            child.stop().await;
+                            wait.await
+                        }
+                    }
+                }
+                None => match self.requests.as_mut() {
+                    Some(requests) => {
+                        let request = tokio::select! {
+                            result = &mut wait => Err(result),
+                            Some(request) = requests.recv() => Ok(request),
+                        };
+                        match request {
+                            Err(result) => result,
+                            Ok(request) => {
+                                debug!("{request:?} received for {}", self.task_id);
+                                self.stopped = true;
+                                self.restart = matches!(request, tui::TaskRequest::Restart { .. });
+                                child.stop().await;
+                                wait.await
+                            }
+                        }
+                    }
+                    None => wait.await,
+                },
+            };
+            let exit_status = match result {
+                Ok(Some(exit_status)) => exit_status,
+                Err(e) => {
+                    telemetry.track_error(TrackedErrors::FailedToPipeOutputs);
+                    error!("unable to pipe outputs from command: {e}");
+                    return ExecOutcome::Internal;
+                }
+                Ok(None) => {
+                    // TODO: how can this happen? we only update the
+                    // exit status with Some and it is only initialized with
+                    // None. Is it still running?
+                    telemetry.track_error(TrackedErrors::UnknownChildExit);
+                    error!("unable to determine why child exited");
+                    return ExecOutcome::Internal;
+                }
+            };
+
+            let should_retry = matches!(exit_status, ChildExit::Finished(Some(code)) if code != 0)
+                && !self.stopped
+                && self.attempts.len() < self.retries as usize;
+            // Attempts are only recorded once a task has been retried
+            if should_retry || !self.attempts.is_empty() {
+                let exit_code = match exit_status {
+                    ChildExit::Finished(exit_code) => exit_code,
+                    _ => None,
+                };
+                self.attempts.push(TaskAttempt {
+                    exit_code,
+                    duration: attempt_start.elapsed().as_millis() as u64,
+                });
+            }
+            if !should_retry {
+                break (process, stdout_writer, exit_status, timed_out, not_ready);
             }
-        };
 
-        let exit_status = match process.wait_with_piped_outputs(&mut stdout_writer).await {
-            Ok(Some(exit_status)) => exit_status,
-            Err(e) => {
-                telemetry.track_error(TrackedErrors::FailedToPipeOutputs);
-                error!("unable to pipe outputs from command: {e}");
-                return ExecOutcome::Internal;
+            // Show the output of the failed attempt before running the command again
+            if let Err(e) = stdout_writer.flush() {
+                error!("error flushing logs: {e}");
             }
-            Ok(None) => {
-                // TODO: how can this happen? we only update the
-                // exit status with Some and it is only initialized with
-                // None. Is it still running?
-                telemetry.track_error(TrackedErrors::UnknownChildExit);
-                error!("unable to determine why child exited");
-                return ExecOutcome::Internal;
+            if let Err(e) = self
+                .task_cache
+                .on_error(prefixed_ui.output_prefixed_writer())
+            {
+                error!("error reading logs: {e}");
             }
+            prefixed_ui.warn(format!(
+                "command finished with error, retrying ({}/{})",
+                self.attempts.len(),
+                self.retries
+            ));
         };
         let task_duration = task_start.elapsed();
 
         match exit_status {
+            // The sidecar was stopped by us once it was no longer needed. Its outputs aren't
+            // cached as it didn't finish on its own.
+            _ if self.is_sidecar && self.sidecars.is_torn_down(&self.task_id) => {
+                if let Err(e) = stdout_writer.flush() {
+                    error!("error flushing logs: {e}");
+                }
+                ExecOutcome::Success(SuccessOutcome::Run)
+            }
+            // The task was stopped from the TUI. Its outputs aren't cached as it didn't finish
+            // on its own, and tasks that depend on it don't run as its outputs may be incomplete.
+            _ if self.stopped => {
+                if let Err(e) = stdout_writer.flush() {
+                    error!("error flushing logs: {e}");
+                }
+                // Persistent tasks are stopped to be restarted or because they're no longer
+                // needed, which doesn't make the run fail
+                if !self.persistent {
+                    self.errors.lock().expect("lock poisoned").push(TaskError {
+                        task_id: self.task_id_for_display.clone(),
+                        cause: TaskErrorCause::Stopped {
+                            command: process.label().to_string(),
+                        },
+                    });
+                }
+                ExecOutcome::Stopped
+            }
+            // The child was stopped by us, so we report the timeout instead of the exit status
+            _ if timed_out || not_ready => {
+                if let Err(e) = stdout_writer.flush() {
+                    error!("error flushing logs: {e}");
+                }
+                if let Err(e) = self
+                    .task_cache
+                    .on_error(prefixed_ui.output_prefixed_writer())
+                {
+                    error!("error reading logs: {e}");
+                }
+                let error = match self.timeout {
+                    Some(timeout) if timed_out => {
+                        TaskErrorCause::from_timeout(process.label().to_string(), timeout)
+                    }
+                    _ => TaskErrorCause::from_not_ready(
+                        process.label().to_string(),
+                        Duration::from_secs(
+                            self.readiness
+                                .as_ref()
+                                .map_or(DEFAULT_READINESS_TIMEOUT, |readiness| readiness.timeout),
+                        ),
+                    ),
+                };
+                let message = error.to_string();
+                if self.continue_on_error && timed_out {
+                    prefixed_ui.warn("command timed out, but continuing...");
+                } else if self.continue_on_error {
+                    prefixed_ui.warn("command was not ready, but continuing...");
+                } else {
+                    prefixed_ui.error(format!("command finished with error: {error}"));
+                }
+                self.errors.lock().expect("lock poisoned").push(TaskError {
+                    task_id: self.task_id_for_display.clone(),
+                    cause: error,
+                });
+                ExecOutcome::Task {
+                    exit_code: None,
+                    message,
+                    timed_out,
+                }
+            }
             ChildExit::Finished(Some(0)) => {
                 // Attempt to flush stdout_writer and log any errors encountered
                 if let Err(e) = stdout_writer.flush() {
                     error!("{e}");
+                } else if !self.attempts.is_empty() && !self.cache_retried {
+                    // A task that only succeeded after being retried is likely flaky, so we
+                    // don't trust its outputs enough to cache them
+                    debug!("not caching {} as it was retried", self.task_id);
                 } else if self
                     .task_access
                     .can_cache(&self.task_hash, &self.task_id_for_display)
                     .unwrap_or(true)
                 {
-                    if let Err(e) = self.task_cache.save_outputs(task_duration, telemetry).await {
-                        error!("error caching output: {e}");
-                    } else {
-                        // If no errors, update hash tracker with expanded outputs
-                        self.hash_tracker.insert_expanded_outputs(
-                            self.task_id.clone(),
-                            self.task_cache.expanded_outputs().to_vec(),
-                        );
+                    match self.task_cache.save_outputs(task_duration, telemetry).await {
+                        Err(e) => error!("error caching output: {e}"),
+                        Ok(saved) => {
+                            self.outputs_cached = saved;
+                            // If no errors, update hash tracker with expanded outputs
+                            self.hash_tracker.insert_expanded_outputs(
+                                self.task_id.clone(),
+                                self.task_cache.expanded_outputs().to_vec(),
+                            );
+                        }
                     }
                 }
 
@@ -1002,6 +1459,7 @@ impl ExecContext {
                 ExecOutcome::Task {
                     exit_code: Some(code),
                     message,
+                    timed_out: false,
                 }
             }
             // All of these indicate a failure where we don't know how to recover
@@ -1012,6 +1470,139 @@ impl ExecContext {
         }
     }
 
+    fn command(&self, package_manager_binary: &Path) -> Command {
+        let mut cmd = Command::new(package_manager_binary);
+        let mut args = vec!["run".to_string(), self.task_id.task().to_string()];
+        if let Some(pass_through_args) = &self.pass_through_args {
+            args.extend(
+                self.package_manager
+                    .arg_separator(pass_through_args.as_slice())
+                    .map(|s| s.to_string()),
+            );
+            args.extend(pass_through_args.iter().cloned());
+        }
+        cmd.args(args);
+        cmd.current_dir(self.workspace_directory.clone());
+
+        // We clear the env before populating it with variables we expect
+        cmd.env_clear();
+        cmd.envs(self.execution_env.iter());
+        // Always last to make sure it overwrites any user configured env var.
+        cmd.env("TURBO_HASH", &self.task_hash);
+        // enable task access tracing
+
+        // set the trace file env var - frameworks that support this can use it to
+        // write out a trace file that we will use to automatically cache the task
+        if self.writes_trace() {
+            let (task_access_trace_key, trace_file) = self.task_access.get_env_var(&self.task_hash);
+            cmd.env(task_access_trace_key, trace_file.to_string());
+        }
+
+        cmd.open_stdin();
+
+        cmd
+    }
+
+    fn writes_trace(&self) -> bool {
+        self.task_access.is_enabled() || self.env_audit.is_some()
+    }
+
+    // Runs the task again each time the user restarts it from the TUI, until the
+    // TUI stops
+    async fn handle_requests(mut self, task: TuiTask) {
+        let mut restart = self.restart;
+        while restart || self.restart_requested().await {
+            restart = self.run_again(&task).await;
+        }
+    }
+
+    // Waits for the user to restart the task, stop requests are ignored as the
+    // task isn't running
+    async fn restart_requested(&mut self) -> bool {
+        let Some(requests) = self.requests.as_mut() else {
+            return false;
+        };
+        while let Some(request) = requests.recv().await {
+            if let tui::TaskRequest::Restart { .. } = request {
+                return true;
+            }
+        }
+        false
+    }
+
+    // Runs the task with the same command and environment as its first run.
+    // Returns if the user restarted the task while it was running.
+    async fn run_again(&mut self, task: &TuiTask) -> bool {
+        let Ok(package_manager_binary) = which(self.package_manager.command()) else {
+            return false;
+        };
+        task.restart();
+        let cmd = self.command(&package_manager_binary);
+        let mut process = match self.manager.spawn(cmd, Duration::from_millis(500)) {
+            Some(Ok(child)) => child,
+            Some(Err(e)) => {
+                let mut output = task.clone();
+                writeln!(output, "unable to restart {}: {e}\r", self.task_id).ok();
+                task.finish(tui::TaskResult::Failure);
+                return false;
+            }
+            // Turbo is shutting down
+            None => return false,
+        };
+        if self.takes_input {
+            if let Some(stdin) = process.stdin() {
+                task.set_stdin(stdin);
+            }
+        }
+
+        let mut child = process.clone();
+        let wait = process.wait_with_piped_outputs(task.clone());
+        tokio::pin!(wait);
+        let request = match self.requests.as_mut() {
+            Some(requests) => tokio::select! {
+                result = &mut wait => Err(result),
+                Some(request) = requests.recv() => Ok(request),
+            },
+            None => Err((&mut wait).await),
+        };
+        let (result, restart) = match request {
+            Ok(request) => {
+                child.stop().await;
+                wait.await.ok();
+                (
+                    tui::TaskResult::Skipped,
+                    matches!(request, tui::TaskRequest::Restart { .. }),
+                )
+            }
+            Err(Ok(Some(ChildExit::Finished(Some(0))))) => (tui::TaskResult::Success, false),
+            Err(_) => (tui::TaskResult::Failure, false),
+        };
+        task.finish(result);
+        restart
+    }
+
+    // How the task is shown once it's finished in the TUI
+    fn task_result(&self, outcome: &ExecOutcome) -> tui::TaskResult {
+        if self.stopped {
+            return tui::TaskResult::Skipped;
+        }
+        match outcome {
+            ExecOutcome::Success(SuccessOutcome::CacheHit) => {
+                let source = match self.hash_tracker.cache_status(&self.task_id) {
+                    Some(CacheHitMetadata {
+                        source: CacheSource::Remote,
+                        ..
+                    }) => tui::CacheSource::Remote,
+                    _ => tui::CacheSource::Local,
+                };
+                tui::TaskResult::CacheHit(source)
+            }
+            ExecOutcome::Success(SuccessOutcome::Run) => tui::TaskResult::Success,
+            ExecOutcome::Task { .. } => tui::TaskResult::Failure,
+            ExecOutcome::Internal | ExecOutcome::Stopped => tui::TaskResult::Skipped,
+        }
+    }
+
     fn spaces_task_info(
         &self,
         task_id: TaskId<'static>,
@@ -1051,10 +1642,14 @@ impl DryRunExecContext {
 }
 
 impl<W: Write> TaskOutput<W> {
-    pub fn finish(self, use_error: bool) -> std::io::Result<Option<Vec<u8>>> {
+    pub fn finish(
+        self,
+        use_error: bool,
+        result: tui::TaskResult,
+    ) -> std::io::Result<Option<Vec<u8>>> {
         match self {
             TaskOutput::Direct(client) => client.finish(use_error),
-            TaskOutput::UI(client) => Ok(Some(client.finish())),
+            TaskOutput::UI(client) => Ok(Some(client.finish(result))),
         }
     }
 
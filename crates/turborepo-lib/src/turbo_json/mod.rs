@@ -6,6 +6,7 @@ use std::{
 
 use camino::Utf8Path;
 use miette::{NamedSource, SourceSpan};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use struct_iterable::Iterable;
 use tracing::debug;
@@ -17,10 +18,13 @@ use crate::{
     cli::OutputLogsMode,
     config::{ConfigurationOptions, Error, InvalidEnvPrefixError},
     run::{
+        summary::TaskSummaryTaskDefinition,
         task_access::{TaskAccessTraceFile, TASK_ACCESS_CONFIG_PATH},
         task_id::{TaskId, TaskName},
     },
-    task_graph::{TaskDefinition, TaskOutputs},
+    task_graph::{
+        Readiness, ReadinessProbe, TaskDefinition, TaskOutputs, DEFAULT_READINESS_TIMEOUT,
+    },
     unescape::UnescapedString,
 };
 
@@ -48,12 +52,23 @@ pub struct SpacesJson {
 pub struct TurboJson {
     text: Option<Arc<str>>,
     path: Option<Arc<str>>,
-    pub(crate) extends: Spanned<Vec<String>>,
+    pub(crate) extends: Spanned<Vec<Spanned<String>>>,
     pub(crate) global_deps: Vec<String>,
     pub(crate) global_dot_env: Option<Vec<RelativeUnixPathBuf>>,
     pub(crate) global_env: Vec<String>,
     pub(crate) global_pass_through_env: Option<Vec<String>>,
     pub(crate) pipeline: Pipeline,
+    pub(crate) pools: BTreeMap<String, u32>,
+    pub(crate) tags: Vec<Spanned<String>>,
+    pub(crate) boundaries: BTreeMap<String, Spanned<BoundaryRule>>,
+}
+
+// Which packages the packages matched by a rule in "boundaries" may depend
+// on. Entries are either a tag prefixed with "tag:" or a package name glob.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BoundaryRule {
+    pub allow: Option<Vec<Spanned<String>>>,
+    pub deny: Vec<Spanned<String>>,
 }
 
 // Iterable is required to enumerate allowed keys
@@ -76,6 +91,22 @@ pub(crate) struct RawRemoteCacheOptions {
     timeout: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    local_cache_max_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    local_cache_max_age: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backend: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
 }
 
 impl From<&RawRemoteCacheOptions> for ConfigurationOptions {
@@ -89,6 +120,14 @@ impl From<&RawRemoteCacheOptions> for ConfigurationOptions {
             preflight: remote_cache_opts.preflight,
             timeout: remote_cache_opts.timeout,
             enabled: remote_cache_opts.enabled,
+            local_cache_max_size: remote_cache_opts.local_cache_max_size.clone(),
+            local_cache_max_age: remote_cache_opts.local_cache_max_age.clone(),
+            backend: remote_cache_opts.backend.clone(),
+            endpoint: remote_cache_opts.endpoint.clone(),
+            bucket: remote_cache_opts.bucket.clone(),
+            region: remote_cache_opts.region.clone(),
+            prefix: remote_cache_opts.prefix.clone(),
+            path: remote_cache_opts.path.clone(),
             ..Self::default()
         }
     }
@@ -110,7 +149,7 @@ pub struct RawTurboJson {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub experimental_spaces: Option<SpacesJson>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    extends: Option<Spanned<Vec<UnescapedString>>>,
+    extends: Option<Spanned<Vec<Spanned<UnescapedString>>>>,
     // Global root filesystem dependencies
     #[serde(skip_serializing_if = "Option::is_none")]
     global_dependencies: Option<Vec<Spanned<UnescapedString>>>,
@@ -130,6 +169,16 @@ pub struct RawTurboJson {
     pub(crate) remote_cache: Option<RawRemoteCacheOptions>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "experimentalUI")]
     pub experimental_ui: Option<bool>,
+    // Named pools that limit how many of the tasks assigned to them run at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pools: Option<BTreeMap<String, Spanned<u32>>>,
+    // Tags of the package, used to select packages in "boundaries"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<Spanned<UnescapedString>>>,
+    // Rules for which packages may depend on each other, keyed by the
+    // packages they apply to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    boundaries: Option<BTreeMap<String, Spanned<RawBoundaryRule>>>,
 }
 
 #[derive(Serialize, Default, Debug, PartialEq, Clone)]
@@ -183,6 +232,56 @@ pub struct RawTaskDefinition {
     output_mode: Option<Spanned<OutputLogsMode>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     interactive: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retries: Option<Spanned<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_retried: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<Spanned<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight: Option<Spanned<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool: Option<Spanned<UnescapedString>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    with: Option<Spanned<Vec<Spanned<UnescapedString>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    readiness: Option<Spanned<RawReadiness>>,
+}
+
+// How to tell that a sidecar is ready. Exactly one probe needs to be set.
+#[derive(Serialize, Default, Debug, PartialEq, Clone, Iterable)]
+pub struct RawReadiness {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tcp: Option<Spanned<u16>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    http: Option<Spanned<UnescapedString>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stdout: Option<Spanned<UnescapedString>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<Spanned<u64>>,
+}
+
+#[derive(Serialize, Default, Debug, PartialEq, Clone, Iterable)]
+pub struct RawBoundaryRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allow: Option<Vec<Spanned<UnescapedString>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deny: Option<Vec<Spanned<UnescapedString>>>,
+}
+
+impl From<RawBoundaryRule> for BoundaryRule {
+    fn from(raw: RawBoundaryRule) -> Self {
+        let entries = |entries: Vec<Spanned<UnescapedString>>| {
+            entries
+                .into_iter()
+                .map(|entry| entry.map(String::from))
+                .collect::<Vec<_>>()
+        };
+        BoundaryRule {
+            allow: raw.allow.map(entries),
+            deny: raw.deny.map(entries).unwrap_or_default(),
+        }
+    }
 }
 
 macro_rules! set_field {
@@ -196,6 +295,16 @@ macro_rules! set_field {
 impl RawTaskDefinition {
     // merge accepts a RawTaskDefinition and
     // merges it into RawTaskDefinition.
+    // Removes the deprecated `$` entries from dependsOn, as converting them
+    // prints a warning. Only used where there's nowhere to print it to.
+    pub(crate) fn remove_env_dependencies(&mut self) {
+        if let Some(depends_on) = &mut self.depends_on {
+            depends_on
+                .value
+                .retain(|dependency| !dependency.starts_with(ENV_PIPELINE_DELIMITER));
+        }
+    }
+
     pub fn merge(&mut self, other: RawTaskDefinition) {
         set_field!(self, other, outputs);
 
@@ -213,6 +322,13 @@ impl RawTaskDefinition {
         set_field!(self, other, pass_through_env);
         set_field!(self, other, dot_env);
         set_field!(self, other, interactive);
+        set_field!(self, other, retries);
+        set_field!(self, other, cache_retried);
+        set_field!(self, other, timeout);
+        set_field!(self, other, weight);
+        set_field!(self, other, pool);
+        set_field!(self, other, with);
+        set_field!(self, other, readiness);
     }
 }
 
@@ -310,6 +426,22 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
         task_dependencies.sort_by(|a, b| a.value.cmp(&b.value));
         topological_dependencies.sort_by(|a, b| a.value.cmp(&b.value));
 
+        let mut sidecars: Vec<Spanned<TaskName>> = Vec::new();
+        if let Some(with) = raw_task.with {
+            for sidecar in with.into_inner() {
+                if sidecar.starts_with(TOPOLOGICAL_PIPELINE_DELIMITER) {
+                    let (span, text) = sidecar.span_and_text("turbo.json");
+                    return Err(Error::TopologicalSidecar { span, text });
+                }
+                let (sidecar, span) = sidecar.split();
+                let sidecar: String = sidecar.into();
+                sidecars.push(span.to(sidecar.into()));
+            }
+        }
+        sidecars.sort_by(|a, b| a.value.cmp(&b.value));
+
+        let readiness = raw_task.readiness.map(Readiness::try_from).transpose()?;
+
         let env = raw_task
             .env
             .map(|env| -> Result<Vec<String>, Error> {
@@ -378,11 +510,66 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
             output_mode: *raw_task.output_mode.unwrap_or_default(),
             persistent: *raw_task.persistent.unwrap_or_default(),
             interactive,
+            retries: *raw_task.retries.unwrap_or_default(),
+            cache_retried: *raw_task.cache_retried.unwrap_or_default(),
+            timeout: *raw_task.timeout.unwrap_or_default(),
+            weight: raw_task.weight.map_or(1, |weight| *weight),
+            pool: raw_task.pool.map(|pool| pool.into_inner().into()),
+            sidecars,
+            readiness,
+        })
+    }
+}
+
+impl TryFrom<Spanned<RawReadiness>> for Readiness {
+    type Error = Error;
+
+    fn try_from(raw_readiness: Spanned<RawReadiness>) -> Result<Self, Error> {
+        let (span, text) = raw_readiness.span_and_text("turbo.json");
+        let RawReadiness {
+            tcp,
+            http,
+            stdout,
+            timeout,
+        } = raw_readiness.into_inner();
+
+        let probe = match (tcp, http, stdout) {
+            (Some(port), None, None) => ReadinessProbe::Tcp(port.into_inner()),
+            (None, Some(url), None) => ReadinessProbe::Http(url.into_inner().into()),
+            (None, None, Some(pattern)) => {
+                if let Err(e) = Regex::new(&pattern) {
+                    let (span, text) = pattern.span_and_text("turbo.json");
+                    return Err(Error::InvalidReadinessPattern {
+                        reason: e.to_string(),
+                        span,
+                        text,
+                    });
+                }
+                ReadinessProbe::Stdout(pattern.into_inner().into())
+            }
+            _ => return Err(Error::InvalidReadiness { span, text }),
+        };
+
+        Ok(Readiness {
+            probe,
+            timeout: timeout.map_or(DEFAULT_READINESS_TIMEOUT, |timeout| *timeout),
         })
     }
 }
 
 impl RawTurboJson {
+    // Removes the deprecated `$` entries from globalDependencies and the
+    // dependsOn of each task, as converting them prints a warning
+    pub(crate) fn remove_env_dependencies(&mut self) {
+        if let Some(global_dependencies) = &mut self.global_dependencies {
+            global_dependencies
+                .retain(|dependency| !dependency.starts_with(ENV_PIPELINE_DELIMITER));
+        }
+        for definition in self.pipeline.iter_mut().flat_map(|p| p.values_mut()) {
+            definition.value.remove_env_dependencies();
+        }
+    }
+
     pub(crate) fn read(
         repo_root: &AbsoluteSystemPath,
         path: &AnchoredSystemPath,
@@ -522,11 +709,35 @@ impl TryFrom<RawTurboJson> for TurboJson {
                 })
                 .transpose()?,
             pipeline: raw_turbo.pipeline.unwrap_or_default(),
+            pools: raw_turbo
+                .pools
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(pool, size)| {
+                    if *size == 0 {
+                        let (span, text) = size.span_and_text("turbo.json");
+                        return Err(Error::InvalidPoolSize { pool, span, text });
+                    }
+                    Ok((pool, size.into_inner()))
+                })
+                .collect::<Result<_, _>>()?,
+            tags: raw_turbo
+                .tags
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tag| tag.map(String::from))
+                .collect(),
+            boundaries: raw_turbo
+                .boundaries
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(selector, rule)| (selector, rule.map(BoundaryRule::from)))
+                .collect(),
             // copy these over, we don't need any changes here.
             extends: raw_turbo
                 .extends
                 .unwrap_or_default()
-                .map(|s| s.into_iter().map(|s| s.into()).collect()),
+                .map(|s| s.into_iter().map(|s| s.map(String::from)).collect()),
             // Spaces and Remote Cache config is handled through layered config
         })
     }
@@ -679,33 +890,70 @@ pub fn validate_no_package_task_syntax(turbo_json: &TurboJson) -> Vec<Error> {
 }
 
 pub fn validate_extends(turbo_json: &TurboJson) -> Vec<Error> {
-    match turbo_json.extends.first() {
-        Some(package_name) if package_name != ROOT_PKG_NAME || turbo_json.extends.len() > 1 => {
-            let (span, text) = turbo_json.extends.span_and_text("turbo.json");
-            vec![Error::ExtendFromNonRoot { span, text }]
+    if !turbo_json.extends.is_empty() {
+        // Whether the extended packages exist is checked when the engine
+        // resolves the chain of extended turbo.json files
+        return vec![];
+    }
+
+    let path = turbo_json
+        .path
+        .as_ref()
+        .map_or("turbo.json", |p| p.as_ref());
+
+    let (span, text) = match turbo_json.text {
+        Some(ref text) => {
+            let len = text.len();
+            let span: SourceSpan = (0, len - 1).into();
+            (Some(span), text.to_string())
         }
-        None => {
-            let path = turbo_json
-                .path
-                .as_ref()
-                .map_or("turbo.json", |p| p.as_ref());
+        None => (None, String::new()),
+    };
 
-            let (span, text) = match turbo_json.text {
-                Some(ref text) => {
-                    let len = text.len();
-                    let span: SourceSpan = (0, len - 1).into();
-                    (Some(span), text.to_string())
-                }
-                None => (None, String::new()),
-            };
+    vec![Error::NoExtends {
+        span,
+        text: NamedSource::new(path, text),
+    }]
+}
 
-            vec![Error::NoExtends {
-                span,
-                text: NamedSource::new(path, text),
-            }]
+/// Resolves the definition of a task from the turbo.json files it's configured
+/// in, ordered from the root turbo.json to the package's own, the same way
+/// `turbo run` merges them. The definition is returned as it's shown in the run
+/// summary, or `None` if none of the files define the task.
+///
+/// Deprecated `$` entries in `dependsOn` are left out, as converting them
+/// prints a warning.
+pub fn resolve_task_definition(
+    turbo_jsons: &[(&AnchoredSystemPath, &str)],
+    task: &str,
+) -> Result<Option<serde_json::Value>, Error> {
+    let task_name = TaskName::from(task.to_string());
+    let package_task_name = task_name.clone().into_non_workspace_task();
+    let mut definition: Option<RawTaskDefinition> = None;
+    for (path, text) in turbo_jsons {
+        let pipeline = RawTurboJson::parse(text, path)?
+            .pipeline
+            .unwrap_or_default();
+        let raw = task_name
+            .task_id()
+            .and_then(|task_id| pipeline.get(&task_id.as_task_name()))
+            .or_else(|| pipeline.get(&package_task_name));
+        if let Some(raw) = raw {
+            match &mut definition {
+                Some(definition) => definition.merge(raw.value.clone()),
+                None => definition = Some(raw.value.clone()),
+            }
         }
-        _ => vec![],
     }
+
+    let Some(mut definition) = definition else {
+        return Ok(None);
+    };
+    definition.remove_env_dependencies();
+    let definition = TaskSummaryTaskDefinition::from(TaskDefinition::try_from(definition)?);
+    Ok(Some(
+        serde_json::to_value(definition).expect("task definition is always serializable"),
+    ))
 }
 
 fn gather_env_vars(
@@ -740,7 +988,7 @@ fn gather_env_vars(
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use std::{collections::BTreeMap, fs};
 
     use anyhow::Result;
     use biome_deserialize::json::deserialize_from_json_str;
@@ -752,12 +1000,17 @@ mod tests {
     use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, RelativeUnixPathBuf};
     use turborepo_repository::package_json::PackageJson;
 
-    use super::{Pipeline, RawTurboJson, Spanned};
+    use super::{
+        parser::{task_definition_keys, turbo_json_keys},
+        resolve_task_definition, Pipeline, RawTurboJson, Spanned,
+    };
     use crate::{
         cli::OutputLogsMode,
         run::task_id::TaskName,
-        task_graph::{TaskDefinition, TaskOutputs},
-        turbo_json::{RawTaskDefinition, TurboJson},
+        task_graph::{
+            Readiness, ReadinessProbe, TaskDefinition, TaskOutputs, DEFAULT_READINESS_TIMEOUT,
+        },
+        turbo_json::{RawReadiness, RawTaskDefinition, TurboJson},
         unescape::UnescapedString,
     };
 
@@ -943,6 +1196,13 @@ mod tests {
             output_mode: Some(Spanned::new(OutputLogsMode::Full).with_range(286..292)),
             persistent: Some(Spanned::new(true).with_range(318..322)),
             interactive: Some(Spanned::new(true).with_range(349..353)),
+            retries: None,
+            cache_retried: None,
+            timeout: None,
+            weight: None,
+            pool: None,
+            with: None,
+            readiness: None,
         },
         TaskDefinition {
           dot_env: Some(vec![RelativeUnixPathBuf::new("package/a/.env").unwrap()]),
@@ -959,6 +1219,13 @@ mod tests {
           topological_dependencies: vec![],
           persistent: true,
           interactive: true,
+          retries: 0,
+          cache_retried: false,
+          timeout: 0,
+          weight: 1,
+          pool: None,
+          sidecars: Vec::new(),
+          readiness: None,
         }
       ; "full"
     )]
@@ -985,6 +1252,13 @@ mod tests {
             output_mode: Some(Spanned::new(OutputLogsMode::Full).with_range(325..331)),
             persistent: Some(Spanned::new(true).with_range(361..365)),
             interactive: None,
+            retries: None,
+            cache_retried: None,
+            timeout: None,
+            weight: None,
+            pool: None,
+            with: None,
+            readiness: None,
         },
         TaskDefinition {
             dot_env: Some(vec![RelativeUnixPathBuf::new("package\\a\\.env").unwrap()]),
@@ -1001,9 +1275,95 @@ mod tests {
             topological_dependencies: vec![],
             persistent: true,
             interactive: false,
+            retries: 0,
+            cache_retried: false,
+            timeout: 0,
+            weight: 1,
+            pool: None,
+            sidecars: Vec::new(),
+            readiness: None,
         }
       ; "full (windows)"
     )]
+    #[test_case(
+        r#"{ "retries": 2, "cacheRetried": true }"#,
+        RawTaskDefinition {
+            retries: Some(Spanned::new(2).with_range(13..14)),
+            cache_retried: Some(Spanned::new(true).with_range(32..36)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            retries: 2,
+            cache_retried: true,
+            ..Default::default()
+        }
+      ; "retries"
+    )]
+    #[test_case(
+        r#"{ "timeout": 600 }"#,
+        RawTaskDefinition {
+            timeout: Some(Spanned::new(600).with_range(13..16)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            timeout: 600,
+            ..Default::default()
+        }
+      ; "timeout"
+    )]
+    #[test_case(
+        r#"{ "weight": 2, "pool": "e2e" }"#,
+        RawTaskDefinition {
+            weight: Some(Spanned::new(2).with_range(12..13)),
+            pool: Some(Spanned::new(UnescapedString::from("e2e")).with_range(23..28)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            weight: 2,
+            pool: Some("e2e".to_string()),
+            ..Default::default()
+        }
+      ; "weight and pool"
+    )]
+    #[test_case(
+        r#"{ "with": ["api#serve"], "readiness": { "tcp": 3000 } }"#,
+        RawTaskDefinition {
+            with: Some(Spanned::new(vec![Spanned::<UnescapedString>::new("api#serve".into()).with_range(11..22)]).with_range(10..23)),
+            readiness: Some(Spanned::new(RawReadiness {
+                tcp: Some(Spanned::new(3000).with_range(47..51)),
+                ..RawReadiness::default()
+            }).with_range(38..53)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            sidecars: vec![Spanned::<TaskName<'_>>::new("api#serve".into()).with_range(11..22)],
+            readiness: Some(Readiness {
+                probe: ReadinessProbe::Tcp(3000),
+                timeout: DEFAULT_READINESS_TIMEOUT,
+            }),
+            ..Default::default()
+        }
+      ; "sidecars"
+    )]
+    #[test_case(
+        r#"{ "readiness": { "stdout": "listening on", "timeout": 10 } }"#,
+        RawTaskDefinition {
+            readiness: Some(Spanned::new(RawReadiness {
+                stdout: Some(Spanned::new(UnescapedString::from("listening on")).with_range(27..41)),
+                timeout: Some(Spanned::new(10).with_range(54..56)),
+                ..RawReadiness::default()
+            }).with_range(15..58)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            readiness: Some(Readiness {
+                probe: ReadinessProbe::Stdout("listening on".to_string()),
+                timeout: 10,
+            }),
+            ..Default::default()
+        }
+      ; "stdout readiness"
+    )]
     fn test_deserialize_task_definition(
         task_definition_content: &str,
         expected_raw_task_definition: RawTaskDefinition,
@@ -1023,6 +1383,42 @@ mod tests {
         Ok(())
     }
 
+    #[test_case(
+        r#"{ "with": ["^serve"] }"#,
+        "\"with\" cannot contain topological dependencies"
+      ; "topological sidecar"
+    )]
+    #[test_case(
+        r#"{ "readiness": {} }"#,
+        "\"readiness\" must set exactly one of \"tcp\", \"http\" or \"stdout\""
+      ; "missing probe"
+    )]
+    #[test_case(
+        r#"{ "readiness": { "tcp": 3000, "http": "http://localhost:3000" } }"#,
+        "\"readiness\" must set exactly one of \"tcp\", \"http\" or \"stdout\""
+      ; "multiple probes"
+    )]
+    #[test_case(
+        r#"{ "readiness": { "stdout": "ready (" } }"#,
+        "Invalid \"stdout\" readiness pattern: regex parse error"
+      ; "invalid pattern"
+    )]
+    fn test_invalid_sidecar_definition(task_definition_content: &str, expected: &str) {
+        let raw_task_definition: RawTaskDefinition = deserialize_from_json_str(
+            task_definition_content,
+            JsonParserOptions::default().with_allow_comments(),
+        )
+        .into_deserialized()
+        .unwrap();
+
+        let error = TaskDefinition::try_from(raw_task_definition).unwrap_err();
+        // Only the start is checked as regex errors span several lines
+        assert!(
+            error.to_string().starts_with(expected),
+            "unexpected error: {error}"
+        );
+    }
+
     #[test_case("[]", TaskOutputs::default() ; "empty")]
     #[test_case(r#"["target/**"]"#, TaskOutputs { inclusions: vec!["target/**".to_string()], exclusions: vec![] })]
     #[test_case(
@@ -1124,4 +1520,110 @@ mod tests {
         let json = RawTurboJson::parse(json, AnchoredSystemPath::new("").unwrap()).unwrap();
         assert_eq!(json.experimental_ui, expected);
     }
+
+    #[test_case(r#"{ "pools": { "e2e": 2, "browser": 1 } }"#, Some(&[("browser", 1), ("e2e", 2)][..]) ; "pools")]
+    #[test_case(r#"{}"#, Some(&[][..]) ; "missing")]
+    #[test_case(r#"{ "pools": { "e2e": 0 } }"#, None ; "empty pool")]
+    fn test_pools(json: &str, expected: Option<&[(&str, u32)]>) {
+        let raw = RawTurboJson::parse(json, AnchoredSystemPath::new("").unwrap()).unwrap();
+        let actual = TurboJson::try_from(raw)
+            .ok()
+            .map(|turbo_json| turbo_json.pools);
+        let expected = expected.map(|pools| {
+            pools
+                .iter()
+                .map(|(pool, size)| (pool.to_string(), *size))
+                .collect::<BTreeMap<_, _>>()
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let json = r#"{
+            "tags": ["app", "internal"],
+            "boundaries": {
+                "tag:app": { "allow": ["tag:ui", "@acme/*"], "deny": ["tag:internal"] },
+                "legacy-*": { "deny": ["*"] }
+            }
+        }"#;
+        let raw = RawTurboJson::parse(json, AnchoredSystemPath::new("").unwrap()).unwrap();
+        let turbo_json = TurboJson::try_from(raw).unwrap();
+
+        let values = |entries: &[Spanned<String>]| {
+            entries
+                .iter()
+                .map(|entry| entry.as_inner().clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(&turbo_json.tags), vec!["app", "internal"]);
+        assert_eq!(
+            turbo_json.boundaries.keys().collect::<Vec<_>>(),
+            vec!["legacy-*", "tag:app"]
+        );
+        let app = turbo_json.boundaries["tag:app"].as_inner();
+        assert_eq!(
+            app.allow.as_deref().map(values),
+            Some(vec!["tag:ui".to_string(), "@acme/*".to_string()])
+        );
+        assert_eq!(values(&app.deny), vec!["tag:internal"]);
+        let legacy = turbo_json.boundaries["legacy-*"].as_inner();
+        assert_eq!(legacy.allow, None);
+        assert_eq!(values(&legacy.deny), vec!["*"]);
+    }
+
+    #[test]
+    fn test_boundaries_unknown_key() {
+        let json = r#"{ "boundaries": { "tag:app": { "forbid": ["tag:internal"] } } }"#;
+        assert!(RawTurboJson::parse(json, AnchoredSystemPath::new("").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_resolve_task_definition() {
+        let root = r#"{
+            "pipeline": {
+                "build": { "outputs": ["dist/**"], "dependsOn": ["^build", "$FOO"] },
+                "web#build": { "inputs": ["src/**"] }
+            }
+        }"#;
+        let package = r#"{ "extends": ["//"], "pipeline": { "build": { "cache": false } } }"#;
+        let root_path = AnchoredSystemPath::new("turbo.json").unwrap();
+        let package_path = AnchoredSystemPath::new("apps/web/turbo.json").unwrap();
+        let turbo_jsons = [(root_path, root), (package_path, package)];
+
+        let web = resolve_task_definition(&turbo_jsons, "web#build")
+            .unwrap()
+            .unwrap();
+        assert_eq!(web["outputs"], json!(["dist/**"]));
+        assert_eq!(web["inputs"], json!(["src/**"]));
+        assert_eq!(web["dependsOn"], json!(["^build"]));
+        assert_eq!(web["cache"], json!(false));
+
+        let docs = resolve_task_definition(&turbo_jsons[..1], "docs#build")
+            .unwrap()
+            .unwrap();
+        assert_eq!(docs["inputs"], json!([]));
+        assert_eq!(docs["cache"], json!(true));
+
+        assert_eq!(resolve_task_definition(&turbo_jsons, "lint").unwrap(), None);
+    }
+
+    #[test]
+    fn test_keys() {
+        let keys = turbo_json_keys();
+        for key in [
+            "$schema",
+            "pipeline",
+            "globalPassThroughEnv",
+            "experimentalUI",
+        ] {
+            assert!(keys.iter().any(|k| k == key), "missing {key}");
+        }
+        assert!(!keys.iter().any(|k| k == "text" || k == "path"));
+
+        let keys = task_definition_keys();
+        for key in ["dependsOn", "passThroughEnv", "outputMode", "cacheRetried"] {
+            assert!(keys.iter().any(|k| k == key), "missing {key}");
+        }
+    }
 }
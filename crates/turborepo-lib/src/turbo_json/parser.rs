@@ -25,7 +25,10 @@ use crate::{
     cli::OutputLogsMode,
     config::ConfigurationOptions,
     run::task_id::TaskName,
-    turbo_json::{Pipeline, RawTaskDefinition, RawTurboJson, SpacesJson, Spanned},
+    turbo_json::{
+        Pipeline, RawBoundaryRule, RawReadiness, RawTaskDefinition, RawTurboJson, SpacesJson,
+        Spanned,
+    },
     unescape::UnescapedString,
 };
 
@@ -91,6 +94,29 @@ fn create_unknown_key_diagnostic_from_struct<T: Iterable>(
     DeserializationDiagnostic::new_unknown_key(unknown_key, range, &allowed_keys_borrowed)
 }
 
+/// The keys that can be set at the top level of a turbo.json, as they're
+/// written in the file
+pub fn turbo_json_keys() -> Vec<String> {
+    RawTurboJson::default()
+        .iter()
+        .filter_map(|(key, _)| match key {
+            // These hold the file the config was read from
+            "text" | "path" => None,
+            "schema" => Some("$schema".to_string()),
+            "experimental_ui" => Some("experimentalUI".to_string()),
+            key => Some(key.to_case(Case::Camel)),
+        })
+        .collect()
+}
+
+/// The keys that can be set in a task definition
+pub fn task_definition_keys() -> Vec<String> {
+    RawTaskDefinition::default()
+        .iter()
+        .map(|(key, _)| key.to_case(Case::Camel))
+        .collect()
+}
+
 impl Deserializable for OutputLogsMode {
     fn deserialize(
         value: &impl DeserializableValue,
@@ -261,6 +287,112 @@ impl DeserializationVisitor for RawTaskDefinitionVisitor {
                         result.interactive = Some(Spanned::new(interactive).with_range(range));
                     }
                 }
+                "retries" => {
+                    if let Some(retries) = u32::deserialize(&value, &key_text, diagnostics) {
+                        result.retries = Some(Spanned::new(retries).with_range(range));
+                    }
+                }
+                "timeout" => {
+                    if let Some(timeout) = u64::deserialize(&value, &key_text, diagnostics) {
+                        result.timeout = Some(Spanned::new(timeout).with_range(range));
+                    }
+                }
+                "cacheRetried" => {
+                    if let Some(cache_retried) = bool::deserialize(&value, &key_text, diagnostics) {
+                        result.cache_retried = Some(Spanned::new(cache_retried).with_range(range));
+                    }
+                }
+                "weight" => {
+                    if let Some(weight) = u32::deserialize(&value, &key_text, diagnostics) {
+                        result.weight = Some(Spanned::new(weight).with_range(range));
+                    }
+                }
+                "pool" => {
+                    if let Some(pool) = UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.pool = Some(Spanned::new(pool).with_range(range));
+                    }
+                }
+                "with" => {
+                    if let Some(with) = Vec::deserialize(&value, &key_text, diagnostics) {
+                        result.with = Some(Spanned::new(with).with_range(range));
+                    }
+                }
+                "readiness" => {
+                    if let Some(readiness) =
+                        RawReadiness::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.readiness = Some(Spanned::new(readiness).with_range(range));
+                    }
+                }
+                unknown_key => {
+                    diagnostics.push(create_unknown_key_diagnostic_from_struct(
+                        &result,
+                        unknown_key,
+                        key.range(),
+                    ));
+                }
+            }
+        }
+
+        Some(result)
+    }
+}
+
+impl Deserializable for RawReadiness {
+    fn deserialize(
+        value: &impl DeserializableValue,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self> {
+        value.deserialize(RawReadinessVisitor, name, diagnostics)
+    }
+}
+
+struct RawReadinessVisitor;
+
+impl DeserializationVisitor for RawReadinessVisitor {
+    type Output = RawReadiness;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::MAP;
+
+    fn visit_map(
+        self,
+        members: impl Iterator<Item = Option<(impl DeserializableValue, impl DeserializableValue)>>,
+        _: TextRange,
+        _: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let mut result = RawReadiness::default();
+        for (key, value) in members.flatten() {
+            let Some(key_text) = Text::deserialize(&key, "", diagnostics) else {
+                continue;
+            };
+            let range = value.range();
+            match key_text.text() {
+                "tcp" => {
+                    if let Some(port) = u16::deserialize(&value, &key_text, diagnostics) {
+                        result.tcp = Some(Spanned::new(port).with_range(range));
+                    }
+                }
+                "http" => {
+                    if let Some(url) = UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.http = Some(Spanned::new(url).with_range(range));
+                    }
+                }
+                "stdout" => {
+                    if let Some(pattern) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.stdout = Some(Spanned::new(pattern).with_range(range));
+                    }
+                }
+                "timeout" => {
+                    if let Some(timeout) = u64::deserialize(&value, &key_text, diagnostics) {
+                        result.timeout = Some(Spanned::new(timeout).with_range(range));
+                    }
+                }
                 unknown_key => {
                     diagnostics.push(create_unknown_key_diagnostic_from_struct(
                         &result,
@@ -409,6 +541,61 @@ impl DeserializationVisitor for RawRemoteCacheOptionsVisitor {
                         result.enabled = Some(enabled);
                     }
                 }
+                "localCacheMaxSize" => {
+                    if let Some(local_cache_max_size) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.local_cache_max_size = Some(local_cache_max_size.into());
+                    }
+                }
+                "localCacheMaxAge" => {
+                    if let Some(local_cache_max_age) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.local_cache_max_age = Some(local_cache_max_age.into());
+                    }
+                }
+                "backend" => {
+                    if let Some(backend) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.backend = Some(backend.into());
+                    }
+                }
+                "endpoint" => {
+                    if let Some(endpoint) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.endpoint = Some(endpoint.into());
+                    }
+                }
+                "bucket" => {
+                    if let Some(bucket) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.bucket = Some(bucket.into());
+                    }
+                }
+                "region" => {
+                    if let Some(region) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.region = Some(region.into());
+                    }
+                }
+                "prefix" => {
+                    if let Some(prefix) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.prefix = Some(prefix.into());
+                    }
+                }
+                "path" => {
+                    if let Some(path) = UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.path = Some(path.into());
+                    }
+                }
                 unknown_key => diagnostics.push(create_unknown_key_diagnostic_from_struct(
                     &result,
                     unknown_key,
@@ -500,6 +687,61 @@ impl DeserializationVisitor for ConfigurationOptionsVisitor {
                         result.enabled = Some(enabled);
                     }
                 }
+                "localCacheMaxSize" => {
+                    if let Some(local_cache_max_size) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.local_cache_max_size = Some(local_cache_max_size.into());
+                    }
+                }
+                "localCacheMaxAge" => {
+                    if let Some(local_cache_max_age) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.local_cache_max_age = Some(local_cache_max_age.into());
+                    }
+                }
+                "backend" => {
+                    if let Some(backend) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.backend = Some(backend.into());
+                    }
+                }
+                "endpoint" => {
+                    if let Some(endpoint) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.endpoint = Some(endpoint.into());
+                    }
+                }
+                "bucket" => {
+                    if let Some(bucket) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.bucket = Some(bucket.into());
+                    }
+                }
+                "region" => {
+                    if let Some(region) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.region = Some(region.into());
+                    }
+                }
+                "prefix" => {
+                    if let Some(prefix) =
+                        UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.prefix = Some(prefix.into());
+                    }
+                }
+                "path" => {
+                    if let Some(path) = UnescapedString::deserialize(&value, &key_text, diagnostics)
+                    {
+                        result.path = Some(path.into());
+                    }
+                }
                 unknown_key => diagnostics.push(create_unknown_key_diagnostic_from_struct(
                     &result,
                     unknown_key,
@@ -606,6 +848,23 @@ impl DeserializationVisitor for RawTurboJsonVisitor {
                         result.experimental_ui = Some(experimental_ui);
                     }
                 }
+                "pools" => {
+                    if let Some(pools) = value.deserialize(PoolsVisitor, &key_text, diagnostics) {
+                        result.pools = Some(pools);
+                    }
+                }
+                "tags" => {
+                    if let Some(tags) = Vec::deserialize(&value, &key_text, diagnostics) {
+                        result.tags = Some(tags);
+                    }
+                }
+                "boundaries" => {
+                    if let Some(boundaries) =
+                        value.deserialize(BoundariesVisitor, &key_text, diagnostics)
+                    {
+                        result.boundaries = Some(boundaries);
+                    }
+                }
                 // Allow for faux-comments at the top level
                 "//" => {}
                 unknown_key => {
@@ -621,22 +880,164 @@ impl DeserializationVisitor for RawTurboJsonVisitor {
     }
 }
 
+struct PoolsVisitor;
+
+impl DeserializationVisitor for PoolsVisitor {
+    type Output = BTreeMap<String, Spanned<u32>>;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::MAP;
+
+    fn visit_map(
+        self,
+        members: impl Iterator<Item = Option<(impl DeserializableValue, impl DeserializableValue)>>,
+        _range: TextRange,
+        _name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let mut result = BTreeMap::new();
+        for (key, value) in members.flatten() {
+            let Some(pool) = String::deserialize(&key, "", diagnostics) else {
+                continue;
+            };
+            if let Some(size) = Spanned::<u32>::deserialize(&value, &pool, diagnostics) {
+                result.insert(pool, size);
+            }
+        }
+
+        Some(result)
+    }
+}
+
+struct BoundariesVisitor;
+
+impl DeserializationVisitor for BoundariesVisitor {
+    type Output = BTreeMap<String, Spanned<RawBoundaryRule>>;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::MAP;
+
+    fn visit_map(
+        self,
+        members: impl Iterator<Item = Option<(impl DeserializableValue, impl DeserializableValue)>>,
+        _range: TextRange,
+        _name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let mut result = BTreeMap::new();
+        for (key, value) in members.flatten() {
+            let Some(selector) = String::deserialize(&key, "", diagnostics) else {
+                continue;
+            };
+            if let Some(rule) =
+                Spanned::<RawBoundaryRule>::deserialize(&value, &selector, diagnostics)
+            {
+                result.insert(selector, rule);
+            }
+        }
+
+        Some(result)
+    }
+}
+
+impl Deserializable for RawBoundaryRule {
+    fn deserialize(
+        value: &impl DeserializableValue,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self> {
+        value.deserialize(RawBoundaryRuleVisitor, name, diagnostics)
+    }
+}
+
+struct RawBoundaryRuleVisitor;
+
+impl DeserializationVisitor for RawBoundaryRuleVisitor {
+    type Output = RawBoundaryRule;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::MAP;
+
+    fn visit_map(
+        self,
+        members: impl Iterator<Item = Option<(impl DeserializableValue, impl DeserializableValue)>>,
+        _: TextRange,
+        _: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let mut result = RawBoundaryRule::default();
+        for (key, value) in members.flatten() {
+            let Some(key_text) = Text::deserialize(&key, "", diagnostics) else {
+                continue;
+            };
+            match key_text.text() {
+                "allow" => {
+                    if let Some(allow) = Vec::deserialize(&value, &key_text, diagnostics) {
+                        result.allow = Some(allow);
+                    }
+                }
+                "deny" => {
+                    if let Some(deny) = Vec::deserialize(&value, &key_text, diagnostics) {
+                        result.deny = Some(deny);
+                    }
+                }
+                unknown_key => {
+                    diagnostics.push(create_unknown_key_diagnostic_from_struct(
+                        &result,
+                        unknown_key,
+                        key.range(),
+                    ));
+                }
+            }
+        }
+
+        Some(result)
+    }
+}
+
 impl WithMetadata for RawTurboJson {
     fn add_text(&mut self, text: Arc<str>) {
         self.text = Some(text.clone());
-        self.extends.add_text(text.clone());
+        if let Some(extends) = &mut self.extends {
+            extends.add_text(text.clone());
+            extends.value.add_text(text.clone());
+        }
         self.global_dependencies.add_text(text.clone());
         self.global_env.add_text(text.clone());
         self.global_pass_through_env.add_text(text.clone());
+        if let Some(pools) = &mut self.pools {
+            for size in pools.values_mut() {
+                size.add_text(text.clone());
+            }
+        }
+        self.tags.add_text(text.clone());
+        if let Some(boundaries) = &mut self.boundaries {
+            for rule in boundaries.values_mut() {
+                rule.add_text(text.clone());
+                rule.value.add_text(text.clone());
+            }
+        }
         self.pipeline.add_text(text);
     }
 
     fn add_path(&mut self, path: Arc<str>) {
         self.path = Some(path.clone());
-        self.extends.add_path(path.clone());
+        if let Some(extends) = &mut self.extends {
+            extends.add_path(path.clone());
+            extends.value.add_path(path.clone());
+        }
         self.global_dependencies.add_path(path.clone());
         self.global_env.add_path(path.clone());
         self.global_pass_through_env.add_path(path.clone());
+        if let Some(pools) = &mut self.pools {
+            for size in pools.values_mut() {
+                size.add_path(path.clone());
+            }
+        }
+        self.tags.add_path(path.clone());
+        if let Some(boundaries) = &mut self.boundaries {
+            for rule in boundaries.values_mut() {
+                rule.add_path(path.clone());
+                rule.value.add_path(path.clone());
+            }
+        }
         self.pipeline.add_path(path);
     }
 }
@@ -670,7 +1071,20 @@ impl WithMetadata for RawTaskDefinition {
         self.persistent.add_text(text.clone());
         self.outputs.add_text(text.clone());
         self.output_mode.add_text(text.clone());
-        self.interactive.add_text(text);
+        self.interactive.add_text(text.clone());
+        self.retries.add_text(text.clone());
+        self.cache_retried.add_text(text.clone());
+        self.timeout.add_text(text.clone());
+        self.weight.add_text(text.clone());
+        self.pool.add_text(text.clone());
+        self.with.add_text(text.clone());
+        if let Some(with) = &mut self.with {
+            with.value.add_text(text.clone());
+        }
+        self.readiness.add_text(text.clone());
+        if let Some(readiness) = &mut self.readiness {
+            readiness.value.add_text(text);
+        }
     }
 
     fn add_path(&mut self, path: Arc<str>) {
@@ -685,7 +1099,48 @@ impl WithMetadata for RawTaskDefinition {
         self.persistent.add_path(path.clone());
         self.outputs.add_path(path.clone());
         self.output_mode.add_path(path.clone());
-        self.interactive.add_path(path);
+        self.interactive.add_path(path.clone());
+        self.retries.add_path(path.clone());
+        self.cache_retried.add_path(path.clone());
+        self.timeout.add_path(path.clone());
+        self.weight.add_path(path.clone());
+        self.pool.add_path(path.clone());
+        self.with.add_path(path.clone());
+        if let Some(with) = &mut self.with {
+            with.value.add_path(path.clone());
+        }
+        self.readiness.add_path(path.clone());
+        if let Some(readiness) = &mut self.readiness {
+            readiness.value.add_path(path);
+        }
+    }
+}
+
+impl WithMetadata for RawReadiness {
+    fn add_text(&mut self, text: Arc<str>) {
+        self.tcp.add_text(text.clone());
+        self.http.add_text(text.clone());
+        self.stdout.add_text(text.clone());
+        self.timeout.add_text(text);
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        self.tcp.add_path(path.clone());
+        self.http.add_path(path.clone());
+        self.stdout.add_path(path.clone());
+        self.timeout.add_path(path);
+    }
+}
+
+impl WithMetadata for RawBoundaryRule {
+    fn add_text(&mut self, text: Arc<str>) {
+        self.allow.add_text(text.clone());
+        self.deny.add_text(text);
+    }
+
+    fn add_path(&mut self, path: Arc<str>) {
+        self.allow.add_path(path.clone());
+        self.deny.add_path(path);
     }
 }
 
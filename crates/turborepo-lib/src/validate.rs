@@ -0,0 +1,340 @@
+//! Checks the turbo.json files in a repository the same way `turbo run` does,
+//! so that editors can report problems while the files are being edited.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Range,
+};
+
+use miette::Diagnostic;
+use thiserror::Error;
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, AnchoredSystemPathBuf};
+use turborepo_errors::Spanned;
+use turborepo_repository::{
+    discovery::PackageDiscovery,
+    package_graph::{self, PackageGraph, PackageName},
+    package_json::{self, PackageJson},
+};
+
+use crate::{
+    config,
+    engine::{EngineBuilder, ValidateError},
+    run::task_id::TaskName,
+    turbo_json::{RawTaskDefinition, RawTurboJson, TurboJson},
+};
+
+/// A problem with a turbo.json
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// The turbo.json the problem is in, if it is known
+    pub path: Option<AnchoredSystemPathBuf>,
+    /// Where the problem is in the file, in bytes
+    pub range: Option<Range<usize>>,
+    pub message: String,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("unable to read root package.json: {0}")]
+    PackageJson(#[from] package_json::Error),
+    #[error("unable to build package graph: {0}")]
+    PackageGraph(#[from] package_graph::Error),
+}
+
+/// Finds the problems in the turbo.json files of a repository. `read` gets the
+/// contents of a turbo.json, which lets files that are being edited be checked
+/// before they are saved.
+///
+/// Only the problems found by the first step that fails are reported, as
+/// `turbo run` would stop there too. Problems that depend on how `turbo run`
+/// is invoked, like not having enough concurrency for the persistent tasks,
+/// aren't reported.
+pub async fn validate_turbo_jsons(
+    repo_root: &AbsoluteSystemPath,
+    package_discovery: impl PackageDiscovery + Send + Sync,
+    read: impl Fn(&AbsoluteSystemPath) -> Option<String>,
+) -> Result<Vec<ConfigProblem>, Error> {
+    let root_package_json = PackageJson::load(&repo_root.join_component("package.json"))?;
+    let package_graph = PackageGraph::builder(repo_root, root_package_json)
+        .with_package_discovery(package_discovery)
+        .build()
+        .await?;
+
+    let mut problems = Vec::new();
+    let mut turbo_jsons = HashMap::new();
+    let mut experimental_ui = false;
+    for (package, _) in package_graph.packages() {
+        let Some(dir) = package_graph.package_dir(package) else {
+            continue;
+        };
+        let path = dir.join_component("turbo.json");
+        let Some(contents) = read(&repo_root.resolve(&path)) else {
+            continue;
+        };
+
+        let turbo_json = RawTurboJson::parse(&contents, &path)
+            .map_err(config::Error::from)
+            .and_then(|mut raw| {
+                if matches!(package, PackageName::Root) {
+                    experimental_ui = raw.experimental_ui.unwrap_or_default();
+                }
+                raw.remove_env_dependencies();
+                TurboJson::try_from(raw)
+            });
+        match turbo_json {
+            Ok(turbo_json) => {
+                turbo_jsons.insert(package.clone(), turbo_json);
+            }
+            Err(error) => problems.extend(diagnostic_problems(&error, Some(&path))),
+        }
+    }
+
+    // Without a valid root turbo.json there's nothing to build the engine from
+    let Some(root_turbo_json) = turbo_jsons.get(&PackageName::Root).cloned() else {
+        return Ok(problems);
+    };
+    if !problems.is_empty() {
+        return Ok(problems);
+    }
+
+    let tasks = turbo_jsons
+        .values()
+        .flat_map(|turbo_json| turbo_json.pipeline.keys())
+        .map(|task_name| task_name.task().to_string())
+        .collect::<BTreeSet<_>>();
+    let engine = EngineBuilder::new(repo_root, &package_graph, false)
+        .with_root_tasks(root_turbo_json.pipeline.keys().cloned())
+        .with_turbo_jsons(Some(turbo_jsons.clone()))
+        .with_workspaces(
+            package_graph
+                .packages()
+                .map(|(name, _)| name.clone())
+                .collect(),
+        )
+        .with_tasks(
+            tasks
+                .into_iter()
+                .map(|task| Spanned::new(TaskName::from(task))),
+        )
+        .build();
+    let engine = match engine {
+        Ok(engine) => engine,
+        Err(error) => return Ok(diagnostic_problems(&error, None)),
+    };
+
+    let mut errors = engine
+        .validate_pools(&package_graph, &root_turbo_json.pools)
+        .err()
+        .unwrap_or_default();
+    errors.extend(
+        engine
+            .validate(&package_graph, u32::MAX, experimental_ui)
+            .err()
+            .into_iter()
+            .flatten(),
+    );
+
+    Ok(errors
+        .iter()
+        .flat_map(|error| match error {
+            // This error doesn't say where the task is, so we find it ourselves
+            ValidateError::InteractiveNeedsUI { task } => {
+                let definition = task_definition(&turbo_jsons, task);
+                vec![ConfigProblem {
+                    path: definition
+                        .and_then(|d| d.path.as_deref())
+                        .and_then(|path| AnchoredSystemPathBuf::from_raw(path).ok()),
+                    range: definition.and_then(|d| d.range.clone()),
+                    message: error.to_string(),
+                }]
+            }
+            error => diagnostic_problems(error, None),
+        })
+        .collect())
+}
+
+/// Converts a diagnostic into problems, one for each of its labels. Errors
+/// that only group others, like the ones from parsing, are converted into the
+/// errors they contain.
+fn diagnostic_problems(
+    diagnostic: &dyn Diagnostic,
+    path: Option<&AnchoredSystemPath>,
+) -> Vec<ConfigProblem> {
+    if let Some(related) = diagnostic.related() {
+        let problems = related
+            .flat_map(|related| diagnostic_problems(related, path))
+            .collect::<Vec<_>>();
+        if !problems.is_empty() {
+            return problems;
+        }
+    }
+
+    let message = diagnostic.to_string();
+    let labels = diagnostic
+        .labels()
+        .map(|labels| labels.collect::<Vec<_>>())
+        .unwrap_or_default();
+    if labels.is_empty() {
+        return vec![ConfigProblem {
+            path: path.map(ToOwned::to_owned),
+            range: None,
+            message,
+        }];
+    }
+
+    labels
+        .into_iter()
+        .map(|label| {
+            // Errors name their source after the turbo.json it was read from
+            let source_path = diagnostic
+                .source_code()
+                .and_then(|source| source.read_span(label.inner(), 0, 0).ok())
+                .and_then(|span| span.name().map(ToOwned::to_owned))
+                .and_then(|name| AnchoredSystemPathBuf::from_raw(name).ok());
+            ConfigProblem {
+                path: source_path.or_else(|| path.map(ToOwned::to_owned)),
+                range: Some(label.offset()..label.offset() + label.len()),
+                message: message.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Finds where a task is defined, preferring its package's turbo.json over the
+/// root one
+fn task_definition<'a>(
+    turbo_jsons: &'a HashMap<PackageName, TurboJson>,
+    task: &str,
+) -> Option<&'a Spanned<RawTaskDefinition>> {
+    let task_name = TaskName::from(task.to_string());
+    let package_task_name = task_name.clone().into_non_workspace_task();
+    let package = task_name.package()?;
+
+    turbo_jsons
+        .get(&PackageName::from(package))
+        .filter(|_| package != "//")
+        .and_then(|turbo_json| turbo_json.pipeline.get(&package_task_name))
+        .or_else(|| {
+            let root = turbo_jsons.get(&PackageName::Root)?;
+            root.pipeline
+                .get(&task_name)
+                .or_else(|| root.pipeline.get(&package_task_name))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::TempDir;
+    use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_repository::{
+        discovery::{self, DiscoveryResponse, WorkspaceData},
+        package_manager::PackageManager,
+    };
+
+    use super::*;
+
+    struct MockDiscovery {
+        workspaces: Vec<WorkspaceData>,
+    }
+
+    impl PackageDiscovery for MockDiscovery {
+        async fn discover_packages(&self) -> Result<DiscoveryResponse, discovery::Error> {
+            Ok(DiscoveryResponse {
+                package_manager: PackageManager::Npm,
+                workspaces: self.workspaces.clone(),
+            })
+        }
+
+        async fn discover_packages_blocking(&self) -> Result<DiscoveryResponse, discovery::Error> {
+            self.discover_packages().await
+        }
+    }
+
+    fn setup(turbo_json: &str) -> (TempDir, AbsoluteSystemPathBuf, MockDiscovery) {
+        let tmp = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        repo_root
+            .join_component("package.json")
+            .create_with_contents(r#"{ "name": "root", "workspaces": ["apps/*"] }"#)
+            .unwrap();
+        repo_root
+            .join_component("turbo.json")
+            .create_with_contents(turbo_json)
+            .unwrap();
+        let package_json = repo_root.join_components(&["apps", "web", "package.json"]);
+        package_json
+            .ensure_dir()
+            .and_then(|_| {
+                package_json.create_with_contents(
+                    r#"{ "name": "web", "scripts": { "build": "next build", "dev": "next dev" } }"#,
+                )
+            })
+            .unwrap();
+        let discovery = MockDiscovery {
+            workspaces: vec![WorkspaceData {
+                package_json,
+                turbo_json: None,
+            }],
+        };
+        (tmp, repo_root, discovery)
+    }
+
+    #[tokio::test]
+    async fn test_valid() {
+        let (_tmp, repo_root, discovery) =
+            setup(r#"{ "pipeline": { "build": { "dependsOn": ["^build"] } } }"#);
+        let problems =
+            validate_turbo_jsons(&repo_root, discovery, |path| path.read_to_string().ok())
+                .await
+                .unwrap();
+        assert_eq!(problems, vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key() {
+        let turbo_json = r#"{ "pipeline": { "build": { "outputz": [] } } }"#;
+        let (_tmp, repo_root, discovery) = setup(turbo_json);
+        let problems =
+            validate_turbo_jsons(&repo_root, discovery, |path| path.read_to_string().ok())
+                .await
+                .unwrap();
+
+        assert_eq!(problems.len(), 1);
+        let problem = &problems[0];
+        assert_eq!(problem.path.as_ref().unwrap().as_str(), "turbo.json");
+        let range = problem.range.clone().unwrap();
+        assert!(turbo_json[range].contains("outputz"));
+    }
+
+    #[tokio::test]
+    async fn test_dependency_on_persistent_task() {
+        let (_tmp, repo_root, discovery) = setup(r#"{ "pipeline": { "build": {} } }"#);
+        // The contents being edited are checked rather than the ones on disk
+        let turbo_json = r#"{
+            "pipeline": {
+                "build": { "dependsOn": ["dev"] },
+                "dev": { "persistent": true, "cache": false }
+            }
+        }"#;
+        let root_turbo_json = repo_root.join_component("turbo.json");
+        let problems = validate_turbo_jsons(&repo_root, discovery, |path| {
+            if path == &*root_turbo_json {
+                Some(turbo_json.to_string())
+            } else {
+                path.read_to_string().ok()
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(problems.len(), 1);
+        let problem = &problems[0];
+        assert_eq!(
+            problem.message,
+            "\"web#dev\" is a persistent task, \"web#build\" cannot depend on it"
+        );
+        assert_eq!(problem.path.as_ref().unwrap().as_str(), "turbo.json");
+        let range = problem.range.clone().unwrap();
+        assert_eq!(turbo_json[range].trim_matches('"'), "dev");
+    }
+}
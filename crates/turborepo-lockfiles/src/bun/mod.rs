@@ -5,6 +5,9 @@ use serde::Deserialize;
 use crate::Lockfile;
 
 mod de;
+mod text;
+
+pub use text::BunTextLockfile;
 
 type Map<K, V> = std::collections::BTreeMap<K, V>;
 
@@ -16,8 +19,15 @@ pub enum Error {
     SymlStructure(#[from] serde_json::Error),
     #[error("unexpected non-utf8 yarn.lock")]
     NonUTF8(#[from] std::str::Utf8Error),
-    #[error("Turborepo cannot serialize Bun lockfiles.")]
+    #[error(
+        "Turborepo cannot serialize binary Bun lockfiles. Run `bun install --save-text-lockfile` \
+         to switch to bun.lock."
+    )]
     NotImplemented(),
+    #[error("unable to parse bun.lock: {0}")]
+    TextParse(#[source] serde_json::Error),
+    #[error("package '{0}' in bun.lock is missing its identifier")]
+    MissingIdent(String),
 }
 
 #[derive(Debug)]
@@ -0,0 +1,794 @@
+//! Bun's text lockfile, `bun.lock`. It's JSON that allows trailing commas and,
+//! unlike the binary `bun.lockb`, can be written as well as read.
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+use turbopath::RelativeUnixPathBuf;
+
+use super::{Error, Map};
+use crate::{Lockfile, Package};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BunTextLockfile {
+    lockfile_version: i64,
+    // Keyed by the path of the workspace, with the root workspace at ""
+    workspaces: Map<String, WorkspaceEntry>,
+    trusted_dependencies: Option<Vec<String>>,
+    patched_dependencies: Option<Map<String, String>>,
+    overrides: Option<Map<String, String>>,
+    catalog: Option<Map<String, String>>,
+    catalogs: Option<Map<String, Map<String, String>>>,
+    // Keyed by package name, prefixed by the key of the package or name of the
+    // workspace that it's nested under if there's more than one version of it
+    #[serde(default)]
+    packages: Map<String, PackageEntry>,
+    // Unknown fields kept for round trip capabilities
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceEntry {
+    name: Option<String>,
+    version: Option<String>,
+    bin: Option<Value>,
+    bin_dir: Option<String>,
+    dependencies: Option<Map<String, String>>,
+    dev_dependencies: Option<Map<String, String>>,
+    optional_dependencies: Option<Map<String, String>>,
+    peer_dependencies: Option<Map<String, String>>,
+    optional_peers: Option<Vec<String>>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+// An entry in `packages` is an array that starts with the package's
+// identifier, `name@version`. What follows depends on where the package comes
+// from, e.g. npm packages also have a registry, info and integrity.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+struct PackageEntry(Vec<PackageField>);
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum PackageField {
+    Info(PackageInfo),
+    Other(Value),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageInfo {
+    dependencies: Option<Map<String, String>>,
+    dev_dependencies: Option<Map<String, String>>,
+    optional_dependencies: Option<Map<String, String>>,
+    peer_dependencies: Option<Map<String, String>>,
+    optional_peers: Option<Vec<String>>,
+    bin: Option<Value>,
+    bin_dir: Option<String>,
+    os: Option<Value>,
+    cpu: Option<Value>,
+    bundled: Option<bool>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+impl BunTextLockfile {
+    pub fn from_bytes(input: &[u8]) -> Result<Self, crate::Error> {
+        let input = std::str::from_utf8(input).map_err(Error::from)?;
+        Self::from_str(input)
+    }
+
+    // Finds the key of the package that `name` resolves to when required from
+    // the package at `parent`, the same way that node resolves modules from
+    // nested node_modules directories
+    fn dependency_key(&self, parent: &str, name: &str) -> Option<String> {
+        let segments = key_segments(parent);
+        (0..=segments.len())
+            .rev()
+            .map(|i| match i {
+                0 => name.to_string(),
+                i => format!("{}/{name}", segments[..i].join("/")),
+            })
+            .find(|key| self.packages.contains_key(key))
+    }
+}
+
+impl FromStr for BunTextLockfile {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lockfile: Self =
+            serde_json::from_str(&strip_trailing_commas(s)).map_err(Error::TextParse)?;
+        if let Some((key, _)) = lockfile
+            .packages
+            .iter()
+            .find(|(_, entry)| entry.ident().is_none())
+        {
+            return Err(Error::MissingIdent(key.clone()).into());
+        }
+        Ok(lockfile)
+    }
+}
+
+impl Lockfile for BunTextLockfile {
+    #[tracing::instrument(skip(self))]
+    fn resolve_package(
+        &self,
+        workspace_path: &str,
+        name: &str,
+        version: &str,
+    ) -> Result<Option<Package>, crate::Error> {
+        let workspace = self
+            .workspaces
+            .get(workspace_path)
+            .ok_or_else(|| crate::Error::MissingWorkspace(workspace_path.to_string()))?;
+
+        // `all_dependencies` returns the keys of the packages that dependencies
+        // resolve to along with their exact versions
+        if self
+            .packages
+            .get(name)
+            .is_some_and(|entry| entry.version() == Some(version))
+        {
+            return Ok(Some(Package {
+                key: name.to_string(),
+                version: version.to_string(),
+            }));
+        }
+
+        // A workspace's own version of a package takes precedence over the
+        // hoisted one
+        let workspace_key = workspace.name.as_ref().map(|ws| format!("{ws}/{name}"));
+        Ok(workspace_key
+            .into_iter()
+            .chain(std::iter::once(name.to_string()))
+            .find_map(|key| {
+                let entry = self.packages.get(&key)?;
+                let version = entry.version().filter(|_| !entry.is_workspace())?;
+                Some(Package {
+                    version: version.to_string(),
+                    key,
+                })
+            }))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn all_dependencies(&self, key: &str) -> Result<Option<HashMap<String, String>>, crate::Error> {
+        let Some(entry) = self.packages.get(key) else {
+            return Ok(None);
+        };
+        let Some(info) = entry.info() else {
+            return Ok(Some(HashMap::new()));
+        };
+
+        Ok(Some(
+            info.dependencies
+                .iter()
+                .chain(&info.optional_dependencies)
+                .chain(&info.peer_dependencies)
+                .flatten()
+                .filter_map(|(name, _)| {
+                    // Optional dependencies for other platforms and optional peers might not
+                    // be in the lockfile
+                    let dependency_key = self.dependency_key(key, name)?;
+                    let version = self.packages[&dependency_key].version()?;
+                    Some((dependency_key, version.to_string()))
+                })
+                .collect(),
+        ))
+    }
+
+    fn subgraph(
+        &self,
+        workspace_packages: &[String],
+        packages: &[String],
+    ) -> Result<Box<dyn Lockfile>, crate::Error> {
+        let mut workspaces = Map::new();
+        if let Some(root) = self.workspaces.get("") {
+            workspaces.insert(String::new(), root.clone());
+        }
+        for workspace_path in workspace_packages {
+            let workspace = self
+                .workspaces
+                .get(workspace_path)
+                .ok_or_else(|| crate::Error::MissingWorkspace(workspace_path.clone()))?;
+            workspaces.insert(workspace_path.clone(), workspace.clone());
+        }
+
+        let mut pruned_packages = Map::new();
+        for key in packages {
+            let entry = self
+                .packages
+                .get(key)
+                .ok_or_else(|| crate::Error::MissingPackage(key.clone()))?;
+            pruned_packages.insert(key.clone(), entry.clone());
+        }
+        // Keep the entries that link the remaining workspaces
+        for (key, entry) in &self.packages {
+            if entry
+                .workspace_path()
+                .is_some_and(|path| workspaces.contains_key(path))
+            {
+                pruned_packages.insert(key.clone(), entry.clone());
+            }
+        }
+
+        let idents = pruned_packages
+            .values()
+            .filter_map(PackageEntry::ident)
+            .collect::<Vec<_>>();
+        let patched_dependencies = self.patched_dependencies.as_ref().map(|patches| {
+            patches
+                .iter()
+                .filter(|(ident, _)| idents.contains(&ident.as_str()))
+                .map(|(ident, patch)| (ident.clone(), patch.clone()))
+                .collect::<Map<_, _>>()
+        });
+        let trusted_dependencies = self.trusted_dependencies.as_ref().map(|trusted| {
+            trusted
+                .iter()
+                .filter(|name| {
+                    idents
+                        .iter()
+                        .any(|ident| split_ident(ident).map(|(n, _)| n) == Some(name.as_str()))
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+
+        Ok(Box::new(Self {
+            lockfile_version: self.lockfile_version,
+            workspaces,
+            trusted_dependencies: trusted_dependencies.filter(|trusted| !trusted.is_empty()),
+            patched_dependencies: patched_dependencies.filter(|patches| !patches.is_empty()),
+            overrides: self.overrides.clone(),
+            catalog: self.catalog.clone(),
+            catalogs: self.catalogs.clone(),
+            packages: pruned_packages,
+            other: self.other.clone(),
+        }))
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, crate::Error> {
+        Ok(self.to_string().into_bytes())
+    }
+
+    fn patches(&self) -> Result<Vec<RelativeUnixPathBuf>, crate::Error> {
+        let mut patches = self
+            .patched_dependencies
+            .iter()
+            .flatten()
+            .map(|(_, patch)| RelativeUnixPathBuf::new(patch))
+            .collect::<Result<Vec<_>, turbopath::PathError>>()?;
+        patches.sort();
+        Ok(patches)
+    }
+
+    fn global_change(&self, other: &dyn Lockfile) -> bool {
+        let any_other = other as &dyn Any;
+        match any_other.downcast_ref::<Self>() {
+            Some(other) => {
+                self.lockfile_version != other.lockfile_version
+                    || self.trusted_dependencies != other.trusted_dependencies
+                    || self.patched_dependencies != other.patched_dependencies
+                    || self.overrides != other.overrides
+                    || self.catalog != other.catalog
+                    || self.catalogs != other.catalogs
+            }
+            // Switching between the binary and text lockfiles or package managers
+            None => true,
+        }
+    }
+}
+
+impl PackageEntry {
+    fn ident(&self) -> Option<&str> {
+        match self.0.first()? {
+            PackageField::Other(Value::String(ident)) => Some(ident),
+            _ => None,
+        }
+    }
+
+    fn version(&self) -> Option<&str> {
+        split_ident(self.ident()?).map(|(_, version)| version)
+    }
+
+    fn info(&self) -> Option<&PackageInfo> {
+        self.0.iter().find_map(|field| match field {
+            PackageField::Info(info) => Some(info),
+            PackageField::Other(_) => None,
+        })
+    }
+
+    fn workspace_path(&self) -> Option<&str> {
+        self.version()?.strip_prefix("workspace:")
+    }
+
+    fn is_workspace(&self) -> bool {
+        self.workspace_path().is_some()
+    }
+}
+
+// Splits `name@version`, taking care of scoped package names
+fn split_ident(ident: &str) -> Option<(&str, &str)> {
+    let at = ident.get(1..)?.find('@')? + 1;
+    Some((&ident[..at], &ident[at + 1..]))
+}
+
+// Splits a package key into the package names it's made of, e.g.
+// `web/@babel/core/semver` is `web`, `@babel/core` and `semver`
+fn key_segments(key: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut rest = key;
+    while !rest.is_empty() {
+        let end = match rest.starts_with('@') {
+            true => rest
+                .find('/')
+                .and_then(|slash| rest[slash + 1..].find('/').map(|end| slash + 1 + end)),
+            false => rest.find('/'),
+        };
+        match end {
+            Some(end) => {
+                segments.push(&rest[..end]);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                segments.push(rest);
+                break;
+            }
+        }
+    }
+    segments
+}
+
+// serde_json doesn't allow trailing commas, which Bun writes after every entry
+fn strip_trailing_commas(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            output.push(c);
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                output.push(c);
+            }
+            ',' if matches!(input[i + 1..].trim_start().chars().next(), Some('}' | ']')) => {}
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+// Bun's formatting is kept when writing the lockfile so that installing from a
+// pruned lockfile doesn't need to rewrite it
+impl fmt::Display for BunTextLockfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{{")?;
+        writeln!(f, "  \"lockfileVersion\": {},", self.lockfile_version)?;
+        writeln!(f, "  \"workspaces\": {{")?;
+        for (path, workspace) in &self.workspaces {
+            writeln!(f, "    {}: {{", json_string(path))?;
+            workspace.write_fields(f)?;
+            writeln!(f, "    }},")?;
+        }
+        writeln!(f, "  }},")?;
+        if let Some(trusted_dependencies) = &self.trusted_dependencies {
+            writeln!(f, "  \"trustedDependencies\": [")?;
+            for name in trusted_dependencies {
+                writeln!(f, "    {},", json_string(name))?;
+            }
+            writeln!(f, "  ],")?;
+        }
+        write_map(
+            f,
+            2,
+            "patchedDependencies",
+            self.patched_dependencies.as_ref(),
+        )?;
+        write_map(f, 2, "overrides", self.overrides.as_ref())?;
+        write_map(f, 2, "catalog", self.catalog.as_ref())?;
+        if let Some(catalogs) = &self.catalogs {
+            writeln!(f, "  \"catalogs\": {{")?;
+            for (name, catalog) in catalogs {
+                write_map(f, 4, name, Some(catalog))?;
+            }
+            writeln!(f, "  }},")?;
+        }
+        for (key, value) in &self.other {
+            writeln!(f, "  {}: {},", json_string(key), Inline(value))?;
+        }
+        writeln!(f, "  \"packages\": {{")?;
+        for (i, (key, entry)) in self.packages.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "    {}: {},", json_string(key), entry)?;
+        }
+        writeln!(f, "  }}")?;
+        writeln!(f, "}}")
+    }
+}
+
+impl WorkspaceEntry {
+    fn write_fields(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "      \"name\": {},", json_string(name))?;
+        }
+        if let Some(version) = &self.version {
+            writeln!(f, "      \"version\": {},", json_string(version))?;
+        }
+        if let Some(bin) = &self.bin {
+            writeln!(f, "      \"bin\": {},", Inline(bin))?;
+        }
+        if let Some(bin_dir) = &self.bin_dir {
+            writeln!(f, "      \"binDir\": {},", json_string(bin_dir))?;
+        }
+        write_map(f, 6, "dependencies", self.dependencies.as_ref())?;
+        write_map(f, 6, "devDependencies", self.dev_dependencies.as_ref())?;
+        write_map(
+            f,
+            6,
+            "optionalDependencies",
+            self.optional_dependencies.as_ref(),
+        )?;
+        write_map(f, 6, "peerDependencies", self.peer_dependencies.as_ref())?;
+        if let Some(optional_peers) = &self.optional_peers {
+            writeln!(f, "      \"optionalPeers\": [")?;
+            for name in optional_peers {
+                writeln!(f, "        {},", json_string(name))?;
+            }
+            writeln!(f, "      ],")?;
+        }
+        for (key, value) in &self.other {
+            writeln!(f, "      {}: {},", json_string(key), Inline(value))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PackageEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, field) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            match field {
+                PackageField::Info(info) => write!(f, "{info}")?,
+                PackageField::Other(value) => write!(f, "{}", Inline(value))?,
+            }
+        }
+        f.write_char(']')
+    }
+}
+
+impl fmt::Display for PackageInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let to_value = |map: &Map<String, String>| {
+            Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+                    .collect(),
+            )
+        };
+        let fields = [
+            ("dependencies", self.dependencies.as_ref().map(to_value)),
+            (
+                "devDependencies",
+                self.dev_dependencies.as_ref().map(to_value),
+            ),
+            (
+                "optionalDependencies",
+                self.optional_dependencies.as_ref().map(to_value),
+            ),
+            (
+                "peerDependencies",
+                self.peer_dependencies.as_ref().map(to_value),
+            ),
+            (
+                "optionalPeers",
+                self.optional_peers
+                    .as_ref()
+                    .map(|peers| peers.iter().cloned().map(Value::String).collect()),
+            ),
+            ("bin", self.bin.clone()),
+            ("binDir", self.bin_dir.clone().map(Value::String)),
+            ("os", self.os.clone()),
+            ("cpu", self.cpu.clone()),
+            ("bundled", self.bundled.map(Value::Bool)),
+        ];
+        let fields = fields
+            .into_iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value?)))
+            .chain(self.other.iter().map(|(k, v)| (k.clone(), v.clone())))
+            .collect::<Vec<_>>();
+
+        if fields.is_empty() {
+            return f.write_str("{}");
+        }
+        f.write_str("{ ")?;
+        for (i, (key, value)) in fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {}", json_string(key), Inline(value))?;
+        }
+        f.write_str(" }")
+    }
+}
+
+// Writes a JSON value on a single line, with spaces inside of objects
+struct Inline<'a>(&'a Value);
+
+impl<'a> fmt::Display for Inline<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::Object(map) if map.is_empty() => f.write_str("{}"),
+            Value::Object(map) => {
+                f.write_str("{ ")?;
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", json_string(key), Inline(value))?;
+                }
+                f.write_str(" }")
+            }
+            Value::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", Inline(item))?;
+                }
+                f.write_char(']')
+            }
+            value => write!(f, "{value}"),
+        }
+    }
+}
+
+fn write_map(
+    f: &mut fmt::Formatter<'_>,
+    indent: usize,
+    name: &str,
+    map: Option<&Map<String, String>>,
+) -> fmt::Result {
+    let Some(map) = map else {
+        return Ok(());
+    };
+    writeln!(f, "{:indent$}{}: {{", "", json_string(name))?;
+    for (key, value) in map {
+        writeln!(
+            f,
+            "{:indent$}  {}: {},",
+            "",
+            json_string(key),
+            json_string(value)
+        )?;
+    }
+    writeln!(f, "{:indent$}}},", "")
+}
+
+fn json_string(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+
+    const LOCKFILE: &str = include_str!("../../fixtures/bun.lock");
+
+    #[test]
+    fn test_roundtrip() {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        assert_eq!(
+            String::from_utf8(lockfile.encode().unwrap()).unwrap(),
+            LOCKFILE
+        );
+    }
+
+    #[test_case("apps/web", "react", "^17.0.2", Some(("web/react", "17.0.2")) ; "workspace version")]
+    #[test_case("apps/docs", "react", "^18.2.0", Some(("react", "18.2.0")) ; "hoisted version")]
+    #[test_case("apps/web", "lodash", "^4.17.21", Some(("lodash", "4.17.21")) ; "unique package")]
+    #[test_case("apps/web", "web/react", "17.0.2", Some(("web/react", "17.0.2")) ; "resolved key")]
+    #[test_case("apps/web", "@repo/ui", "*", None ; "workspace package")]
+    #[test_case("apps/web", "left-pad", "^1.0.0", None ; "missing package")]
+    fn test_resolve_package(
+        workspace: &str,
+        name: &str,
+        version: &str,
+        expected: Option<(&str, &str)>,
+    ) {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        let expected = expected.map(|(key, version)| Package {
+            key: key.to_string(),
+            version: version.to_string(),
+        });
+        assert_eq!(
+            lockfile.resolve_package(workspace, name, version).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_resolve_package_missing_workspace() {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        assert!(lockfile
+            .resolve_package("apps/missing", "react", "*")
+            .is_err());
+    }
+
+    #[test_case("web/react", &[("loose-envify", "1.4.0"), ("object-assign", "4.1.1")] ; "nested package")]
+    #[test_case("loose-envify", &[("js-tokens", "4.0.0")] ; "hoisted package")]
+    #[test_case("turbo", &[("turbo-darwin-arm64", "2.0.4"), ("turbo-linux-64", "2.0.4")] ; "optional dependencies")]
+    #[test_case("lodash", &[] ; "no dependencies")]
+    fn test_all_dependencies(key: &str, expected: &[(&str, &str)]) {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        let expected = expected
+            .iter()
+            .map(|(key, version)| (key.to_string(), version.to_string()))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(lockfile.all_dependencies(key).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        let closure = crate::transitive_closure(
+            &lockfile,
+            "apps/web",
+            HashMap::from([
+                ("react".to_string(), "^17.0.2".to_string()),
+                ("lodash".to_string(), "^4.17.21".to_string()),
+            ]),
+        )
+        .unwrap();
+        let mut keys = closure.into_iter().map(|pkg| pkg.key).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "js-tokens",
+                "lodash",
+                "loose-envify",
+                "object-assign",
+                "web/react"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        let pruned = lockfile
+            .subgraph(
+                &["apps/docs".to_string(), "packages/ui".to_string()],
+                &[
+                    "js-tokens".to_string(),
+                    "loose-envify".to_string(),
+                    "react".to_string(),
+                ],
+            )
+            .unwrap();
+        let pruned = String::from_utf8(pruned.encode().unwrap()).unwrap();
+        assert_eq!(
+            pruned,
+            r#"{
+  "lockfileVersion": 1,
+  "workspaces": {
+    "": {
+      "name": "bun-monorepo",
+      "devDependencies": {
+        "turbo": "^2.0.4",
+      },
+    },
+    "apps/docs": {
+      "name": "docs",
+      "version": "0.1.0",
+      "dependencies": {
+        "@repo/ui": "*",
+        "react": "^18.2.0",
+      },
+    },
+    "packages/ui": {
+      "name": "@repo/ui",
+      "version": "0.0.0",
+      "peerDependencies": {
+        "react": "*",
+      },
+    },
+  },
+  "packages": {
+    "@repo/ui": ["@repo/ui@workspace:packages/ui"],
+
+    "docs": ["docs@workspace:apps/docs"],
+
+    "js-tokens": ["js-tokens@4.0.0", "", {}, "sha512-RdJUflcE3cUzKiMqQgsCu06FPu9UdIJO0beYbPhHN4k6apgJtifcoCtT9bcxOpYBtpD2kCM6Sbzg4CNNBvsCxg=="],
+
+    "loose-envify": ["loose-envify@1.4.0", "", { "dependencies": { "js-tokens": "^3.0.0 || ^4.0.0" }, "bin": { "loose-envify": "cli.js" } }, "sha512-lyuxPGr/Wfhrlem2CL/UcnUc1zcqKAImBDzukY7Y5F/yQiNdko6+fRLevlw1HgMySw7f611UIY408EtxRSoK3Q=="],
+
+    "react": ["react@18.2.0", "", { "dependencies": { "loose-envify": "^1.1.0" } }, "sha512-/3IjMdb2L9QbBdWiW5e3P2/npwMBaU9mHCSCUzNln0ZCYbcfTsGbTJrU/kGemdH2IWmB2ioZ+zkxtmq6g09fGQ=="],
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_subgraph_keeps_patches_of_remaining_packages() {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        let pruned = lockfile
+            .subgraph(
+                &["apps/web".to_string()],
+                &["lodash".to_string(), "turbo".to_string()],
+            )
+            .unwrap();
+        assert_eq!(
+            pruned.patches().unwrap(),
+            vec![RelativeUnixPathBuf::new("patches/lodash@4.17.21.patch").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_global_change() {
+        let lockfile = BunTextLockfile::from_str(LOCKFILE).unwrap();
+        let mut other = lockfile.clone();
+        assert!(!lockfile.global_change(&other));
+
+        other.overrides = Some(Map::from([("react".to_string(), "18.3.1".to_string())]));
+        assert!(lockfile.global_change(&other));
+
+        let binary =
+            super::super::BunLockfile::from_str(include_str!("../../fixtures/yarn1full.lock"))
+                .unwrap();
+        assert!(lockfile.global_change(&binary));
+    }
+
+    #[test_case("react@18.2.0", Some(("react", "18.2.0")) ; "unscoped")]
+    #[test_case("@repo/ui@workspace:packages/ui", Some(("@repo/ui", "workspace:packages/ui")) ; "scoped")]
+    #[test_case("react", None ; "no version")]
+    fn test_split_ident(ident: &str, expected: Option<(&str, &str)>) {
+        assert_eq!(split_ident(ident), expected);
+    }
+
+    #[test_case("react", &["react"] ; "single")]
+    #[test_case("web/react", &["web", "react"] ; "nested")]
+    #[test_case("web/@babel/core/semver", &["web", "@babel/core", "semver"] ; "scoped")]
+    fn test_key_segments(key: &str, expected: &[&str]) {
+        assert_eq!(key_segments(key), expected);
+    }
+
+    #[test_case("[1, 2,]", "[1, 2]" ; "array")]
+    #[test_case("{\"a\": 1,\n}", "{\"a\": 1\n}" ; "object")]
+    #[test_case("{\"a\": \",}\"}", "{\"a\": \",}\"}" ; "inside of strings")]
+    #[test_case("[\"\\\",]\",]", "[\"\\\",]\"]" ; "escaped quotes")]
+    fn test_strip_trailing_commas(input: &str, expected: &str) {
+        assert_eq!(strip_trailing_commas(input), expected);
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     iter,
     str::FromStr,
     sync::{Arc, Mutex},
@@ -11,7 +11,7 @@ use std::{
 
 use itertools::{chain, Itertools};
 use jsonc_parser::{
-    ast::{ObjectPropName, StringLit},
+    ast::{self, Object, ObjectProp, ObjectPropName, StringLit},
     CollectOptions,
 };
 use serde_json::Value;
@@ -22,8 +22,11 @@ use tower_lsp::{
     lsp_types::*,
     Client, LanguageServer,
 };
-use turbopath::AbsoluteSystemPathBuf;
-use turborepo_lib::{DaemonClient, DaemonConnector, DaemonPackageDiscovery, DaemonPaths};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_lib::{
+    resolve_task_definition, task_definition_keys, turbo_json_keys, validate_turbo_jsons,
+    DaemonClient, DaemonConnector, DaemonPackageDiscovery, DaemonPaths,
+};
 use turborepo_repository::{
     discovery::{self, DiscoveryResponse, PackageDiscovery, WorkspaceData},
     package_json::PackageJson,
@@ -33,6 +36,11 @@ pub struct Backend {
     client: Client,
     repo_root: Arc<Mutex<Option<AbsoluteSystemPathBuf>>>,
     files: Mutex<HashMap<Url, crop::Rope>>,
+    // the diagnostics found in each file on its own, which are published along
+    // with the problems found when validating the whole repo
+    diagnostics: Mutex<HashMap<Url, Vec<Diagnostic>>>,
+    // the files that had problems the last time the repo was validated
+    invalid_files: Mutex<HashSet<Url>>,
     initializer: Sender<Option<DaemonClient<DaemonConnector>>>,
     daemon: Receiver<Option<DaemonClient<DaemonConnector>>>,
 
@@ -125,7 +133,7 @@ impl LanguageServer for Backend {
                 )),
                 completion_provider: Some(CompletionOptions {
                     resolve_provider: Some(false),
-                    trigger_characters: Some(vec![".".to_string()]),
+                    trigger_characters: Some(vec![".".to_string(), "\"".to_string()]),
                     work_done_progress_options: Default::default(),
                     all_commit_characters: None,
                     ..Default::default()
@@ -164,6 +172,10 @@ impl LanguageServer for Backend {
                         work_done_progress: None,
                     },
                 })),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
                 ..ServerCapabilities::default()
             },
         })
@@ -338,6 +350,167 @@ impl LanguageServer for Backend {
         Ok(Some(locations))
     }
 
+    async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
+        let params = params.text_document_position_params;
+        let Some(rope) = self.rope(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        let Some(task) = task_at(&rope, params.position) else {
+            return Ok(None);
+        };
+        let Some(repo_root) = self.repo_root().await else {
+            return Ok(None);
+        };
+        let packages = self.packages(&repo_root).await?;
+        let task_id = qualify_task(&packages, &params.text_document.uri, &task.name);
+
+        let mut contents = format!("**{}**\n", task_id);
+
+        // the root turbo.json is always applied, followed by the package's own
+        let turbo_jsons = chain(
+            iter::once(repo_root.join_component("turbo.json")),
+            packages
+                .iter()
+                .filter(|p| p.name != "//" && Some(p.name.as_str()) == split_task(&task_id).0)
+                .map(|p| p.dir.join_component("turbo.json")),
+        )
+        .filter_map(|path| {
+            let contents = self.read(&path)?;
+            let path = repo_root.anchor(&path).ok()?;
+            Some((path, contents))
+        })
+        .collect::<Vec<_>>();
+        let turbo_jsons = turbo_jsons
+            .iter()
+            .map(|(path, contents)| (&**path, contents.as_str()))
+            .collect::<Vec<_>>();
+
+        match resolve_task_definition(&turbo_jsons, &task_id) {
+            Ok(Some(definition)) => {
+                contents.push_str(&format!(
+                    "\n```json\n{}\n```\n",
+                    serde_json::to_string_pretty(&definition).expect("json is always serializable")
+                ));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.client
+                    .log_message(
+                        MessageType::WARNING,
+                        format!("failed to resolve {}: {}", task_id, e),
+                    )
+                    .await;
+            }
+        }
+
+        for (package, script) in scripts(&packages, &task_id) {
+            contents.push_str(&format!("\n- `{}`: `{}`", package.name, script));
+        }
+
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: contents,
+            }),
+            range: Some(task.range),
+        }))
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> LspResult<Option<GotoDefinitionResponse>> {
+        let params = params.text_document_position_params;
+        let Some(rope) = self.rope(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        let Some(task) = task_at(&rope, params.position) else {
+            return Ok(None);
+        };
+        let Some(repo_root) = self.repo_root().await else {
+            return Ok(None);
+        };
+        let packages = self.packages(&repo_root).await?;
+        let task_id = qualify_task(&packages, &params.text_document.uri, &task.name);
+        let (_, task) = split_task(&task_id);
+
+        let locations = scripts(&packages, &task_id)
+            .filter_map(|(package, _)| {
+                let package_json = package.dir.join_component("package.json");
+                let range = script_range(&self.read(&package_json)?, task)?;
+                let uri = Url::from_file_path(&package_json).ok()?;
+                Some(Location::new(uri, range))
+            })
+            .collect();
+
+        Ok(Some(GotoDefinitionResponse::Array(locations)))
+    }
+
+    async fn rename(&self, params: RenameParams) -> LspResult<Option<WorkspaceEdit>> {
+        let position = params.text_document_position;
+        if params.new_name.is_empty()
+            || params.new_name.contains('#')
+            || params.new_name.starts_with('^')
+        {
+            return Err(Error::invalid_params(
+                "a task cannot be renamed to an empty name, or a name with a package or `^`",
+            ));
+        }
+
+        let Some(rope) = self.rope(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        let Some(task) = task_at(&rope, position.position) else {
+            return Ok(None);
+        };
+        let Some(repo_root) = self.repo_root().await else {
+            return Ok(None);
+        };
+        let packages = self.packages(&repo_root).await?;
+        let (_, task) = split_task(&task.name);
+
+        // packages include the root, so this covers every turbo.json
+        let changes = packages
+            .iter()
+            .filter_map(|package| {
+                let turbo_json = package.dir.join_component("turbo.json");
+                let edits = rename_edits(&self.read(&turbo_json)?, task, &params.new_name);
+                let uri = Url::from_file_path(&turbo_json).ok()?;
+                (!edits.is_empty()).then_some((uri, edits))
+            })
+            .collect();
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> LspResult<Option<DocumentSymbolResponse>> {
+        let Some(rope) = self.rope(&params.text_document.uri) else {
+            return Ok(None);
+        };
+
+        let text = rope.chunks().join("");
+        let parse = jsonc_parser::parse_to_ast(&text, &Default::default(), &Default::default());
+        let Some(object) = parse
+            .as_ref()
+            .ok()
+            .and_then(|p| p.value.as_ref())
+            .and_then(|v| v.as_object())
+        else {
+            // if it is not a valid json, then there is no outline
+            return Ok(None);
+        };
+
+        Ok(Some(DocumentSymbolResponse::Nested(document_symbols(
+            &rope, object, false,
+        ))))
+    }
+
     async fn code_lens(&self, params: CodeLensParams) -> LspResult<Option<Vec<CodeLens>>> {
         self.client
             .log_message(MessageType::INFO, "code lens!")
@@ -439,6 +612,32 @@ impl LanguageServer for Backend {
         self.client
             .log_message(MessageType::INFO, "initialized!")
             .await;
+
+        // the repo is validated again whenever its configuration changes, even
+        // if the files aren't open
+        let watchers = ["**/turbo.json", "**/package.json"]
+            .into_iter()
+            .map(|glob| FileSystemWatcher {
+                glob_pattern: GlobPattern::String(glob.to_string()),
+                kind: None,
+            })
+            .collect();
+        let registration = Registration {
+            id: "turbo-config-files".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
+                watchers,
+            })
+            .ok(),
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            self.client
+                .log_message(
+                    MessageType::WARNING,
+                    format!("failed to watch configuration files: {}", e),
+                )
+                .await;
+        }
     }
 
     async fn shutdown(&self) -> LspResult<()> {
@@ -457,10 +656,30 @@ impl LanguageServer for Backend {
             .await;
     }
 
-    async fn did_change_watched_files(&self, _: DidChangeWatchedFilesParams) {
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
         self.client
             .log_message(MessageType::INFO, "watched files have changed!")
             .await;
+
+        let mut changed = false;
+        for change in params.changes {
+            if !is_config_file(&change.uri) {
+                continue;
+            }
+            changed = true;
+            if change.typ == FileChangeType::DELETED {
+                self.diagnostics
+                    .lock()
+                    .expect("only fails if poisoned")
+                    .remove(&change.uri);
+            }
+        }
+
+        if changed {
+            if let Some(repo_root) = self.repo_root().await {
+                self.validate(&repo_root, None).await;
+            }
+        }
     }
 
     async fn execute_command(&self, _: ExecuteCommandParams) -> LspResult<Option<Value>> {
@@ -539,38 +758,76 @@ impl LanguageServer for Backend {
             .await;
     }
 
-    async fn completion(&self, _: CompletionParams) -> LspResult<Option<CompletionResponse>> {
-        let packages = self
-            .package_discovery()
-            .await
-            .map_err(|_e| Error::internal_error())?;
+    async fn completion(&self, params: CompletionParams) -> LspResult<Option<CompletionResponse>> {
+        let position = params.text_document_position;
+        let context = match self.rope(&position.text_document.uri) {
+            Some(rope) => completion_context(&rope, position.position),
+            None => CompletionContext::Unknown,
+        };
 
-        let package_jsons = packages
-            .workspaces
-            .into_iter()
-            .flat_map(|wd| PackageJson::load(&wd.package_json).ok()) // if we can't parse a package.json, then we can't infer its tasks
-            .collect::<Vec<_>>();
+        let keys = |keys: Vec<String>| {
+            keys.into_iter()
+                .map(|key| CompletionItem {
+                    label: key,
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>()
+        };
+        match context {
+            CompletionContext::Key => {
+                return Ok(Some(CompletionResponse::Array(keys(turbo_json_keys()))))
+            }
+            CompletionContext::TaskKey => {
+                return Ok(Some(CompletionResponse::Array(
+                    keys(task_definition_keys()),
+                )))
+            }
+            _ => {}
+        }
 
-        let tasks = package_jsons
+        let Some(repo_root) = self.repo_root().await else {
+            return Ok(None);
+        };
+        let packages = self.packages(&repo_root).await?;
+
+        let task_names = packages
             .iter()
             .flat_map(|p| p.scripts.keys())
             .unique()
-            .map(|s| CompletionItem {
-                label: s.to_owned(),
-                kind: Some(CompletionItemKind::FIELD),
-                ..Default::default()
-            });
+            .collect::<Vec<_>>();
+        let field = |label: String| CompletionItem {
+            label,
+            kind: Some(CompletionItemKind::FIELD),
+            ..Default::default()
+        };
 
-        let keys = package_jsons
+        let tasks = task_names.iter().map(|task| field(task.to_string()));
+        let package_tasks = packages.iter().flat_map(|p| {
+            p.scripts
+                .keys()
+                .map(move |task| field(format!("{}#{}", p.name, task)))
+        });
+        let dependencies = task_names
             .iter()
-            .flat_map(|p| p.scripts.keys().map(move |k| (p.name.clone(), k)))
-            .map(|(package, s)| CompletionItem {
-                label: format!("{}#{}", package.unwrap_or_default(), s),
-                kind: Some(CompletionItemKind::FIELD),
+            .filter(|_| matches!(context, CompletionContext::Dependency))
+            .map(|task| field(format!("^{}", task)));
+        let package_names = packages
+            .iter()
+            .filter(|_| matches!(context, CompletionContext::Unknown))
+            .map(|p| CompletionItem {
+                label: p.name.clone(),
+                kind: Some(CompletionItemKind::MODULE),
                 ..Default::default()
             });
 
-        Ok(Some(CompletionResponse::Array(keys.chain(tasks).collect())))
+        Ok(Some(CompletionResponse::Array(
+            package_tasks
+                .chain(tasks)
+                .chain(dependencies)
+                .chain(package_names)
+                .collect(),
+        )))
     }
 }
 
@@ -582,6 +839,8 @@ impl Backend {
             client,
             repo_root: Arc::new(Mutex::new(None)),
             files: Mutex::new(HashMap::new()),
+            diagnostics: Mutex::new(HashMap::new()),
+            invalid_files: Mutex::new(HashSet::new()),
             initializer: rx,
             daemon: tx,
 
@@ -589,22 +848,97 @@ impl Backend {
         }
     }
 
-    pub async fn package_discovery(&self) -> Result<DiscoveryResponse, discovery::Error> {
-        let daemon = {
-            let mut daemon = self.daemon.clone();
-            let daemon = daemon.wait_for(|d| d.is_some()).await;
-            let daemon = daemon.as_ref().expect("only fails if self is dropped");
-            daemon
-                .as_ref()
-                .expect("guaranteed to be some above")
-                .clone()
-        };
+    async fn daemon_client(&self) -> DaemonClient<DaemonConnector> {
+        let mut daemon = self.daemon.clone();
+        let daemon = daemon.wait_for(|d| d.is_some()).await;
+        let daemon = daemon.as_ref().expect("only fails if self is dropped");
+        daemon
+            .as_ref()
+            .expect("guaranteed to be some above")
+            .clone()
+    }
 
-        DaemonPackageDiscovery::new(daemon)
+    pub async fn package_discovery(&self) -> Result<DiscoveryResponse, discovery::Error> {
+        DaemonPackageDiscovery::new(self.daemon_client().await)
             .discover_packages_blocking()
             .await
     }
 
+    /// Get the contents of an open file
+    fn rope(&self, uri: &Url) -> Option<crop::Rope> {
+        self.files
+            .lock()
+            .expect("only fails if poisoned")
+            .get(uri)
+            .cloned() // cloning is cheap
+    }
+
+    /// Read a file, preferring its contents in the editor if it is open
+    fn read(&self, path: &AbsoluteSystemPath) -> Option<String> {
+        let uri = Url::from_file_path(path).ok()?;
+        match self.rope(&uri) {
+            Some(rope) => Some(rope.chunks().join("")),
+            None => path.read_to_string().ok(),
+        }
+    }
+
+    async fn repo_root(&self) -> Option<AbsoluteSystemPathBuf> {
+        let repo_root = self
+            .repo_root
+            .lock()
+            .expect("only fails if poisoned")
+            .clone();
+
+        if repo_root.is_none() {
+            self.client
+                .log_message(MessageType::INFO, "received request before initialization")
+                .await;
+        }
+
+        repo_root
+    }
+
+    /// Get the packages in the repo along with their scripts, including the
+    /// root package
+    async fn packages(&self, repo_root: &AbsoluteSystemPath) -> LspResult<Vec<Package>> {
+        let packages = match self.package_discovery().await {
+            Ok(packages) => packages,
+            Err(e) => {
+                self.client
+                    .log_message(MessageType::WARNING, e.to_string())
+                    .await;
+
+                let mut error = Error::internal_error();
+                error.message = "failed to get package list from the daemon".into();
+                return Err(error);
+            }
+        };
+
+        // package discovery does not yield the root, so we must add it
+        Ok(packages
+            .workspaces
+            .into_iter()
+            .map(|wd| wd.package_json)
+            .chain(iter::once(repo_root.join_component("package.json")))
+            .unique()
+            .filter_map(|package_json| {
+                // if we can't load a package.json, then we can't infer its tasks
+                let contents = PackageJson::load(&package_json).ok()?;
+                let dir = package_json.parent()?;
+                let name = if dir == repo_root {
+                    "//".to_string()
+                } else {
+                    contents.name?
+                };
+                Some(Package {
+                    name,
+                    dir: dir.to_owned(),
+                    scripts: contents.scripts,
+                })
+            })
+            .collect())
+    }
+
     /// Handle a file update to a rope, emitting diagnostics if necessary.
     async fn handle_file_update(&self, uri: Url, rope: Option<crop::Rope>, version: Option<i32>) {
         let rope = match rope {
@@ -691,13 +1025,14 @@ impl Backend {
 
         // ParseResult cannot be sent across threads, so we must ensure it is dropped
         // before we send the diagnostics. easiest way is just to scope it
-        {
+        'diagnostics: {
             let parse =
                 jsonc_parser::parse_to_ast(&contents, &Default::default(), &Default::default());
 
             let parse = match parse {
                 Ok(parse) => parse,
-                Err(_) => return, // if it is not a valid json, then there are no diagnostics
+                // if it is not a valid json, then only the errors from validating it are reported
+                Err(_) => break 'diagnostics,
             };
 
             let object = parse.value.as_ref().and_then(|v| v.as_object());
@@ -839,9 +1174,409 @@ impl Backend {
             }
         }
 
-        self.client
-            .publish_diagnostics(uri, diagnostics, version)
-            .await;
+        if !is_config_file(&uri) {
+            self.client
+                .publish_diagnostics(uri, diagnostics, version)
+                .await;
+            return;
+        }
+
+        self.diagnostics
+            .lock()
+            .expect("only fails if poisoned")
+            .insert(uri.clone(), diagnostics);
+        self.validate(&repo_root, Some((&uri, version))).await;
+    }
+
+    /// Validate the turbo.json files in the repo the same way `turbo run`
+    /// does, and publish the problems found along with the diagnostics of
+    /// each file.
+    async fn validate(&self, repo_root: &AbsoluteSystemPath, changed: Option<(&Url, Option<i32>)>) {
+        let discovery = DaemonPackageDiscovery::new(self.daemon_client().await);
+        let problems =
+            match validate_turbo_jsons(repo_root, discovery, |path| self.read(path)).await {
+                Ok(problems) => problems,
+                Err(e) => {
+                    // we can still publish the diagnostics of each file
+                    self.client
+                        .log_message(MessageType::WARNING, format!("failed to validate: {}", e))
+                        .await;
+                    vec![]
+                }
+            };
+
+        let mut problems_by_file: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+        for problem in problems {
+            // problems that aren't in a particular file are reported in the root turbo.json
+            let path = match &problem.path {
+                Some(path) => repo_root.resolve(path),
+                None => repo_root.join_component("turbo.json"),
+            };
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            let range = match (problem.range, self.read(&path)) {
+                (Some(range), Some(contents)) => {
+                    let rope = crop::Rope::from(contents);
+                    let len = rope.byte_len();
+                    convert_ranges(
+                        &rope,
+                        jsonc_parser::common::Range {
+                            start: range.start.min(len),
+                            end: range.end.min(len),
+                        },
+                    )
+                }
+                _ => Range::default(),
+            };
+            problems_by_file.entry(uri).or_default().push(Diagnostic {
+                message: problem.message,
+                range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("turbo".to_string()),
+                ..Default::default()
+            });
+        }
+
+        let published = {
+            let diagnostics = self.diagnostics.lock().expect("only fails if poisoned");
+            let mut invalid_files = self.invalid_files.lock().expect("only fails if poisoned");
+
+            // files that had problems before are published again to clear them
+            let files = invalid_files
+                .drain()
+                .chain(problems_by_file.keys().cloned())
+                .chain(changed.map(|(uri, _)| uri.clone()))
+                .collect::<HashSet<_>>();
+            invalid_files.extend(problems_by_file.keys().cloned());
+
+            files
+                .into_iter()
+                .map(|uri| {
+                    let mut file_diagnostics = diagnostics.get(&uri).cloned().unwrap_or_default();
+                    file_diagnostics.extend(problems_by_file.remove(&uri).unwrap_or_default());
+                    (uri, file_diagnostics)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (uri, diagnostics) in published {
+            let version = changed
+                .filter(|(changed, _)| **changed == uri)
+                .and_then(|(_, version)| version);
+            self.client
+                .publish_diagnostics(uri, diagnostics, version)
+                .await;
+        }
+    }
+}
+
+/// Whether a file is one that `turbo run` reads its configuration from
+fn is_config_file(uri: &Url) -> bool {
+    uri.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .is_some_and(|name| matches!(name, "turbo.json" | "package.json"))
+}
+
+/// A package in the repo, with the root package named `//`
+struct Package {
+    name: String,
+    dir: AbsoluteSystemPathBuf,
+    scripts: BTreeMap<String, String>,
+}
+
+/// A task named in a turbo.json, either as a key in the pipeline or in a
+/// task's `dependsOn`
+struct TaskReference {
+    /// The task as written, without the `^` of a topological dependency
+    name: String,
+    range: Range,
+}
+
+/// What is being completed in a turbo.json
+enum CompletionContext {
+    /// A key at the top level of the file
+    Key,
+    /// A task in the pipeline
+    Task,
+    /// A key in a task definition
+    TaskKey,
+    /// A task in `dependsOn` or `with`
+    Dependency,
+    Unknown,
+}
+
+fn byte_offset(rope: &crop::Rope, position: Position) -> usize {
+    if position.line as usize >= rope.line_len() {
+        return rope.byte_len();
+    }
+    rope.byte_of_line(position.line as usize) + position.character as usize
+}
+
+fn contains(range: jsonc_parser::common::Range, offset: usize) -> bool {
+    range.start <= offset && offset <= range.end
+}
+
+fn property_at<'a>(object: &'a Object<'a>, offset: usize) -> Option<&'a ObjectProp<'a>> {
+    object.properties.iter().find(|p| contains(p.range, offset))
+}
+
+fn name_range(name: &ObjectPropName) -> jsonc_parser::common::Range {
+    match name {
+        ObjectPropName::String(string) => collapse_string_range(string.range),
+        ObjectPropName::Word(word) => word.range,
+    }
+}
+
+/// split a task into its package, if it has one, and its name
+fn split_task(task: &str) -> (Option<&str>, &str) {
+    task.split_once('#') // turbo packages may not have # in them
+        .map(|(p, t)| (Some(p), t))
+        .unwrap_or((None, task))
+}
+
+/// Find the task under the cursor in a turbo.json
+fn task_at(rope: &crop::Rope, position: Position) -> Option<TaskReference> {
+    let text = rope.chunks().join("");
+    let parse = jsonc_parser::parse_to_ast(&text, &Default::default(), &Default::default()).ok()?;
+    let offset = byte_offset(rope, position);
+    let pipeline = parse.value.as_ref()?.as_object()?.get_object("pipeline")?;
+
+    for property in &pipeline.properties {
+        if let ObjectPropName::String(name) = &property.name {
+            if contains(name.range, offset) {
+                return Some(TaskReference {
+                    name: name.value.to_string(),
+                    range: convert_ranges(rope, collapse_string_range(name.range)),
+                });
+            }
+        }
+
+        let depends_on = property
+            .value
+            .as_object()
+            .and_then(|o| o.get_array("dependsOn"))
+            .map(|a| &a.elements);
+        for string in depends_on
+            .into_iter()
+            .flatten()
+            .filter_map(|d| d.as_string_lit())
+        {
+            if !contains(string.range, offset) {
+                continue;
+            }
+            let string = strip_lit_prefix(string, "^").unwrap_or_else(|| string.clone());
+            // environment variables are not tasks
+            if string.value.starts_with('$') {
+                return None;
+            }
+            return Some(TaskReference {
+                name: string.value.to_string(),
+                range: convert_ranges(rope, collapse_string_range(string.range)),
+            });
+        }
+    }
+
+    None
+}
+
+/// Tasks in a package's turbo.json belong to that package, so qualify them
+/// with its name
+fn qualify_task(packages: &[Package], uri: &Url, task: &str) -> String {
+    if task.contains('#') {
+        return task.to_string();
+    }
+
+    let package = uri.to_file_path().ok().and_then(|path| {
+        let dir = path.parent()?;
+        packages
+            .iter()
+            .find(|p| p.name != "//" && p.dir.as_std_path() == dir)
+    });
+
+    match package {
+        Some(package) => format!("{}#{}", package.name, task),
+        None => task.to_string(),
+    }
+}
+
+/// Get the scripts that run a task, along with the packages they are in
+fn scripts<'a>(
+    packages: &'a [Package],
+    task: &'a str,
+) -> impl Iterator<Item = (&'a Package, &'a String)> {
+    let (package, task) = split_task(task);
+    packages
+        .iter()
+        .filter(move |p| package.map_or(true, |name| name == p.name))
+        .filter_map(move |p| Some((p, p.scripts.get(task)?)))
+}
+
+/// Find the name of a script in a package.json
+fn script_range(contents: &str, script: &str) -> Option<Range> {
+    let parse =
+        jsonc_parser::parse_to_ast(contents, &Default::default(), &Default::default()).ok()?;
+    let scripts = parse.value.as_ref()?.as_object()?.get_object("scripts")?;
+    let property = scripts
+        .properties
+        .iter()
+        .find(|p| p.name.as_str() == script)?;
+
+    let rope = crop::Rope::from(contents.to_string());
+    Some(convert_ranges(&rope, name_range(&property.name)))
+}
+
+/// Get the edits renaming a task in a turbo.json, wherever it is named
+fn rename_edits(contents: &str, task: &str, new_name: &str) -> Vec<TextEdit> {
+    let Ok(parse) = jsonc_parser::parse_to_ast(contents, &Default::default(), &Default::default())
+    else {
+        // if it is not a valid json, then we can't rename anything in it
+        return vec![];
+    };
+    let Some(pipeline) = parse
+        .value
+        .as_ref()
+        .and_then(|v| v.as_object())
+        .and_then(|o| o.get_object("pipeline"))
+    else {
+        return vec![];
+    };
+
+    let rope = crop::Rope::from(contents.to_string());
+    let mut edits = vec![];
+    let mut rename = |string: &StringLit| {
+        let range = collapse_string_range(string.range);
+        let (package, name) = split_task(&string.value);
+        if name == task {
+            let start = range.start + package.map_or(0, |p| p.len() + 1);
+            edits.push(TextEdit {
+                range: convert_ranges(
+                    &rope,
+                    jsonc_parser::common::Range {
+                        start,
+                        end: range.end,
+                    },
+                ),
+                new_text: new_name.to_string(),
+            });
+        }
+    };
+
+    for property in &pipeline.properties {
+        if let ObjectPropName::String(name) = &property.name {
+            rename(name);
+        }
+
+        let dependencies = ["dependsOn", "with"]
+            .iter()
+            .filter_map(|key| property.value.as_object()?.get_array(key))
+            .flat_map(|a| &a.elements)
+            .filter_map(|d| d.as_string_lit());
+        for string in dependencies {
+            rename(&strip_lit_prefix(string, "^").unwrap_or_else(|| string.clone()));
+        }
+    }
+
+    edits
+}
+
+/// Get the outline of an object in a turbo.json, where the objects in the
+/// pipeline are tasks
+fn document_symbols(rope: &crop::Rope, object: &Object, is_pipeline: bool) -> Vec<DocumentSymbol> {
+    object
+        .properties
+        .iter()
+        .map(|property| {
+            let (kind, children) = match &property.value {
+                ast::Value::Object(object) => {
+                    let children = document_symbols(
+                        rope,
+                        object,
+                        !is_pipeline && property.name.as_str() == "pipeline",
+                    );
+                    let kind = if is_pipeline {
+                        SymbolKind::FUNCTION
+                    } else {
+                        SymbolKind::OBJECT
+                    };
+                    (kind, Some(children))
+                }
+                ast::Value::Array(_) => (SymbolKind::ARRAY, None),
+                ast::Value::StringLit(_) => (SymbolKind::STRING, None),
+                ast::Value::NumberLit(_) => (SymbolKind::NUMBER, None),
+                ast::Value::BooleanLit(_) => (SymbolKind::BOOLEAN, None),
+                ast::Value::NullKeyword(_) => (SymbolKind::NULL, None),
+            };
+
+            #[allow(deprecated)] // `deprecated` has to be set, but is replaced by `tags`
+            DocumentSymbol {
+                name: property.name.as_str().to_string(),
+                detail: None,
+                kind,
+                tags: None,
+                deprecated: None,
+                range: convert_ranges(rope, property.range),
+                selection_range: convert_ranges(rope, name_range(&property.name)),
+                children,
+            }
+        })
+        .collect()
+}
+
+/// Work out what is being completed from where the cursor is in a turbo.json
+fn completion_context(rope: &crop::Rope, position: Position) -> CompletionContext {
+    let text = rope.chunks().join("");
+    let Ok(parse) = jsonc_parser::parse_to_ast(&text, &Default::default(), &Default::default())
+    else {
+        return CompletionContext::Unknown;
+    };
+    let Some(root) = parse.value.as_ref().and_then(|v| v.as_object()) else {
+        return CompletionContext::Unknown;
+    };
+
+    let offset = byte_offset(rope, position);
+    // the cursor is either on the key of a property, or somewhere in its value
+    let key_or_unknown = |property: &ObjectProp, context| {
+        if contains(name_range(&property.name), offset) {
+            context
+        } else {
+            CompletionContext::Unknown
+        }
+    };
+
+    let Some(property) = property_at(root, offset) else {
+        return CompletionContext::Key;
+    };
+    if property.name.as_str() != "pipeline" {
+        return key_or_unknown(property, CompletionContext::Key);
+    }
+    let Some(pipeline) = property
+        .value
+        .as_object()
+        .filter(|o| contains(o.range, offset))
+    else {
+        return key_or_unknown(property, CompletionContext::Key);
+    };
+
+    let Some(task) = property_at(pipeline, offset) else {
+        return CompletionContext::Task;
+    };
+    let Some(definition) = task.value.as_object().filter(|o| contains(o.range, offset)) else {
+        return key_or_unknown(task, CompletionContext::Task);
+    };
+
+    match property_at(definition, offset) {
+        None => CompletionContext::TaskKey,
+        Some(key)
+            if matches!(key.name.as_str(), "dependsOn" | "with")
+                && key
+                    .value
+                    .as_array()
+                    .is_some_and(|a| contains(a.range, offset)) =>
+        {
+            CompletionContext::Dependency
+        }
+        Some(key) => key_or_unknown(key, CompletionContext::TaskKey),
     }
 }
 
@@ -1,7 +1,7 @@
 //! Maps changed files to changed packages in a repository.
 //! Used for both `--filter` and for isolated builds.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub use package::{
     DefaultPackageChangeMapper, GlobalDepsPackageChangeMapper, PackageChangeMapper, PackageMapping,
@@ -28,6 +28,21 @@ pub enum PackageChanges {
     Some(HashSet<WorkspacePackage>),
 }
 
+/// The files that changed in each package, for callers that need more than
+/// the set of changed packages, such as matching against task inputs.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PackageFileChanges {
+    All,
+    Some(HashMap<WorkspacePackage, ChangedPackageFiles>),
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ChangedPackageFiles {
+    pub files: HashSet<AnchoredSystemPathBuf>,
+    /// Whether the package's external dependencies changed in the lockfile
+    pub dependencies_changed: bool,
+}
+
 pub struct ChangeMapper<'a, PD> {
     pkg_graph: &'a PackageGraph,
 
@@ -88,6 +103,51 @@ impl<'a, PD: PackageChangeMapper> ChangeMapper<'a, PD> {
         }
     }
 
+    /// Like `changed_packages`, but keeps track of which files changed in
+    /// each package.
+    pub fn changed_package_files(
+        &self,
+        changed_files: HashSet<AnchoredSystemPathBuf>,
+        lockfile_change: Option<LockfileChange>,
+    ) -> Result<PackageFileChanges, ChangeMapError> {
+        if Self::default_global_file_changed(&changed_files) {
+            return Ok(PackageFileChanges::All);
+        }
+
+        let mut changed_pkgs: HashMap<WorkspacePackage, ChangedPackageFiles> = HashMap::new();
+        for file in self.filter_ignored_files(changed_files.iter())? {
+            match self.package_detector.detect_package(file) {
+                PackageMapping::Package(pkg) => {
+                    changed_pkgs
+                        .entry(pkg)
+                        .or_default()
+                        .files
+                        .insert(file.clone());
+                }
+                PackageMapping::All => return Ok(PackageFileChanges::All),
+                PackageMapping::None => {}
+            }
+        }
+
+        match lockfile_change {
+            Some(LockfileChange::WithContent(content)) => {
+                // if we run into issues, don't error, just assume all packages have changed
+                let Ok(lockfile_changes) = self.get_changed_packages_from_lockfile(content) else {
+                    return Ok(PackageFileChanges::All);
+                };
+
+                for pkg in lockfile_changes {
+                    changed_pkgs.entry(pkg).or_default().dependencies_changed = true;
+                }
+
+                Ok(PackageFileChanges::Some(changed_pkgs))
+            }
+            // We don't have the actual contents, so just invalidate everything
+            Some(LockfileChange::Empty) => Ok(PackageFileChanges::All),
+            None => Ok(PackageFileChanges::Some(changed_pkgs)),
+        }
+    }
+
     fn filter_ignored_files<'b>(
         &self,
         changed_files: impl Iterator<Item = &'b AnchoredSystemPathBuf> + 'b,
@@ -173,10 +233,151 @@ impl From<ChangedPackagesError> for ChangeMapError {
 
 #[cfg(test)]
 mod test {
+    use std::collections::{HashMap, HashSet};
+
     use test_case::test_case;
+    use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+
+    use super::{ChangeMapper, ChangedPackageFiles, LockfileChange, PackageFileChanges};
+    use crate::{
+        change_mapper::package::DefaultPackageChangeMapper,
+        discovery::{self, PackageDiscovery},
+        package_graph::{PackageGraph, PackageGraphBuilder, PackageName, WorkspacePackage},
+        package_json::PackageJson,
+    };
+
+    struct MockDiscovery;
+
+    impl PackageDiscovery for MockDiscovery {
+        async fn discover_packages(
+            &self,
+        ) -> Result<discovery::DiscoveryResponse, discovery::Error> {
+            Ok(discovery::DiscoveryResponse {
+                package_manager: crate::package_manager::PackageManager::Npm,
+                workspaces: vec![],
+            })
+        }
+
+        async fn discover_packages_blocking(
+            &self,
+        ) -> Result<discovery::DiscoveryResponse, discovery::Error> {
+            self.discover_packages().await
+        }
+    }
+
+    // A repository with `web` in `apps/web` and `ui` in `packages/ui`
+    async fn package_graph() -> PackageGraph {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { r"C:\repo" } else { "/repo" }).unwrap();
+        let package_jsons = [("web", ["apps", "web"]), ("ui", ["packages", "ui"])]
+            .into_iter()
+            .map(|(name, [parent, dir])| {
+                (
+                    root.join_components(&[parent, dir, "package.json"]),
+                    PackageJson {
+                        name: Some(name.into()),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        PackageGraphBuilder::new(&root, PackageJson::default())
+            .with_package_discovery(MockDiscovery)
+            .with_package_jsons(Some(package_jsons))
+            .build()
+            .await
+            .unwrap()
+    }
+
+    fn files(files: &[&str]) -> HashSet<AnchoredSystemPathBuf> {
+        files
+            .iter()
+            .map(|f| AnchoredSystemPathBuf::from_raw(f).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_changed_package_files_grouped_by_package() {
+        let pkg_graph = package_graph().await;
+        let change_mapper = ChangeMapper::new(
+            &pkg_graph,
+            vec![],
+            DefaultPackageChangeMapper::new(&pkg_graph),
+        );
+
+        let changes = change_mapper
+            .changed_package_files(
+                files(&[
+                    "apps/web/src/index.ts",
+                    "apps/web/README.md",
+                    "packages/ui/button.tsx",
+                ]),
+                None,
+            )
+            .unwrap();
+
+        let package = |name: &str, path: &str| WorkspacePackage {
+            name: PackageName::from(name),
+            path: AnchoredSystemPathBuf::from_raw(path).unwrap(),
+        };
+        assert_eq!(
+            changes,
+            PackageFileChanges::Some(HashMap::from([
+                (
+                    package("web", "apps/web"),
+                    ChangedPackageFiles {
+                        files: files(&["apps/web/src/index.ts", "apps/web/README.md"]),
+                        dependencies_changed: false,
+                    }
+                ),
+                (
+                    package("ui", "packages/ui"),
+                    ChangedPackageFiles {
+                        files: files(&["packages/ui/button.tsx"]),
+                        dependencies_changed: false,
+                    }
+                ),
+            ]))
+        );
+    }
+
+    #[test_case(&["turbo.json"], None ; "global file")]
+    #[test_case(&["README.md"], None ; "file outside of packages")]
+    #[test_case(&["apps/web/src/index.ts"], Some(LockfileChange::Empty) ; "lockfile without contents")]
+    #[tokio::test]
+    async fn test_changed_package_files_all(
+        changed_files: &[&str],
+        lockfile_change: Option<LockfileChange>,
+    ) {
+        let pkg_graph = package_graph().await;
+        let change_mapper = ChangeMapper::new(
+            &pkg_graph,
+            vec![],
+            DefaultPackageChangeMapper::new(&pkg_graph),
+        );
 
-    use super::ChangeMapper;
-    use crate::change_mapper::package::DefaultPackageChangeMapper;
+        let changes = change_mapper
+            .changed_package_files(files(changed_files), lockfile_change)
+            .unwrap();
+
+        assert_eq!(changes, PackageFileChanges::All);
+    }
+
+    #[tokio::test]
+    async fn test_changed_package_files_ignored() {
+        let pkg_graph = package_graph().await;
+        let change_mapper = ChangeMapper::new(
+            &pkg_graph,
+            vec!["**/*.md".to_string()],
+            DefaultPackageChangeMapper::new(&pkg_graph),
+        );
+
+        let changes = change_mapper
+            .changed_package_files(files(&["README.md", "apps/web/README.md"]), None)
+            .unwrap();
+
+        assert_eq!(changes, PackageFileChanges::Some(HashMap::new()));
+    }
 
     #[cfg(unix)]
     #[test_case("/a/b/c", &["package.lock"], "/a/b/c/package.lock", true ; "simple")]
@@ -1,8 +1,15 @@
-use turbopath::AbsoluteSystemPath;
+use std::collections::HashSet;
 
-use crate::package_manager::{Error, PackageManager};
+use serde_json::Value;
+use turbopath::{AbsoluteSystemPath, RelativeUnixPath};
+
+use crate::{
+    package_json::PackageJson,
+    package_manager::{Error, PackageManager},
+};
 
 pub const LOCKFILE: &str = "bun.lockb";
+pub const TEXT_LOCKFILE: &str = "bun.lock";
 
 pub struct BunDetector<'a> {
     repo_root: &'a AbsoluteSystemPath,
@@ -27,14 +34,31 @@ impl<'a> Iterator for BunDetector<'a> {
         }
 
         self.found = true;
-        let package_json = self.repo_root.join_component(LOCKFILE);
+        [TEXT_LOCKFILE, LOCKFILE]
+            .iter()
+            .any(|lockfile| self.repo_root.join_component(lockfile).exists())
+            .then(|| Ok(PackageManager::Bun))
+    }
+}
 
-        if package_json.exists() {
-            Some(Ok(PackageManager::Bun))
-        } else {
-            None
-        }
+pub(crate) fn prune_patches<R: AsRef<RelativeUnixPath>>(
+    package_json: &PackageJson,
+    patches: &[R],
+) -> PackageJson {
+    let mut pruned_json = package_json.clone();
+    let patches = patches.iter().map(|r| r.as_ref()).collect::<HashSet<_>>();
+
+    if let Some(Value::Object(existing_patches)) = pruned_json.other.get_mut("patchedDependencies")
+    {
+        existing_patches.retain(|_, patch_path| {
+            patch_path
+                .as_str()
+                .and_then(|patch_path| RelativeUnixPath::new(patch_path).ok())
+                .is_some_and(|patch_path| patches.contains(patch_path))
+        });
     }
+
+    pruned_json
 }
 
 #[cfg(test)]
@@ -42,22 +66,43 @@ mod tests {
     use std::fs::File;
 
     use anyhow::Result;
+    use serde_json::json;
     use tempfile::tempdir;
-    use turbopath::AbsoluteSystemPathBuf;
+    use test_case::test_case;
+    use turbopath::{AbsoluteSystemPathBuf, RelativeUnixPathBuf};
 
-    use super::LOCKFILE;
-    use crate::package_manager::PackageManager;
+    use super::{prune_patches, LOCKFILE, TEXT_LOCKFILE};
+    use crate::{package_json::PackageJson, package_manager::PackageManager};
 
-    #[test]
-    fn test_detect_bun() -> Result<()> {
+    #[test_case(LOCKFILE ; "binary lockfile")]
+    #[test_case(TEXT_LOCKFILE ; "text lockfile")]
+    fn test_detect_bun(lockfile: &str) -> Result<()> {
         let repo_root = tempdir()?;
         let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
 
-        let lockfile_path = repo_root.path().join(LOCKFILE);
+        let lockfile_path = repo_root.path().join(lockfile);
         File::create(lockfile_path)?;
         let package_manager = PackageManager::detect_package_manager(&repo_root_path)?;
         assert_eq!(package_manager, PackageManager::Bun);
 
         Ok(())
     }
+
+    #[test]
+    fn test_patch_pruning() {
+        let package_json: PackageJson = serde_json::from_value(json!({
+            "name": "bun-patches",
+            "patchedDependencies": {
+                "foo@1.0.0": "patches/foo@1.0.0.patch",
+                "bar@1.2.3": "patches/bar@1.2.3.patch",
+            }
+        }))
+        .unwrap();
+        let patches = vec![RelativeUnixPathBuf::new("patches/foo@1.0.0.patch").unwrap()];
+        let pruned = prune_patches(&package_json, &patches);
+        assert_eq!(
+            pruned.other.get("patchedDependencies"),
+            Some(&json!({"foo@1.0.0": "patches/foo@1.0.0.patch"}))
+        );
+    }
 }
@@ -513,6 +513,9 @@ impl PackageManager {
     ) -> Result<Box<dyn Lockfile>, Error> {
         let lockfile_path = self.lockfile_path(root_path);
         let contents = match self {
+            // The text lockfile can be read directly, but the binary one needs to be
+            // printed by bun
+            PackageManager::Bun if !self.has_binary_lockfile(root_path) => lockfile_path.read()?,
             PackageManager::Bun => {
                 Command::new(which("bun")?)
                     .arg(lockfile_path.to_string())
@@ -539,6 +542,9 @@ impl PackageManager {
             PackageManager::Yarn => {
                 Box::new(turborepo_lockfiles::Yarn1Lockfile::from_bytes(contents)?)
             }
+            PackageManager::Bun if is_text_lockfile(contents) => {
+                Box::new(turborepo_lockfiles::BunTextLockfile::from_bytes(contents)?)
+            }
             PackageManager::Bun => {
                 Box::new(turborepo_lockfiles::BunLockfile::from_bytes(contents)?)
             }
@@ -565,14 +571,32 @@ impl PackageManager {
             PackageManager::Pnpm6 | PackageManager::Pnpm => {
                 pnpm::prune_patches(package_json, patches)
             }
-            PackageManager::Yarn | PackageManager::Npm | PackageManager::Bun => {
-                unreachable!("bun, npm, and yarn 1 don't have a concept of patches")
+            PackageManager::Bun => bun::prune_patches(package_json, patches),
+            PackageManager::Yarn | PackageManager::Npm => {
+                unreachable!("npm and yarn 1 don't have a concept of patches")
             }
         }
     }
 
     pub fn lockfile_path(&self, turbo_root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
-        turbo_root.join_component(self.lockfile_name())
+        match self {
+            // Bun prefers the text lockfile if both exist
+            PackageManager::Bun => {
+                let text_lockfile = turbo_root.join_component(bun::TEXT_LOCKFILE);
+                match text_lockfile.exists() {
+                    true => text_lockfile,
+                    false => turbo_root.join_component(bun::LOCKFILE),
+                }
+            }
+            _ => turbo_root.join_component(self.lockfile_name()),
+        }
+    }
+
+    /// Bun's binary lockfile can be read, but not written, so commands that
+    /// need to write a lockfile require the text lockfile instead.
+    pub fn has_binary_lockfile(&self, turbo_root: &AbsoluteSystemPath) -> bool {
+        matches!(self, PackageManager::Bun)
+            && self.lockfile_path(turbo_root).file_name() == Some(bun::LOCKFILE)
     }
 
     pub fn arg_separator(&self, user_args: &[String]) -> Option<&str> {
@@ -594,6 +618,15 @@ impl PackageManager {
     }
 }
 
+// Bun's text lockfile is JSON while the binary lockfile is printed in the yarn
+// v1 format
+fn is_text_lockfile(contents: &[u8]) -> bool {
+    contents
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| *byte == b'{')
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashSet, fs::File};
@@ -143,7 +143,7 @@ pub(crate) fn wait_for_success<R: Read, T>(
     Err(Error::Git(err_text, Backtrace::capture()))
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Git {
     root: AbsoluteSystemPathBuf,
     bin: AbsoluteSystemPathBuf,
@@ -207,7 +207,7 @@ fn find_git_root(turbo_root: &AbsoluteSystemPath) -> Result<AbsoluteSystemPathBu
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SCM {
     Git(Git),
     Manual,
@@ -16,13 +16,18 @@ const DEFAULT_APP_HEIGHT: u16 = 60;
 const PANE_SIZE_RATIO: f32 = 3.0 / 4.0;
 const FRAMERATE: Duration = Duration::from_millis(3);
 
-use super::{input, AppReceiver, Error, Event, TaskTable, TerminalPane};
+use super::{input, AppReceiver, Error, Event, TaskRequest, TaskTable, TerminalPane, Timeline};
 
 pub struct App<I> {
     table: TaskTable,
     pane: TerminalPane<I>,
+    timeline: Timeline,
+    // If the timeline is shown instead of the task table and output
+    show_timeline: bool,
     done: bool,
-    interact: bool,
+    mode: InputMode,
+    // The filter or search being typed
+    query: String,
 }
 
 pub enum Direction {
@@ -30,14 +35,30 @@ pub enum Direction {
     Down,
 }
 
+/// What key presses are used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputMode {
+    /// Keys navigate between tasks
+    Navigate,
+    /// Keys are forwarded to the selected task
+    Interact,
+    /// Keys edit the filter of the task table
+    Filter,
+    /// Keys edit the search of the selected task's output
+    Search,
+}
+
 impl<I> App<I> {
-    pub fn new(rows: u16, cols: u16, tasks: Vec<String>) -> Self {
+    pub fn new(rows: u16, cols: u16, tasks: Vec<String>, timeline: Timeline) -> Self {
         debug!("tasks: {tasks:?}");
         let mut this = Self {
             table: TaskTable::new(tasks.clone()),
             pane: TerminalPane::new(rows, cols, tasks),
+            timeline,
+            show_timeline: false,
             done: false,
-            interact: false,
+            mode: InputMode::Navigate,
+            query: String::new(),
         };
         // Start with first task selected
         this.next();
@@ -45,29 +66,141 @@ impl<I> App<I> {
     }
 
     pub fn next(&mut self) {
-        self.table.next();
+        if self.show_timeline {
+            self.timeline_select(Direction::Down);
+        } else {
+            self.table.next();
+        }
         if let Some(task) = self.table.selected() {
             self.pane.select(task).unwrap();
         }
     }
 
     pub fn previous(&mut self) {
-        self.table.previous();
+        if self.show_timeline {
+            self.timeline_select(Direction::Up);
+        } else {
+            self.table.previous();
+        }
         if let Some(task) = self.table.selected() {
             self.pane.select(task).unwrap();
         }
     }
 
+    pub fn toggle_timeline(&mut self) {
+        self.show_timeline = !self.show_timeline;
+    }
+
+    pub fn export_timeline(&mut self) {
+        self.timeline
+            .export(&self.table.spans(), self.table.elapsed());
+    }
+
+    // The timeline is ordered by when tasks started instead of the order of the
+    // table
+    fn timeline_select(&mut self, direction: Direction) {
+        let spans = self.table.spans();
+        if let Some(task) = Timeline::neighbor(&spans, self.table.selected(), direction) {
+            let task = task.to_string();
+            self.table.select(&task);
+        }
+    }
+
     pub fn interact(&mut self, interact: bool) {
         let Some(selected_task) = self.table.selected() else {
             return;
         };
         if self.pane.has_stdin(selected_task) {
-            self.interact = interact;
+            self.mode = if interact {
+                InputMode::Interact
+            } else {
+                InputMode::Navigate
+            };
             self.pane.highlight(interact);
         }
     }
 
+    pub fn enter_query(&mut self, mode: InputMode) {
+        if self.mode != InputMode::Navigate {
+            return;
+        }
+        // Search only makes sense if there's a task output to search
+        if mode == InputMode::Search && self.table.selected().is_none() {
+            return;
+        }
+        self.mode = mode;
+        self.query.clear();
+        self.update_query();
+    }
+
+    pub fn edit_query(&mut self, edit: impl FnOnce(&mut String)) {
+        if !matches!(self.mode, InputMode::Filter | InputMode::Search) {
+            return;
+        }
+        edit(&mut self.query);
+        self.update_query();
+    }
+
+    /// Keep the current filter or search and go back to navigating
+    pub fn confirm_query(&mut self) {
+        if matches!(self.mode, InputMode::Filter | InputMode::Search) {
+            self.mode = InputMode::Navigate;
+        }
+    }
+
+    /// Clear the current filter or search, when navigating both are cleared
+    pub fn cancel_query(&mut self) {
+        match self.mode {
+            InputMode::Filter => self.set_filter(None),
+            InputMode::Search => self.pane.clear_search(),
+            InputMode::Navigate => {
+                self.set_filter(None);
+                self.pane.clear_search();
+            }
+            InputMode::Interact => return,
+        }
+        self.mode = InputMode::Navigate;
+    }
+
+    pub fn next_match(&mut self, direction: Direction) {
+        let Some(selected_task) = self.table.selected() else {
+            return;
+        };
+        self.pane
+            .next_match(selected_task, direction)
+            .expect("selected task should be in pane");
+    }
+
+    // Apply the query as it's typed
+    fn update_query(&mut self) {
+        match self.mode {
+            InputMode::Filter => {
+                let filter = (!self.query.is_empty()).then(|| self.query.clone());
+                self.set_filter(filter);
+            }
+            InputMode::Search => {
+                let Some(selected_task) = self.table.selected() else {
+                    return;
+                };
+                if self.query.is_empty() {
+                    self.pane.clear_search();
+                } else {
+                    self.pane
+                        .search(selected_task, &self.query)
+                        .expect("selected task should be in pane");
+                }
+            }
+            InputMode::Navigate | InputMode::Interact => (),
+        }
+    }
+
+    fn set_filter(&mut self, filter: Option<String>) {
+        self.table.set_filter(filter);
+        if let Some(task) = self.table.selected() {
+            self.pane.select(task).unwrap();
+        }
+    }
+
     pub fn scroll(&mut self, direction: Direction) {
         let Some(selected_task) = self.table.selected() else {
             return;
@@ -85,7 +218,7 @@ impl<I> App<I> {
 impl<I: std::io::Write> App<I> {
     pub fn forward_input(&mut self, bytes: &[u8]) -> Result<(), Error> {
         // If we aren't in interactive mode, ignore input
-        if !self.interact {
+        if self.mode != InputMode::Interact {
             return Ok(());
         }
         let selected_task = self
@@ -99,12 +232,12 @@ impl<I: std::io::Write> App<I> {
 
 /// Handle the rendering of the `App` widget based on events received by
 /// `receiver`
-pub fn run_app(tasks: Vec<String>, receiver: AppReceiver) -> Result<(), Error> {
+pub fn run_app(tasks: Vec<String>, timeline: Timeline, receiver: AppReceiver) -> Result<(), Error> {
     let (mut terminal, app_height) = startup()?;
     let size = terminal.size()?;
 
     let pane_height = (f32::from(app_height) * PANE_SIZE_RATIO) as u16;
-    let app: App<Box<dyn io::Write + Send>> = App::new(pane_height, size.width, tasks);
+    let app: App<Box<dyn io::Write + Send>> = App::new(pane_height, size.width, tasks, timeline);
 
     let result = run_app_inner(&mut terminal, app, receiver);
 
@@ -124,8 +257,8 @@ fn run_app_inner<B: Backend>(
     terminal.draw(|f| view(&mut app, f))?;
     let mut last_render = Instant::now();
 
-    while let Some(event) = poll(app.interact, &receiver, last_render + FRAMERATE) {
-        if let Some(message) = update(terminal, &mut app, event)? {
+    while let Some(event) = poll(app.mode, &receiver, last_render + FRAMERATE) {
+        if let Some(message) = update(terminal, &mut app, &receiver, event)? {
             persist_bytes(terminal, &message)?;
         }
         if app.done {
@@ -145,8 +278,8 @@ fn run_app_inner<B: Backend>(
 
 /// Blocking poll for events, will only return None if app handle has been
 /// dropped
-fn poll(interact: bool, receiver: &AppReceiver, deadline: Instant) -> Option<Event> {
-    match input(interact) {
+fn poll(mode: InputMode, receiver: &AppReceiver, deadline: Instant) -> Option<Event> {
+    match input(mode) {
         Ok(Some(event)) => Some(event),
         Ok(None) => receiver.recv(deadline).ok(),
         // Unable to read from stdin, shut down and attempt to clean up
@@ -190,6 +323,7 @@ fn cleanup<B: Backend + io::Write>(mut terminal: Terminal<B>) -> io::Result<()>
 fn update<B: Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App<Box<dyn io::Write + Send>>,
+    receiver: &AppReceiver,
     event: Event,
 ) -> Result<Option<Vec<u8>>, Error> {
     match event {
@@ -208,10 +342,14 @@ fn update<B: Backend>(
         Event::Log { message } => {
             return Ok(Some(message));
         }
-        Event::EndTask { task } => {
-            app.table.finish_task(&task)?;
+        Event::EndTask { task, result } => {
+            app.table.finish_task(&task, result)?;
             app.pane.render_screen(&task, terminal)?;
         }
+        Event::RestartTask { task } => {
+            app.table.restart_task(&task)?;
+            app.pane.restart(&task)?;
+        }
         Event::Up => {
             app.previous();
         }
@@ -236,11 +374,79 @@ fn update<B: Backend>(
         Event::SetStdin { task, stdin } => {
             app.pane.insert_stdin(&task, Some(stdin))?;
         }
+        Event::EnterFilter => {
+            app.enter_query(InputMode::Filter);
+        }
+        Event::EnterSearch => {
+            app.enter_query(InputMode::Search);
+        }
+        Event::QueryInput { c } => {
+            app.edit_query(|query| query.push(c));
+        }
+        Event::QueryBackspace => {
+            app.edit_query(|query| {
+                query.pop();
+            });
+        }
+        Event::ConfirmQuery => {
+            app.confirm_query();
+        }
+        Event::CancelQuery => {
+            app.cancel_query();
+        }
+        // Searches start from the most recent output, so the next match is older
+        Event::NextMatch => {
+            app.next_match(Direction::Up);
+        }
+        Event::PreviousMatch => {
+            app.next_match(Direction::Down);
+        }
+        Event::RestartSelected => {
+            // Tasks that haven't started yet will be run by the run itself
+            if let Some(task) = app
+                .table
+                .selected()
+                .filter(|task| app.table.tasks_started().any(|t| t == *task))
+            {
+                receiver.request(TaskRequest::Restart {
+                    task: task.to_string(),
+                });
+            }
+        }
+        Event::ToggleTimeline => {
+            app.toggle_timeline();
+        }
+        Event::ExportTimeline => {
+            app.export_timeline();
+        }
+        Event::StopSelected => {
+            if let Some(task) = app
+                .table
+                .selected()
+                .filter(|task| app.table.is_running(task))
+            {
+                receiver.request(TaskRequest::Stop {
+                    task: task.to_string(),
+                });
+            }
+        }
     }
     Ok(None)
 }
 
 fn view<I>(app: &mut App<I>, f: &mut Frame) {
+    if app.show_timeline {
+        let spans = app.table.spans();
+        let area = f.size();
+        app.timeline.render(
+            &spans,
+            app.table.elapsed(),
+            app.table.selected(),
+            area,
+            f.buffer_mut(),
+        );
+        return;
+    }
     let (term_height, _) = app.term_size();
     let vertical = Layout::vertical([Constraint::Min(5), Constraint::Length(term_height)]);
     let [table, pane] = vertical.areas(f.size());
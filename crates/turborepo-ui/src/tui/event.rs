@@ -1,3 +1,5 @@
+use super::task::TaskResult;
+
 pub enum Event {
     StartTask {
         task: String,
@@ -8,6 +10,10 @@ pub enum Event {
     },
     EndTask {
         task: String,
+        result: TaskResult,
+    },
+    RestartTask {
+        task: String,
     },
     Stop,
     Tick,
@@ -27,6 +33,23 @@ pub enum Event {
     Input {
         bytes: Vec<u8>,
     },
+    // Filtering the task table and searching the output of the selected task
+    EnterFilter,
+    EnterSearch,
+    QueryInput {
+        c: char,
+    },
+    QueryBackspace,
+    ConfirmQuery,
+    CancelQuery,
+    NextMatch,
+    PreviousMatch,
+    // Restarting and stopping the selected task
+    RestartSelected,
+    StopSelected,
+    // Showing when tasks ran
+    ToggleTimeline,
+    ExportTimeline,
 }
 
 #[cfg(test)]
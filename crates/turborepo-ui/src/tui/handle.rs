@@ -3,7 +3,7 @@ use std::{
     time::Instant,
 };
 
-use super::Event;
+use super::{Event, TaskResult};
 use crate::LineWriter;
 
 /// Struct for sending app events to TUI rendering
@@ -15,6 +15,21 @@ pub struct AppSender {
 /// Struct for receiving app events
 pub struct AppReceiver {
     primary: mpsc::Receiver<Event>,
+    requests: mpsc::Sender<TaskRequest>,
+}
+
+/// Struct for receiving the requests the user makes to control tasks
+pub struct TaskRequests {
+    requests: mpsc::Receiver<TaskRequest>,
+}
+
+/// A request made by the user to control a task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskRequest {
+    /// Stop the task if it's running and then run it again
+    Restart { task: String },
+    /// Stop the task if it's running
+    Stop { task: String },
 }
 
 /// Struct for sending events related to a specific task
@@ -41,14 +56,20 @@ impl AppSender {
     ///
     /// AppSender is meant to be held by the actual task runner
     /// AppReceiver should be passed to `crate::tui::run_app`
-    pub fn new() -> (Self, AppReceiver) {
+    /// TaskRequests should be used by the task runner to restart or stop tasks
+    pub fn new() -> (Self, AppReceiver, TaskRequests) {
         let (primary_tx, primary_rx) = mpsc::channel();
+        let (requests_tx, requests_rx) = mpsc::channel();
         (
             Self {
                 primary: primary_tx,
             },
             AppReceiver {
                 primary: primary_rx,
+                requests: requests_tx,
+            },
+            TaskRequests {
+                requests: requests_rx,
             },
         )
     }
@@ -80,6 +101,28 @@ impl AppReceiver {
             Err(mpsc::RecvTimeoutError::Disconnected) => Err(mpsc::RecvError),
         }
     }
+
+    /// Send a request to the task runner, if the task runner isn't listening
+    /// for requests it's ignored
+    pub fn request(&self, request: TaskRequest) {
+        self.requests.send(request).ok();
+    }
+}
+
+impl TaskRequests {
+    /// Blocks until the user makes a request, returns `None` once the app has
+    /// stopped.
+    pub fn recv(&self) -> Option<TaskRequest> {
+        self.requests.recv().ok()
+    }
+}
+
+impl TaskRequest {
+    pub fn task(&self) -> &str {
+        match self {
+            TaskRequest::Restart { task } | TaskRequest::Stop { task } => task,
+        }
+    }
 }
 
 impl TuiTask {
@@ -98,12 +141,24 @@ impl TuiTask {
             .ok();
     }
 
+    /// Mark the task as running again after it was restarted, its previous
+    /// output is kept
+    pub fn restart(&self) {
+        self.handle
+            .primary
+            .send(Event::RestartTask {
+                task: self.name.clone(),
+            })
+            .ok();
+    }
+
     /// Mark the task as finished
-    pub fn finish(&self) -> Vec<u8> {
+    pub fn finish(&self, result: TaskResult) -> Vec<u8> {
         self.handle
             .primary
             .send(Event::EndTask {
                 task: self.name.clone(),
+                result,
             })
             .ok();
         self.logs.lock().expect("logs lock poisoned").clone()
@@ -2,15 +2,15 @@ use std::time::Duration;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
-use super::{event::Event, Error};
+use super::{app::InputMode, event::Event, Error};
 
 /// Return any immediately available event
-pub fn input(interact: bool) -> Result<Option<Event>, Error> {
+pub fn input(mode: InputMode) -> Result<Option<Event>, Error> {
     // poll with 0 duration will only return true if event::read won't need to wait
     // for input
     if crossterm::event::poll(Duration::from_millis(0))? {
         match crossterm::event::read()? {
-            crossterm::event::Event::Key(k) => Ok(translate_key_event(interact, k)),
+            crossterm::event::Event::Key(k) => Ok(translate_key_event(mode, k)),
             crossterm::event::Event::Mouse(m) => match m.kind {
                 crossterm::event::MouseEventKind::ScrollDown => Ok(Some(Event::ScrollDown)),
                 crossterm::event::MouseEventKind::ScrollUp => Ok(Some(Event::ScrollUp)),
@@ -24,7 +24,7 @@ pub fn input(interact: bool) -> Result<Option<Event>, Error> {
 }
 
 /// Converts a crostterm key event into a TUI interaction event
-fn translate_key_event(interact: bool, key_event: KeyEvent) -> Option<Event> {
+fn translate_key_event(mode: InputMode, key_event: KeyEvent) -> Option<Event> {
     // On Windows events for releasing a key are produced
     // We skip these to avoid emitting 2 events per key press.
     // There is still a `Repeat` event for when a key is held that will pass through
@@ -32,10 +32,20 @@ fn translate_key_event(interact: bool, key_event: KeyEvent) -> Option<Event> {
     if key_event.kind == KeyEventKind::Release {
         return None;
     }
+    let interact = mode == InputMode::Interact;
+    let query = matches!(mode, InputMode::Filter | InputMode::Search);
     match key_event.code {
         KeyCode::Char('c') if key_event.modifiers == crossterm::event::KeyModifiers::CONTROL => {
             ctrl_c()
         }
+        // Filter and search branches, keys are used to edit the query
+        KeyCode::Enter if query => Some(Event::ConfirmQuery),
+        KeyCode::Esc if query => Some(Event::CancelQuery),
+        KeyCode::Backspace if query => Some(Event::QueryBackspace),
+        KeyCode::Char(c) if query && !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Event::QueryInput { c })
+        }
+        _ if query => None,
         // Interactive branches
         KeyCode::Char('z')
             if interact && key_event.modifiers == crossterm::event::KeyModifiers::CONTROL =>
@@ -54,6 +64,15 @@ fn translate_key_event(interact: bool, key_event: KeyEvent) -> Option<Event> {
         KeyCode::Up => Some(Event::Up),
         KeyCode::Down => Some(Event::Down),
         KeyCode::Enter => Some(Event::EnterInteractive),
+        KeyCode::Char('/') => Some(Event::EnterFilter),
+        KeyCode::Char('s') => Some(Event::EnterSearch),
+        KeyCode::Char('n') => Some(Event::NextMatch),
+        KeyCode::Char('N') => Some(Event::PreviousMatch),
+        KeyCode::Char('r') => Some(Event::RestartSelected),
+        KeyCode::Char('x') => Some(Event::StopSelected),
+        KeyCode::Char('t') => Some(Event::ToggleTimeline),
+        KeyCode::Char('e') => Some(Event::ExportTimeline),
+        KeyCode::Esc => Some(Event::CancelQuery),
         _ => None,
     }
 }
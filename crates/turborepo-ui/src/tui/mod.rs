@@ -6,13 +6,18 @@ mod pane;
 mod table;
 mod task;
 mod task_duration;
+mod timeline;
 
 pub use app::run_app;
 use event::Event;
-pub use handle::{AppReceiver, AppSender, PersistedWriterInner, TuiTask};
+pub use handle::{
+    AppReceiver, AppSender, PersistedWriterInner, TaskRequest, TaskRequests, TuiTask,
+};
 use input::input;
 pub use pane::TerminalPane;
 pub use table::TaskTable;
+pub use task::{CacheSource, TaskResult};
+pub use timeline::{TaskSpan, Timeline};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -5,7 +5,8 @@ use std::{
 
 use ratatui::{
     backend::Backend,
-    style::Style,
+    layout::Rect,
+    style::{Modifier, Style},
     widgets::{
         block::{Position, Title},
         Block, Borders, Widget,
@@ -24,6 +25,16 @@ pub struct TerminalPane<W> {
     rows: u16,
     cols: u16,
     highlight: bool,
+    search: Option<Search>,
+}
+
+// A search through the output of the displayed task
+struct Search {
+    query: String,
+    // Rows containing the query, counted from the oldest row in the scrollback
+    matches: Vec<usize>,
+    // Index into `matches` of the match being shown
+    current: Option<usize>,
 }
 
 struct TerminalOutput<W> {
@@ -48,6 +59,7 @@ impl<W> TerminalPane<W> {
             rows,
             cols,
             highlight: false,
+            search: None,
         }
     }
 
@@ -95,11 +107,68 @@ impl<W> TerminalPane<W> {
             let terminal = self.task_mut(task)?;
             terminal.resize(rows, cols);
         }
+        if self.displayed.as_deref() != Some(task) {
+            self.search = None;
+        }
         self.displayed = Some(task.into());
 
         Ok(())
     }
 
+    /// Mark the output of the task as being from a new run of the task, the
+    /// output of previous runs is kept above the marker
+    pub fn restart(&mut self, task: &str) -> Result<(), Error> {
+        let cols = usize::from(self.cols);
+        let terminal = self.task_mut(task)?;
+        // Input is sent to the new process once its stdin is set
+        terminal.stdin = None;
+        let marker = format!("{:─^cols$}", " restarted ");
+        terminal
+            .parser
+            .process(format!("\x1b[0m\r\n\x1b[2m{marker}\x1b[0m\r\n").as_bytes());
+        Ok(())
+    }
+
+    /// Search the output of the task for `query` and scroll to the most
+    /// recent match
+    pub fn search(&mut self, task: &str, query: &str) -> Result<(), Error> {
+        let terminal = self.task_mut(task)?;
+        let matches = terminal.parser.screen().search(query);
+        let current = matches.len().checked_sub(1);
+        if let Some(row) = current.map(|i| matches[i]) {
+            terminal.parser.screen_mut().scroll_to_row(row);
+        }
+        self.search = Some(Search {
+            query: query.to_string(),
+            matches,
+            current,
+        });
+        Ok(())
+    }
+
+    /// Move to the next match of the current search, `Direction::Up` moves
+    /// to older output and `Direction::Down` to newer output.
+    pub fn next_match(&mut self, task: &str, direction: Direction) -> Result<(), Error> {
+        let Some(search) = &mut self.search else {
+            return Ok(());
+        };
+        let (Some(current), len) = (search.current, search.matches.len()) else {
+            return Ok(());
+        };
+        let next = match direction {
+            Direction::Up => current.checked_sub(1).unwrap_or(len - 1),
+            Direction::Down => (current + 1) % len,
+        };
+        search.current = Some(next);
+        let row = search.matches[next];
+        self.task_mut(task)?.parser.screen_mut().scroll_to_row(row);
+        Ok(())
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
     pub fn scroll(&mut self, task: &str, direction: Direction) -> Result<(), Error> {
         let task = self.task_mut(task)?;
         let scrollback = task.parser.screen().scrollback();
@@ -221,14 +290,38 @@ impl<W> Widget for &TerminalPane<W> {
             return;
         };
         let screen = task.parser.screen();
-        let mut block = Block::default()
-            .borders(Borders::ALL)
-            .title(format!(" {task_name} >"));
+        let title = match &self.search {
+            Some(search) => match search.current {
+                Some(current) => format!(
+                    " {task_name} > /{} ({}/{}) ",
+                    search.query,
+                    current + 1,
+                    search.matches.len()
+                ),
+                None => format!(" {task_name} > /{} (no matches) ", search.query),
+            },
+            None => format!(" {task_name} >"),
+        };
+        let mut block = Block::default().borders(Borders::ALL).title(title);
         if self.highlight {
             block = block.border_style(Style::new().fg(ratatui::style::Color::Yellow));
         }
+        let inner = block.inner(area);
         let term = PseudoTerminal::new(screen).block(block);
-        term.render(area, buf)
+        term.render(area, buf);
+
+        // Highlight the row of the current match if it's in view
+        let current_row = self
+            .search
+            .as_ref()
+            .and_then(|search| search.matches.get(search.current?))
+            .and_then(|row| screen.visible_row_index(*row));
+        if let Some(row) = current_row.filter(|row| *row < inner.height) {
+            buf.set_style(
+                Rect::new(inner.x, inner.y + row, inner.width, 1),
+                Style::new().add_modifier(Modifier::REVERSED),
+            );
+        }
     }
 }
 
@@ -265,4 +358,48 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn test_restart() {
+        let mut pane: TerminalPane<()> = TerminalPane::new(5, 17, vec!["foo".into()]);
+        pane.select("foo").unwrap();
+        pane.process_output("foo", b"crashed").unwrap();
+        pane.restart("foo").unwrap();
+        pane.process_output("foo", b"again").unwrap();
+
+        assert_eq!(
+            pane.tasks["foo"].parser.screen().contents(),
+            "crashed\n── restarted ──\nagain"
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let mut pane: TerminalPane<()> = TerminalPane::new(4, 8, vec!["foo".into()]);
+        pane.select("foo").unwrap();
+        pane.process_output("foo", b"a\r\nwarn 1\r\nb\r\nwarn 2\r\nc\r\nd")
+            .unwrap();
+
+        pane.search("foo", "WARN").unwrap();
+        let search = pane.search.as_ref().unwrap();
+        assert_eq!(search.matches, vec![1, 3]);
+        assert_eq!(search.current, Some(1));
+        assert_eq!(pane.tasks["foo"].parser.screen().scrollback(), 1);
+
+        pane.next_match("foo", Direction::Up).unwrap();
+        assert_eq!(pane.search.as_ref().unwrap().current, Some(0));
+        assert_eq!(pane.tasks["foo"].parser.screen().scrollback(), 3);
+        assert_eq!(pane.tasks["foo"].parser.screen().contents(), "warn 1\nb");
+
+        // Moving past the newest match wraps around to the oldest
+        pane.next_match("foo", Direction::Up).unwrap();
+        assert_eq!(pane.search.as_ref().unwrap().current, Some(1));
+        pane.next_match("foo", Direction::Down).unwrap();
+        assert_eq!(pane.search.as_ref().unwrap().current, Some(0));
+
+        pane.search("foo", "error").unwrap();
+        assert_eq!(pane.search.as_ref().unwrap().current, None);
+        pane.next_match("foo", Direction::Down).unwrap();
+        assert_eq!(pane.search.as_ref().unwrap().current, None);
+    }
 }
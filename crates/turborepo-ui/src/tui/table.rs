@@ -1,10 +1,10 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
     style::{Color, Style},
-    text::Line,
+    text::{Line, Span},
     widgets::{
         Block, BorderType, Borders, Cell, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
     },
@@ -12,24 +12,27 @@ use ratatui::{
 use tracing::debug;
 
 use super::{
-    task::{Finished, Planned, Running, Task},
+    task::{CacheSource, Finished, Planned, Running, Task, TaskResult},
     task_duration::TaskDuration,
+    timeline::TaskSpan,
     Error,
 };
 
 const FOOTER_TEXT: &str = "Use arrow keys to navigate. Press `Enter` to interact with a task and \
-                           `Ctrl-Z` to stop interacting";
+                           `Ctrl-Z` to stop interacting. Press `/` to filter tasks and `s` to \
+                           search the output of a task. Press `r` to restart a task and `x` to \
+                           stop it. Press `t` to show the timeline";
 
 /// A widget that renders a table of their tasks and their current status
 ///
 /// The table contains finished tasks, running tasks, and planned tasks rendered
-/// in that order.
+/// in that order. Only the tasks whose name contains the filter are shown.
 pub struct TaskTable {
     // Start of the run and the current time
     start: Instant,
     current: Instant,
     // Tasks to be displayed
-    // Ordered by when they finished
+    // Ordered by when they finished, with failed tasks first
     finished: Vec<Task<Finished>>,
     // Ordered by when they started
     running: Vec<Task<Running>>,
@@ -38,6 +41,7 @@ pub struct TaskTable {
     // State used for showing things
     task_column_width: u16,
     scroll: TableState,
+    filter: Option<String>,
 }
 
 impl TaskTable {
@@ -61,12 +65,13 @@ impl TaskTable {
             finished: Vec::new(),
             task_column_width,
             scroll: TableState::default(),
+            filter: None,
         }
     }
 
     /// Number of rows in the table
     pub fn len(&self) -> usize {
-        self.finished.len() + self.running.len() + self.planned.len()
+        self.rows().count()
     }
 
     /// If there are no tasks in the table
@@ -84,30 +89,19 @@ impl TaskTable {
                 debug!("could not find '{task}' to start");
                 Error::TaskNotFound { name: task.into() }
             })?;
+        let selected = self.selected().map(str::to_owned);
         let planned = self.planned.remove(planned_idx);
-        let old_row_idx = self.finished.len() + self.running.len() + planned_idx;
-        let new_row_idx = self.finished.len() + self.running.len();
         let running = planned.start();
         self.running.push(running);
 
-        if let Some(selected_idx) = self.scroll.selected() {
-            // If task that was just started is selected, then update selection to follow
-            // task
-            if selected_idx == old_row_idx {
-                self.scroll.select(Some(new_row_idx));
-            } else if new_row_idx <= selected_idx && selected_idx < old_row_idx {
-                // If the selected task is between the old and new row positions
-                // then increment the selection index to keep selection the same.
-                self.scroll.select(Some(selected_idx + 1));
-            }
-        }
+        self.reselect(selected.as_deref());
         self.tick();
         Ok(())
     }
 
     /// Mark the given running task as finished
     /// Errors if given task wasn't a running task
-    pub fn finish_task(&mut self, task: &str) -> Result<(), Error> {
+    pub fn finish_task(&mut self, task: &str, result: TaskResult) -> Result<(), Error> {
         let running_idx = self
             .running
             .iter()
@@ -116,27 +110,115 @@ impl TaskTable {
                 debug!("could not find '{task}' to finish");
                 Error::TaskNotFound { name: task.into() }
             })?;
-        let old_row_idx = self.finished.len() + running_idx;
-        let new_row_idx = self.finished.len();
+        let selected = self.selected().map(str::to_owned);
         let running = self.running.remove(running_idx);
-        self.finished.push(running.finish());
-
-        if let Some(selected_row) = self.scroll.selected() {
-            // If task that was just started is selected, then update selection to follow
-            // task
-            if selected_row == old_row_idx {
-                self.scroll.select(Some(new_row_idx));
-            } else if new_row_idx <= selected_row && selected_row < old_row_idx {
-                // If the selected task is between the old and new row positions then increment
-                // the selection index to keep selection the same.
-                self.scroll.select(Some(selected_row + 1));
-            }
+        // Failed tasks are listed first so they can be found without scrolling
+        let finished_idx = match result {
+            TaskResult::Failure => self
+                .finished
+                .iter()
+                .take_while(|task| task.result() == TaskResult::Failure)
+                .count(),
+            _ => self.finished.len(),
+        };
+        self.finished.insert(finished_idx, running.finish(result));
+
+        self.reselect(selected.as_deref());
+        self.tick();
+        Ok(())
+    }
+
+    /// Mark the given task as running again after it was restarted
+    /// Errors if given task hasn't been started
+    pub fn restart_task(&mut self, task: &str) -> Result<(), Error> {
+        let selected = self.selected().map(str::to_owned);
+        if let Some(finished_idx) = self.finished.iter().position(|t| t.name() == task) {
+            let finished = self.finished.remove(finished_idx);
+            self.running.push(finished.restart());
+        } else if let Some(running) = self.running.iter_mut().find(|t| t.name() == task) {
+            *running = Task::new(task.to_string()).start();
+        } else {
+            debug!("could not find '{task}' to restart");
+            return Err(Error::TaskNotFound { name: task.into() });
         }
 
+        self.reselect(selected.as_deref());
         self.tick();
         Ok(())
     }
 
+    /// When each task that has started ran, ordered by when they started
+    pub fn spans(&self) -> Vec<TaskSpan> {
+        let mut spans = self
+            .finished
+            .iter()
+            .map(|task| TaskSpan {
+                name: task.name(),
+                start: task.start().duration_since(self.start),
+                end: Some(task.end().duration_since(self.start)),
+                result: Some(task.result()),
+            })
+            .chain(self.running.iter().map(|task| TaskSpan {
+                name: task.name(),
+                start: task.start().duration_since(self.start),
+                end: None,
+                result: None,
+            }))
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|span| span.start);
+        spans
+    }
+
+    /// How long the run has been going
+    pub fn elapsed(&self) -> Duration {
+        self.current.duration_since(self.start)
+    }
+
+    /// Select the given task, if it isn't shown the first task is selected
+    pub fn select(&mut self, task: &str) {
+        self.reselect(Some(task));
+    }
+
+    pub fn is_running(&self, task: &str) -> bool {
+        self.running.iter().any(|t| t.name() == task)
+    }
+
+    /// Only show the tasks whose name contains `filter`, ignoring case
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        let selected = self.selected().map(str::to_owned);
+        self.filter = filter;
+        self.reselect(selected.as_deref());
+    }
+
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    // Keeps the given task selected as rows move around, falling back to the
+    // first row if it's no longer shown
+    fn reselect(&mut self, task: Option<&str>) {
+        let row = task
+            .and_then(|task| self.rows().position(|row| row == task))
+            .or_else(|| (!self.is_empty()).then_some(0));
+        self.scroll.select(row);
+    }
+
+    fn is_shown(&self, task: &str) -> bool {
+        self.filter.as_deref().map_or(true, |filter| {
+            task.to_lowercase().contains(&filter.to_lowercase())
+        })
+    }
+
+    // The names of the tasks shown in the table, in the order they're shown
+    fn rows(&self) -> impl Iterator<Item = &str> + '_ {
+        self.finished
+            .iter()
+            .map(|task| task.name())
+            .chain(self.running.iter().map(|task| task.name()))
+            .chain(self.planned.iter().map(|task| task.name()))
+            .filter(|task| self.is_shown(task))
+    }
+
     /// Update the current time of the table
     pub fn tick(&mut self) {
         self.current = Instant::now();
@@ -145,6 +227,10 @@ impl TaskTable {
     /// Select the next row
     pub fn next(&mut self) {
         let num_rows = self.len();
+        if num_rows == 0 {
+            self.scroll.select(None);
+            return;
+        }
         let i = match self.scroll.selected() {
             Some(i) => (i + 1).clamp(0, num_rows - 1),
             None => 0,
@@ -164,20 +250,7 @@ impl TaskTable {
 
     pub fn selected(&self) -> Option<&str> {
         let i = self.scroll.selected()?;
-        if i < self.finished.len() {
-            let task = self.finished.get(i)?;
-            Some(task.name())
-        } else if i < self.finished.len() + self.running.len() {
-            let task = self.running.get(i - self.finished.len())?;
-            Some(task.name())
-        } else if i < self.finished.len() + self.running.len() + self.planned.len() {
-            let task = self
-                .planned
-                .get(i - (self.finished.len() + self.running.len()))?;
-            Some(task.name())
-        } else {
-            None
-        }
+        self.rows().nth(i)
     }
 
     pub fn tasks_started(&self) -> impl Iterator<Item = &str> + '_ {
@@ -187,43 +260,64 @@ impl TaskTable {
             .chain(self.running.iter().map(|task| task.name()))
     }
 
-    fn finished_rows(&self, duration_width: u16) -> impl Iterator<Item = Row> + '_ {
-        self.finished.iter().map(move |task| {
-            Row::new(vec![
-                Cell::new(task.name()),
-                Cell::new(TaskDuration::new(
-                    duration_width,
-                    self.start,
-                    self.current,
-                    task.start(),
-                    Some(task.end()),
-                )),
-            ])
-        })
+    fn finished_rows(&self, status_width: u16) -> impl Iterator<Item = Row> + '_ {
+        self.finished
+            .iter()
+            .filter(|task| self.is_shown(task.name()))
+            .map(move |task| {
+                let (icon, color) = result_icon(task.result());
+                Row::new(vec![
+                    Cell::new(task.name()),
+                    Cell::new(Self::status(
+                        Span::styled(icon, Style::default().fg(color)),
+                        TaskDuration::new(
+                            status_width.saturating_sub(2),
+                            self.start,
+                            self.current,
+                            task.start(),
+                            Some(task.end()),
+                        ),
+                    )),
+                ])
+            })
     }
 
-    fn running_rows(&self, duration_width: u16) -> impl Iterator<Item = Row> + '_ {
-        self.running.iter().map(move |task| {
-            Row::new(vec![
-                Cell::new(task.name()),
-                Cell::new(TaskDuration::new(
-                    duration_width,
-                    self.start,
-                    self.current,
-                    task.start(),
-                    None,
-                )),
-            ])
-        })
+    fn running_rows(&self, status_width: u16) -> impl Iterator<Item = Row> + '_ {
+        self.running
+            .iter()
+            .filter(|task| self.is_shown(task.name()))
+            .map(move |task| {
+                Row::new(vec![
+                    Cell::new(task.name()),
+                    Cell::new(Self::status(
+                        Span::raw("▶"),
+                        TaskDuration::new(
+                            status_width.saturating_sub(2),
+                            self.start,
+                            self.current,
+                            task.start(),
+                            None,
+                        ),
+                    )),
+                ])
+            })
+    }
+
+    // An icon for the state of the task followed by its duration
+    fn status(icon: Span<'static>, duration: TaskDuration) -> Line<'static> {
+        Line::from(vec![icon, Span::raw(" "), Span::raw(duration.bar())])
     }
 
     fn planned_rows(&self, duration_width: u16) -> impl Iterator<Item = Row> + '_ {
-        self.planned.iter().map(move |task| {
-            Row::new(vec![
-                Cell::new(task.name()),
-                Cell::new(" ".repeat(duration_width as usize)),
-            ])
-        })
+        self.planned
+            .iter()
+            .filter(|task| self.is_shown(task.name()))
+            .map(move |task| {
+                Row::new(vec![
+                    Cell::new(task.name()),
+                    Cell::new(" ".repeat(duration_width as usize)),
+                ])
+            })
     }
 
     /// Convenience method which renders and updates scroll state
@@ -245,8 +339,15 @@ impl TaskTable {
         }
     }
 
-    fn render_footer(area: Rect, buf: &mut Buffer) {
-        let footer = Paragraph::new(Line::from(FOOTER_TEXT)).centered().block(
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+        let text = match self.filter.as_deref() {
+            Some(filter) => format!(
+                "Showing tasks matching \"{filter}\". Press `Enter` to keep the filter and `Esc` \
+                 to clear it"
+            ),
+            None => FOOTER_TEXT.to_string(),
+        };
+        let footer = Paragraph::new(Line::from(text)).centered().block(
             Block::default()
                 .borders(Borders::TOP)
                 .border_type(BorderType::Plain),
@@ -284,7 +385,17 @@ impl<'a> StatefulWidget for &'a TaskTable {
                 .height(2),
         );
         StatefulWidget::render(table, areas[0], buf, state);
-        TaskTable::render_footer(areas[1], buf);
+        self.render_footer(areas[1], buf);
+    }
+}
+
+fn result_icon(result: TaskResult) -> (&'static str, Color) {
+    match result {
+        TaskResult::Success => ("✔", Color::Green),
+        TaskResult::Failure => ("✘", Color::Red),
+        TaskResult::CacheHit(CacheSource::Local) => ("↺", Color::Cyan),
+        TaskResult::CacheHit(CacheSource::Remote) => ("↓", Color::Magenta),
+        TaskResult::Skipped => ("⊘", Color::DarkGray),
     }
 }
 
@@ -329,7 +440,7 @@ mod test {
         table.start_task("a").unwrap();
         assert_eq!(table.scroll.selected(), Some(0), "b stays selected");
         assert_eq!(table.selected(), Some("b"), "selected b");
-        table.finish_task("a").unwrap();
+        table.finish_task("a", TaskResult::Success).unwrap();
         assert_eq!(table.scroll.selected(), Some(1), "b stays selected");
         assert_eq!(table.selected(), Some("b"), "selected b");
     }
@@ -355,15 +466,84 @@ mod test {
         table.previous();
         assert_eq!(table.scroll.selected(), Some(0), "selected c");
         assert_eq!(table.selected(), Some("c"), "selected c");
-        table.finish_task("a").unwrap();
+        table.finish_task("a", TaskResult::Success).unwrap();
         assert_eq!(table.scroll.selected(), Some(1), "c stays selected");
         assert_eq!(table.selected(), Some("c"), "selected c");
         table.previous();
-        table.finish_task("c").unwrap();
+        table.finish_task("c", TaskResult::Success).unwrap();
         assert_eq!(table.scroll.selected(), Some(0), "a stays selected");
         assert_eq!(table.selected(), Some("a"), "selected a");
     }
 
+    #[test]
+    fn test_failures_listed_first() {
+        let mut table = TaskTable::new(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        table.start_task("a").unwrap();
+        table.start_task("b").unwrap();
+        table.start_task("c").unwrap();
+        table.finish_task("a", TaskResult::Success).unwrap();
+        table.finish_task("b", TaskResult::Failure).unwrap();
+        table.finish_task("c", TaskResult::Failure).unwrap();
+        let names = table.rows().collect::<Vec<_>>();
+        assert_eq!(names, vec!["b", "c", "a"]);
+        assert_eq!(table.finished[2].result(), TaskResult::Success);
+    }
+
+    #[test]
+    fn test_restart() {
+        let mut table = TaskTable::new(vec!["a".to_string(), "b".to_string()]);
+        table.next();
+        table.start_task("a").unwrap();
+        table.start_task("b").unwrap();
+        table.finish_task("a", TaskResult::Failure).unwrap();
+        assert_eq!(table.selected(), Some("a"));
+        assert!(!table.is_running("a"));
+
+        table.restart_task("a").unwrap();
+        assert!(table.is_running("a"));
+        assert_eq!(table.rows().collect::<Vec<_>>(), vec!["b", "a"]);
+        assert_eq!(table.selected(), Some("a"), "selection follows task");
+
+        // Restarting a running task keeps it running
+        table.restart_task("b").unwrap();
+        assert_eq!(table.rows().collect::<Vec<_>>(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut table = TaskTable::new(vec![
+            "web#build".to_string(),
+            "docs#build".to_string(),
+            "web#lint".to_string(),
+        ]);
+        table.next();
+        table.next();
+        assert_eq!(table.selected(), Some("web#build"));
+        table.set_filter(Some("WEB".to_string()));
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.selected(), Some("web#build"), "selection is kept");
+        table.next();
+        assert_eq!(table.selected(), Some("web#lint"));
+        table.next();
+        assert_eq!(table.selected(), Some("web#lint"), "scroll stays in bounds");
+
+        table.set_filter(Some("docs".to_string()));
+        assert_eq!(
+            table.selected(),
+            Some("docs#build"),
+            "falls back to first row"
+        );
+        table.set_filter(Some("missing".to_string()));
+        assert!(table.is_empty());
+        assert_eq!(table.selected(), None);
+        table.next();
+        assert_eq!(table.selected(), None);
+
+        table.set_filter(None);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.selected(), Some("web#build"));
+    }
+
     #[test]
     fn test_footer_always_rendered() {
         let table = TaskTable::new(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
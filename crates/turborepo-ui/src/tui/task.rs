@@ -12,6 +12,25 @@ pub struct Running {
 pub struct Finished {
     start: Instant,
     end: Instant,
+    result: TaskResult,
+}
+
+/// How a task finished
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum TaskResult {
+    Success,
+    Failure,
+    CacheHit(CacheSource),
+    // The task didn't run to completion, e.g. it was stopped as the run was
+    // shutting down
+    Skipped,
+}
+
+/// Where the outputs of a task that hit the cache were restored from
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum CacheSource {
+    Local,
+    Remote,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -45,7 +64,7 @@ impl Task<Planned> {
 }
 
 impl Task<Running> {
-    pub fn finish(self) -> Task<Finished> {
+    pub fn finish(self, result: TaskResult) -> Task<Finished> {
         let Task {
             name,
             state: Running { start },
@@ -55,6 +74,7 @@ impl Task<Running> {
             state: Finished {
                 start,
                 end: Instant::now(),
+                result,
             },
         }
     }
@@ -72,4 +92,17 @@ impl Task<Finished> {
     pub fn end(&self) -> Instant {
         self.state.end
     }
+
+    pub fn result(&self) -> TaskResult {
+        self.state.result
+    }
+
+    pub fn restart(self) -> Task<Running> {
+        Task {
+            name: self.name,
+            state: Running {
+                start: Instant::now(),
+            },
+        }
+    }
 }
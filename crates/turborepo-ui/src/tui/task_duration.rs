@@ -53,19 +53,22 @@ impl TaskDuration {
     fn scale(&self) -> f64 {
         self.width as f64 / self.run_duration_ms() as f64
     }
-}
 
-impl From<TaskDuration> for Text<'static> {
-    fn from(value: TaskDuration) -> Self {
-        let scale = value.scale();
-        let last_index = value.width - 1;
+    /// Renders the duration of the task as a bar relative to the duration of
+    /// the run
+    pub fn bar(&self) -> String {
+        if self.width == 0 {
+            return String::new();
+        }
+        let scale = self.scale();
+        let last_index = self.width - 1;
         // We clamp these to the last visible char in the case either of events happen
         // to be happen at the 'current' instant.
-        let start_index = ((value.start_offset() as f64 * scale) as u16).min(last_index);
-        let end_index = ((value.end_offset() as f64 * scale) as u16).min(last_index);
+        let start_index = ((self.start_offset() as f64 * scale) as u16).min(last_index);
+        let end_index = ((self.end_offset() as f64 * scale) as u16).min(last_index);
 
-        let mut bar = String::with_capacity(value.width.into());
-        for idx in 0..value.width {
+        let mut bar = String::with_capacity(self.width.into());
+        for idx in 0..self.width {
             if idx < start_index {
                 bar.push(' ');
             } else if idx == start_index {
@@ -73,12 +76,18 @@ impl From<TaskDuration> for Text<'static> {
             } else if start_index < idx && idx < end_index {
                 bar.push('-');
             } else if idx == end_index {
-                bar.push(value.end_marker());
+                bar.push(self.end_marker());
             } else {
                 bar.push(' ');
             }
         }
-        Text::raw(bar)
+        bar
+    }
+}
+
+impl From<TaskDuration> for Text<'static> {
+    fn from(value: TaskDuration) -> Self {
+        Text::raw(value.bar())
     }
 }
 
@@ -0,0 +1,422 @@
+use std::{collections::HashMap, time::Duration};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Widget},
+};
+use serde_json::{json, Value};
+use turbopath::AbsoluteSystemPathBuf;
+
+use super::{
+    app::Direction,
+    task::{CacheSource, TaskResult},
+};
+
+const FOOTER_TEXT: &str = "Press `t` to return to the task list and `e` to export the timeline as \
+                           a Chrome trace. █ ran ░ cache hit, dependencies are yellow and \
+                           dependents are blue";
+
+// All tasks are shown as part of a single process in the trace
+const TRACE_PID: u64 = 1;
+
+/// When a task ran, relative to the start of the run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskSpan<'a> {
+    pub name: &'a str,
+    pub start: Duration,
+    // Not set if the task is still running
+    pub end: Option<Duration>,
+    pub result: Option<TaskResult>,
+}
+
+/// A full screen view of when each task ran, with the dependencies and
+/// dependents of the selected task highlighted.
+pub struct Timeline {
+    // The direct dependencies of each task
+    dependencies: HashMap<String, Vec<String>>,
+    // Where the timeline is exported to
+    path: AbsoluteSystemPathBuf,
+    // The outcome of the last export
+    message: Option<String>,
+}
+
+impl Timeline {
+    pub fn new(dependencies: HashMap<String, Vec<String>>, path: AbsoluteSystemPathBuf) -> Self {
+        Self {
+            dependencies,
+            path,
+            message: None,
+        }
+    }
+
+    /// Returns the task before or after the selected task on the timeline
+    pub fn neighbor<'a>(
+        spans: &[TaskSpan<'a>],
+        selected: Option<&str>,
+        direction: Direction,
+    ) -> Option<&'a str> {
+        let index = selected.and_then(|selected| spans.iter().position(|s| s.name == selected));
+        let index = match (index, direction) {
+            (None, _) => 0,
+            (Some(i), Direction::Up) => i.saturating_sub(1),
+            (Some(i), Direction::Down) => (i + 1).min(spans.len().saturating_sub(1)),
+        };
+        spans.get(index).map(|span| span.name)
+    }
+
+    /// Write the timeline to a file in the Chrome trace format
+    pub fn export(&mut self, spans: &[TaskSpan], elapsed: Duration) {
+        let trace = self.chrome_trace(spans, elapsed);
+        let result = self
+            .path
+            .ensure_dir()
+            .and_then(|()| self.path.create_with_contents(trace.to_string()));
+        self.message = Some(match result {
+            Ok(()) => format!("Exported timeline to {}", self.path),
+            Err(e) => format!("Unable to export timeline to {}: {e}", self.path),
+        });
+    }
+
+    /// The timeline as trace events that can be loaded by `chrome://tracing`
+    /// and Perfetto. Tasks that ran at the same time are put on separate
+    /// threads.
+    pub fn chrome_trace(&self, spans: &[TaskSpan], elapsed: Duration) -> Value {
+        let lanes = lanes(spans, elapsed);
+        let num_lanes = lanes.iter().max().map_or(0, |lane| lane + 1);
+        let mut events = vec![json!({
+            "ph": "M",
+            "pid": TRACE_PID,
+            "name": "process_name",
+            "args": { "name": "turbo run" },
+        })];
+        events.extend((0..num_lanes).map(|lane| {
+            json!({
+                "ph": "M",
+                "pid": TRACE_PID,
+                "tid": lane,
+                "name": "thread_name",
+                "args": { "name": format!("slot {}", lane + 1) },
+            })
+        }));
+        events.extend(spans.iter().zip(lanes).map(|(span, lane)| {
+            let end = span.end.unwrap_or(elapsed);
+            json!({
+                "ph": "X",
+                "pid": TRACE_PID,
+                "tid": lane,
+                "name": span.name,
+                "cat": "task",
+                "ts": span.start.as_micros() as u64,
+                "dur": end.saturating_sub(span.start).as_micros() as u64,
+                "args": {
+                    "result": result_name(span.result),
+                    "dependencies": self.dependencies.get(span.name).cloned().unwrap_or_default(),
+                },
+            })
+        }));
+        Value::Array(events)
+    }
+
+    pub fn render(
+        &self,
+        spans: &[TaskSpan],
+        elapsed: Duration,
+        selected: Option<&str>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let block = Block::default().borders(Borders::ALL).title(" Timeline ");
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.height < 3 || inner.width < 10 {
+            return;
+        }
+
+        let name_width = spans
+            .iter()
+            .map(|span| span.name.len())
+            .max()
+            .unwrap_or_default()
+            .min(usize::from(inner.width / 3)) as u16;
+        let axis_x = inner.x + name_width + 1;
+        let axis_width = inner.width.saturating_sub(name_width + 1).max(1);
+        let column = |offset: Duration| -> u16 {
+            let scale = offset.as_secs_f64() / elapsed.as_secs_f64().max(f64::EPSILON);
+            axis_x + ((scale * f64::from(axis_width)) as u16).min(axis_width - 1)
+        };
+
+        // Axis with the start and the current duration of the run
+        let total = format!("{:.1}s", elapsed.as_secs_f64());
+        buf.set_string(axis_x, inner.y, "0s", Style::default());
+        buf.set_string(
+            (axis_x + axis_width).saturating_sub(total.len() as u16),
+            inner.y,
+            &total,
+            Style::default(),
+        );
+        let footer = self.message.as_deref().unwrap_or(FOOTER_TEXT);
+        buf.set_stringn(
+            inner.x,
+            inner.bottom() - 1,
+            footer,
+            inner.width.into(),
+            Style::default().fg(Color::DarkGray),
+        );
+
+        // Keep the selected task in view
+        let rows = usize::from(inner.height - 2);
+        let selected_index =
+            selected.and_then(|selected| spans.iter().position(|s| s.name == selected));
+        let offset = selected_index.map_or(0, |i| (i + 1).saturating_sub(rows));
+        let dependencies = selected
+            .and_then(|selected| self.dependencies.get(selected))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let is_dependent = |task: &str| {
+            selected.map_or(false, |selected| {
+                self.dependencies
+                    .get(task)
+                    .map_or(false, |deps| deps.iter().any(|dep| dep == selected))
+            })
+        };
+
+        let row_y = |index: usize| inner.y + 1 + (index - offset) as u16;
+        for (index, span) in spans.iter().enumerate().skip(offset).take(rows) {
+            let y = row_y(index);
+            let relation = if Some(index) == selected_index {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else if dependencies.iter().any(|dep| dep == span.name) {
+                Style::default().fg(Color::Yellow)
+            } else if is_dependent(span.name) {
+                Style::default().fg(Color::Blue)
+            } else {
+                Style::default()
+            };
+            buf.set_stringn(inner.x, y, span.name, name_width.into(), relation);
+
+            let (symbol, color) = bar_symbol(span.result);
+            let start = column(span.start);
+            let end = column(span.end.unwrap_or(elapsed));
+            for x in start..=end {
+                buf.get_mut(x, y)
+                    .set_symbol(symbol)
+                    .set_style(Style::default().fg(color));
+            }
+        }
+
+        // Connect the selected task to its dependencies and dependents
+        let Some(selected_index) = selected_index else {
+            return;
+        };
+        let selected_span = spans[selected_index];
+        let mut edge = |index: usize, x: u16, color: Color| {
+            let (from, to) = match index < selected_index {
+                true => (index, selected_index),
+                false => (selected_index, index),
+            };
+            for i in (from..=to).filter(|i| (offset..offset + rows).contains(i)) {
+                let cell = buf.get_mut(x, row_y(i));
+                if i != selected_index && cell.symbol() == " " {
+                    let symbol = if i == index { "●" } else { "│" };
+                    cell.set_symbol(symbol)
+                        .set_style(Style::default().fg(color));
+                }
+            }
+        };
+        for (index, span) in spans.iter().enumerate() {
+            if dependencies.iter().any(|dep| dep == span.name) {
+                edge(index, column(selected_span.start), Color::Yellow);
+            } else if is_dependent(span.name) {
+                edge(
+                    index,
+                    column(selected_span.end.unwrap_or(elapsed)),
+                    Color::Blue,
+                );
+            }
+        }
+    }
+}
+
+// Assigns each task to the first lane that's free when it starts
+fn lanes(spans: &[TaskSpan], elapsed: Duration) -> Vec<usize> {
+    let mut lane_ends: Vec<Duration> = Vec::new();
+    spans
+        .iter()
+        .map(|span| {
+            let end = span.end.unwrap_or(elapsed);
+            match lane_ends
+                .iter()
+                .position(|lane_end| *lane_end <= span.start)
+            {
+                Some(lane) => {
+                    lane_ends[lane] = end;
+                    lane
+                }
+                None => {
+                    lane_ends.push(end);
+                    lane_ends.len() - 1
+                }
+            }
+        })
+        .collect()
+}
+
+fn bar_symbol(result: Option<TaskResult>) -> (&'static str, Color) {
+    match result {
+        None => ("█", Color::White),
+        Some(TaskResult::Success) => ("█", Color::Green),
+        Some(TaskResult::Failure) => ("█", Color::Red),
+        Some(TaskResult::CacheHit(CacheSource::Local)) => ("░", Color::Cyan),
+        Some(TaskResult::CacheHit(CacheSource::Remote)) => ("░", Color::Magenta),
+        Some(TaskResult::Skipped) => ("█", Color::DarkGray),
+    }
+}
+
+fn result_name(result: Option<TaskResult>) -> &'static str {
+    match result {
+        None => "running",
+        Some(TaskResult::Success) => "success",
+        Some(TaskResult::Failure) => "failure",
+        Some(TaskResult::CacheHit(CacheSource::Local)) => "cache hit (local)",
+        Some(TaskResult::CacheHit(CacheSource::Remote)) => "cache hit (remote)",
+        Some(TaskResult::Skipped) => "skipped",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::{tempdir, TempDir};
+
+    use super::*;
+
+    fn span(name: &str, start: u64, end: Option<u64>, result: TaskResult) -> TaskSpan {
+        TaskSpan {
+            name,
+            start: Duration::from_secs(start),
+            end: end.map(Duration::from_secs),
+            result: end.map(|_| result),
+        }
+    }
+
+    fn timeline() -> (TempDir, Timeline) {
+        let dir = tempdir().unwrap();
+        let path = AbsoluteSystemPathBuf::try_from(dir.path())
+            .unwrap()
+            .join_components(&[".turbo", "timeline.json"]);
+        let timeline = Timeline::new(
+            HashMap::from([
+                ("web#build".to_string(), vec!["ui#build".to_string()]),
+                ("docs#build".to_string(), vec!["ui#build".to_string()]),
+            ]),
+            path,
+        );
+        (dir, timeline)
+    }
+
+    #[test]
+    fn test_lanes() {
+        let spans = [
+            span("ui#build", 0, Some(2), TaskResult::Success),
+            span("ui#lint", 0, Some(5), TaskResult::Success),
+            span("web#build", 2, Some(4), TaskResult::Success),
+            span("docs#build", 3, None, TaskResult::Success),
+        ];
+        assert_eq!(lanes(&spans, Duration::from_secs(6)), vec![0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_chrome_trace() {
+        let spans = [
+            span(
+                "ui#build",
+                0,
+                Some(2),
+                TaskResult::CacheHit(CacheSource::Local),
+            ),
+            span("web#build", 2, None, TaskResult::Success),
+        ];
+        let (_dir, timeline) = timeline();
+        let trace = timeline.chrome_trace(&spans, Duration::from_secs(3));
+        let events = trace.as_array().unwrap();
+        // Process name, one thread and the two tasks
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events[2],
+            json!({
+                "ph": "X",
+                "pid": 1,
+                "tid": 0,
+                "name": "ui#build",
+                "cat": "task",
+                "ts": 0,
+                "dur": 2_000_000,
+                "args": { "result": "cache hit (local)", "dependencies": [] },
+            })
+        );
+        assert_eq!(events[3]["dur"], 1_000_000);
+        assert_eq!(events[3]["args"]["result"], "running");
+        assert_eq!(events[3]["args"]["dependencies"], json!(["ui#build"]));
+    }
+
+    #[test]
+    fn test_export() {
+        let (_dir, mut timeline) = timeline();
+        let spans = [span("ui#build", 0, Some(2), TaskResult::Success)];
+        timeline.export(&spans, Duration::from_secs(2));
+        let contents = timeline.path.read_to_string().unwrap();
+        let trace: Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(trace, timeline.chrome_trace(&spans, Duration::from_secs(2)));
+        assert!(timeline
+            .message
+            .unwrap()
+            .starts_with("Exported timeline to"));
+    }
+
+    #[test]
+    fn test_neighbor() {
+        let spans = [
+            span("a", 0, Some(1), TaskResult::Success),
+            span("b", 1, Some(2), TaskResult::Success),
+        ];
+        assert_eq!(Timeline::neighbor(&spans, None, Direction::Down), Some("a"));
+        assert_eq!(
+            Timeline::neighbor(&spans, Some("a"), Direction::Down),
+            Some("b")
+        );
+        assert_eq!(
+            Timeline::neighbor(&spans, Some("b"), Direction::Down),
+            Some("b")
+        );
+        assert_eq!(
+            Timeline::neighbor(&spans, Some("a"), Direction::Up),
+            Some("a")
+        );
+        assert_eq!(Timeline::neighbor(&[], Some("a"), Direction::Up), None);
+    }
+
+    #[test]
+    fn test_render_highlights_dependencies() {
+        let spans = [
+            span("ui#build", 0, Some(4), TaskResult::Success),
+            span("web#build", 4, Some(8), TaskResult::Failure),
+        ];
+        let area = Rect::new(0, 0, 30, 6);
+        let mut buffer = Buffer::empty(area);
+        let (_dir, timeline) = timeline();
+        timeline.render(
+            &spans,
+            Duration::from_secs(8),
+            Some("web#build"),
+            area,
+            &mut buffer,
+        );
+        // The dependency's name is highlighted
+        assert_eq!(buffer.get(1, 2).fg, Color::Yellow);
+        assert!(buffer.get(1, 3).modifier.contains(Modifier::REVERSED));
+        // The bar of the failed task is red
+        assert_eq!(buffer.get(28, 3).symbol(), "█");
+        assert_eq!(buffer.get(28, 3).fg, Color::Red);
+    }
+}
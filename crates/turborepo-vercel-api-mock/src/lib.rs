@@ -283,3 +283,38 @@ pub async fn start_test_server(port: u16) -> Result<()> {
 
     Ok(())
 }
+
+/// Starts a server that stores whatever is `PUT` to it and serves it back on
+/// `GET` (and `HEAD`), standing in for WebDAV servers and S3-compatible object
+/// stores in tests. Request signatures aren't checked.
+pub async fn start_artifact_server(port: u16) -> Result<()> {
+    let objects: Arc<Mutex<HashMap<String, Vec<u8>>>> = Arc::new(Mutex::new(HashMap::new()));
+    let put_objects_ref = objects.clone();
+    let get_objects_ref = objects;
+
+    let app = Router::new().route(
+        "/*key",
+        put(|Path(key): Path<String>, mut body: BodyStream| async move {
+            let mut contents = Vec::new();
+            while let Some(item) = body.next().await {
+                contents.extend_from_slice(&item.unwrap());
+            }
+            put_objects_ref.lock().await.insert(key, contents);
+
+            StatusCode::CREATED
+        })
+        .get(|Path(key): Path<String>| async move {
+            match get_objects_ref.lock().await.get(&key) {
+                Some(contents) => (StatusCode::OK, contents.clone()),
+                None => (StatusCode::NOT_FOUND, Vec::new()),
+            }
+        }),
+    );
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    axum_server::bind(addr)
+        .serve(app.into_make_service())
+        .await?;
+
+    Ok(())
+}
@@ -223,6 +223,33 @@ impl Grid {
         self.scrollback_offset = rows.min(self.scrollback.len());
     }
 
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        self.all_rows()
+            .enumerate()
+            .filter(|(_, row)| {
+                let mut contents = String::new();
+                row.write_contents(&mut contents, 0, self.size.cols, false);
+                contents.to_lowercase().contains(&query)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn scroll_to_row(&mut self, row: usize) {
+        self.set_scrollback(self.scrollback.len().saturating_sub(row));
+    }
+
+    pub fn visible_row_index(&self, row: usize) -> Option<u16> {
+        let first_visible = self.scrollback.len() - self.scrollback_offset;
+        let index = row.checked_sub(first_visible)?;
+        if index < self.rows.len() {
+            index.try_into().ok()
+        } else {
+            None
+        }
+    }
+
     pub fn write_contents(&self, contents: &mut String) {
         let mut wrapping = false;
         for row in self.visible_rows() {
@@ -139,6 +139,30 @@ impl Screen {
         self.grid().scrollback()
     }
 
+    /// Returns the rows of the scrollback and the screen that contain
+    /// `query`, ignoring case.
+    ///
+    /// Rows are counted from the oldest row in the scrollback, and are in
+    /// order.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        self.grid().search(query)
+    }
+
+    /// Scrolls so that the given row, counted from the oldest row in the
+    /// scrollback, is at the top of the screen. Rows that aren't in the
+    /// scrollback are shown by scrolling back to the normal screen.
+    pub fn scroll_to_row(&mut self, row: usize) {
+        self.grid_mut().scroll_to_row(row);
+    }
+
+    /// Returns where the given row, counted from the oldest row in the
+    /// scrollback, is on the screen, if it's in view.
+    #[must_use]
+    pub fn visible_row_index(&self, row: usize) -> Option<u16> {
+        self.grid().visible_row_index(row)
+    }
+
     /// Returns the text contents of the terminal.
     ///
     /// This will not include any formatting information, and will be in plain
@@ -0,0 +1,34 @@
+use turborepo_vt100 as vt100;
+
+#[test]
+fn test_search_includes_scrollback() {
+    let mut parser = vt100::Parser::new(2, 20, 100);
+    parser.process(
+        b"Compiling foo\r\nwarning: unused\r\ncompiled\r\nWARNING: again",
+    );
+    let screen = parser.screen();
+    assert_eq!(screen.search("warning"), vec![1, 3]);
+    assert_eq!(screen.search("compil"), vec![0, 2]);
+    assert!(screen.search("error").is_empty());
+}
+
+#[test]
+fn test_scroll_to_row() {
+    let mut parser = vt100::Parser::new(2, 20, 100);
+    parser.process(b"one\r\ntwo\r\nthree\r\nfour\r\nfive");
+    let screen = parser.screen_mut();
+    assert_eq!(screen.visible_row_index(3), Some(0));
+    assert_eq!(screen.visible_row_index(1), None);
+
+    screen.scroll_to_row(1);
+    assert_eq!(screen.scrollback(), 2);
+    assert_eq!(screen.contents(), "two\nthree");
+    assert_eq!(screen.visible_row_index(1), Some(0));
+    assert_eq!(screen.visible_row_index(2), Some(1));
+    assert_eq!(screen.visible_row_index(3), None);
+
+    // Rows on the normal screen scroll back to it
+    screen.scroll_to_row(4);
+    assert_eq!(screen.scrollback(), 0);
+    assert_eq!(screen.visible_row_index(4), Some(1));
+}
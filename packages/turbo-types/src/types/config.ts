@@ -22,6 +22,16 @@ export interface BaseSchema {
      */
     [script: string]: Pipeline;
   };
+
+  /**
+   * Tags of the package that rules in `boundaries` can select it by. Tags can
+   * also be set with a `tags` list in the package's package.json.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#tags
+   *
+   * @defaultValue `[]`
+   */
+  tags?: Array<string>;
 }
 
 export interface WorkspaceSchema extends BaseSchema {
@@ -33,7 +43,8 @@ export interface WorkspaceSchema extends BaseSchema {
    * and overrides with the keys provided
    * in your Workspace Configs.
    *
-   * Currently, only the "//" value is allowed.
+   * Use "//" for the root `turbo.json` and workspace names
+   * to extend the Workspace Configs of other workspaces.
    *
    * @defaultValue ["//"]
    */
@@ -120,6 +131,45 @@ export interface RootSchema extends BaseSchema {
    * @defaultValue `{}`
    */
   experimentalUI?: boolean;
+
+  /**
+   * Named pools that limit how many of the tasks assigned to them can run at
+   * the same time. Each key is the name of a pool and its value is the pool's
+   * size.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#pools
+   *
+   * @defaultValue `{}`
+   */
+  pools?: Record<string, number>;
+
+  /**
+   * Rules for which packages may depend on each other, checked by
+   * `turbo boundaries`. Each key selects the packages a rule applies to,
+   * either by a tag prefixed with `tag:` or by a package name glob.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#boundaries
+   *
+   * @defaultValue `{}`
+   */
+  boundaries?: Record<string, BoundaryRule>;
+}
+
+export interface BoundaryRule {
+  /**
+   * The only packages that the selected packages may depend on, as tags
+   * prefixed with `tag:` or package name globs. Any package is allowed
+   * when it isn't set.
+   */
+  allow?: Array<string>;
+
+  /**
+   * Packages that the selected packages must not depend on, as tags
+   * prefixed with `tag:` or package name globs.
+   *
+   * @defaultValue `[]`
+   */
+  deny?: Array<string>;
 }
 
 export interface Pipeline {
@@ -267,8 +317,103 @@ export interface Pipeline {
    * Documentation: https://turbo.build/repo/docs/reference/configuration#interactive
    */
   interactive?: boolean;
+
+  /**
+   * The number of times to run the task again if it exits with a non-zero
+   * exit code before considering it failed.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#retries
+   *
+   * @defaultValue 0
+   */
+  retries?: number;
+
+  /**
+   * Save the outputs of a task to the cache even if it only succeeded after
+   * being retried. These outputs aren't cached by default as the task may be
+   * flaky.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#cacheretried
+   *
+   * @defaultValue false
+   */
+  cacheRetried?: boolean;
+
+  /**
+   * The number of seconds a task can run for before it is stopped and
+   * considered failed. Persistent tasks are never stopped.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#timeout
+   *
+   * @defaultValue 0 (no timeout)
+   */
+  timeout?: number;
+
+  /**
+   * The number of concurrency slots the task takes up while it runs.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#weight
+   *
+   * @defaultValue 1
+   */
+  weight?: number;
+
+  /**
+   * The name of a pool from `pools` in the root turbo.json that limits how
+   * many of its tasks can run at the same time.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#pool
+   */
+  pool?: string;
+
+  /**
+   * Tasks to run as sidecars of this task. The task only starts once its
+   * sidecars are ready and they are stopped once every task using them has
+   * finished.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#with
+   *
+   * @defaultValue []
+   */
+  with?: Array<string>;
+
+  /**
+   * How to tell that the task is ready when it is used as a sidecar.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#readiness
+   */
+  readiness?: Readiness;
 }
 
+export type Readiness = (
+  | {
+      /**
+       * A port on localhost that accepts connections once the task is ready.
+       */
+      tcp: number;
+    }
+  | {
+      /**
+       * A URL that responds with a 200 status once the task is ready.
+       */
+      http: string;
+    }
+  | {
+      /**
+       * A regular expression that matches a line of the task's output once it
+       * is ready.
+       */
+      stdout: string;
+    }
+) & {
+  /**
+   * The number of seconds the task has to become ready.
+   *
+   * @defaultValue 60
+   */
+  timeout?: number;
+};
+
 export interface RemoteCache {
   /**
    * Indicates if signature verification is enabled for requests to the remote cache. When
@@ -289,6 +434,70 @@ export interface RemoteCache {
    * @defaultValue true
    */
   enabled?: boolean;
+
+  /**
+   * The maximum total size of the local filesystem cache, e.g. "10GB". When the cache
+   * grows beyond this size, the least recently used artifacts are evicted after each run.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/command-line-reference/cache
+   */
+  localCacheMaxSize?: string;
+
+  /**
+   * Local filesystem cache artifacts that haven't been used for longer than this, e.g. "7d",
+   * are evicted after each run.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/command-line-reference/cache
+   */
+  localCacheMaxAge?: string;
+
+  /**
+   * Where the remote cache stores artifacts. "vercel" uses the Remote Caching API,
+   * the other backends store artifacts directly and don't require logging in.
+   *
+   * Documentation: https://turbo.build/repo/docs/core-concepts/remote-caching#remote-cache-backends
+   *
+   * @defaultValue `"vercel"`
+   */
+  backend?: "vercel" | "s3" | "directory" | "http";
+
+  /**
+   * The URL of the "http" backend, or of an S3-compatible store other than AWS S3
+   * for the "s3" backend.
+   *
+   * Documentation: https://turbo.build/repo/docs/core-concepts/remote-caching#remote-cache-backends
+   */
+  endpoint?: string;
+
+  /**
+   * The bucket used by the "s3" backend.
+   *
+   * Documentation: https://turbo.build/repo/docs/core-concepts/remote-caching#remote-cache-backends
+   */
+  bucket?: string;
+
+  /**
+   * The region used by the "s3" backend.
+   *
+   * Documentation: https://turbo.build/repo/docs/core-concepts/remote-caching#remote-cache-backends
+   *
+   * @defaultValue `"us-east-1"`
+   */
+  region?: string;
+
+  /**
+   * A prefix for the keys written by the "s3" backend.
+   *
+   * Documentation: https://turbo.build/repo/docs/core-concepts/remote-caching#remote-cache-backends
+   */
+  prefix?: string;
+
+  /**
+   * The directory used by the "directory" backend, relative to the repository root.
+   *
+   * Documentation: https://turbo.build/repo/docs/core-concepts/remote-caching#remote-cache-backends
+   */
+  path?: string;
 }
 
 export type OutputMode =
@@ -0,0 +1,5 @@
+import { Button } from "ui";
+import { format } from "utils/format";
+import { Page } from "./page";
+
+export default Page(Button, format);
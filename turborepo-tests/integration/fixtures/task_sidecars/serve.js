@@ -0,0 +1,10 @@
+// Becomes ready after a short delay and then runs until it is stopped
+const fs = require("fs");
+
+fs.writeFileSync(".serve.pid", String(process.pid));
+console.log("starting");
+setTimeout(() => {
+  fs.writeFileSync(".serve.ready", "");
+  console.log("ready");
+}, 500);
+setInterval(() => {}, 1000);
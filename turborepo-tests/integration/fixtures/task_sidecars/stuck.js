@@ -0,0 +1,6 @@
+// Never becomes ready
+const fs = require("fs");
+
+fs.writeFileSync(".stuck.pid", String(process.pid));
+console.log("starting");
+setInterval(() => {}, 1000);
@@ -0,0 +1,5 @@
+// Records whether the sidecar was ready by the time the task started
+const fs = require("fs");
+
+const ready = fs.existsSync(".serve.ready");
+fs.writeFileSync(".test.result", ready ? "ready" : "not ready");
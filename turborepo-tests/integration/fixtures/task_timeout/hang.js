@@ -0,0 +1,6 @@
+// Records its pid so tests can check that it was stopped, then never exits
+const fs = require("fs");
+
+fs.writeFileSync(".hang.pid", String(process.pid));
+console.log("hanging");
+setInterval(() => {}, 1000);
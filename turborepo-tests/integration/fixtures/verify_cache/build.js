@@ -0,0 +1,8 @@
+// Writes dist/out.txt, which includes the time of the build with --timestamp
+const fs = require("fs");
+
+const contents = process.argv.includes("--timestamp")
+  ? `built at ${process.hrtime.bigint()}`
+  : "built";
+fs.mkdirSync("dist", { recursive: true });
+fs.writeFileSync("dist/out.txt", contents);
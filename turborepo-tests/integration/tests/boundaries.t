@@ -0,0 +1,31 @@
+Setup
+  $ . ${TESTDIR}/../../helpers/setup_integration_test.sh package_boundaries
+
+Dependencies that break the rules are reported
+  $ ${TURBO} boundaries > out.txt 2>&1
+  [1]
+  $ grep --only-matching '"[a-z]*" depends on .*' out.txt
+  "web" depends on "db", which is denied by "tag:internal"
+  "web" depends on "logger", which isn't allowed
+  $ grep --only-matching 'help: .*' out.txt
+  help: denied by the rule for "tag:app" in the root turbo.json
+  help: allowed by the rule for "tag:app": "tag:ui", "utils"
+
+Undeclared imports of workspace packages are reported with --imports
+  $ ${TURBO} boundaries --imports > out.txt 2>&1
+  [1]
+  $ grep --only-matching '"[a-z]*" imports .*' out.txt
+  "docs" imports "utils" without declaring it as a dependency
+
+Rules can only be set in the root turbo.json
+  $ cp apps/web/turbo.json web-turbo.json
+  $ jq '.boundaries = {"tag:app": {"deny": ["*"]}}' web-turbo.json > apps/web/turbo.json
+  $ ${TURBO} boundaries 2>&1 | grep --only-matching '"boundaries" can only be set in the root turbo.json'
+  "boundaries" can only be set in the root turbo.json
+  $ cp web-turbo.json apps/web/turbo.json
+
+Without violations the command succeeds
+  $ jq '.boundaries."tag:app".allow += ["logger"] | del(.boundaries."tag:app".deny)' turbo.json > new-turbo.json
+  $ mv new-turbo.json turbo.json
+  $ ${TURBO} boundaries
+  No boundary violations found in 7 packages
@@ -0,0 +1,25 @@
+Setup
+  $ . ${TESTDIR}/../../helpers/setup_integration_test.sh
+
+Options that aren't set show their defaults
+  $ ${TURBO} config --json | jq -c .api_url
+  {"value":"https://vercel.com/api","source":"default"}
+
+Flags and environment variables are shown as the source
+  $ TURBO_TEAM=env-team ${TURBO} config --json --api http://localhost:8000 | jq -c '.api_url, .team_slug'
+  {"value":"http://localhost:8000","source":"flag"}
+  {"value":"env-team","source":"env"}
+
+The local config file is shown as the source
+  $ mkdir -p .turbo && echo '{"teamslug": "local-team"}' > .turbo/config.json
+  $ ${TURBO} config --json | jq -c .team_slug
+  {"value":"local-team","source":"localConfig"}
+  $ ${TURBO} config | grep team_slug
+  team_slug\s+local-team \(.turbo/config.json\) (re)
+
+Tokens are redacted
+  $ TURBO_TOKEN=my-secret-token ${TURBO} config --json | jq -c .token
+  {"value":"********","source":"env"}
+  $ TURBO_TOKEN=my-secret-token ${TURBO} config | grep --count my-secret-token
+  0
+  [1]
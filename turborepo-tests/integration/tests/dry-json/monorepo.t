@@ -88,7 +88,8 @@ Setup
       "dotEnv": [
         ".env.local"
       ],
-      "interactive": false
+      "interactive": false,
+      "retries": 0
     },
     "expandedOutputs": [],
     "framework": "",
@@ -144,7 +145,8 @@ Setup
       ],
       "passThroughEnv": null,
       "dotEnv": null,
-      "interactive": false
+      "interactive": false,
+      "retries": 0
     },
     "expandedOutputs": [],
     "framework": "",
@@ -70,7 +70,8 @@ Setup
           "env": [],
           "passThroughEnv": null,
           "dotEnv": null,
-          "interactive": false
+          "interactive": false,
+          "retries": 0
         },
         "expandedOutputs": [],
         "framework": "",
@@ -125,7 +126,8 @@ Setup
           "env": [],
           "passThroughEnv": null,
           "dotEnv": null,
-          "interactive": false
+          "interactive": false,
+          "retries": 0
         },
         "expandedOutputs": [],
         "framework": "",
@@ -68,7 +68,8 @@ Setup
           "env": [],
           "passThroughEnv": null,
           "dotEnv": null,
-          "interactive": false
+          "interactive": false,
+          "retries": 0
         },
         "expandedOutputs": [],
         "framework": "",
@@ -18,3 +18,7 @@ Setup
      Time:\s*[\.0-9]+m?s  (re)
   Summary:    .+\.turbo(\/|\\)runs(\/|\\)[a-zA-Z0-9]+.json (re)
   
+  Critical path:    my-app#build \([\.0-9]+m?s\) (re)
+    Concurrency:    [\.0-9]+ average, 1 peak, [0-9]+ available (re)
+    Bottlenecks:    my-app#build \(took [\.0-9]+m?s, up to [\.0-9]+m?s faster\) (re)
+  
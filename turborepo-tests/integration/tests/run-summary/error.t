@@ -62,7 +62,8 @@ Validate that we got a full task summary for the failed task with an error in .e
       "env": [],
       "passThroughEnv": null,
       "dotEnv": null,
-      "interactive": false
+      "interactive": false,
+      "retries": 0
     },
     "expandedOutputs": [],
     "framework": "",
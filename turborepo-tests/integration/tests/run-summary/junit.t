@@ -0,0 +1,43 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh
+
+  $ rm -rf .turbo/runs
+
+--summarize=junit writes the summary as JUnit XML instead of JSON
+  $ ${TURBO} run maybefails --summarize=junit --force --continue > /dev/null 2>&1
+  [1]
+  $ /bin/ls .turbo/runs/*.json 2> /dev/null | wc -l
+  \s*0 (re)
+  $ SUMMARY=$(/bin/ls .turbo/runs/*.xml | head -n1)
+
+There's a test suite for each package, with a test case for each of its tasks
+  $ grep "<testsuites\|<testsuite \|<testcase" $SUMMARY
+  <testsuites name="turbo run maybefails --continue" tests="2" failures="1" skipped="0" time="[\.0-9]+"> (re)
+    <testsuite name="my-app" tests="1" failures="1" skipped="0" time="[\.0-9]+"> (re)
+      <testcase name="maybefails" classname="my-app" time="[\.0-9]+"> (re)
+    <testsuite name="util" tests="1" failures="0" skipped="0" time="[\.0-9]+"> (re)
+      <testcase name="maybefails" classname="util" time="[\.0-9]+"/> (re)
+
+Failures include the error and the end of the task's log
+  $ grep "<failure" $SUMMARY
+        <failure message="command .*npm(?:\.cmd)? run maybefails exited \(1\)">.* (re)
+  $ grep "exit 4" $SUMMARY
+  &gt; exit 4
+
+On GitHub Actions failed tasks are annotated
+  $ GITHUB_ACTIONS=1 GITHUB_STEP_SUMMARY=.turbo/summary.md ${TURBO} run maybefails --filter=my-app 2> /dev/null | grep "::error"
+  ::error title=my-app#maybefails failed::command .*npm(?:\.cmd)? run maybefails exited \(1\)%0A%0A.*exit 4.* (re)
+  $ test -e .turbo/summary.md
+  [1]
+
+With --summarize the run is also added to the job summary
+  $ GITHUB_ACTIONS=1 GITHUB_STEP_SUMMARY=.turbo/summary.md ${TURBO} run maybefails --filter=my-app --summarize > /dev/null 2>&1
+  [1]
+  $ head -n 7 .turbo/summary.md
+  ### `turbo run maybefails --filter=my-app`
+  
+  0 successful, 0 cached, 1 failed, 1 total
+  
+  | Task | Status | Duration |
+  | ---- | ------ | -------- |
+  \| `my-app#maybefails` \| :x: Failed \| [\.0-9]+m?s \| (re)
@@ -30,6 +30,7 @@ Setup
 
   $ cat $FIRST | jq 'keys'
   [
+    "analysis",
     "envMode",
     "execution",
     "frameworkInference",
@@ -71,6 +72,30 @@ Setup
   $ cat $FIRST | jq '.execution.endTime'
   [0-9]+ (re)
 
+# the analysis of what bounded the run
+  $ cat $FIRST | jq '.analysis | keys'
+  [
+    "bottlenecks",
+    "concurrency",
+    "criticalPath",
+    "criticalPathDuration",
+    "slack"
+  ]
+  $ cat $FIRST | jq '.analysis.slack | keys'
+  [
+    "my-app#build",
+    "util#build"
+  ]
+  $ cat $FIRST | jq '.analysis.concurrency | keys'
+  [
+    "available",
+    "average",
+    "peak",
+    "timeline"
+  ]
+  $ cat $FIRST | jq '.analysis.concurrency.timeline | last | .running'
+  0
+
 # Extract some task-specific summaries from each
   $ source "$TESTDIR/../../../helpers/run_summary.sh"
   $ FIRST_APP_BUILD=$(getSummaryTaskId "$FIRST" "my-app#build")
@@ -151,6 +176,7 @@ Setup
 
   $ cat $FIRST | jq 'keys'
   [
+    "analysis",
     "envMode",
     "execution",
     "frameworkInference",
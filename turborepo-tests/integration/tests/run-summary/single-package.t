@@ -30,6 +30,7 @@ Check
 
   $ cat $SUMMARY | jq 'keys'
   [
+    "analysis",
     "envMode",
     "execution",
     "frameworkInference",
@@ -141,6 +142,7 @@ Check Rust implementation
 
   $ cat $SUMMARY | jq 'keys'
   [
+    "analysis",
     "envMode",
     "execution",
     "frameworkInference",
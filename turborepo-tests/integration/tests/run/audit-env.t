@@ -0,0 +1,30 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh env_audit
+
+Variables that tasks read without declaring them are reported with a suggested fix
+  $ ${TURBO} run build --audit-env --output-logs=none > out.txt
+  $ sed -n '/Environment variables/,/audited/p' out.txt
+  Environment variables read by tasks without being declared in turbo.json:
+    web#build: API_URL
+  
+  To declare them, add them to "env" in turbo.json:
+  {
+    "pipeline": {
+      "build": {
+        "env": [
+          "API_URL",
+          "NODE_ENV"
+        ]
+      }
+    }
+  }
+  1 tasks couldn't be audited because they were restored from the cache or didn't write a trace
+
+Tasks restored from the cache can't be audited
+  $ ${TURBO} run build --audit-env --output-logs=none | grep audited
+  3 tasks couldn't be audited because they were restored from the cache or didn't write a trace
+
+Once the variable is declared, nothing is reported
+  $ jq '.pipeline.build.env += ["API_URL"]' turbo.json > new-turbo.json && mv new-turbo.json turbo.json
+  $ ${TURBO} run build --audit-env --output-logs=none | grep "environment variables"
+  No undeclared environment variables found in 2 traced tasks
@@ -0,0 +1,36 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh single_package
+
+Run events are written to stdout, and the regular output to stderr. We keep the
+files in .turbo so that they don't change the inputs of the task.
+  $ mkdir .turbo
+  $ ${TURBO} run build --output-format=ndjson > .turbo/events.ndjson 2> .turbo/output.txt
+  $ jq -r '.type' .turbo/events.ndjson | uniq
+  runStarted
+  taskScheduled
+  cacheMiss
+  taskStarted
+  taskOutput
+  taskFinished
+  runFinished
+  $ jq -r '.version' .turbo/events.ndjson | sort -u
+  1
+  $ jq -c 'select(.type == "taskFinished") | {taskId, status, exitCode}' .turbo/events.ndjson
+  {"taskId":"//#build","status":"succeeded","exitCode":0}
+  $ jq -c 'select(.type == "runFinished") | {exitCode, tasks: (.summary.tasks | length)}' .turbo/events.ndjson
+  {"exitCode":0,"tasks":1}
+  $ grep "cache miss" .turbo/output.txt
+  build: cache miss, executing f09bf783beacf5c9
+
+Cache hits include where the outputs were restored from
+  $ ${TURBO} run build --events-file=.turbo/events.ndjson > /dev/null
+  $ jq -r '.type' .turbo/events.ndjson
+  runStarted
+  taskScheduled
+  cacheHit
+  taskFinished
+  runFinished
+  $ jq -c 'select(.type == "cacheHit") | {taskId, hash, source}' .turbo/events.ndjson
+  {"taskId":"//#build","hash":"f09bf783beacf5c9","source":"LOCAL"}
+  $ jq -c 'select(.type == "taskFinished") | {status, exitCode}' .turbo/events.ndjson
+  {"status":"cached","exitCode":0}
@@ -0,0 +1,48 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh
+
+Populate the cache
+  $ ${TURBO} run build --filter=my-app > /dev/null 2>&1
+
+--explain prints what changed since a task that missed the cache was last cached
+  $ echo "new" > apps/my-app/new.txt
+  $ NODE_ENV=production ${TURBO} run build --filter=my-app --explain 2> /dev/null | sed -n '/missed the cache/,/^$/p'
+  my-app#build missed the cache. Changes since it was last cached:
+    + file: new.txt
+    + env var: NODE_ENV
+  
+
+turbo why explains the last run of a task
+  $ ${TURBO} why my-app#build
+  my-app#build missed the cache. Changes since it was last cached:
+    + file: new.txt
+    + env var: NODE_ENV
+  
+  $ NODE_ENV=production ${TURBO} run build --filter=my-app > /dev/null 2>&1
+  $ ${TURBO} why my-app#build
+  my-app#build hit the cache in its last run
+
+Changes to the task definition are explained
+  $ sed -i.bak 's/"banana.txt", "apple.json"/"banana.txt"/' turbo.json
+  $ NODE_ENV=production ${TURBO} run build --filter=my-app --explain 2> /dev/null | sed -n '/missed the cache/,/^$/p'
+  my-app#build missed the cache. Changes since it was last cached:
+    ~ turbo.json: outputs
+  
+
+A task whose inputs haven't changed can still miss
+  $ NODE_ENV=production ${TURBO} run build --filter=my-app --explain --force 2> /dev/null | sed -n '/missed the cache/,/^$/p'
+  my-app#build missed the cache
+    Its inputs haven't changed since it was last cached. The cache entry may have been evicted or the cache was bypassed
+  
+
+Runs that don't write to the cache aren't treated as cached
+  $ echo "uncached" > apps/my-app/uncached.txt
+  $ NODE_ENV=production ${TURBO} run build --filter=my-app --no-cache > /dev/null 2>&1
+  $ NODE_ENV=production ${TURBO} run build --filter=my-app --explain --no-cache 2> /dev/null | sed -n '/missed the cache/,/^$/p'
+  my-app#build missed the cache. Changes since it was last cached:
+    + file: uncached.txt
+  
+
+turbo why fails for tasks that haven't been run
+  $ ${TURBO} why util#build > /dev/null 2>&1
+  [1]
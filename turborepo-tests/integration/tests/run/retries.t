@@ -0,0 +1,39 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh task_retries
+
+A task that fails once is retried and then succeeds
+  $ ${TURBO} run flaky --summarize | grep -E "failing|passing|retrying|successful"
+  flaky: failing
+  flaky: .*retrying \(1/1\) (re)
+  flaky: passing
+   Tasks:    1 successful, 1 total
+
+Each attempt is recorded in the run summary
+  $ source "$TESTDIR/../../../helpers/run_summary.sh"
+  $ SUMMARY=$(/bin/ls .turbo/runs/*.json | head -n1)
+  $ TASK_SUMMARY=$(getSummaryTask "$SUMMARY" "flaky")
+  $ echo $TASK_SUMMARY | jq '.execution.exitCode'
+  0
+  $ echo $TASK_SUMMARY | jq '.execution.attempts | map(.exitCode)'
+  [
+    1,
+    0
+  ]
+  $ echo $TASK_SUMMARY | jq '.resolvedTaskDefinition.retries'
+  1
+
+A task that only succeeded after being retried isn't cached
+  $ ${TURBO} run flaky | grep -E "cache (hit|miss)"
+  flaky: cache miss, executing [0-9a-f]+ (re)
+
+Unless cacheRetried is set
+  $ ${TURBO} run flaky-cached | grep -E "cache (hit|miss)|retrying"
+  flaky-cached: cache miss, executing [0-9a-f]+ (re)
+  flaky-cached: .*retrying \(1/1\) (re)
+  $ ${TURBO} run flaky-cached | grep -E "cache (hit|miss)"
+  flaky-cached: cache hit, replaying logs [0-9a-f]+ (re)
+
+--retries overrides turbo.json
+  $ rm -rf .attempts
+  $ ${TURBO} run flaky --retries=0 > /dev/null
+  [1]
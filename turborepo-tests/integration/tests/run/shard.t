@@ -0,0 +1,31 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh
+
+Every shard gets the same number of tasks
+  $ ${TURBO} run build --shard=1/2 --dry=json | jq -c '.shard'
+  {"index":1,"count":2,"shards":[{"index":1,"tasks":["another#build","util#build"]},{"index":2,"tasks":["my-app#build"]}]}
+
+Each shard only runs its own tasks
+  $ ${TURBO} run build --shard=1/2 --dry=json | jq -c '[.tasks[].taskId]'
+  ["another#build","util#build"]
+  $ ${TURBO} run build --shard=2/2 --dry=json | jq -c '[.tasks[].taskId]'
+  ["my-app#build"]
+
+Shards are balanced by the durations in a shared run summary
+  $ cat > durations.json <<EOF
+  > {"monorepo": true, "tasks": [{"taskId": "my-app#build", "cache": {"status": "MISS", "timeSaved": 0}, "execution": {"startTime": 0, "endTime": 1000}}, {"taskId": "util#build", "cache": {"status": "MISS", "timeSaved": 0}, "execution": {"startTime": 0, "endTime": 3000}}]}
+  > EOF
+  $ ${TURBO} run build --shard=1/2 --shard-durations=durations.json --dry=json | jq -c '.shard'
+  {"index":1,"count":2,"shards":[{"index":1,"tasks":["util#build"],"estimatedDuration":3000},{"index":2,"tasks":["another#build","my-app#build"],"estimatedDuration":3000}]}
+
+Shards without tasks don't run anything
+  $ ${TURBO} run build --shard=4/4 --dry=json | jq -c '.tasks'
+  []
+
+The shard must be within the shard count
+  $ ${TURBO} run build --shard=5/4
+   ERROR  invalid value '5/4' for '--shard <INDEX/COUNT>': shard index must be between 1 and 4
+  
+  For more information, try '--help'.
+  
+  [1]
@@ -0,0 +1,25 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh task_sidecars
+  $ source "$TESTDIR/../../../helpers/run_summary.sh"
+
+A task only starts once its sidecar is ready and the sidecar is stopped once the task finishes
+  $ ${TURBO} run test > /dev/null 2>&1
+  $ cat .test.result
+  ready (no-eol)
+  $ kill -0 $(cat .serve.pid) 2> /dev/null
+  [1]
+
+A sidecar that doesn't become ready in time is stopped and its dependents don't run
+  $ rm .test.result
+  $ ${TURBO} run test-stuck --summarize > /dev/null 2>&1
+  [1]
+  $ kill -0 $(cat .stuck.pid) 2> /dev/null
+  [1]
+  $ test -f .test.result
+  [1]
+  $ SUMMARY=$(/bin/ls .turbo/runs/*.json | head -n1)
+  $ getSummaryTask "$SUMMARY" "stuck" | jq '.execution | {exitCode, error}'
+  {
+    "exitCode": null,
+    "error": "command .* was not ready after 1s" (re)
+  }
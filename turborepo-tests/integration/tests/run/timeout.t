@@ -0,0 +1,33 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh task_timeout
+  $ source "$TESTDIR/../../../helpers/run_summary.sh"
+
+A task that runs for longer than its timeout is stopped and fails the run
+  $ ${TURBO} run hang --summarize > /dev/null 2>&1
+  [1]
+  $ kill -0 $(cat .hang.pid) 2> /dev/null
+  [1]
+  $ SUMMARY=$(/bin/ls .turbo/runs/*.json | head -n1)
+  $ getSummaryTask "$SUMMARY" "hang" | jq '.execution | {timedOut, exitCode, error}'
+  {
+    "timedOut": true,
+    "exitCode": null,
+    "error": "command .* timed out after 1s" (re)
+  }
+
+With --continue, the other tasks still run and the run fails
+  $ rm -rf .turbo/runs
+  $ ${TURBO} run hang quick --continue --summarize > /dev/null 2>&1
+  [1]
+  $ kill -0 $(cat .hang.pid) 2> /dev/null
+  [1]
+  $ SUMMARY=$(/bin/ls .turbo/runs/*.json | head -n1)
+  $ getSummaryTask "$SUMMARY" "hang" | jq '.execution.timedOut'
+  true
+  $ getSummaryTask "$SUMMARY" "quick" | jq '.execution.exitCode'
+  0
+  $ cat $SUMMARY | jq '.execution | {failed, success}'
+  {
+    "failed": 1,
+    "success": 1
+  }
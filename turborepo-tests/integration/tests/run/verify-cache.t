@@ -0,0 +1,23 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh verify_cache
+
+Populate the cache
+  $ ${TURBO} run build --output-logs=none > /dev/null
+  $ cp apps/flaky/dist/out.txt cached-out.txt
+
+Cache hits are re-run and the outputs that differ from the cache are reported
+  $ ${TURBO} run build --verify-cache --output-logs=none > out.txt
+  $ sed -n '/Cache verification/,/verified tasks/p' out.txt
+  Cache verification:
+    flaky#build: outputs differ from the cache
+      changed apps/flaky/dist/out.txt
+    stable#build: reproduced 1 file
+  1 of 2 verified tasks didn't reproduce their cached outputs
+
+The workspace keeps the cached outputs and the scratch copies are removed
+  $ diff cached-out.txt apps/flaky/dist/out.txt
+  $ ls .turbo/verify
+
+Tasks that miss the cache aren't verified
+  $ ${TURBO} run build --verify-cache --force --output-logs=none | grep "Cache verification"
+  [1]
@@ -0,0 +1,22 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh composable_config
+
+# extends-shared extends from both the root and shared-config. The task definition is merged
+# from the root, then shared-config, then the workspace's own turbo.json.
+  $ ${TURBO} run extends-shared-task --filter=extends-shared --dry=json | jq -c '.tasks[0].resolvedTaskDefinition | {inputs, outputs, env, outputMode}'
+  {"inputs":["src/shared.txt"],"outputs":["shared/**"],"env":["OWN_VAR"],"outputMode":"new-only"}
+
+Extending from a package that doesn't exist is an error
+  $ cp apps/extends-shared/turbo.json turbo.json.bak
+  $ jq '.extends = ["//", "missing-config"]' turbo.json.bak > apps/extends-shared/turbo.json
+  $ ${TURBO} run extends-shared-task --filter=extends-shared > tmp.log 2>&1
+  [1]
+  $ grep --quiet "Cannot extend from \"missing-config\": package not found" tmp.log
+
+Extending in a cycle is an error
+  $ jq '.extends = ["//", "extends-shared"]' packages/shared-config/turbo.json > tmp.json
+  $ mv tmp.json packages/shared-config/turbo.json
+  $ cp turbo.json.bak apps/extends-shared/turbo.json
+  $ ${TURBO} run extends-shared-task --filter=extends-shared > tmp.log 2>&1
+  [1]
+  $ grep --quiet "Cyclic \"extends\" found: extends-shared -> shared-config -> extends-shared" tmp.log
@@ -35,7 +35,8 @@ Setup
     "env": [],
     "passThroughEnv": null,
     "dotEnv": null,
-    "interactive": false
+    "interactive": false,
+    "retries": 0
   }
 
 # This task is similar, but `dependsOn` in the root turbo.json _only_ has a topological dependency
@@ -51,5 +52,6 @@ Setup
     "env": [],
     "passThroughEnv": null,
     "dotEnv": null,
-    "interactive": false
+    "interactive": false,
+    "retries": 0
   }